@@ -1,44 +1,64 @@
 //! `Clock` dictates how timestamps are done in the Quicklog.
-//! The idea is to use TSC time, storing a start TSC time, and a start System time.
 //!
-//! We store TSC time when logging is done in Quicklog, only getting the System
-//! time on the writer thread that is performance sensitive, as we would
-//! be able to decode the true System time, given the delta between
-//! `Instant` of generating the log line and `Instant` of the start time to get a
-//! `Duration`, which can be added to start System time to give a final `DateTime<Utc>`.
+//! A [`Clock`] returns the current time as nanoseconds since the unix epoch. This
+//! is taken on the logging thread, which is performance sensitive, so the
+//! conversion into a `DateTime<Utc>` is only done when log lines are flushed.
+//!
+//! The default [`QuantaClock`] uses TSC time, storing a start TSC time and a
+//! start System time, and adds the elapsed TSC time to the start System time.
+//! [`SystemClock`] reads the System time on every call instead.
+//!
+//! Any other time source, such as a simulated clock for backtesting or a
+//! PTP-disciplined clock, can be used by implementing [`Clock`].
 //!
 //! Here's an example of how things are done in time taking.
 //!
 //! ```rust no_run
 //! use std::thread;
-//! use quicklog_clock::{Clock, quanta::QuantaClock};
+//! use quicklog_clock::{Clock, quanta::QuantaClock, to_system_time};
 //!
 //! // initialize the clock, impls `Clock` trait
 //! let clock = QuantaClock::new();
 //!
-//! let some_log_line_instant = clock.get_instant();
+//! let some_log_line_time = clock.get_time();
 //! // add log_line onto some queue
 //!
 //! // simulate flush thread
 //! let flush_thread = thread::spawn(move || {
 //!     // some code to flush log lines
-//!     let actual_system_time = clock.compute_system_time_from_instant(some_log_line_instant);
+//!     let actual_system_time = to_system_time(some_log_line_time);
 //! });
 //!
 //! # flush_thread.join();
 //! ```
+//!
+//! [`QuantaClock`]: quanta::QuantaClock
+//! [`SystemClock`]: system::SystemClock
 
-use ::quanta::Instant;
-use chrono::{DateTime, OutOfRangeError, Utc};
+use chrono::{DateTime, TimeZone, Utc};
 
 pub mod quanta;
+pub mod system;
 
 pub trait Clock {
-    /// Returns current tsc instant
-    fn get_instant(&self) -> Instant;
-    /// Returns system time from TSC time
-    fn compute_system_time_from_instant(
-        &self,
-        instant: Instant,
-    ) -> Result<DateTime<Utc>, OutOfRangeError>;
+    /// Returns the current time in nanoseconds since the unix epoch
+    fn get_time(&self) -> u64;
+}
+
+/// Converts nanoseconds since the unix epoch, as returned by
+/// [`Clock::get_time`], into a `DateTime<Utc>`
+pub fn to_system_time(nanos: u64) -> DateTime<Utc> {
+    Utc.timestamp_nanos(nanos.min(i64::MAX as u64) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_nanos_to_system_time() {
+        let time = to_system_time(1_698_048_000_123_456_789);
+        assert_eq!(time.timestamp(), 1_698_048_000);
+        assert_eq!(time.timestamp_subsec_nanos(), 123_456_789);
+    }
 }
@@ -1,12 +1,11 @@
-use chrono::{DateTime, Duration, OutOfRangeError, Utc};
 use quanta::Instant;
 
-use crate::Clock;
+use crate::{system::SystemClock, Clock};
 
 pub struct QuantaClock {
     clock: quanta::Clock,
-    start_time: DateTime<Utc>,
-    start_instant: quanta::Instant,
+    start_time: u64,
+    start_instant: Instant,
 }
 
 impl QuantaClock {
@@ -17,7 +16,7 @@ impl QuantaClock {
         let start_instant = clock.now();
         QuantaClock {
             clock,
-            start_time: Utc::now(),
+            start_time: SystemClock.get_time(),
             start_instant,
         }
     }
@@ -30,16 +29,8 @@ impl Default for QuantaClock {
 }
 
 impl Clock for QuantaClock {
-    fn get_instant(&self) -> Instant {
-        self.clock.now()
-    }
-
-    fn compute_system_time_from_instant(
-        &self,
-        instant: Instant,
-    ) -> Result<DateTime<Utc>, OutOfRangeError> {
-        let elapsed_time = instant.duration_since(self.start_instant);
-        let chrono_duration = Duration::from_std(elapsed_time);
-        chrono_duration.map(|duration| self.start_time + duration)
+    fn get_time(&self) -> u64 {
+        let elapsed_time = self.clock.now().duration_since(self.start_instant);
+        self.start_time + elapsed_time.as_nanos() as u64
     }
 }
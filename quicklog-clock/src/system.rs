@@ -0,0 +1,25 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::Clock;
+
+/// Reads `std::time::SystemTime` every time it is called. Slower than
+/// [`QuantaClock`], but follows any adjustments made to the System time.
+///
+/// [`QuantaClock`]: crate::quanta::QuantaClock
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl SystemClock {
+    pub fn new() -> SystemClock {
+        SystemClock
+    }
+}
+
+impl Clock for SystemClock {
+    fn get_time(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,81 @@
+use std::io;
+
+use tokio::{fs::File, fs::OpenOptions, io::AsyncWriteExt};
+
+use crate::{AsyncFlush, FlushFuture, Level};
+
+/// Appends to a file through tokio's async file I/O, which is opened on the
+/// first flush. Ensure that the directory of the file exists.
+///
+/// ```rust,no_run
+/// use quicklog_flush::async_file_flusher::AsyncFileFlusher;
+///
+/// let flusher = AsyncFileFlusher::new("logs/app.log");
+/// ```
+pub struct AsyncFileFlusher {
+    path: String,
+    file: Option<File>,
+}
+
+impl AsyncFileFlusher {
+    /// Flushes into the file at `path`, created if it does not exist
+    pub fn new(path: impl Into<String>) -> AsyncFileFlusher {
+        AsyncFileFlusher {
+            path: path.into(),
+            file: None,
+        }
+    }
+
+    async fn write(&mut self, display: String) -> io::Result<()> {
+        let file = match &mut self.file {
+            Some(file) => file,
+            file @ None => file.insert(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)
+                    .await?,
+            ),
+        };
+        file.write_all(display.as_bytes()).await
+    }
+}
+
+impl AsyncFlush for AsyncFileFlusher {
+    fn flush_record(&mut self, _level: Level, display: String) -> FlushFuture<'_> {
+        Box::pin(self.write(display))
+    }
+
+    /// Waits for the writes still in flight on tokio's blocking pool
+    fn flush_buffered(&mut self) -> FlushFuture<'_> {
+        Box::pin(async move {
+            match &mut self.file {
+                Some(file) => file.flush().await,
+                None => Ok(()),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestFile;
+
+    #[tokio::test]
+    async fn appends_lines() {
+        let file = TestFile::new("async-file");
+        let mut flusher = AsyncFileFlusher::new(file.0.to_str().unwrap());
+        flusher
+            .flush_record(Level::Info, "first\n".to_string())
+            .await
+            .unwrap();
+        flusher
+            .flush_record(Level::Warn, "second\n".to_string())
+            .await
+            .unwrap();
+        flusher.flush_buffered().await.unwrap();
+
+        assert_eq!(file.contents(), "first\nsecond\n");
+    }
+}
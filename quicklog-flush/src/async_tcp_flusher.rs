@@ -0,0 +1,199 @@
+use std::{io, time::Duration};
+
+use tokio::{io::AsyncWriteExt, net::TcpStream, time::timeout};
+
+use crate::{
+    tcp_backlog::{Backlog, Backoff, DEFAULT_TIMEOUT},
+    AsyncFlush, FlushFuture, Level,
+};
+
+/// Streams log lines to a remote collector over TCP through tokio, the async
+/// counterpart of [`TcpFlusher`](crate::tcp_flusher::TcpFlusher). Needs a
+/// tokio runtime with I/O and time enabled.
+///
+/// Lines are kept in a bounded backlog until they are written to the socket.
+/// When the connection drops, the flusher reconnects with exponential backoff
+/// on later flushes, and sends the backlog once connected again. Once the
+/// backlog is full, the oldest lines are dropped.
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use quicklog_flush::async_tcp_flusher::AsyncTcpFlusher;
+///
+/// let flusher = AsyncTcpFlusher::new("collector.internal:5170")
+///     .with_backlog_capacity(16 * 1024 * 1024)
+///     .with_backoff(Duration::from_millis(50), Duration::from_secs(10));
+/// ```
+pub struct AsyncTcpFlusher {
+    addr: String,
+    stream: Option<TcpStream>,
+    backlog: Backlog,
+    backoff: Backoff,
+    timeout: Duration,
+}
+
+impl AsyncTcpFlusher {
+    /// Streams into the collector at `addr`, e.g. `"10.0.0.5:5170"`. The
+    /// connection is established on the first flush.
+    pub fn new(addr: impl Into<String>) -> AsyncTcpFlusher {
+        AsyncTcpFlusher {
+            addr: addr.into(),
+            stream: None,
+            backlog: Backlog::new(),
+            backoff: Backoff::new(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Maximum number of bytes of log lines kept while the collector is
+    /// unreachable, 4 MiB by default
+    pub fn with_backlog_capacity(mut self, bytes: usize) -> AsyncTcpFlusher {
+        self.backlog.set_capacity(bytes);
+        self
+    }
+
+    /// Delay before the first reconnection attempt, doubling after every
+    /// failed attempt up to `max`. Defaults to 100ms and 30s.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> AsyncTcpFlusher {
+        self.backoff.set(initial, max);
+        self
+    }
+
+    /// Timeout of connecting to and writing into the collector, 1s by default
+    pub fn with_timeout(mut self, timeout: Duration) -> AsyncTcpFlusher {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Whether the flusher is currently connected to the collector
+    pub fn is_connected(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    /// Number of lines waiting to be sent
+    pub fn backlog_len(&self) -> usize {
+        self.backlog.len()
+    }
+
+    /// Number of lines dropped because the backlog was full
+    pub fn dropped(&self) -> u64 {
+        self.backlog.dropped()
+    }
+
+    /// Connects to the collector unless waiting for the next attempt
+    async fn connect(&mut self) -> io::Result<()> {
+        if self.stream.is_some() {
+            return Ok(());
+        }
+        self.backoff.ready()?;
+        let connected = timeout(self.timeout, TcpStream::connect(self.addr.as_str()))
+            .await
+            .unwrap_or_else(|_| Err(io::ErrorKind::TimedOut.into()));
+        self.stream = Some(self.backoff.attempted(connected)?);
+        Ok(())
+    }
+
+    /// Writes as much of the backlog as possible, disconnecting on error
+    async fn send(&mut self) -> io::Result<()> {
+        while let Some(line) = self.backlog.front() {
+            let Some(stream) = self.stream.as_mut() else {
+                self.connect().await?;
+                continue;
+            };
+            let written = timeout(self.timeout, stream.write(line))
+                .await
+                .unwrap_or_else(|_| Err(io::ErrorKind::TimedOut.into()));
+            if let Err(err) = self.backlog.wrote(written) {
+                self.stream = None;
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl AsyncFlush for AsyncTcpFlusher {
+    /// Lines which cannot be sent yet are kept in the backlog, so this only
+    /// fails when lines had to be dropped from the backlog
+    fn flush_record(&mut self, _level: Level, display: String) -> FlushFuture<'_> {
+        Box::pin(async move {
+            let pushed = self.backlog.push(display);
+            // failures to send are retried on the next flush
+            let _ = self.send().await;
+            pushed
+        })
+    }
+
+    /// Tries to send the backlog, which is kept if the collector is
+    /// unreachable
+    fn flush_buffered(&mut self) -> FlushFuture<'_> {
+        Box::pin(self.send())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::{
+        io::{AsyncBufReadExt, BufReader},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    async fn read_lines(listener: &TcpListener, count: usize) -> Vec<String> {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut lines = BufReader::new(stream).lines();
+        let mut read = Vec::new();
+        while read.len() < count {
+            read.push(lines.next_line().await.unwrap().unwrap());
+        }
+        read
+    }
+
+    async fn flush(flusher: &mut AsyncTcpFlusher, line: &str) -> io::Result<()> {
+        flusher.flush_record(Level::Info, line.to_string()).await
+    }
+
+    #[tokio::test]
+    async fn streams_lines() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let mut flusher = AsyncTcpFlusher::new(listener.local_addr().unwrap().to_string());
+        for i in 0..3 {
+            flush(&mut flusher, &format!("line {}\n", i)).await.unwrap();
+        }
+
+        assert!(flusher.is_connected());
+        assert_eq!(flusher.backlog_len(), 0);
+        assert_eq!(
+            read_lines(&listener, 3).await,
+            ["line 0", "line 1", "line 2"]
+        );
+    }
+
+    #[tokio::test]
+    async fn reconnects_and_sends_backlog() {
+        // reserve a port with nothing listening on it
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let mut flusher = AsyncTcpFlusher::new(addr.to_string())
+            .with_backoff(Duration::ZERO, Duration::ZERO)
+            .with_backlog_capacity(20);
+
+        flush(&mut flusher, "dropped\n").await.unwrap();
+        flush(&mut flusher, "first\n").await.unwrap();
+        assert!(!flusher.is_connected());
+        assert!(flush(&mut flusher, "second\n").await.is_err());
+        assert_eq!(flusher.dropped(), 1);
+        assert_eq!(flusher.backlog_len(), 2);
+
+        let listener = TcpListener::bind(addr).await.unwrap();
+        flush(&mut flusher, "third\n").await.unwrap();
+        assert!(flusher.is_connected());
+        assert_eq!(
+            read_lines(&listener, 3).await,
+            ["first", "second", "third"]
+        );
+    }
+}
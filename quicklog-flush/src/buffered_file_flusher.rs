@@ -0,0 +1,223 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::{Flush, Level};
+
+/// Default capacity of the write buffer
+pub const DEFAULT_BUFFER_CAPACITY: usize = 64 * 1024;
+
+/// When [`BufferedFileFlusher`] writes its buffer out to the file
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FlushPolicy {
+    /// After every given number of records
+    EveryRecords(usize),
+    /// On the first record after the given time has passed since the last
+    /// write. There is no background thread, so nothing is written while no
+    /// records are flushed.
+    Every(Duration),
+    /// Only once the buffer is full, or when [`BufferedFileFlusher::flush`]
+    /// or [`BufferedFileFlusher::sync_all`] is called
+    Manual,
+}
+
+impl Default for FlushPolicy {
+    fn default() -> Self {
+        FlushPolicy::Every(Duration::from_millis(100))
+    }
+}
+
+/// Flushes into a file through a `BufWriter`, writing to the file according
+/// to its [`FlushPolicy`] rather than on every record.
+///
+/// Writes only reach the OS page cache unless [`BufferedFileFlusher::sync_all`]
+/// is called, or [`BufferedFileFlusher::with_sync`] is enabled to fsync every
+/// time the buffer is written out. The buffer is written out when the flusher
+/// is dropped.
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use quicklog_flush::buffered_file_flusher::{BufferedFileFlusher, FlushPolicy};
+///
+/// let flusher = BufferedFileFlusher::new("logs/quicklog.log")
+///     .with_capacity(1024 * 1024)
+///     .with_flush_policy(FlushPolicy::Every(Duration::from_millis(50)));
+/// ```
+pub struct BufferedFileFlusher {
+    path: PathBuf,
+    writer: Option<BufWriter<File>>,
+    capacity: usize,
+    policy: FlushPolicy,
+    sync: bool,
+    /// Records buffered since the last write to the file
+    pending: usize,
+    last_flush: Instant,
+}
+
+impl BufferedFileFlusher {
+    /// Appends to the file at `path`, which is created on the first flush if
+    /// needed. Ensure that its directory exists.
+    pub fn new(path: impl Into<PathBuf>) -> BufferedFileFlusher {
+        BufferedFileFlusher {
+            path: path.into(),
+            writer: None,
+            capacity: DEFAULT_BUFFER_CAPACITY,
+            policy: FlushPolicy::default(),
+            sync: false,
+            pending: 0,
+            last_flush: Instant::now(),
+        }
+    }
+
+    /// Size of the write buffer in bytes, [`DEFAULT_BUFFER_CAPACITY`] by
+    /// default
+    pub fn with_capacity(mut self, capacity: usize) -> BufferedFileFlusher {
+        self.capacity = capacity;
+        self
+    }
+
+    /// When the buffer is written out to the file, every 100ms by default
+    pub fn with_flush_policy(mut self, policy: FlushPolicy) -> BufferedFileFlusher {
+        self.policy = policy;
+        self
+    }
+
+    /// Whether to fsync the file every time the buffer is written out by the
+    /// flush policy, off by default
+    pub fn with_sync(mut self, sync: bool) -> BufferedFileFlusher {
+        self.sync = sync;
+        self
+    }
+
+    /// Current flush policy
+    pub fn flush_policy(&self) -> FlushPolicy {
+        self.policy
+    }
+
+    /// Path of the file written to
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Writes out the buffer to the file
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.pending = 0;
+        self.last_flush = Instant::now();
+        match &mut self.writer {
+            Some(writer) => writer.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes out the buffer and waits for the file to reach the disk
+    pub fn sync_all(&mut self) -> io::Result<()> {
+        self.flush()?;
+        match &self.writer {
+            Some(writer) => writer.get_ref().sync_all(),
+            None => Ok(()),
+        }
+    }
+
+    fn writer(&mut self) -> io::Result<&mut BufWriter<File>> {
+        if self.writer.is_none() {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+            self.writer = Some(BufWriter::with_capacity(self.capacity, file));
+        }
+        Ok(self.writer.as_mut().expect("writer is opened above"))
+    }
+
+    fn should_flush(&self) -> bool {
+        match self.policy {
+            FlushPolicy::EveryRecords(records) => self.pending >= records,
+            FlushPolicy::Every(interval) => self.last_flush.elapsed() >= interval,
+            FlushPolicy::Manual => false,
+        }
+    }
+}
+
+impl Flush for BufferedFileFlusher {
+    fn flush_one(&mut self, display: String) {
+        if self.try_flush_one(display).is_err() {
+            panic!("Unable to write to file")
+        }
+    }
+
+    fn try_flush_one(&mut self, display: String) -> io::Result<()> {
+        self.try_flush_str(Level::Info, &display)
+    }
+
+    fn try_flush_str(&mut self, _level: Level, display: &str) -> io::Result<()> {
+        self.writer()?.write_all(display.as_bytes())?;
+        self.pending += 1;
+
+        if !self.should_flush() {
+            return Ok(());
+        }
+        if self.sync {
+            self.sync_all()
+        } else {
+            self.flush()
+        }
+    }
+
+    fn flush_buffered(&mut self) -> io::Result<()> {
+        self.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestFile;
+
+    #[test]
+    fn flushes_every_n_records() {
+        let file = TestFile::new("buffered-records");
+        let mut flusher =
+            BufferedFileFlusher::new(&file.0).with_flush_policy(FlushPolicy::EveryRecords(2));
+
+        flusher.try_flush_one("a\n".to_string()).unwrap();
+        assert_eq!(file.contents(), "");
+        flusher.try_flush_one("b\n".to_string()).unwrap();
+        assert_eq!(file.contents(), "a\nb\n");
+        flusher.try_flush_one("c\n".to_string()).unwrap();
+        assert_eq!(file.contents(), "a\nb\n");
+
+        drop(flusher);
+        assert_eq!(file.contents(), "a\nb\nc\n");
+    }
+
+    #[test]
+    fn flushes_after_interval() {
+        let file = TestFile::new("buffered-interval");
+        let mut flusher =
+            BufferedFileFlusher::new(&file.0).with_flush_policy(FlushPolicy::Every(Duration::ZERO));
+
+        flusher.try_flush_one("a\n".to_string()).unwrap();
+        assert_eq!(file.contents(), "a\n");
+    }
+
+    #[test]
+    fn flushes_manually() {
+        let file = TestFile::new("buffered-manual");
+        let mut flusher = BufferedFileFlusher::new(&file.0)
+            .with_capacity(4)
+            .with_flush_policy(FlushPolicy::Manual);
+        assert_eq!(flusher.flush_policy(), FlushPolicy::Manual);
+
+        flusher.try_flush_one("a\n".to_string()).unwrap();
+        assert_eq!(file.contents(), "");
+        flusher.sync_all().unwrap();
+        assert_eq!(file.contents(), "a\n");
+
+        // lines larger than the buffer go straight to the file
+        flusher.try_flush_one("longer\n".to_string()).unwrap();
+        assert_eq!(file.contents(), "a\nlonger\n");
+    }
+}
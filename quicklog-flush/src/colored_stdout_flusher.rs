@@ -0,0 +1,157 @@
+use std::io::{self, IsTerminal, Write};
+
+use crate::{Flush, Level};
+
+/// Whether [`ColoredStdoutFlusher`] writes colors
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Only when stdout is a terminal and the `NO_COLOR` environment variable
+    /// is not set
+    #[default]
+    Auto,
+    /// Always, e.g. when piping into `less -R`
+    Always,
+    /// Never
+    Never,
+}
+
+impl ColorChoice {
+    fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Auto => {
+                std::env::var_os("NO_COLOR").is_none() && io::stdout().is_terminal()
+            }
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+/// Flushes into stdout, coloring the level of each record with ANSI escape
+/// codes: red for ERROR, yellow for WARN, green for INFO, blue for DEBUG and
+/// dimmed for TRACE.
+///
+/// The first occurrence of the level name in the log line is colored, or the
+/// whole line if the formatter does not write the level. Lines flushed
+/// without a level are written as-is.
+///
+/// ```rust
+/// use quicklog_flush::colored_stdout_flusher::{ColorChoice, ColoredStdoutFlusher};
+///
+/// // colors only when writing to a terminal
+/// let flusher = ColoredStdoutFlusher::new();
+/// // colors even when piped
+/// let flusher = ColoredStdoutFlusher::new().with_color(ColorChoice::Always);
+/// ```
+pub struct ColoredStdoutFlusher {
+    colored: bool,
+}
+
+impl ColoredStdoutFlusher {
+    /// Colors according to [`ColorChoice::Auto`]
+    pub fn new() -> ColoredStdoutFlusher {
+        ColoredStdoutFlusher {
+            colored: ColorChoice::Auto.enabled(),
+        }
+    }
+
+    /// Overrides whether colors are written
+    pub fn with_color(mut self, choice: ColorChoice) -> ColoredStdoutFlusher {
+        self.colored = choice.enabled();
+        self
+    }
+
+    /// Whether colors are written
+    pub fn is_colored(&self) -> bool {
+        self.colored
+    }
+}
+
+impl Default for ColoredStdoutFlusher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Flush for ColoredStdoutFlusher {
+    fn flush_one(&mut self, display: String) {
+        print!("{}", display);
+    }
+
+    fn try_flush_one(&mut self, display: String) -> io::Result<()> {
+        io::stdout().write_all(display.as_bytes())
+    }
+
+    fn try_flush_record(&mut self, level: Level, display: String) -> io::Result<()> {
+        if !self.colored {
+            return self.try_flush_one(display);
+        }
+        io::stdout().write_all(colorize(level, &display).as_bytes())
+    }
+}
+
+/// ANSI escape code of the color of `level`
+fn color(level: Level) -> &'static str {
+    match level {
+        Level::Error => "\x1b[31m",
+        Level::Warn => "\x1b[33m",
+        Level::Info => "\x1b[32m",
+        Level::Debug => "\x1b[34m",
+        Level::Trace => "\x1b[2m",
+    }
+}
+
+const RESET: &str = "\x1b[0m";
+
+/// Colors the first occurrence of the name of `level` in `line`, or the whole
+/// line without its line break if the name is missing
+fn colorize(level: Level, line: &str) -> String {
+    let name = level.to_string();
+    let (start, end) = match line.find(&name) {
+        Some(start) => (start, start + name.len()),
+        None => (0, line.trim_end_matches(['\r', '\n']).len()),
+    };
+
+    let mut colored = String::with_capacity(line.len() + 9);
+    colored.push_str(&line[..start]);
+    colored.push_str(color(level));
+    colored.push_str(&line[start..end]);
+    colored.push_str(RESET);
+    colored.push_str(&line[end..]);
+    colored
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_level_token() {
+        assert_eq!(
+            colorize(Level::Error, "[ts][ERROR] failed\n"),
+            "[ts][\x1b[31mERROR\x1b[0m] failed\n"
+        );
+        assert_eq!(
+            colorize(Level::Warn, "ts=1 level=WARN msg=crossed\n"),
+            "ts=1 level=\x1b[33mWARN\x1b[0m msg=crossed\n"
+        );
+    }
+
+    #[test]
+    fn colors_line_without_level() {
+        assert_eq!(
+            colorize(Level::Info, "[ts]filled\n"),
+            "\x1b[32m[ts]filled\x1b[0m\n"
+        );
+    }
+
+    #[test]
+    fn color_choice() {
+        assert!(ColoredStdoutFlusher::new()
+            .with_color(ColorChoice::Always)
+            .is_colored());
+        assert!(!ColoredStdoutFlusher::new()
+            .with_color(ColorChoice::Never)
+            .is_colored());
+    }
+}
@@ -0,0 +1,245 @@
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use crate::{Flush, Level};
+
+/// Default time after which the current frame is finished, see
+/// [`CompressedFileFlusher::with_frame_interval`]
+pub const DEFAULT_FRAME_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Compression format of a [`CompressedFileFlusher`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Codec {
+    /// gzip, with a level from 0 to 9, enabled with the `gzip` feature
+    #[cfg(feature = "gzip")]
+    Gzip { level: u32 },
+    /// zstd, with a level from 1 to 22, enabled with the `zstd` feature
+    #[cfg(feature = "zstd")]
+    Zstd { level: i32 },
+}
+
+impl Codec {
+    /// Opens a new frame, or gzip member, writing into `file`
+    fn begin(self, file: File) -> io::Result<Frame> {
+        match self {
+            #[cfg(feature = "gzip")]
+            Codec::Gzip { level } => Ok(Frame::Gzip(flate2::write::GzEncoder::new(
+                file,
+                flate2::Compression::new(level),
+            ))),
+            #[cfg(feature = "zstd")]
+            Codec::Zstd { level } => {
+                Ok(Frame::Zstd(zstd::stream::write::Encoder::new(file, level)?))
+            }
+        }
+    }
+}
+
+/// Frame being compressed into the file
+enum Frame {
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::write::GzEncoder<File>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::stream::write::Encoder<'static, File>),
+}
+
+impl Frame {
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        match self {
+            #[cfg(feature = "gzip")]
+            Frame::Gzip(encoder) => encoder.write_all(buf),
+            #[cfg(feature = "zstd")]
+            Frame::Zstd(encoder) => encoder.write_all(buf),
+        }
+    }
+
+    /// Writes out the end of the frame, returning the file
+    fn finish(self) -> io::Result<File> {
+        match self {
+            #[cfg(feature = "gzip")]
+            Frame::Gzip(encoder) => encoder.finish(),
+            #[cfg(feature = "zstd")]
+            Frame::Zstd(encoder) => encoder.finish(),
+        }
+    }
+}
+
+/// What is currently written to
+enum Writer {
+    /// File without an open frame, e.g. right after a frame was finished
+    File(File),
+    Frame(Frame),
+}
+
+/// Flushes into a file compressed with gzip or zstd, e.g. to cut the volume
+/// of logs on hosts with little disk space.
+///
+/// Records are compressed into a frame, or gzip member, which is finished
+/// on the first record after [`DEFAULT_FRAME_INTERVAL`] has passed since it
+/// was opened, and when the flusher is dropped. The file is a sequence of
+/// complete frames, as read by `zcat` and `zstdcat`, so its tail stays
+/// readable while records are written. Records compressed into the frame
+/// being written are only readable once the frame is finished. There is no
+/// background thread, so nothing is written while no records are flushed.
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use quicklog_flush::compressed_file_flusher::{Codec, CompressedFileFlusher};
+///
+/// let flusher = CompressedFileFlusher::new("logs/quicklog.log.zst", Codec::Zstd { level: 3 })
+///     .with_frame_interval(Duration::from_secs(5));
+/// ```
+pub struct CompressedFileFlusher {
+    path: PathBuf,
+    codec: Codec,
+    writer: Option<Writer>,
+    frame_interval: Duration,
+    frame_opened: Instant,
+}
+
+impl CompressedFileFlusher {
+    /// Appends frames to the file at `path`, which is created on the first
+    /// flush if needed. Ensure that its directory exists.
+    pub fn new(path: impl Into<PathBuf>, codec: Codec) -> CompressedFileFlusher {
+        CompressedFileFlusher {
+            path: path.into(),
+            codec,
+            writer: None,
+            frame_interval: DEFAULT_FRAME_INTERVAL,
+            frame_opened: Instant::now(),
+        }
+    }
+
+    /// Time after which the current frame is finished, trading compression
+    /// ratio for how soon records can be read, [`DEFAULT_FRAME_INTERVAL`] by
+    /// default
+    pub fn with_frame_interval(mut self, interval: Duration) -> CompressedFileFlusher {
+        self.frame_interval = interval;
+        self
+    }
+
+    /// Path of the file written to
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Finishes the current frame, so that every record flushed so far can
+    /// be read from the file
+    pub fn finish_frame(&mut self) -> io::Result<()> {
+        if let Some(Writer::Frame(frame)) = self.writer.take() {
+            self.writer = Some(Writer::File(frame.finish()?));
+        }
+        Ok(())
+    }
+
+    fn frame(&mut self) -> io::Result<&mut Frame> {
+        let file = match self.writer.take() {
+            Some(Writer::Frame(frame)) => {
+                self.writer = Some(Writer::Frame(frame));
+                None
+            }
+            Some(Writer::File(file)) => Some(file),
+            None => Some(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)?,
+            ),
+        };
+        if let Some(file) = file {
+            self.writer = Some(Writer::Frame(self.codec.begin(file)?));
+            self.frame_opened = Instant::now();
+        }
+        match &mut self.writer {
+            Some(Writer::Frame(frame)) => Ok(frame),
+            _ => unreachable!("frame is opened above"),
+        }
+    }
+}
+
+impl Drop for CompressedFileFlusher {
+    fn drop(&mut self) {
+        let _ = self.finish_frame();
+    }
+}
+
+impl Flush for CompressedFileFlusher {
+    fn flush_one(&mut self, display: String) {
+        if self.try_flush_one(display).is_err() {
+            panic!("Unable to write to file")
+        }
+    }
+
+    fn try_flush_one(&mut self, display: String) -> io::Result<()> {
+        self.try_flush_str(Level::Info, &display)
+    }
+
+    fn try_flush_str(&mut self, _level: Level, display: &str) -> io::Result<()> {
+        self.frame()?.write_all(display.as_bytes())?;
+        if self.frame_opened.elapsed() >= self.frame_interval {
+            self.finish_frame()?;
+        }
+        Ok(())
+    }
+
+    fn flush_buffered(&mut self) -> io::Result<()> {
+        self.finish_frame()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+    use crate::test_utils::TestFile;
+
+    /// Writes frames, checking that the file holds complete frames along the
+    /// way
+    fn write_frames(file: &TestFile, codec: Codec, decode: fn(&[u8]) -> String) {
+        let mut flusher =
+            CompressedFileFlusher::new(&file.0, codec).with_frame_interval(Duration::MAX);
+        flusher.try_flush_one("a\n".to_string()).unwrap();
+        flusher.try_flush_one("b\n".to_string()).unwrap();
+        flusher.finish_frame().unwrap();
+        assert_eq!(decode(&file.bytes()), "a\nb\n");
+
+        // finished on the first record after the interval
+        flusher = flusher.with_frame_interval(Duration::ZERO);
+        flusher.try_flush_one("c\n".to_string()).unwrap();
+        assert_eq!(decode(&file.bytes()), "a\nb\nc\n");
+        drop(flusher);
+
+        // appends to the existing file, and finishes the frame when dropped
+        let mut flusher = CompressedFileFlusher::new(&file.0, codec);
+        flusher.try_flush_one("d\n".to_string()).unwrap();
+        drop(flusher);
+        assert_eq!(decode(&file.bytes()), "a\nb\nc\nd\n");
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzip() {
+        let file = TestFile::new("compressed-gzip");
+        write_frames(&file, Codec::Gzip { level: 6 }, |bytes| {
+            let mut contents = String::new();
+            flate2::read::MultiGzDecoder::new(bytes)
+                .read_to_string(&mut contents)
+                .unwrap();
+            contents
+        });
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn zstd() {
+        let file = TestFile::new("compressed-zstd");
+        write_frames(&file, Codec::Zstd { level: 3 }, |bytes| {
+            String::from_utf8(zstd::decode_all(bytes).unwrap()).unwrap()
+        });
+    }
+}
@@ -0,0 +1,316 @@
+use std::{
+    io,
+    net::{ToSocketAddrs, UdpSocket},
+};
+
+#[cfg(unix)]
+use std::{os::unix::net::UnixDatagram, path::Path};
+
+use crate::Flush;
+
+/// Default maximum size of a UDP datagram, which fits in a single Ethernet
+/// frame without IP fragmentation
+pub const DEFAULT_MAX_UDP_DATAGRAM_SIZE: usize = 1472;
+/// Default maximum size of a unix datagram
+pub const DEFAULT_MAX_UNIX_DATAGRAM_SIZE: usize = 8192;
+
+/// What happens to a log line longer than the maximum datagram size
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Oversized {
+    /// Sends the line as several datagrams of at most the maximum size, split
+    /// on character boundaries
+    #[default]
+    Split,
+    /// Sends only the start of the line which fits into a single datagram
+    Truncate,
+    /// Drops the line entirely
+    Drop,
+}
+
+/// Sends lines through a connected datagram socket without blocking, shared
+/// by [`UdpFlusher`] and [`UnixDatagramFlusher`]
+struct DatagramSender {
+    max_datagram_size: usize,
+    oversized: Oversized,
+    dropped: u64,
+}
+
+impl DatagramSender {
+    fn new(max_datagram_size: usize) -> DatagramSender {
+        DatagramSender {
+            max_datagram_size,
+            oversized: Oversized::default(),
+            dropped: 0,
+        }
+    }
+
+    /// Sends `display` without its trailing newline, as datagram boundaries
+    /// already delimit records. Lines which cannot be sent without blocking
+    /// are dropped.
+    fn send(
+        &mut self,
+        display: &str,
+        mut send: impl FnMut(&[u8]) -> io::Result<usize>,
+    ) -> io::Result<()> {
+        let line = display.strip_suffix('\n').unwrap_or(display);
+        let result = chunks(line, self.max_datagram_size, self.oversized)
+            .try_for_each(|chunk| send(chunk.as_bytes()).map(|_| ()));
+        if result.is_err() {
+            self.dropped += 1;
+        }
+        result
+    }
+}
+
+/// Splits `line` into the datagrams sent for it
+fn chunks(line: &str, max_size: usize, oversized: Oversized) -> impl Iterator<Item = &str> {
+    let max_size = max_size.max(1);
+    let fits = line.len() <= max_size;
+    let mut rest = match oversized {
+        _ if fits => line,
+        Oversized::Split => line,
+        Oversized::Truncate => &line[..floor_char_boundary(line, max_size)],
+        Oversized::Drop => "",
+    };
+    let mut first = true;
+
+    std::iter::from_fn(move || {
+        // empty lines are still sent as an empty datagram
+        if rest.is_empty() && !(first && fits) {
+            return None;
+        }
+        first = false;
+        // always make progress, even with a character longer than `max_size`
+        let end = match floor_char_boundary(rest, max_size) {
+            0 => rest.chars().next().map_or(0, char::len_utf8),
+            end => end,
+        };
+        let (chunk, tail) = rest.split_at(end);
+        rest = tail;
+        Some(chunk)
+    })
+}
+
+/// Largest index of a character boundary in `s` not greater than `index`
+fn floor_char_boundary(s: &str, index: usize) -> usize {
+    if index >= s.len() {
+        return s.len();
+    }
+    (0..=index)
+        .rev()
+        .find(|&i| s.is_char_boundary(i))
+        .unwrap_or(0)
+}
+
+/// Sends each log line as a UDP datagram, e.g. to a local Vector or fluent-bit
+/// agent. Sending never blocks: lines which cannot be sent immediately are
+/// dropped, see [`UdpFlusher::dropped`].
+///
+/// ```rust,no_run
+/// use quicklog_flush::datagram_flusher::{Oversized, UdpFlusher};
+///
+/// let flusher = UdpFlusher::new("127.0.0.1:9000")?
+///     .with_max_datagram_size(8192)
+///     .with_oversized(Oversized::Truncate);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct UdpFlusher {
+    socket: UdpSocket,
+    sender: DatagramSender,
+}
+
+impl UdpFlusher {
+    /// Sends datagrams to `addr` from an ephemeral local port, with a maximum
+    /// datagram size of [`DEFAULT_MAX_UDP_DATAGRAM_SIZE`]
+    pub fn new<A: ToSocketAddrs>(addr: A) -> io::Result<UdpFlusher> {
+        let addr = addr.to_socket_addrs()?.next().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "address resolved to nothing")
+        })?;
+        let local = if addr.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        let socket = UdpSocket::bind(local)?;
+        socket.connect(addr)?;
+        socket.set_nonblocking(true)?;
+
+        Ok(UdpFlusher {
+            socket,
+            sender: DatagramSender::new(DEFAULT_MAX_UDP_DATAGRAM_SIZE),
+        })
+    }
+
+    /// Maximum number of bytes sent in a single datagram
+    pub fn with_max_datagram_size(mut self, size: usize) -> UdpFlusher {
+        self.sender.max_datagram_size = size;
+        self
+    }
+
+    /// What happens to lines longer than the maximum datagram size,
+    /// [`Oversized::Split`] by default
+    pub fn with_oversized(mut self, oversized: Oversized) -> UdpFlusher {
+        self.sender.oversized = oversized;
+        self
+    }
+
+    /// Number of lines which failed to be sent, e.g. because the socket
+    /// buffer was full
+    pub fn dropped(&self) -> u64 {
+        self.sender.dropped
+    }
+}
+
+impl Flush for UdpFlusher {
+    fn flush_one(&mut self, display: String) {
+        let _ = self.try_flush_one(display);
+    }
+
+    fn try_flush_one(&mut self, display: String) -> io::Result<()> {
+        let socket = &self.socket;
+        self.sender.send(&display, |chunk| socket.send(chunk))
+    }
+}
+
+/// Sends each log line as a datagram on a unix socket, e.g. to a local Vector
+/// or fluent-bit agent. Sending never blocks: lines which cannot be sent
+/// immediately are dropped, see [`UnixDatagramFlusher::dropped`].
+///
+/// ```rust,no_run
+/// use quicklog_flush::datagram_flusher::UnixDatagramFlusher;
+///
+/// let flusher = UnixDatagramFlusher::new("/var/run/vector.sock")?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[cfg(unix)]
+pub struct UnixDatagramFlusher {
+    socket: UnixDatagram,
+    sender: DatagramSender,
+}
+
+#[cfg(unix)]
+impl UnixDatagramFlusher {
+    /// Sends datagrams to the socket bound at `path`, with a maximum datagram
+    /// size of [`DEFAULT_MAX_UNIX_DATAGRAM_SIZE`]
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<UnixDatagramFlusher> {
+        let socket = UnixDatagram::unbound()?;
+        socket.connect(path)?;
+        socket.set_nonblocking(true)?;
+
+        Ok(UnixDatagramFlusher {
+            socket,
+            sender: DatagramSender::new(DEFAULT_MAX_UNIX_DATAGRAM_SIZE),
+        })
+    }
+
+    /// Maximum number of bytes sent in a single datagram
+    pub fn with_max_datagram_size(mut self, size: usize) -> UnixDatagramFlusher {
+        self.sender.max_datagram_size = size;
+        self
+    }
+
+    /// What happens to lines longer than the maximum datagram size,
+    /// [`Oversized::Split`] by default
+    pub fn with_oversized(mut self, oversized: Oversized) -> UnixDatagramFlusher {
+        self.sender.oversized = oversized;
+        self
+    }
+
+    /// Number of lines which failed to be sent, e.g. because the socket
+    /// buffer was full or nothing is listening on the socket anymore
+    pub fn dropped(&self) -> u64 {
+        self.sender.dropped
+    }
+}
+
+#[cfg(unix)]
+impl Flush for UnixDatagramFlusher {
+    fn flush_one(&mut self, display: String) {
+        let _ = self.try_flush_one(display);
+    }
+
+    fn try_flush_one(&mut self, display: String) -> io::Result<()> {
+        let socket = &self.socket;
+        self.sender.send(&display, |chunk| socket.send(chunk))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(unix)]
+    use crate::test_utils::TestDir;
+
+    fn split(line: &str, max_size: usize, oversized: Oversized) -> Vec<&str> {
+        chunks(line, max_size, oversized).collect()
+    }
+
+    #[test]
+    fn chunking() {
+        assert_eq!(split("hello", 8, Oversized::Split), ["hello"]);
+        assert_eq!(split("", 8, Oversized::Split), [""]);
+        assert_eq!(
+            split("hello world", 4, Oversized::Split),
+            ["hell", "o wo", "rld"]
+        );
+        assert_eq!(split("hello world", 4, Oversized::Truncate), ["hell"]);
+        assert!(split("hello world", 4, Oversized::Drop).is_empty());
+        assert_eq!(split("hello", 5, Oversized::Drop), ["hello"]);
+
+        // splits on character boundaries
+        assert_eq!(split("aé€b", 3, Oversized::Split), ["aé", "€", "b"]);
+        assert_eq!(split("€€", 2, Oversized::Split), ["€", "€"]);
+        assert_eq!(split("a€", 3, Oversized::Truncate), ["a"]);
+    }
+
+    #[test]
+    fn udp() {
+        let receiver = UdpSocket::bind("127.0.0.1:0").unwrap();
+        let mut flusher = UdpFlusher::new(receiver.local_addr().unwrap())
+            .unwrap()
+            .with_max_datagram_size(8);
+        flusher.try_flush_one("short\n".to_string()).unwrap();
+        flusher
+            .try_flush_one("a longer line\n".to_string())
+            .unwrap();
+
+        let mut buf = [0; 64];
+        let mut recv = || {
+            let n = receiver.recv(&mut buf).unwrap();
+            String::from_utf8(buf[..n].to_vec()).unwrap()
+        };
+        assert_eq!(recv(), "short");
+        assert_eq!(recv(), "a longer");
+        assert_eq!(recv(), " line");
+        assert_eq!(flusher.dropped(), 0);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_datagram() {
+        let dir = TestDir::new("datagram");
+        let path = dir.0.join("datagram.sock");
+        let receiver = UnixDatagram::bind(&path).unwrap();
+
+        let mut flusher = UnixDatagramFlusher::new(&path)
+            .unwrap()
+            .with_oversized(Oversized::Drop)
+            .with_max_datagram_size(16);
+        flusher.try_flush_one("first\n".to_string()).unwrap();
+        flusher
+            .try_flush_one("far too long to be sent\n".to_string())
+            .unwrap();
+        flusher.try_flush_one("second\n".to_string()).unwrap();
+
+        let mut buf = [0; 64];
+        let n = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"first");
+        let n = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"second");
+
+        // nothing is listening anymore, so lines are dropped
+        drop(receiver);
+        assert!(flusher.try_flush_one("lost\n".to_string()).is_err());
+        assert_eq!(flusher.dropped(), 1);
+    }
+}
@@ -3,7 +3,7 @@ use std::{
     io::{LineWriter, Write},
 };
 
-use crate::Flush;
+use crate::{Flush, Level};
 
 /// Flushes into a file
 pub struct FileFlusher(&'static str);
@@ -29,4 +29,13 @@ impl Flush for FileFlusher {
             Err(_) => panic!("Unable to open file"),
         }
     }
+
+    fn try_flush_one(&mut self, display: String) -> std::io::Result<()> {
+        self.try_flush_str(Level::Info, &display)
+    }
+
+    fn try_flush_str(&mut self, _level: Level, display: &str) -> std::io::Result<()> {
+        let file = OpenOptions::new().create(true).append(true).open(self.0)?;
+        LineWriter::new(file).write_all(display.as_bytes())
+    }
 }
@@ -0,0 +1,200 @@
+use std::{
+    io,
+    os::unix::net::UnixDatagram,
+    path::{Path, PathBuf},
+};
+
+use crate::{Flush, Level};
+
+/// Socket journald listens on for entries in its native protocol
+pub const JOURNALD_SOCKET: &str = "/run/systemd/journal/socket";
+
+/// Syslog priority of a level, written as the `PRIORITY` field of entries
+pub fn priority(level: Level) -> u8 {
+    match level {
+        Level::Error => 3,
+        Level::Warn => 4,
+        Level::Info => 6,
+        Level::Debug | Level::Trace => 7,
+    }
+}
+
+/// Sends each record to journald through its native protocol, for services
+/// deployed under systemd. Sending never blocks: entries which cannot be sent
+/// immediately, e.g. because they exceed the maximum datagram size of the
+/// socket, are dropped, see [`JournaldFlusher::dropped`].
+///
+/// Lines formatted by `quicklog::journald::JournaldFormatter` are entries
+/// with one `KEY=value` field per line, starting with `PRIORITY`, in which
+/// newlines and backslashes within values are escaped as `\n` and `\\`. Any
+/// other line is sent as the `MESSAGE` of an entry with the `PRIORITY` of its
+/// level.
+///
+/// ```rust,no_run
+/// use quicklog_flush::journald_flusher::JournaldFlusher;
+///
+/// let flusher = JournaldFlusher::new()?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct JournaldFlusher {
+    socket: UnixDatagram,
+    path: PathBuf,
+    /// Entry being sent, reused for every record
+    payload: Vec<u8>,
+    dropped: u64,
+}
+
+impl JournaldFlusher {
+    /// Sends entries to journald, on [`JOURNALD_SOCKET`]
+    pub fn new() -> io::Result<JournaldFlusher> {
+        Self::with_path(JOURNALD_SOCKET)
+    }
+
+    /// Sends entries to the socket bound at `path` instead of the socket of
+    /// journald
+    pub fn with_path<P: AsRef<Path>>(path: P) -> io::Result<JournaldFlusher> {
+        let socket = UnixDatagram::unbound()?;
+        socket.set_nonblocking(true)?;
+
+        Ok(JournaldFlusher {
+            socket,
+            path: path.as_ref().to_path_buf(),
+            payload: Vec::new(),
+            dropped: 0,
+        })
+    }
+
+    /// Number of entries which failed to be sent
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
+
+impl Flush for JournaldFlusher {
+    fn flush_one(&mut self, display: String) {
+        let _ = self.try_flush_one(display);
+    }
+
+    fn try_flush_one(&mut self, display: String) -> io::Result<()> {
+        self.try_flush_str(Level::Info, &display)
+    }
+
+    fn try_flush_record(&mut self, level: Level, display: String) -> io::Result<()> {
+        self.try_flush_str(level, &display)
+    }
+
+    fn try_flush_str(&mut self, level: Level, display: &str) -> io::Result<()> {
+        self.payload.clear();
+        encode_entry(&mut self.payload, level, display);
+        let result = self.socket.send_to(&self.payload, &self.path).map(|_| ());
+        if result.is_err() {
+            self.dropped += 1;
+        }
+        result
+    }
+}
+
+/// Encodes a line into an entry of the native protocol
+fn encode_entry(payload: &mut Vec<u8>, level: Level, display: &str) {
+    let display = display.strip_suffix('\n').unwrap_or(display);
+    if !display.starts_with("PRIORITY=") {
+        encode_field(payload, "PRIORITY", &priority(level).to_string());
+        encode_field(payload, "MESSAGE", display);
+        return;
+    }
+    for field in display.split('\n') {
+        let Some((key, value)) = field.split_once('=') else {
+            continue;
+        };
+        if value.contains('\\') {
+            encode_field(payload, key, &unescape(value));
+        } else {
+            encode_field(payload, key, value);
+        }
+    }
+}
+
+/// Writes `KEY=value\n`, or the binary form of the field for values spanning
+/// several lines: the key, a newline, the length of the value as a little
+/// endian `u64`, the value and a newline
+fn encode_field(payload: &mut Vec<u8>, key: &str, value: &str) {
+    payload.extend_from_slice(key.as_bytes());
+    if value.contains('\n') {
+        payload.push(b'\n');
+        payload.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        payload.push(b'=');
+    }
+    payload.extend_from_slice(value.as_bytes());
+    payload.push(b'\n');
+}
+
+/// Reverts the escaping of `\n` and `\\` within a value
+fn unescape(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                chars.next();
+                unescaped.push('\n');
+            }
+            ('\\', Some('\\')) => {
+                chars.next();
+                unescaped.push('\\');
+            }
+            (c, _) => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestDir;
+
+    fn entry(level: Level, display: &str) -> Vec<u8> {
+        let mut payload = Vec::new();
+        encode_entry(&mut payload, level, display);
+        payload
+    }
+
+    #[test]
+    fn encodes_entries() {
+        assert_eq!(
+            entry(Level::Warn, "[ts]order rejected\n"),
+            b"PRIORITY=4\nMESSAGE=[ts]order rejected\n"
+        );
+        assert_eq!(
+            entry(
+                Level::Error,
+                "PRIORITY=3\nMESSAGE=a\\\\b\nCODE_LINE=12\nTRACE=at main\\nat run\n"
+            ),
+            b"PRIORITY=3\nMESSAGE=a\\b\nCODE_LINE=12\nTRACE\n\x0e\0\0\0\0\0\0\0at main\nat run\n"
+        );
+        // multi-line lines of other formatters
+        assert_eq!(
+            entry(Level::Debug, "two\nlines\n"),
+            b"PRIORITY=7\nMESSAGE\n\x09\0\0\0\0\0\0\0two\nlines\n"
+        );
+    }
+
+    #[test]
+    fn sends_to_socket() {
+        let dir = TestDir::new("journald");
+        let path = dir.0.join("journal.sock");
+        let receiver = UnixDatagram::bind(&path).unwrap();
+
+        let mut flusher = JournaldFlusher::with_path(&path).unwrap();
+        flusher.try_flush_str(Level::Info, "hello\n").unwrap();
+        let mut buf = [0; 64];
+        let n = receiver.recv(&mut buf).unwrap();
+        assert_eq!(&buf[..n], b"PRIORITY=6\nMESSAGE=hello\n");
+
+        // nothing is listening anymore, so entries are dropped
+        drop(receiver);
+        assert!(flusher.try_flush_str(Level::Info, "lost\n").is_err());
+        assert_eq!(flusher.dropped(), 1);
+    }
+}
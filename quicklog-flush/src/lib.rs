@@ -26,12 +26,88 @@
 //! }
 //! ```
 
+use std::{future::Future, pin::Pin};
+
+/// Flushes to a file through tokio's async file I/O
+#[cfg(feature = "tokio")]
+pub mod async_file_flusher;
+/// Streams to a remote collector over TCP through tokio's async I/O
+#[cfg(feature = "tokio")]
+pub mod async_tcp_flusher;
+/// Flushes to a file through a buffer, written out by a configurable policy
+pub mod buffered_file_flusher;
+/// Flushes to stdout, coloring the level of each record
+pub mod colored_stdout_flusher;
+/// Sends each record as a UDP or unix datagram
+pub mod datagram_flusher;
+/// Flushes to a file compressed with gzip or zstd
+#[cfg(any(feature = "gzip", feature = "zstd"))]
+pub mod compressed_file_flusher;
 /// Flushes to a file
 pub mod file_flusher;
+/// Sends records to journald through its native protocol
+#[cfg(all(target_os = "linux", feature = "journald"))]
+pub mod journald_flusher;
+/// Keeps the most recent records in a memory-mapped ring file
+#[cfg(feature = "mmap")]
+pub mod mmap_ring_flusher;
+/// Flushes into several flushers at once, optionally routed by level
+pub mod multi_flusher;
 /// No-op Flush, does nothing
 pub mod noop_flusher;
+/// Flushes to a file which is rotated by size, time or a custom schedule
+pub mod rotating_flusher;
+/// Flushes a deterministic sample of records
+pub mod sampling_flusher;
 /// Flushes to stdout through `print!` macro
 pub mod stdout_flusher;
+/// Backlog and reconnection backoff shared by the sync and async TCP flushers
+mod tcp_backlog;
+/// Streams to a remote collector over TCP
+pub mod tcp_flusher;
+/// Temporary files and directories removed after each test
+#[cfg(test)]
+mod test_utils;
+/// Writes to the Windows Event Log or to the debugger output
+#[cfg(all(windows, feature = "windows"))]
+pub mod windows_flusher;
+
+/// Level a record was logged at, re-exported as `quicklog::level::Level`.
+/// Passed to [`Flush::try_flush_record`] so that flushers can route records
+/// by level.
+#[repr(u8)]
+#[derive(Clone, Copy, Eq, PartialEq, PartialOrd)]
+pub enum Level {
+    /// Designates trace information, which is of very low priority
+    Trace = 0,
+    /// Designates debug information, which is of low priority
+    Debug = 1,
+    /// Designates useful information
+    Info = 2,
+    /// Designates potentially hazardous situations
+    Warn = 3,
+    /// Designates serious errors
+    Error = 4,
+}
+
+impl std::fmt::Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let level = match self {
+            Self::Trace => "TRACE",
+            Self::Debug => "DEBUG",
+            Self::Info => "INFO",
+            Self::Warn => "WARN",
+            Self::Error => "ERROR",
+        };
+        write!(f, "{}", level)
+    }
+}
+
+impl std::fmt::Debug for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
+}
 
 /// Simple trait that allows an underlying implementation of Flush to
 /// perform some type of IO operation, i.e. writing to file, writing to
@@ -40,4 +116,55 @@ pub trait Flush {
     /// Handles a string from another thread, and potentially performs I/O
     /// operations such as writing to a file or to stdout
     fn flush_one(&mut self, display: String);
+
+    /// Fallible version of [`Flush::flush_one`], returning any I/O error
+    /// encountered instead of panicking. Defaults to calling `flush_one`.
+    fn try_flush_one(&mut self, display: String) -> std::io::Result<()> {
+        self.flush_one(display);
+        Ok(())
+    }
+
+    /// Flushes a record logged at `level`, which is how the logger hands
+    /// records over. Defaults to calling `try_flush_one`, ignoring the level.
+    fn try_flush_record(&mut self, level: Level, display: String) -> std::io::Result<()> {
+        let _ = level;
+        self.try_flush_one(display)
+    }
+
+    /// Flushes a record logged at `level` from a buffer the logger reuses
+    /// for every record, so that flushers which only write the line out need
+    /// not allocate. Defaults to copying the line into `try_flush_record`.
+    fn try_flush_str(&mut self, level: Level, display: &str) -> std::io::Result<()> {
+        self.try_flush_record(level, display.to_string())
+    }
+
+    /// Writes out anything the flusher buffered rather than writing right
+    /// away, e.g. before the application exits. Defaults to doing nothing.
+    fn flush_buffered(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Future returned by the methods of [`AsyncFlush`]
+pub type FlushFuture<'a> = Pin<Box<dyn Future<Output = std::io::Result<()>> + Send + 'a>>;
+
+/// Asynchronous counterpart of [`Flush`], for flushers performing async I/O,
+/// e.g. within a tokio task. The returned futures are boxed so that the
+/// logger can hold any flusher behind `Box<dyn AsyncFlush>`.
+pub trait AsyncFlush: Send {
+    /// Flushes a record logged at `level`
+    fn flush_record(&mut self, level: Level, display: String) -> FlushFuture<'_>;
+
+    /// Flushes a record logged at `level` from a buffer the logger reuses
+    /// for every record, so that flushers which only write the line out need
+    /// not allocate. Defaults to copying the line into `flush_record`.
+    fn flush_str<'a>(&'a mut self, level: Level, display: &'a str) -> FlushFuture<'a> {
+        self.flush_record(level, display.to_string())
+    }
+
+    /// Writes out anything the flusher buffered rather than writing right
+    /// away. Defaults to doing nothing.
+    fn flush_buffered(&mut self) -> FlushFuture<'_> {
+        Box::pin(std::future::ready(Ok(())))
+    }
 }
@@ -0,0 +1,206 @@
+use std::{
+    fs::OpenOptions,
+    io,
+    path::{Path, PathBuf},
+};
+
+use memmap2::MmapMut;
+
+use crate::Flush;
+
+/// Identifies ring files, followed by the version of their layout
+const MAGIC: &[u8; 8] = b"QLRING01";
+/// Bytes before the ring: magic, capacity and total bytes written
+const HEADER_SIZE: usize = 24;
+
+/// Keeps the most recent log lines in a memory-mapped file, for recovering
+/// what happened right before a crash.
+///
+/// Lines are copied into a fixed-size ring in the mapping, overwriting the
+/// oldest lines once full. As the pages belong to the OS page cache, they
+/// are written back to the file even if the process is killed or panics
+/// without flushing. Call [`MmapRingFlusher::sync`] to also survive the host
+/// going down.
+///
+/// The file is laid out as:
+///
+/// ```text
+/// [magic: b"QLRING01"][capacity: u64][written: u64][ring: capacity bytes]
+/// ```
+///
+/// where `written` is the total number of bytes ever written, updated after
+/// every line, so that [`read_ring`] only returns complete lines.
+///
+/// ```rust,no_run
+/// use quicklog_flush::mmap_ring_flusher::{read_ring, MmapRingFlusher};
+///
+/// // keep the last 64 MiB
+/// let flusher = MmapRingFlusher::new("logs/crash.ring", 64 * 1024 * 1024)?;
+///
+/// // after a crash
+/// let recent = read_ring("logs/crash.ring")?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct MmapRingFlusher {
+    path: PathBuf,
+    mmap: MmapMut,
+    capacity: usize,
+    written: u64,
+}
+
+impl MmapRingFlusher {
+    /// Maps a ring of `capacity` bytes into the file at `path`, which is
+    /// created or truncated. Ensure that its directory exists, and that the
+    /// ring of a previous run has been read or moved aside.
+    ///
+    /// On Windows, mapped files cannot be truncated, so this fails while
+    /// another flusher still maps the same file.
+    pub fn new(path: impl Into<PathBuf>, capacity: usize) -> io::Result<MmapRingFlusher> {
+        if capacity == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "ring capacity must not be zero",
+            ));
+        }
+
+        let path = path.into();
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(&path)?;
+        file.set_len((HEADER_SIZE + capacity) as u64)?;
+        // Safety: the file was just created for this flusher, and is only
+        // modified through this mapping
+        let mut mmap = unsafe { MmapMut::map_mut(&file)? };
+        mmap[..8].copy_from_slice(MAGIC);
+        mmap[8..16].copy_from_slice(&(capacity as u64).to_le_bytes());
+        mmap[16..HEADER_SIZE].copy_from_slice(&0_u64.to_le_bytes());
+
+        Ok(MmapRingFlusher {
+            path,
+            mmap,
+            capacity,
+            written: 0,
+        })
+    }
+
+    /// Path of the ring file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Size of the ring in bytes
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Waits for the ring to be written back to the file
+    pub fn sync(&self) -> io::Result<()> {
+        self.mmap.flush()
+    }
+
+    fn write(&mut self, mut bytes: &[u8]) {
+        // only the end of lines longer than the ring fits
+        if bytes.len() > self.capacity {
+            bytes = &bytes[bytes.len() - self.capacity..];
+        }
+
+        let start = (self.written % self.capacity as u64) as usize;
+        let (head, tail) = bytes.split_at(bytes.len().min(self.capacity - start));
+        let ring = &mut self.mmap[HEADER_SIZE..];
+        ring[start..start + head.len()].copy_from_slice(head);
+        ring[..tail.len()].copy_from_slice(tail);
+
+        self.written += bytes.len() as u64;
+        self.mmap[16..HEADER_SIZE].copy_from_slice(&self.written.to_le_bytes());
+    }
+}
+
+impl Flush for MmapRingFlusher {
+    fn flush_one(&mut self, display: String) {
+        self.write(display.as_bytes());
+    }
+}
+
+/// Reads the lines kept in the ring file at `path`, oldest first. Once the
+/// ring has wrapped around, the oldest line is left out, as it may have been
+/// partially overwritten.
+pub fn read_ring(path: impl AsRef<Path>) -> io::Result<Vec<u8>> {
+    let bytes = std::fs::read(path)?;
+    let invalid = |msg| io::Error::new(io::ErrorKind::InvalidData, msg);
+    if bytes.len() < HEADER_SIZE || &bytes[..8] != MAGIC {
+        return Err(invalid("not a quicklog ring file"));
+    }
+
+    let capacity = u64::from_le_bytes(bytes[8..16].try_into().unwrap()) as usize;
+    let written = u64::from_le_bytes(bytes[16..HEADER_SIZE].try_into().unwrap());
+    let ring = &bytes[HEADER_SIZE..];
+    if capacity == 0 || ring.len() != capacity {
+        return Err(invalid("ring file is truncated"));
+    }
+
+    if written <= capacity as u64 {
+        return Ok(ring[..written as usize].to_vec());
+    }
+
+    let start = (written % capacity as u64) as usize;
+    let mut lines = Vec::with_capacity(capacity);
+    lines.extend_from_slice(&ring[start..]);
+    lines.extend_from_slice(&ring[..start]);
+    match lines.iter().position(|&b| b == b'\n') {
+        Some(end) => Ok(lines.split_off(end + 1)),
+        None => Ok(lines),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestFile;
+
+    #[test]
+    fn keeps_recent_lines() {
+        let file = TestFile::new("ring-recent");
+        let mut flusher = MmapRingFlusher::new(&file.0, 16).unwrap();
+
+        flusher.flush_one("first\n".to_string());
+        flusher.flush_one("second\n".to_string());
+        assert_eq!(read_ring(&file.0).unwrap(), b"first\nsecond\n");
+
+        // wraps around, partially overwriting "second"
+        flusher.flush_one("third\n".to_string());
+        flusher.flush_one("fourth\n".to_string());
+        assert_eq!(read_ring(&file.0).unwrap(), b"third\nfourth\n");
+    }
+
+    #[test]
+    fn survives_without_drop() {
+        let file = TestFile::new("ring-forget");
+        let mut flusher = MmapRingFlusher::new(&file.0, 64).unwrap();
+        flusher.flush_one("before crash\n".to_string());
+        // nothing is written on drop, so the line must already be in the file
+        std::mem::forget(flusher);
+
+        assert_eq!(read_ring(&file.0).unwrap(), b"before crash\n");
+    }
+
+    #[test]
+    fn keeps_end_of_long_lines() {
+        let file = TestFile::new("ring-long");
+        let mut flusher = MmapRingFlusher::new(&file.0, 8).unwrap();
+        flusher.flush_one("0123456789\n".to_string());
+
+        assert_eq!(read_ring(&file.0).unwrap(), b"3456789\n");
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        let file = TestFile::new("ring-other");
+        std::fs::write(&file.0, b"not a ring file at all").unwrap();
+
+        let err = read_ring(&file.0).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
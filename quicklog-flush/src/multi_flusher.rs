@@ -0,0 +1,177 @@
+use crate::{Flush, Level};
+
+/// Flushes every record into each of a list of flushers, in the order they
+/// were added. Flushers added with [`MultiFlusher::with_min_level`] only
+/// receive records logged at or above their level.
+///
+/// Records are formatted once by the logger, and the formatted line is only
+/// copied for every additional flusher it is routed to.
+///
+/// ```rust,no_run
+/// use quicklog_flush::{
+///     file_flusher::FileFlusher, multi_flusher::MultiFlusher, stdout_flusher::StdoutFlusher,
+///     Level,
+/// };
+///
+/// // everything to a file, WARN and ERROR to stdout as well
+/// let flusher = MultiFlusher::new()
+///     .with(FileFlusher::new("logs/quicklog.log"))
+///     .with_min_level(Level::Warn, StdoutFlusher::new());
+/// ```
+pub struct MultiFlusher(Vec<(Option<Level>, Box<dyn Flush>)>);
+
+impl MultiFlusher {
+    pub fn new() -> MultiFlusher {
+        MultiFlusher(Vec::new())
+    }
+
+    /// Adds a flusher which every record is also flushed into
+    pub fn with<F: Flush + 'static>(mut self, flusher: F) -> MultiFlusher {
+        self.0.push((None, Box::new(flusher)));
+        self
+    }
+
+    /// Adds a flusher which records logged at `level` or above are also
+    /// flushed into
+    pub fn with_min_level<F: Flush + 'static>(mut self, level: Level, flusher: F) -> MultiFlusher {
+        self.0.push((Some(level), Box::new(flusher)));
+        self
+    }
+
+    /// Flushes into every flusher accepting `level`, or into all of them if
+    /// the level is unknown. Continues even if some of them fail, returning
+    /// the first error encountered.
+    fn route(&mut self, level: Option<Level>, display: String) -> std::io::Result<()> {
+        let accepts = |min: &Option<Level>| match (min, level) {
+            (Some(min), Some(level)) => level >= *min,
+            _ => true,
+        };
+        let Some(last) = self.0.iter().rposition(|(min, _)| accepts(min)) else {
+            return Ok(());
+        };
+
+        let mut result = Ok(());
+        let (rest, last) = self.0.split_at_mut(last);
+        for (_, flusher) in rest.iter_mut().filter(|(min, _)| accepts(min)) {
+            let flushed = match level {
+                Some(level) => flusher.try_flush_record(level, display.clone()),
+                None => flusher.try_flush_one(display.clone()),
+            };
+            result = result.and(flushed);
+        }
+        let last = &mut last[0].1;
+        let flushed = match level {
+            Some(level) => last.try_flush_record(level, display),
+            None => last.try_flush_one(display),
+        };
+        result.and(flushed)
+    }
+}
+
+impl Default for MultiFlusher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Flush for MultiFlusher {
+    fn flush_one(&mut self, display: String) {
+        if let Some(((_, last), rest)) = self.0.split_last_mut() {
+            for (_, flusher) in rest {
+                flusher.flush_one(display.clone());
+            }
+            last.flush_one(display);
+        }
+    }
+
+    /// Flushes into every flusher even if some of them fail, returning the
+    /// first error encountered. Without a level, records are flushed into
+    /// every flusher regardless of their minimum level.
+    fn try_flush_one(&mut self, display: String) -> std::io::Result<()> {
+        self.route(None, display)
+    }
+
+    fn try_flush_record(&mut self, level: Level, display: String) -> std::io::Result<()> {
+        self.route(Some(level), display)
+    }
+
+    /// Lends the line to every flusher accepting `level` rather than copying
+    /// it, continuing even if some of them fail
+    fn try_flush_str(&mut self, level: Level, display: &str) -> std::io::Result<()> {
+        let mut result = Ok(());
+        for (min, flusher) in &mut self.0 {
+            if min.is_some_and(|min| level < min) {
+                continue;
+            }
+            result = result.and(flusher.try_flush_str(level, display));
+        }
+        result
+    }
+
+    fn flush_buffered(&mut self) -> std::io::Result<()> {
+        let mut result = Ok(());
+        for (_, flusher) in &mut self.0 {
+            result = result.and(flusher.flush_buffered());
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+
+    struct VecFlusher(Rc<RefCell<Vec<String>>>);
+
+    impl Flush for VecFlusher {
+        fn flush_one(&mut self, display: String) {
+            self.0.borrow_mut().push(display);
+        }
+    }
+
+    #[test]
+    fn routes_by_level() {
+        let all = Rc::new(RefCell::new(Vec::new()));
+        let warn = Rc::new(RefCell::new(Vec::new()));
+        let error = Rc::new(RefCell::new(Vec::new()));
+        let mut flusher = MultiFlusher::new()
+            .with_min_level(Level::Error, VecFlusher(error.clone()))
+            .with(VecFlusher(all.clone()))
+            .with_min_level(Level::Warn, VecFlusher(warn.clone()));
+
+        for (level, line) in [
+            (Level::Debug, "debug"),
+            (Level::Warn, "warn"),
+            (Level::Error, "error"),
+        ] {
+            flusher.try_flush_record(level, line.to_string()).unwrap();
+        }
+        // unknown level
+        flusher.try_flush_one("plain".to_string()).unwrap();
+
+        assert_eq!(*all.borrow(), ["debug", "warn", "error", "plain"]);
+        assert_eq!(*warn.borrow(), ["warn", "error", "plain"]);
+        assert_eq!(*error.borrow(), ["error", "plain"]);
+    }
+
+    #[test]
+    fn routes_lent_lines_by_level() {
+        let all = Rc::new(RefCell::new(Vec::new()));
+        let error = Rc::new(RefCell::new(Vec::new()));
+        let mut flusher = MultiFlusher::new()
+            .with(VecFlusher(all.clone()))
+            .with_min_level(Level::Error, VecFlusher(error.clone()));
+
+        let mut line = String::new();
+        for (level, text) in [(Level::Info, "info"), (Level::Error, "error")] {
+            line.clear();
+            line.push_str(text);
+            flusher.try_flush_str(level, &line).unwrap();
+        }
+
+        assert_eq!(*all.borrow(), ["info", "error"]);
+        assert_eq!(*error.borrow(), ["error"]);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::Flush;
+use crate::{Flush, Level};
 
 pub struct NoopFlusher;
 
@@ -16,4 +16,8 @@ impl Default for NoopFlusher {
 
 impl Flush for NoopFlusher {
     fn flush_one(&mut self, _display: String) {}
+
+    fn try_flush_str(&mut self, _level: Level, _display: &str) -> std::io::Result<()> {
+        Ok(())
+    }
 }
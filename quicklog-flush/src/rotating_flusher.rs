@@ -0,0 +1,425 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::Flush;
+
+/// State of the current file, passed to a [`RotationTrigger`] before every
+/// line is written
+#[derive(Clone, Copy, Debug)]
+pub struct RotationContext {
+    /// Current time
+    pub now: SystemTime,
+    /// When the current file was started, or last written to if it already
+    /// existed when the flusher opened it
+    pub opened_at: SystemTime,
+    /// Number of bytes in the current file
+    pub bytes_written: u64,
+    /// Number of bytes of the line about to be written
+    pub pending: usize,
+}
+
+/// Decides when [`RotatingFileFlusher`] starts a new file.
+///
+/// Implemented for closures taking a [`RotationContext`], so that custom
+/// schedules can be plugged in directly:
+///
+/// ```rust
+/// use quicklog_flush::rotating_flusher::{RotatingFileFlusher, RotationContext};
+///
+/// // a new file for every 100k lines
+/// let mut lines = 0;
+/// let flusher = RotatingFileFlusher::new("logs/quicklog.log").with_trigger(
+///     move |_: &RotationContext| {
+///         lines += 1;
+///         lines % 100_000 == 0
+///     },
+/// );
+/// ```
+pub trait RotationTrigger {
+    /// Whether the current file should be rotated before writing the next line
+    fn should_rotate(&mut self, ctx: &RotationContext) -> bool;
+}
+
+impl<F: FnMut(&RotationContext) -> bool> RotationTrigger for F {
+    fn should_rotate(&mut self, ctx: &RotationContext) -> bool {
+        self(ctx)
+    }
+}
+
+/// Rotates once the next line would grow the file beyond a number of bytes
+pub struct SizeTrigger(u64);
+
+impl SizeTrigger {
+    /// Rotates files before they grow beyond `max_bytes`. A single line longer
+    /// than `max_bytes` is still written, into a file of its own.
+    pub fn new(max_bytes: u64) -> SizeTrigger {
+        SizeTrigger(max_bytes)
+    }
+}
+
+impl RotationTrigger for SizeTrigger {
+    fn should_rotate(&mut self, ctx: &RotationContext) -> bool {
+        ctx.bytes_written > 0 && ctx.bytes_written + ctx.pending as u64 > self.0
+    }
+}
+
+/// Rotates at fixed intervals since the unix epoch, shifted by an offset,
+/// e.g. daily at 22:00 UTC
+pub struct IntervalTrigger {
+    interval: Duration,
+    offset: Duration,
+}
+
+impl IntervalTrigger {
+    /// Rotates whenever a multiple of `interval` since the unix epoch is
+    /// crossed, e.g. every hour on the hour
+    pub fn new(interval: Duration) -> IntervalTrigger {
+        IntervalTrigger {
+            interval,
+            offset: Duration::ZERO,
+        }
+    }
+
+    /// Shifts the boundaries by `offset`, e.g. an interval of a day with an
+    /// offset of 22 hours rotates daily at 22:00 UTC
+    pub fn with_offset(mut self, offset: Duration) -> IntervalTrigger {
+        self.offset = offset;
+        self
+    }
+
+    /// Index of the interval `time` falls into
+    fn period(&self, time: SystemTime) -> u128 {
+        let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        let shifted = since_epoch.as_nanos() + self.interval.as_nanos()
+            - self.offset.as_nanos() % self.interval.as_nanos().max(1);
+        shifted / self.interval.as_nanos().max(1)
+    }
+}
+
+impl RotationTrigger for IntervalTrigger {
+    fn should_rotate(&mut self, ctx: &RotationContext) -> bool {
+        self.period(ctx.now) > self.period(ctx.opened_at)
+    }
+}
+
+/// Rotates at boundaries computed by a user supplied schedule, such as the
+/// open of an exchange session in its local time zone.
+///
+/// The schedule returns the first boundary strictly after the time it is
+/// given.
+///
+/// ```rust
+/// use std::time::{Duration, SystemTime, UNIX_EPOCH};
+/// use quicklog_flush::rotating_flusher::{RotatingFileFlusher, ScheduleTrigger};
+///
+/// // sessions open at 17:00 Chicago, i.e. 22:00 UTC ignoring daylight saving.
+/// // A time zone aware library such as `chrono-tz` can be used instead.
+/// fn next_session_open(after: SystemTime) -> SystemTime {
+///     const DAY: u64 = 24 * 60 * 60;
+///     const OPEN: u64 = 22 * 60 * 60;
+///     let secs = after.duration_since(UNIX_EPOCH).unwrap().as_secs();
+///     let today = secs - secs % DAY + OPEN;
+///     let next = if today > secs { today } else { today + DAY };
+///     UNIX_EPOCH + Duration::from_secs(next)
+/// }
+///
+/// let flusher = RotatingFileFlusher::new("logs/quicklog.log")
+///     .with_trigger(ScheduleTrigger::new(next_session_open));
+/// ```
+pub struct ScheduleTrigger<F: FnMut(SystemTime) -> SystemTime> {
+    next_boundary: F,
+    next: Option<SystemTime>,
+}
+
+impl<F: FnMut(SystemTime) -> SystemTime> ScheduleTrigger<F> {
+    /// Rotates at every boundary returned by `next_boundary`
+    pub fn new(next_boundary: F) -> ScheduleTrigger<F> {
+        ScheduleTrigger {
+            next_boundary,
+            next: None,
+        }
+    }
+}
+
+impl<F: FnMut(SystemTime) -> SystemTime> RotationTrigger for ScheduleTrigger<F> {
+    fn should_rotate(&mut self, ctx: &RotationContext) -> bool {
+        let next = *self
+            .next
+            .get_or_insert_with(|| (self.next_boundary)(ctx.opened_at));
+        if ctx.now < next {
+            return false;
+        }
+        self.next = Some((self.next_boundary)(ctx.now));
+        true
+    }
+}
+
+/// Flushes into a file, which is renamed and replaced by a new file whenever
+/// one of its [`RotationTrigger`]s fires.
+///
+/// Rotated files keep the path of the active file, with the unix time of the
+/// rotation appended, e.g. `logs/quicklog.log.1700000000`.
+///
+/// ```rust
+/// use std::time::Duration;
+/// use quicklog_flush::rotating_flusher::{IntervalTrigger, RotatingFileFlusher, SizeTrigger};
+///
+/// let flusher = RotatingFileFlusher::new("logs/quicklog.log")
+///     .with_trigger(SizeTrigger::new(512 * 1024 * 1024))
+///     .with_trigger(IntervalTrigger::new(Duration::from_secs(60 * 60)));
+/// ```
+pub struct RotatingFileFlusher {
+    path: PathBuf,
+    file: Option<File>,
+    opened_at: SystemTime,
+    bytes_written: u64,
+    triggers: Vec<Box<dyn RotationTrigger>>,
+    now: Box<dyn FnMut() -> SystemTime>,
+}
+
+impl RotatingFileFlusher {
+    /// Flushes into the file at `path`, which is created if needed. Ensure
+    /// that its directory exists.
+    pub fn new(path: impl Into<PathBuf>) -> RotatingFileFlusher {
+        RotatingFileFlusher {
+            path: path.into(),
+            file: None,
+            opened_at: UNIX_EPOCH,
+            bytes_written: 0,
+            triggers: Vec::new(),
+            now: Box::new(SystemTime::now),
+        }
+    }
+
+    /// Adds a trigger, the file is rotated as soon as any trigger fires
+    pub fn with_trigger<T: RotationTrigger + 'static>(mut self, trigger: T) -> RotatingFileFlusher {
+        self.triggers.push(Box::new(trigger));
+        self
+    }
+
+    /// Sets the source of the current time, `SystemTime::now` by default
+    pub fn with_time_source<F: FnMut() -> SystemTime + 'static>(
+        mut self,
+        now: F,
+    ) -> RotatingFileFlusher {
+        self.now = Box::new(now);
+        self
+    }
+
+    /// Path of the file currently written to
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn open(&mut self, now: SystemTime) -> io::Result<()> {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let metadata = file.metadata()?;
+        self.bytes_written = metadata.len();
+        // an existing file belongs to when it was last written to
+        self.opened_at = match metadata.modified() {
+            Ok(modified) if self.bytes_written > 0 => modified,
+            _ => now,
+        };
+        self.file = Some(file);
+        Ok(())
+    }
+
+    /// Moves the current file aside and starts a new one
+    fn rotate(&mut self, now: SystemTime) -> io::Result<()> {
+        self.file = None;
+        let secs = now.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let mut rotated = self.path.as_os_str().to_owned();
+        rotated.push(format!(".{}", secs));
+        let mut candidate = PathBuf::from(&rotated);
+        let mut n = 1;
+        while candidate.exists() {
+            candidate = PathBuf::from(&rotated);
+            candidate.as_mut_os_string().push(format!(".{}", n));
+            n += 1;
+        }
+        move_aside(&self.path, &candidate)?;
+        self.open(now)?;
+        self.opened_at = now;
+        Ok(())
+    }
+}
+
+/// Renames the active file to `rotated`. Files which are open in another
+/// process, e.g. a log viewer, cannot be renamed on Windows, so they are
+/// copied and truncated instead.
+fn move_aside(path: &Path, rotated: &Path) -> io::Result<()> {
+    match fs::rename(path, rotated) {
+        #[cfg(windows)]
+        Err(err) if err.kind() == io::ErrorKind::PermissionDenied => {
+            fs::copy(path, rotated)?;
+            OpenOptions::new()
+                .write(true)
+                .truncate(true)
+                .open(path)
+                .map(drop)
+        }
+        result => result,
+    }
+}
+
+impl Flush for RotatingFileFlusher {
+    fn flush_one(&mut self, display: String) {
+        if self.try_flush_one(display).is_err() {
+            panic!("Unable to write to file")
+        }
+    }
+
+    fn try_flush_one(&mut self, display: String) -> io::Result<()> {
+        let now = (self.now)();
+        if self.file.is_none() {
+            self.open(now)?;
+        }
+
+        let ctx = RotationContext {
+            now,
+            opened_at: self.opened_at,
+            bytes_written: self.bytes_written,
+            pending: display.len(),
+        };
+        // every trigger sees every line, so that stateful triggers stay in sync
+        let rotate = self.triggers.iter_mut().fold(false, |rotate, trigger| {
+            trigger.should_rotate(&ctx) | rotate
+        });
+        if rotate && self.bytes_written > 0 {
+            self.rotate(now)?;
+        }
+
+        let file = self.file.as_mut().expect("file is opened above");
+        file.write_all(display.as_bytes())?;
+        self.bytes_written += display.len() as u64;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, rc::Rc};
+
+    use super::*;
+    use crate::test_utils::TestDir;
+
+    const HOUR: u64 = 60 * 60;
+
+    /// Time source which can be moved forward by the test
+    fn clock(start_secs: u64) -> (Rc<Cell<u64>>, impl FnMut() -> SystemTime) {
+        let secs = Rc::new(Cell::new(start_secs));
+        let source = secs.clone();
+        (secs, move || UNIX_EPOCH + Duration::from_secs(source.get()))
+    }
+
+    #[test]
+    fn rotates_by_size() {
+        let dir = TestDir::new("rotating-size");
+        let (_, now) = clock(1_000);
+        let mut flusher = RotatingFileFlusher::new(dir.0.join("q.log"))
+            .with_trigger(SizeTrigger::new(10))
+            .with_time_source(now);
+        for line in ["aaaa\n", "bbbb\n", "cccc\n"] {
+            flusher.try_flush_one(line.to_string()).unwrap();
+        }
+
+        assert_eq!(
+            dir.files(),
+            [
+                ("q.log".to_string(), "cccc\n".to_string()),
+                ("q.log.1000".to_string(), "aaaa\nbbbb\n".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn rotates_by_schedule() {
+        let dir = TestDir::new("rotating-schedule");
+        let (secs, now) = clock(10 * HOUR);
+        // sessions open at 22:00 every day
+        let next_open = |after: SystemTime| {
+            let secs = after.duration_since(UNIX_EPOCH).unwrap().as_secs();
+            let today = secs - secs % (24 * HOUR) + 22 * HOUR;
+            let next = if today > secs {
+                today
+            } else {
+                today + 24 * HOUR
+            };
+            UNIX_EPOCH + Duration::from_secs(next)
+        };
+        let mut flusher = RotatingFileFlusher::new(dir.0.join("q.log"))
+            .with_trigger(ScheduleTrigger::new(next_open))
+            .with_time_source(now);
+
+        flusher.try_flush_one("day 0\n".to_string()).unwrap();
+        secs.set(21 * HOUR);
+        flusher.try_flush_one("day 0 close\n".to_string()).unwrap();
+        secs.set(22 * HOUR);
+        flusher.try_flush_one("day 1 open\n".to_string()).unwrap();
+        secs.set(30 * HOUR);
+        flusher.try_flush_one("day 1\n".to_string()).unwrap();
+
+        assert_eq!(
+            dir.files(),
+            [
+                ("q.log".to_string(), "day 1 open\nday 1\n".to_string()),
+                (
+                    "q.log.79200".to_string(),
+                    "day 0\nday 0 close\n".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn rotates_by_interval_with_offset() {
+        let trigger = IntervalTrigger::new(Duration::from_secs(24 * HOUR))
+            .with_offset(Duration::from_secs(22 * HOUR));
+        let at = |hours: u64| UNIX_EPOCH + Duration::from_secs(hours * HOUR);
+        let should_rotate = |opened: u64, now: u64| {
+            let mut trigger = IntervalTrigger { ..trigger };
+            trigger.should_rotate(&RotationContext {
+                now: at(now),
+                opened_at: at(opened),
+                bytes_written: 1,
+                pending: 1,
+            })
+        };
+
+        assert!(!should_rotate(1, 21));
+        assert!(should_rotate(21, 22));
+        assert!(!should_rotate(22, 45));
+        assert!(should_rotate(22, 46));
+    }
+
+    #[test]
+    fn existing_file_and_name_collisions() {
+        let dir = TestDir::new("rotating-existing");
+        fs::write(dir.0.join("q.log"), "before restart\n").unwrap();
+        fs::write(dir.0.join("q.log.5"), "older\n").unwrap();
+
+        let (_, now) = clock(5);
+        let mut flusher = RotatingFileFlusher::new(dir.0.join("q.log"))
+            .with_trigger(|ctx: &RotationContext| ctx.bytes_written > 0)
+            .with_time_source(now);
+        flusher
+            .try_flush_one("after restart\n".to_string())
+            .unwrap();
+
+        assert_eq!(
+            dir.files(),
+            [
+                ("q.log".to_string(), "after restart\n".to_string()),
+                ("q.log.5".to_string(), "older\n".to_string()),
+                ("q.log.5.1".to_string(), "before restart\n".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{Flush, Level};
+
+/// How [`SamplingFlusher`] decides which records to keep
+#[derive(Clone, Copy)]
+pub enum SampleBy {
+    /// Keeps records evenly spaced by the order in which they are flushed, so
+    /// exactly `ratio` of all records are kept
+    Sequence,
+    /// Keeps records based on a hash of a key extracted from the log line, so
+    /// records sharing a key are either all kept or all dropped. The whole log
+    /// line is hashed when no key is extracted.
+    Key(fn(&str) -> Option<&str>),
+}
+
+/// Forwards only a sampled subset of records to an inner flusher. Sampling is
+/// deterministic, so the same stream of records always produces the same
+/// archive.
+///
+/// Usually combined with [`MultiFlusher`] to keep a full-fidelity sink next to
+/// a sampled archival sink.
+///
+/// ```rust
+/// use quicklog_flush::{
+///     file_flusher::FileFlusher,
+///     multi_flusher::MultiFlusher,
+///     sampling_flusher::{SampleBy, SamplingFlusher},
+/// };
+///
+/// let flusher = MultiFlusher::new()
+///     .with(FileFlusher::new("logs/full.log"))
+///     // archive 1% of records
+///     .with(SamplingFlusher::new(
+///         FileFlusher::new("logs/archive.log"),
+///         0.01,
+///         SampleBy::Sequence,
+///     ));
+/// ```
+///
+/// [`MultiFlusher`]: crate::multi_flusher::MultiFlusher
+pub struct SamplingFlusher<F: Flush> {
+    inner: F,
+    ratio: f64,
+    by: SampleBy,
+    seq: u64,
+}
+
+impl<F: Flush> SamplingFlusher<F> {
+    /// Keeps `ratio` of records, which is clamped to between 0.0 and 1.0
+    pub fn new(inner: F, ratio: f64, by: SampleBy) -> SamplingFlusher<F> {
+        SamplingFlusher {
+            inner,
+            ratio: ratio.clamp(0.0, 1.0),
+            by,
+            seq: 0,
+        }
+    }
+
+    fn should_keep(&mut self, display: &str) -> bool {
+        match self.by {
+            SampleBy::Sequence => {
+                let seq = self.seq;
+                self.seq += 1;
+                // keep a record whenever the running count of records to
+                // keep crosses over to the next integer
+                ((seq + 1) as f64 * self.ratio).floor() > (seq as f64 * self.ratio).floor()
+            }
+            SampleBy::Key(key) => {
+                let hash = hash_key(key(display).unwrap_or(display));
+                (hash as f64) < self.ratio * u64::MAX as f64
+            }
+        }
+    }
+}
+
+impl<F: Flush> Flush for SamplingFlusher<F> {
+    fn flush_one(&mut self, display: String) {
+        if self.should_keep(&display) {
+            self.inner.flush_one(display)
+        }
+    }
+
+    fn try_flush_one(&mut self, display: String) -> std::io::Result<()> {
+        if self.should_keep(&display) {
+            self.inner.try_flush_one(display)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn try_flush_record(&mut self, level: Level, display: String) -> std::io::Result<()> {
+        if self.should_keep(&display) {
+            self.inner.try_flush_record(level, display)
+        } else {
+            Ok(())
+        }
+    }
+
+    fn flush_buffered(&mut self) -> std::io::Result<()> {
+        self.inner.flush_buffered()
+    }
+}
+
+/// FNV-1a followed by the MurmurHash3 finalizer, which spreads differences in
+/// short keys across the high bits. Used over `DefaultHasher` as its output is
+/// stable across Rust releases.
+fn hash_key(key: &str) -> u64 {
+    let mut hash = key.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    });
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51afd7ed558ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ceb9fe1a85ec53);
+    hash ^ (hash >> 33)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct VecFlusher(Rc<RefCell<Vec<String>>>);
+
+    impl Flush for VecFlusher {
+        fn flush_one(&mut self, display: String) {
+            self.0.borrow_mut().push(display)
+        }
+    }
+
+    #[test]
+    fn sample_by_sequence() {
+        let output = VecFlusher::default();
+        let mut flusher = SamplingFlusher::new(output.clone(), 0.25, SampleBy::Sequence);
+        for i in 0..100 {
+            flusher.flush_one(i.to_string());
+        }
+
+        let output = output.0.borrow();
+        assert_eq!(output.len(), 25);
+        assert_eq!(output[..3], ["3", "7", "11"]);
+    }
+
+    #[test]
+    fn sample_by_key() {
+        fn symbol(line: &str) -> Option<&str> {
+            line.split(' ')
+                .find_map(|part| part.strip_prefix("symbol="))
+        }
+
+        let output = VecFlusher::default();
+        let mut flusher = SamplingFlusher::new(output.clone(), 0.5, SampleBy::Key(symbol));
+        for i in 0..1000 {
+            flusher.flush_one(format!("seq={} symbol=SYM{}", i, i % 10));
+        }
+
+        // every record of a kept symbol is kept
+        let output = output.0.borrow();
+        let mut kept = output
+            .iter()
+            .filter_map(|line| symbol(line))
+            .collect::<Vec<_>>();
+        kept.sort();
+        kept.dedup();
+        assert!(!kept.is_empty() && kept.len() < 10);
+        assert_eq!(output.len(), kept.len() * 100);
+    }
+
+    #[test]
+    fn sample_none_or_all() {
+        let output = VecFlusher::default();
+        let mut none = SamplingFlusher::new(output.clone(), 0.0, SampleBy::Sequence);
+        let mut all = SamplingFlusher::new(output.clone(), 1.0, SampleBy::Key(|_| None));
+        for i in 0..10 {
+            none.flush_one(i.to_string());
+            all.flush_one(i.to_string());
+        }
+
+        assert_eq!(output.0.borrow().len(), 10);
+    }
+}
@@ -1,4 +1,6 @@
-use crate::Flush;
+use std::io::Write;
+
+use crate::{Flush, Level};
 
 /// Flushes into stdout
 pub struct StdoutFlusher;
@@ -19,4 +21,12 @@ impl Flush for StdoutFlusher {
     fn flush_one(&mut self, display: String) {
         print!("{}", display);
     }
+
+    fn try_flush_one(&mut self, display: String) -> std::io::Result<()> {
+        self.try_flush_str(Level::Info, &display)
+    }
+
+    fn try_flush_str(&mut self, _level: Level, display: &str) -> std::io::Result<()> {
+        std::io::stdout().write_all(display.as_bytes())
+    }
 }
@@ -0,0 +1,182 @@
+use std::{
+    collections::VecDeque,
+    io,
+    time::{Duration, Instant},
+};
+
+/// Default number of bytes of log lines kept while disconnected
+pub(crate) const DEFAULT_BACKLOG_CAPACITY: usize = 4 * 1024 * 1024;
+/// Default delay before the first reconnection attempt
+pub(crate) const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+/// Default upper bound of the delay between reconnection attempts
+pub(crate) const DEFAULT_MAX_BACKOFF: Duration = Duration::from_secs(30);
+/// Default timeout of connecting and of writing to the collector
+pub(crate) const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Bounded queue of log lines waiting to be written to the collector
+pub(crate) struct Backlog {
+    lines: VecDeque<String>,
+    /// Bytes of the front line already written
+    written: usize,
+    bytes: usize,
+    capacity: usize,
+    dropped: u64,
+}
+
+impl Backlog {
+    pub(crate) fn new() -> Backlog {
+        Backlog {
+            lines: VecDeque::new(),
+            written: 0,
+            bytes: 0,
+            capacity: DEFAULT_BACKLOG_CAPACITY,
+            dropped: 0,
+        }
+    }
+
+    pub(crate) fn set_capacity(&mut self, bytes: usize) {
+        self.capacity = bytes;
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    pub(crate) fn dropped(&self) -> u64 {
+        self.dropped
+    }
+
+    /// Appends `display`, dropping the oldest lines which do not fit. Fails
+    /// with how many lines were dropped, if any.
+    pub(crate) fn push(&mut self, display: String) -> io::Result<()> {
+        self.bytes += display.len();
+        self.lines.push_back(display);
+
+        let mut dropped = 0;
+        while self.bytes > self.capacity {
+            let Some(line) = self.lines.pop_front() else {
+                break;
+            };
+            // the rest of a partially written line is dropped as well
+            self.bytes -= line.len();
+            self.written = 0;
+            dropped += 1;
+        }
+        self.dropped += dropped;
+
+        if dropped > 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::OutOfMemory,
+                format!("backlog full, dropped {} lines", dropped),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Bytes of the front line left to write
+    pub(crate) fn front(&self) -> Option<&[u8]> {
+        self.lines
+            .front()
+            .map(|line| &line.as_bytes()[self.written..])
+    }
+
+    /// Accounts for the result of writing [`Backlog::front`], failing when
+    /// the connection should be dropped
+    pub(crate) fn wrote(&mut self, written: io::Result<usize>) -> io::Result<()> {
+        match written {
+            Ok(0) => Err(io::ErrorKind::WriteZero.into()),
+            Ok(n) => {
+                self.written += n;
+                if self
+                    .lines
+                    .front()
+                    .is_some_and(|line| self.written == line.len())
+                {
+                    self.bytes -= self.written;
+                    self.lines.pop_front();
+                    self.written = 0;
+                }
+                Ok(())
+            }
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => Ok(()),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Exponential backoff between reconnection attempts
+pub(crate) struct Backoff {
+    initial: Duration,
+    max: Duration,
+    pub(crate) current: Duration,
+    next_attempt: Option<Instant>,
+}
+
+impl Backoff {
+    pub(crate) fn new() -> Backoff {
+        Backoff {
+            initial: DEFAULT_INITIAL_BACKOFF,
+            max: DEFAULT_MAX_BACKOFF,
+            current: DEFAULT_INITIAL_BACKOFF,
+            next_attempt: None,
+        }
+    }
+
+    pub(crate) fn set(&mut self, initial: Duration, max: Duration) {
+        self.initial = initial;
+        self.max = max.max(initial);
+        self.current = initial;
+    }
+
+    /// Fails while waiting for the next attempt
+    pub(crate) fn ready(&self) -> io::Result<()> {
+        if self
+            .next_attempt
+            .is_some_and(|next_attempt| Instant::now() < next_attempt)
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::NotConnected,
+                "waiting to reconnect",
+            ));
+        }
+        Ok(())
+    }
+
+    /// Resets the delay once an attempt succeeded, or doubles it
+    pub(crate) fn attempted<T>(&mut self, connected: io::Result<T>) -> io::Result<T> {
+        match connected {
+            Ok(_) => {
+                self.current = self.initial;
+                self.next_attempt = None;
+            }
+            Err(_) => {
+                self.next_attempt = Some(Instant::now() + self.current);
+                self.current = (self.current * 2).min(self.max);
+            }
+        }
+        connected
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backlog_drops_oldest_lines() {
+        let mut backlog = Backlog::new();
+        backlog.set_capacity(10);
+        backlog.push("first\n".to_string()).unwrap();
+        backlog.wrote(Ok(2)).unwrap();
+        assert_eq!(backlog.front(), Some(&b"rst\n"[..]));
+
+        // the partially written line is dropped as a whole
+        assert!(backlog.push("second\n".to_string()).is_err());
+        assert_eq!(backlog.dropped(), 1);
+        assert_eq!(backlog.front(), Some(&b"second\n"[..]));
+
+        backlog.wrote(Ok(7)).unwrap();
+        assert_eq!(backlog.len(), 0);
+        assert!(backlog.wrote(Ok(0)).is_err());
+    }
+}
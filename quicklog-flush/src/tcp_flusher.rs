@@ -0,0 +1,223 @@
+use std::{
+    io::{self, Write},
+    net::{SocketAddr, TcpStream, ToSocketAddrs},
+    time::Duration,
+};
+
+use crate::{
+    tcp_backlog::{Backlog, Backoff, DEFAULT_TIMEOUT},
+    Flush,
+};
+
+/// Streams log lines to a remote collector over TCP, for hosts without a
+/// local disk budget for logs.
+///
+/// Lines are kept in a bounded backlog until they are written to the socket.
+/// When the connection drops, the flusher reconnects with exponential backoff
+/// on later flushes, and sends the backlog once connected again. Once the
+/// backlog is full, the oldest lines are dropped.
+///
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use quicklog_flush::tcp_flusher::TcpFlusher;
+///
+/// let flusher = TcpFlusher::new("collector.internal:5170")
+///     .with_backlog_capacity(16 * 1024 * 1024)
+///     .with_backoff(Duration::from_millis(50), Duration::from_secs(10));
+/// ```
+pub struct TcpFlusher {
+    addr: String,
+    stream: Option<TcpStream>,
+    backlog: Backlog,
+    backoff: Backoff,
+    timeout: Duration,
+}
+
+impl TcpFlusher {
+    /// Streams into the collector at `addr`, e.g. `"10.0.0.5:5170"`. The
+    /// connection is established on the first flush.
+    pub fn new(addr: impl Into<String>) -> TcpFlusher {
+        TcpFlusher {
+            addr: addr.into(),
+            stream: None,
+            backlog: Backlog::new(),
+            backoff: Backoff::new(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+
+    /// Maximum number of bytes of log lines kept while the collector is
+    /// unreachable, 4 MiB by default
+    pub fn with_backlog_capacity(mut self, bytes: usize) -> TcpFlusher {
+        self.backlog.set_capacity(bytes);
+        self
+    }
+
+    /// Delay before the first reconnection attempt, doubling after every
+    /// failed attempt up to `max`. Defaults to 100ms and 30s.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> TcpFlusher {
+        self.backoff.set(initial, max);
+        self
+    }
+
+    /// Timeout of connecting to and writing into the collector, 1s by default
+    pub fn with_timeout(mut self, timeout: Duration) -> TcpFlusher {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Whether the flusher is currently connected to the collector
+    pub fn is_connected(&self) -> bool {
+        self.stream.is_some()
+    }
+
+    /// Number of lines waiting to be sent
+    pub fn backlog_len(&self) -> usize {
+        self.backlog.len()
+    }
+
+    /// Number of lines dropped because the backlog was full
+    pub fn dropped(&self) -> u64 {
+        self.backlog.dropped()
+    }
+
+    /// Connects to the collector unless waiting for the next attempt
+    fn connect(&mut self) -> io::Result<()> {
+        if self.stream.is_some() {
+            return Ok(());
+        }
+        self.backoff.ready()?;
+        self.stream = Some(self.backoff.attempted(self.try_connect())?);
+        Ok(())
+    }
+
+    fn try_connect(&self) -> io::Result<TcpStream> {
+        let mut last_err = None;
+        for addr in self.addr.to_socket_addrs()? {
+            match self.connect_to(addr) {
+                Ok(stream) => return Ok(stream),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "address resolved to nothing")
+        }))
+    }
+
+    fn connect_to(&self, addr: SocketAddr) -> io::Result<TcpStream> {
+        let stream = TcpStream::connect_timeout(&addr, self.timeout)?;
+        stream.set_write_timeout(Some(self.timeout))?;
+        Ok(stream)
+    }
+
+    /// Writes as much of the backlog as possible, disconnecting on error
+    fn send(&mut self) -> io::Result<()> {
+        while let Some(line) = self.backlog.front() {
+            let Some(stream) = self.stream.as_mut() else {
+                self.connect()?;
+                continue;
+            };
+            let written = stream.write(line);
+            if let Err(err) = self.backlog.wrote(written) {
+                self.stream = None;
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Flush for TcpFlusher {
+    fn flush_one(&mut self, display: String) {
+        let _ = self.try_flush_one(display);
+    }
+
+    /// Lines which cannot be sent yet are kept in the backlog, so this only
+    /// fails when lines had to be dropped from the backlog
+    fn try_flush_one(&mut self, display: String) -> io::Result<()> {
+        let pushed = self.backlog.push(display);
+        // failures to send are retried on the next flush
+        let _ = self.send();
+        pushed
+    }
+
+    /// Tries to send the backlog, which is kept if the collector is
+    /// unreachable
+    fn flush_buffered(&mut self) -> io::Result<()> {
+        self.send()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufRead, BufReader},
+        net::TcpListener,
+    };
+
+    use super::*;
+
+    fn read_lines(listener: &TcpListener, count: usize) -> Vec<String> {
+        let (stream, _) = listener.accept().unwrap();
+        BufReader::new(stream)
+            .lines()
+            .take(count)
+            .map(Result::unwrap)
+            .collect()
+    }
+
+    #[test]
+    fn streams_lines() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut flusher = TcpFlusher::new(listener.local_addr().unwrap().to_string());
+        for i in 0..3 {
+            flusher.try_flush_one(format!("line {}\n", i)).unwrap();
+        }
+
+        assert!(flusher.is_connected());
+        assert_eq!(flusher.backlog_len(), 0);
+        assert_eq!(read_lines(&listener, 3), ["line 0", "line 1", "line 2"]);
+    }
+
+    #[test]
+    fn reconnects_and_sends_backlog() {
+        // reserve a port with nothing listening on it
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let mut flusher = TcpFlusher::new(addr.to_string())
+            .with_backoff(Duration::ZERO, Duration::ZERO)
+            .with_backlog_capacity(20);
+
+        flusher.try_flush_one("dropped\n".to_string()).unwrap();
+        flusher.try_flush_one("first\n".to_string()).unwrap();
+        assert!(!flusher.is_connected());
+        assert!(flusher.try_flush_one("second\n".to_string()).is_err());
+        assert_eq!(flusher.dropped(), 1);
+        assert_eq!(flusher.backlog_len(), 2);
+
+        let listener = TcpListener::bind(addr).unwrap();
+        flusher.try_flush_one("third\n".to_string()).unwrap();
+        assert!(flusher.is_connected());
+        assert_eq!(read_lines(&listener, 3), ["first", "second", "third"]);
+    }
+
+    #[test]
+    fn backs_off_exponentially() {
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let mut flusher = TcpFlusher::new(addr.to_string())
+            .with_backoff(Duration::from_secs(60), Duration::from_secs(300));
+
+        flusher.flush_one("line\n".to_string());
+        assert_eq!(flusher.backoff.current, Duration::from_secs(120));
+        // still waiting for the next attempt, even once the collector is up
+        let _listener = TcpListener::bind(addr).unwrap();
+        flusher.flush_one("line\n".to_string());
+        assert!(!flusher.is_connected());
+        assert_eq!(flusher.backlog_len(), 2);
+    }
+}
@@ -0,0 +1,62 @@
+use std::{fs, path::PathBuf};
+
+/// File in the temporary directory, removed when dropped. Names must be
+/// unique across tests, which run in parallel within the same process.
+pub(crate) struct TestFile(pub(crate) PathBuf);
+
+impl TestFile {
+    pub(crate) fn new(name: &str) -> TestFile {
+        let path =
+            std::env::temp_dir().join(format!("quicklog-{}-{}.log", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        TestFile(path)
+    }
+
+    /// Contents of the file, empty if it was not created yet
+    pub(crate) fn bytes(&self) -> Vec<u8> {
+        fs::read(&self.0).unwrap_or_default()
+    }
+
+    pub(crate) fn contents(&self) -> String {
+        String::from_utf8(self.bytes()).unwrap()
+    }
+}
+
+impl Drop for TestFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Empty directory in the temporary directory, removed with its contents
+/// when dropped
+pub(crate) struct TestDir(pub(crate) PathBuf);
+
+impl TestDir {
+    pub(crate) fn new(name: &str) -> TestDir {
+        let dir = std::env::temp_dir().join(format!("quicklog-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        TestDir(dir)
+    }
+
+    /// Contents of every file in the directory, sorted by file name
+    pub(crate) fn files(&self) -> Vec<(String, String)> {
+        let mut files = fs::read_dir(&self.0)
+            .unwrap()
+            .map(|entry| {
+                let path = entry.unwrap().path();
+                let name = path.file_name().unwrap().to_string_lossy().into_owned();
+                (name, fs::read_to_string(&path).unwrap())
+            })
+            .collect::<Vec<_>>();
+        files.sort();
+        files
+    }
+}
+
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
@@ -0,0 +1,201 @@
+use std::{io, ptr};
+
+use windows_sys::Win32::{
+    Foundation::HANDLE,
+    System::{
+        Diagnostics::Debug::OutputDebugStringW,
+        EventLog::{
+            DeregisterEventSource, RegisterEventSourceW, ReportEventW, EVENTLOG_ERROR_TYPE,
+            EVENTLOG_INFORMATION_TYPE, EVENTLOG_WARNING_TYPE, REPORT_EVENT_TYPE,
+        },
+    },
+};
+
+use crate::{Flush, Level};
+
+/// Writes each record as an event of the Windows Event Log, with the type of
+/// event given by its level: errors and warnings as such, and any other
+/// level as information.
+///
+/// Events are reported under an event source, which should be registered in
+/// the registry along with a message file, e.g. when installing the service.
+/// Otherwise, the Event Viewer prefixes every line with a note that the
+/// description of the event cannot be found, but still shows the line.
+///
+/// ```rust,no_run
+/// use quicklog_flush::windows_flusher::EventLogFlusher;
+///
+/// let flusher = EventLogFlusher::new("RiskService")?.with_event_id(1000);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct EventLogFlusher {
+    handle: HANDLE,
+    event_id: u32,
+    /// Line being reported as UTF-16, reused for every record
+    wide: Vec<u16>,
+}
+
+// SAFETY: event source handles can be used from any thread
+unsafe impl Send for EventLogFlusher {}
+
+impl EventLogFlusher {
+    /// Reports events under the event source `source` of the local machine
+    pub fn new(source: &str) -> io::Result<EventLogFlusher> {
+        let source = to_wide(source);
+        // SAFETY: `source` is nul-terminated, and a null server name stands
+        // for the local machine
+        let handle = unsafe { RegisterEventSourceW(ptr::null(), source.as_ptr()) };
+        if handle.is_null() {
+            return Err(io::Error::last_os_error());
+        }
+
+        Ok(EventLogFlusher {
+            handle,
+            event_id: 0,
+            wide: Vec::new(),
+        })
+    }
+
+    /// Identifier of the reported events, `0` by default, which selects the
+    /// message of the event in the message file of the source
+    pub fn with_event_id(mut self, event_id: u32) -> EventLogFlusher {
+        self.event_id = event_id;
+        self
+    }
+}
+
+impl Drop for EventLogFlusher {
+    fn drop(&mut self) {
+        // SAFETY: registered in `new`, and only deregistered here
+        unsafe { DeregisterEventSource(self.handle) };
+    }
+}
+
+impl Flush for EventLogFlusher {
+    fn flush_one(&mut self, display: String) {
+        let _ = self.try_flush_one(display);
+    }
+
+    fn try_flush_one(&mut self, display: String) -> io::Result<()> {
+        self.try_flush_str(Level::Info, &display)
+    }
+
+    fn try_flush_record(&mut self, level: Level, display: String) -> io::Result<()> {
+        self.try_flush_str(level, &display)
+    }
+
+    fn try_flush_str(&mut self, level: Level, display: &str) -> io::Result<()> {
+        let line = display.strip_suffix('\n').unwrap_or(display);
+        write_wide(&mut self.wide, line);
+        let strings = [self.wide.as_ptr()];
+        // SAFETY: the handle is valid until dropped, and the single string
+        // passed is nul-terminated
+        let reported = unsafe {
+            ReportEventW(
+                self.handle,
+                event_type(level),
+                0,
+                self.event_id,
+                ptr::null_mut(),
+                1,
+                0,
+                strings.as_ptr(),
+                ptr::null(),
+            )
+        };
+        if reported == 0 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(())
+    }
+}
+
+/// Writes each record with `OutputDebugStringW`, to be shown by an attached
+/// debugger or by tools such as DebugView. Records are silently discarded
+/// when nothing is listening.
+///
+/// ```rust,no_run
+/// use quicklog_flush::windows_flusher::DebugOutputFlusher;
+///
+/// let flusher = DebugOutputFlusher::new();
+/// ```
+pub struct DebugOutputFlusher {
+    /// Line being written as UTF-16, reused for every record
+    wide: Vec<u16>,
+}
+
+impl DebugOutputFlusher {
+    pub fn new() -> DebugOutputFlusher {
+        DebugOutputFlusher { wide: Vec::new() }
+    }
+}
+
+impl Default for DebugOutputFlusher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Flush for DebugOutputFlusher {
+    fn flush_one(&mut self, display: String) {
+        let _ = self.try_flush_one(display);
+    }
+
+    fn try_flush_one(&mut self, display: String) -> io::Result<()> {
+        self.try_flush_str(Level::Info, &display)
+    }
+
+    fn try_flush_str(&mut self, _level: Level, display: &str) -> io::Result<()> {
+        write_wide(&mut self.wide, display);
+        // SAFETY: the string is nul-terminated
+        unsafe { OutputDebugStringW(self.wide.as_ptr()) };
+        Ok(())
+    }
+}
+
+/// Type of the event reported for a record logged at `level`
+fn event_type(level: Level) -> REPORT_EVENT_TYPE {
+    match level {
+        Level::Error => EVENTLOG_ERROR_TYPE,
+        Level::Warn => EVENTLOG_WARNING_TYPE,
+        Level::Info | Level::Debug | Level::Trace => EVENTLOG_INFORMATION_TYPE,
+    }
+}
+
+/// Writes `s` into `wide` as a nul-terminated UTF-16 string, replacing any
+/// nul character, which would otherwise end the string early
+fn write_wide(wide: &mut Vec<u16>, s: &str) {
+    wide.clear();
+    wide.extend(s.encode_utf16().map(|c| if c == 0 { 0xfffd } else { c }));
+    wide.push(0);
+}
+
+fn to_wide(s: &str) -> Vec<u16> {
+    let mut wide = Vec::new();
+    write_wide(&mut wide, s);
+    wide
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_strings() {
+        assert_eq!(to_wide("ok"), [b'o' as u16, b'k' as u16, 0]);
+        assert_eq!(to_wide("a\0€"), [b'a' as u16, 0xfffd, 0x20ac, 0]);
+    }
+
+    #[test]
+    fn event_types() {
+        assert_eq!(event_type(Level::Error), EVENTLOG_ERROR_TYPE);
+        assert_eq!(event_type(Level::Warn), EVENTLOG_WARNING_TYPE);
+        assert_eq!(event_type(Level::Debug), EVENTLOG_INFORMATION_TYPE);
+    }
+
+    #[test]
+    fn debug_output() {
+        let mut flusher = DebugOutputFlusher::new();
+        flusher.try_flush_str(Level::Info, "hello\n").unwrap();
+    }
+}
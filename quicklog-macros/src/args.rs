@@ -1,9 +1,11 @@
 use proc_macro2::{TokenStream as TokenStream2, TokenTree};
 use quote::{quote, ToTokens};
 use syn::{
+    ext::IdentExt,
+    parenthesized,
     parse::{self, Parse, ParseStream},
     punctuated::Punctuated,
-    Expr, Ident, LitStr, Token,
+    token, Expr, Ident, LitBool, LitInt, LitStr, Token,
 };
 
 use crate::format_arg::FormatArg;
@@ -11,12 +13,72 @@ use crate::format_arg::FormatArg;
 /// Dot-delimited identifiers, e.g. `ident_a.some_field.other_field`
 pub(crate) type DotDelimitedIdent = Punctuated<Ident, Token![.]>;
 
+/// Name of a structured field, either dot-delimited identifiers which may be
+/// keywords, e.g. `order.type`, or a string literal, e.g. `"http.status-code"`
+pub(crate) enum FieldName {
+    Path(DotDelimitedIdent),
+    Literal(LitStr),
+}
+
+impl FieldName {
+    /// Name written before the value, e.g. `order.type` for `order.r#type`
+    pub(crate) fn value(&self) -> String {
+        match self {
+            Self::Path(path) => path
+                .iter()
+                .map(|ident| ident.unraw().to_string())
+                .collect::<Vec<_>>()
+                .join("."),
+            Self::Literal(name) => name.value(),
+        }
+    }
+}
+
+impl Parse for FieldName {
+    fn parse(input: ParseStream) -> parse::Result<Self> {
+        if !input.peek(LitStr) {
+            return Ok(Self::Path(DotDelimitedIdent::parse_separated_nonempty_with(
+                input,
+                Ident::parse_any,
+            )?));
+        }
+
+        let name = input.parse::<LitStr>()?;
+        let value = name.value();
+        if value.is_empty() {
+            return Err(syn::Error::new(name.span(), "field name cannot be empty"));
+        }
+        // Would otherwise not be parsed back as a single key from logfmt
+        if value
+            .chars()
+            .any(|c| c.is_whitespace() || c == '=' || c == '"' || c.is_control())
+        {
+            return Err(syn::Error::new(
+                name.span(),
+                "field name cannot contain whitespace, `=` or `\"`",
+            ));
+        }
+        Ok(Self::Literal(name))
+    }
+}
+
+impl ToTokens for FieldName {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        match self {
+            Self::Path(path) => path.to_tokens(tokens),
+            Self::Literal(name) => name.to_tokens(tokens),
+        }
+    }
+}
+
 /// Comma-separated sequence of `PrefixedArg`-based named fields
 /// e.g. `my.name = ?debug_struct`, `%display_struct`
 pub(crate) type PrefixedFields = Punctuated<NamedField<PrefixedArg>, Token![,]>;
 
 /// Formatting argument with an optional prefix
-/// e.g. `?debug_struct`, `%display_struct`, `^serialize_struct`, `some_struct`
+/// e.g. `?debug_struct`, `%display_struct`, `^serialize_struct`,
+/// `compressed(^serialize_struct)`, `hex(^payload)`, `lazy || summary()`,
+/// `some_struct`
 #[derive(Clone)]
 pub(crate) enum PrefixedArg {
     /// `?debug_struct`
@@ -25,27 +87,118 @@ pub(crate) enum PrefixedArg {
     Display(Expr),
     /// `^serialize_struct`
     Serialize(Expr),
+    /// `compressed(^serialize_struct)`
+    Compressed(Expr),
+    /// `hex(^payload)`, `base64(^payload)`, `byte_len(^payload)`
+    Bytes(BytesKind, Expr),
+    /// `lazy || summary()`, `?lazy || summary()`, `^lazy || summary()`
+    Lazy(LazyKind, Expr),
     /// `some_struct`
     Normal(Expr),
 }
 
+/// How the bytes of a `hex(^..)`, `base64(^..)` or `byte_len(^..)` argument
+/// are rendered
+#[derive(Clone, Copy)]
+pub(crate) enum BytesKind {
+    Hex,
+    Base64,
+    Len,
+}
+
+impl BytesKind {
+    fn from_ident(ident: &Ident) -> Option<Self> {
+        match ident.to_string().as_str() {
+            "hex" => Some(Self::Hex),
+            "base64" => Some(Self::Base64),
+            "byte_len" => Some(Self::Len),
+            _ => None,
+        }
+    }
+
+    /// Constructor of the `Bytes` wrapping the argument
+    pub(crate) fn constructor(self) -> TokenStream2 {
+        match self {
+            Self::Hex => quote! { quicklog::serialize::Bytes::hex },
+            Self::Base64 => quote! { quicklog::serialize::Bytes::base64 },
+            Self::Len => quote! { quicklog::serialize::Bytes::len_only },
+        }
+    }
+}
+
+/// Function-like wrapper of a `^` argument, e.g. `compressed(^..)`
+enum Wrapper {
+    Compressed,
+    Bytes(BytesKind),
+}
+
+/// How the value returned by the closure of a `lazy` argument is formatted,
+/// given by the prefix before `lazy`
+#[derive(Clone, Copy)]
+pub(crate) enum LazyKind {
+    /// `lazy || ...` or `%lazy || ...`
+    Display,
+    /// `?lazy || ...`
+    Debug,
+    /// `^lazy || ...`
+    Serialize,
+}
+
 impl PrefixedArg {
     /// The captured expression for this argument
     pub(crate) fn expr(&self) -> &Expr {
         match self {
-            Self::Debug(i) | Self::Display(i) | Self::Serialize(i) | Self::Normal(i) => i,
+            Self::Debug(i)
+            | Self::Display(i)
+            | Self::Serialize(i)
+            | Self::Compressed(i)
+            | Self::Bytes(_, i)
+            | Self::Lazy(_, i)
+            | Self::Normal(i) => i,
         }
     }
 }
 
 impl ToTokens for PrefixedArg {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
-        self.expr().to_tokens(tokens);
+        match self {
+            // Named after the body of the closure when no name is given
+            Self::Lazy(_, Expr::Closure(closure)) => closure.body.to_tokens(tokens),
+            _ => self.expr().to_tokens(tokens),
+        }
     }
 }
 
 impl Parse for PrefixedArg {
     fn parse(input: ParseStream) -> syn::Result<Self> {
+        let lazy_kind = if input.peek(Token![?]) {
+            Some(LazyKind::Debug)
+        } else if input.peek(Token![%]) {
+            Some(LazyKind::Display)
+        } else if input.peek(Token![^]) {
+            Some(LazyKind::Serialize)
+        } else {
+            None
+        };
+        if is_lazy(input, lazy_kind.is_some()) {
+            if lazy_kind.is_some() {
+                input.parse::<proc_macro2::Punct>()?;
+            }
+            input.parse::<Ident>()?;
+            let closure = input.parse::<Expr>()?;
+            if !matches!(closure, Expr::Closure(_)) {
+                return Err(syn::Error::new_spanned(
+                    closure,
+                    "expected a closure after `lazy`, e.g. `lazy || summary()`",
+                ));
+            }
+
+            return Ok(PrefixedArg::Lazy(
+                lazy_kind.unwrap_or(LazyKind::Display),
+                closure,
+            ));
+        }
+
         if input.peek(Token![?]) {
             input.parse::<Token![?]>()?;
 
@@ -58,25 +211,74 @@ impl Parse for PrefixedArg {
             input.parse::<Token![^]>()?;
 
             Ok(PrefixedArg::Serialize(input.parse()?))
+        } else if let Some(wrapper) = peek_wrapper(input) {
+            input.parse::<Ident>()?;
+            let content;
+            parenthesized!(content in input);
+            content.parse::<Token![^]>()?;
+            let expr = content.parse()?;
+
+            Ok(match wrapper {
+                Wrapper::Compressed => PrefixedArg::Compressed(expr),
+                Wrapper::Bytes(kind) => PrefixedArg::Bytes(kind, expr),
+            })
         } else {
             Ok(PrefixedArg::Normal(input.parse()?))
         }
     }
 }
 
+/// Whether the input starts with `lazy` followed by a closure, after a prefix
+/// if `prefixed`, as opposed to an expression using a variable named `lazy`
+fn is_lazy(input: ParseStream, prefixed: bool) -> bool {
+    let fork = input.fork();
+    if prefixed && fork.parse::<proc_macro2::Punct>().is_err() {
+        return false;
+    }
+    fork.parse::<Ident>().is_ok_and(|ident| ident == "lazy")
+        && (fork.peek(Token![||]) || fork.peek(Token![|]) || fork.peek(Token![move]))
+}
+
+/// The wrapper the input starts with, e.g. `compressed(^`, as opposed to a
+/// call to a function named `compressed`
+fn peek_wrapper(input: ParseStream) -> Option<Wrapper> {
+    peek_wrapper_call(&input.fork()).ok().flatten()
+}
+
+fn peek_wrapper_call(input: ParseStream) -> syn::Result<Option<Wrapper>> {
+    let ident = input.parse::<Ident>()?;
+    let wrapper = match BytesKind::from_ident(&ident) {
+        Some(kind) => Wrapper::Bytes(kind),
+        None if ident == "compressed" => Wrapper::Compressed,
+        None => return Ok(None),
+    };
+    if !input.peek(token::Paren) {
+        return Ok(None);
+    }
+
+    let content;
+    parenthesized!(content in input);
+    Ok(content.peek(Token![^]).then_some(wrapper))
+}
+
 impl FormatArg for PrefixedArg {
     fn formatter(&self) -> &'static str {
         match self {
             Self::Debug(_) => "{:?}",
-            Self::Display(_) | Self::Serialize(_) | Self::Normal(_) => "{}",
+            Self::Display(_)
+            | Self::Serialize(_)
+            | Self::Compressed(_)
+            | Self::Bytes(..)
+            | Self::Lazy(..)
+            | Self::Normal(_) => "{}",
         }
     }
 }
 
 /// Describes a logging argument of the form `a.b.c = ?debug_struct`, `a.b.c = some_expr()`
 pub(crate) struct NamedField<T: Parse> {
-    /// `a.b.c`, optional
-    pub(crate) name: Option<DotDelimitedIdent>,
+    /// `a.b.c` or `"a-b"`, optional
+    pub(crate) name: Option<FieldName>,
     /// `=` token, optional
     pub(crate) assign: Option<Token![=]>,
     /// `?debug_struct`, `some_expr()`
@@ -87,13 +289,17 @@ impl<T: Parse + FormatArg + ToTokens> NamedField<T> {
     /// Helper method for describing how to form this `NamedField` as part
     /// of a format string
     pub(crate) fn formatter(&self) -> String {
-        let name = if let Some(n) = &self.name {
-            n.into_token_stream().to_string()
+        self.name().replace('{', "{{").replace('}', "}}") + "=" + self.arg.formatter()
+    }
+
+    /// Name of this field, which is the captured expression if no name
+    /// was given
+    pub(crate) fn name(&self) -> String {
+        if let Some(n) = &self.name {
+            n.value()
         } else {
             (&self.arg).into_token_stream().to_string()
-        };
-
-        name + "=" + self.arg.formatter()
+        }
     }
 }
 
@@ -115,10 +321,7 @@ impl<T: Parse + ToTokens> Parse for NamedField<T> {
         }
 
         let (name, assign) = if has_assign {
-            (
-                Some(DotDelimitedIdent::parse_separated_nonempty(input)?),
-                Some(input.parse()?),
-            )
+            (Some(input.parse()?), Some(input.parse()?))
         } else {
             (None, None)
         };
@@ -164,6 +367,21 @@ impl<T: Parse + ToTokens> ToTokens for NamedField<T> {
 ///   - These are the (optionally) prefixed expressions that will be substituted
 ///     into the format string, similar to how `format!` works.
 pub(crate) struct Args {
+    /// `min_interval: 100ms`, in nanoseconds
+    pub(crate) min_interval: Option<u64>,
+    /// `throttle: 1s`, in nanoseconds
+    pub(crate) throttle: Option<u64>,
+    /// `sample: 1000`, logging one call out of every 1000
+    pub(crate) sample: Option<u64>,
+    /// `once: true`, logging only the first call
+    pub(crate) once: bool,
+    /// Name of the first option given which may skip calls, i.e.
+    /// `min_interval`, `throttle`, `sample` or `once`
+    pub(crate) skipping_option: Option<Ident>,
+    /// `tag: audit`
+    pub(crate) tag: Option<LitStr>,
+    /// `target: market_data_logger`, the logger instance to log into
+    pub(crate) target: Option<Expr>,
     /// `?debug_struct`, `%display_struct`
     pub(crate) prefixed_fields: PrefixedFields,
     /// `"Hello World {some_data}"`
@@ -178,9 +396,20 @@ impl Parse for Args {
             return Err(input.error("no tokens passed to macro"));
         }
 
+        let Options {
+            min_interval,
+            throttle,
+            sample,
+            once,
+            skipping_option,
+            tag,
+            target,
+        } = input.parse()?;
+        let once = once.unwrap_or(false);
+
         let mut prefixed_fields: PrefixedFields = Punctuated::new();
         loop {
-            if input.is_empty() || input.peek(LitStr) {
+            if input.is_empty() || (input.peek(LitStr) && !is_literal_field_name(input)) {
                 // No more prefixed fields
                 // Or encountered format string, so no longer accepting prefixed
                 // fields
@@ -206,6 +435,13 @@ impl Parse for Args {
             };
 
             Ok(Self {
+                min_interval,
+                throttle,
+                sample,
+                once,
+                skipping_option,
+                tag,
+                target,
                 prefixed_fields,
                 format_string: Some(format_string),
                 formatting_args,
@@ -213,6 +449,13 @@ impl Parse for Args {
         } else {
             // No format string, just terminate
             Ok(Self {
+                min_interval,
+                throttle,
+                sample,
+                once,
+                skipping_option,
+                tag,
+                target,
                 prefixed_fields,
                 format_string: None,
                 formatting_args: PrefixedFields::new(),
@@ -221,6 +464,148 @@ impl Parse for Args {
     }
 }
 
+/// Whether the input starts with a string literal followed by a single `=`,
+/// naming a field rather than being the format string
+fn is_literal_field_name(input: ParseStream) -> bool {
+    let fork = input.fork();
+    fork.parse::<LitStr>().is_ok() && fork.peek(Token![=]) && !fork.peek(Token![==])
+}
+
+/// Options given as `name: value` at the start of a logging command, in any
+/// order, e.g. `info!(tag: audit, min_interval: 100ms, "...")`
+#[derive(Default)]
+struct Options {
+    /// `min_interval: 100ms`, in nanoseconds
+    min_interval: Option<u64>,
+    /// `throttle: 1s`, in nanoseconds
+    throttle: Option<u64>,
+    /// `sample: 1000`
+    sample: Option<u64>,
+    /// `once: true`
+    once: Option<bool>,
+    /// First of the options above given
+    skipping_option: Option<Ident>,
+    /// `tag: audit` or `tag: "audit"`
+    tag: Option<LitStr>,
+    /// `target: market_data_logger`
+    target: Option<Expr>,
+}
+
+impl Options {
+    /// Checks if the input starts with an option, i.e. a known option name
+    /// followed by a single `:`
+    fn peek(input: ParseStream) -> bool {
+        let fork = input.fork();
+        fork.parse::<Ident>()
+            .is_ok_and(|ident| {
+                ident == "min_interval"
+                    || ident == "throttle"
+                    || ident == "sample"
+                    || ident == "once"
+                    || ident == "tag"
+                    || ident == "target"
+            })
+            && fork.peek(Token![:])
+            && !fork.peek(Token![::])
+    }
+}
+
+impl Parse for Options {
+    fn parse(input: ParseStream) -> parse::Result<Self> {
+        let mut options = Self::default();
+        while Self::peek(input) {
+            let name = input.parse::<Ident>()?;
+            input.parse::<Token![:]>()?;
+            if name != "tag" && name != "target" && options.skipping_option.is_none() {
+                options.skipping_option = Some(name.clone());
+            }
+            let duplicate = if name == "min_interval" {
+                options
+                    .min_interval
+                    .replace(parse_duration(input)?)
+                    .is_some()
+            } else if name == "throttle" {
+                options.throttle.replace(parse_duration(input)?).is_some()
+            } else if name == "sample" {
+                options.sample.replace(parse_sample(input)?).is_some()
+            } else if name == "once" {
+                options.once.replace(input.parse::<LitBool>()?.value).is_some()
+            } else if name == "target" {
+                options.target.replace(input.parse::<Expr>()?).is_some()
+            } else {
+                options.tag.replace(parse_tag(input)?).is_some()
+            };
+            if duplicate {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("`{}` can only be given once", name),
+                ));
+            }
+
+            if options.min_interval.is_some() && options.throttle.is_some() {
+                return Err(syn::Error::new(
+                    name.span(),
+                    "`min_interval` and `throttle` cannot both be given",
+                ));
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(options)
+    }
+}
+
+/// Parses the duration of a `min_interval: <duration>` or
+/// `throttle: <duration>` option, an integer literal suffixed by `ns`, `us`,
+/// `ms` or `s`, returning the duration in nanoseconds
+fn parse_duration(input: ParseStream) -> parse::Result<u64> {
+    let interval = input.parse::<LitInt>()?;
+    let nanos_per_unit: u64 = match interval.suffix() {
+        "ns" => 1,
+        "us" => 1_000,
+        "ms" => 1_000_000,
+        "s" => 1_000_000_000,
+        _ => return Err(syn::Error::new(
+            interval.span(),
+            "expected a duration such as `100ms`, with one of the units `ns`, `us`, `ms` or `s`",
+        )),
+    };
+    interval
+        .base10_parse::<u64>()?
+        .checked_mul(nanos_per_unit)
+        .ok_or_else(|| syn::Error::new(interval.span(), "duration is too large"))
+}
+
+/// Parses the rate of a `sample: <n>` option, a non-zero integer literal
+fn parse_sample(input: ParseStream) -> parse::Result<u64> {
+    let rate = input.parse::<LitInt>()?;
+    match rate.base10_parse::<u64>()? {
+        0 => Err(syn::Error::new(
+            rate.span(),
+            "sample rate must be at least 1",
+        )),
+        rate => Ok(rate),
+    }
+}
+
+/// Parses the name of a `tag: <name>` option, either an identifier or a
+/// non-empty string literal
+fn parse_tag(input: ParseStream) -> parse::Result<LitStr> {
+    if input.peek(LitStr) {
+        let tag = input.parse::<LitStr>()?;
+        if tag.value().is_empty() {
+            return Err(syn::Error::new(tag.span(), "tag cannot be empty"));
+        }
+        Ok(tag)
+    } else {
+        let tag = input.parse::<Ident>()?;
+        Ok(LitStr::new(&tag.to_string(), tag.span()))
+    }
+}
+
 /// Replaces all expression arguments with a new set of identifiers.
 /// e.g. for the expression field `a = &my_struct` and the new identifier `x`,
 /// the field gets transformed to `a = &my_struct` -> `a = x`
@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use proc_macro::TokenStream;
+use quote::{quote, ToTokens};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input, parse_quote,
+    visit_mut::VisitMut,
+    Ident, Item, ItemUse, Macro, MacroDelimiter, Path, Token, UseTree,
+};
+
+use crate::quicklog::Level;
+
+/// Arguments of `#[default_level(Info)]` or
+/// `#[default_level(Level::Info, crate = path::to::quicklog)]`
+struct DefaultLevelArgs {
+    threshold: Level,
+    krate: Path,
+}
+
+impl Parse for DefaultLevelArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let level_path: Path = input.parse()?;
+        let threshold = level_path
+            .segments
+            .last()
+            .and_then(|s| Level::from_name(&s.ident.to_string()))
+            .ok_or_else(|| {
+                syn::Error::new_spanned(
+                    &level_path,
+                    "expected one of `Trace`, `Debug`, `Info`, `Warn`, `Error`",
+                )
+            })?;
+
+        let mut krate = parse_quote!(quicklog);
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            input.parse::<Token![crate]>()?;
+            input.parse::<Token![=]>()?;
+            krate = input.parse()?;
+            input.parse::<Option<Token![,]>>()?;
+        }
+        Ok(Self { threshold, krate })
+    }
+}
+
+/// Level of the logging macro `name`, e.g. `info`
+fn macro_level(name: &Ident) -> Option<Level> {
+    let name = name.to_string();
+    Level::from_name(&name).filter(|_| name.chars().all(|c| c.is_ascii_lowercase()))
+}
+
+/// Collects the logging macros imported from the crate by the `use` items
+/// within the annotated item, keyed by the name they are called with
+struct ImportedMacros<'a> {
+    krate: &'a Path,
+    imported: HashMap<Ident, Level>,
+}
+
+impl ImportedMacros<'_> {
+    /// Walks `tree`, imported below the leading segments `path`
+    fn collect(&mut self, tree: &UseTree, mut path: Vec<Ident>) {
+        match tree {
+            UseTree::Path(use_path) => {
+                path.push(use_path.ident.clone());
+                self.collect(&use_path.tree, path);
+            }
+            UseTree::Group(group) => {
+                for tree in &group.items {
+                    self.collect(tree, path.clone());
+                }
+            }
+            UseTree::Name(name) if self.is_crate_root(&path) => {
+                if let Some(level) = macro_level(&name.ident) {
+                    self.imported.insert(name.ident.clone(), level);
+                }
+            }
+            UseTree::Rename(rename) if self.is_crate_root(&path) => {
+                if let Some(level) = macro_level(&rename.ident) {
+                    self.imported.insert(rename.rename.clone(), level);
+                }
+            }
+            UseTree::Glob(_) if self.is_crate_root(&path) => {
+                for name in ["trace", "debug", "info", "warn", "error"] {
+                    let name = Ident::new(name, proc_macro2::Span::call_site());
+                    if let Some(level) = macro_level(&name) {
+                        self.imported.insert(name, level);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Whether `path` names the root of the crate, e.g. `quicklog`
+    fn is_crate_root(&self, path: &[Ident]) -> bool {
+        path.iter().eq(self.krate.segments.iter().map(|s| &s.ident))
+    }
+}
+
+impl VisitMut for ImportedMacros<'_> {
+    fn visit_item_use_mut(&mut self, item: &mut ItemUse) {
+        self.collect(&item.tree, Vec::new());
+    }
+}
+
+/// Rewrites every call of a logging macro of the crate below `threshold` into
+/// a disabled call
+struct DisableBelow {
+    threshold: Level,
+    krate: Path,
+    imported: HashMap<Ident, Level>,
+}
+
+impl DisableBelow {
+    /// Level of the call if `path` names a logging macro of the crate: either
+    /// qualified with the crate path, or imported from it within the item
+    fn level_of(&self, path: &Path) -> Option<Level> {
+        let mut segments = path.segments.iter().map(|s| &s.ident);
+        let name = segments.next_back()?;
+        if path.segments.len() == 1 {
+            return self.imported.get(name).copied();
+        }
+        let krate = self.krate.segments.iter().map(|s| &s.ident);
+        segments.eq(krate).then(|| macro_level(name)).flatten()
+    }
+}
+
+impl VisitMut for DisableBelow {
+    fn visit_macro_mut(&mut self, mac: &mut Macro) {
+        match self.level_of(&mac.path) {
+            Some(level) if level < self.threshold => {
+                // Wrap the original call, so that the path used to
+                // import the macro still resolves as it would have
+                let call = mac.to_token_stream();
+                let krate = &self.krate;
+                mac.tokens = quote! { #call };
+                mac.path = parse_quote!(#krate::__disabled_log);
+                mac.delimiter = MacroDelimiter::Paren(Default::default());
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Lowers the logging macro calls of quicklog below the given level within
+/// the annotated item to no-ops at compile time.
+///
+/// Calls qualified with the crate path, e.g. `quicklog::info!`, are lowered,
+/// as well as bare calls such as `info!` when the macro is imported from the
+/// crate by a `use` within the annotated item. Other macros of the same name,
+/// e.g. those of `log` or `tracing`, are left alone. The crate path is
+/// `quicklog` unless given with `crate = path`.
+///
+/// The arguments of disabled calls are still type-checked, but are never
+/// evaluated.
+pub(crate) fn default_level(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let DefaultLevelArgs { threshold, krate } = parse_macro_input!(attr as DefaultLevelArgs);
+    let mut item = parse_macro_input!(item as Item);
+
+    let mut imports = ImportedMacros {
+        krate: &krate,
+        imported: HashMap::new(),
+    };
+    imports.visit_item_mut(&mut item);
+    let imported = imports.imported;
+    DisableBelow {
+        threshold,
+        krate,
+        imported,
+    }
+    .visit_item_mut(&mut item);
+
+    item.into_token_stream().into()
+}
@@ -1,7 +1,10 @@
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, TokenStream as TokenStream2};
 use quote::quote;
-use syn::{parse_macro_input, Data, DataStruct, DeriveInput, Type};
+use syn::{
+    ext::IdentExt, parse_macro_input, punctuated::Punctuated, Attribute, Data, DataEnum,
+    DataStruct, DeriveInput, Field, Fields, Lit, LitInt, LitStr, Token, Type, Variant,
+};
 
 /// Generates a `quicklog` `Serialize` implementation for a user-defined struct.
 ///
@@ -32,44 +35,82 @@ use syn::{parse_macro_input, Data, DataStruct, DeriveInput, Type};
 ///         let (_, chunk_rest) = self.b.encode(chunk_rest);
 ///         let (_, chunk_rest) = self.c.encode(chunk_rest);
 ///         assert!(chunk_rest.is_empty());
-///         (quicklog::serialize::Store::new(Self::decode, chunk), rest)
+///         (quicklog::serialize::Store::new_into(Self::decode_into, chunk), rest)
 ///     }
 ///     fn decode(read_buf: &[u8]) -> (String, &[u8]) {
-///         let (a, read_buf) = <usize as quicklog::serialize::Serialize>::decode(read_buf);
-///         let (b, read_buf) = <i32 as quicklog::serialize::Serialize>::decode(read_buf);
-///         let (c, read_buf) = <u32 as quicklog::serialize::Serialize>::decode(read_buf);
-///         (
-///             {
-///                 let res = ::alloc::fmt::format(format_args!("{0} {1} {2}", a, b, c));
-///                 res
-///             },
-///             read_buf,
-///         )
+///         Self::try_decode(read_buf).unwrap()
+///     }
+///     // every field is checked, returning `Error::DecodeCorrupt` on the
+///     // first one which is corrupt
+///     fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), quicklog::Error> {
+///         let (a, read_buf) = <usize as quicklog::serialize::Serialize>::try_decode(read_buf)?;
+///         let (b, read_buf) = <i32 as quicklog::serialize::Serialize>::try_decode(read_buf)?;
+///         let (c, read_buf) = <u32 as quicklog::serialize::Serialize>::try_decode(read_buf)?;
+///         Ok((format!("a={} b={} c={}", a, b, c), read_buf))
 ///     }
 ///     fn buffer_size_required(&self) -> usize {
 ///         self.a.buffer_size_required() + self.b.buffer_size_required()
 ///             + self.c.buffer_size_required()
 ///     }
+///     // same as `decode`, writing into `out` rather than allocating
+///     fn decode_into<'a>(
+///         read_buf: &'a [u8],
+///         out: &mut dyn std::fmt::Write,
+///     ) -> Result<&'a [u8], std::fmt::Error> {
+///         out.write_str("a=")?;
+///         let read_buf = <usize as quicklog::serialize::Serialize>::decode_into(read_buf, out)?;
+///         out.write_str(" b=")?;
+///         let read_buf = <i32 as quicklog::serialize::Serialize>::decode_into(read_buf, out)?;
+///         out.write_str(" c=")?;
+///         let read_buf = <u32 as quicklog::serialize::Serialize>::decode_into(read_buf, out)?;
+///         Ok(read_buf)
+///     }
 /// }
 /// ```
+///
+/// Enums are encoded as a 1-byte index of the variant, in declaration order,
+/// followed by the fields of the variant. See [`derive_enum`].
 pub(crate) fn derive(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
-    let struct_name = &input.ident;
-    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
 
-    let Data::Struct(DataStruct { fields, .. }) = input.data else {
-        todo!("Deriving Serialize only supported for structs currently")
+    let representation = match Representation::parse(&input.attrs) {
+        Ok(representation) => representation,
+        Err(err) => return err.to_compile_error().into(),
     };
 
+    match &input.data {
+        Data::Struct(DataStruct { fields, .. }) => derive_struct(&input, fields, representation),
+        Data::Enum(DataEnum { variants, .. }) => derive_enum(&input, variants, representation),
+        Data::Union(_) => syn::Error::new_spanned(&input, "Serialize cannot be derived for unions")
+            .to_compile_error(),
+    }
+    .into()
+}
+
+fn derive_struct(
+    input: &DeriveInput,
+    fields: &Fields,
+    representation: Option<Representation>,
+) -> TokenStream2 {
+    let struct_name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
     if fields.is_empty() {
-        return quote! {}.into();
+        return quote! {};
     }
 
+    // Fields marked with `#[serialize(skip)]` are left out, keeping the index
+    // of every other field for accessing tuple struct fields
+    let fields_kind = fields;
+    let fields = match serialized_fields(fields) {
+        Ok(fields) => fields,
+        Err(err) => return err.to_compile_error(),
+    };
+
     // Handle both named fields (regular structs) and unnamed fields (tuple structs)
     let field_accessors: Vec<_> = fields
         .iter()
-        .enumerate()
-        .map(|(i, field)| {
+        .map(|&(i, field, _)| {
             if let Some(name) = &field.ident {
                 quote! { #name } // Named field: self.field_name
             } else {
@@ -79,15 +120,35 @@ pub(crate) fn derive(input: TokenStream) -> TokenStream {
         })
         .collect();
 
+    let representation = representation.unwrap_or(match fields_kind {
+        Fields::Named(_) => Representation::Fields,
+        Fields::Unnamed(_) | Fields::Unit => Representation::Transparent,
+    });
+
     // If we have > 1 field, then we split once at the top-level to get the
     // single chunk that has enough capacity to encode all the fields.
     // From there, each field will just encode into this single chunk.
     //
     // Otherwise, if we only have 1 field, we can simply let the single field
     // directly read off the main `write_buf` chunk and return the remainder
-    // unread.
+    // unread. This is only possible for `transparent` structs, as the decode
+    // fn of other structs must be stored to print field or struct names, and
+    // only if the field is decoded as-is, without a unit or scale.
     let (initial_chunk_split, chunk_encode_and_store): (TokenStream2, TokenStream2) =
-        if field_accessors.len() > 1 {
+        if field_accessors.is_empty() {
+            // Every field is skipped, so there is nothing to encode
+            let initial_split = quote! {
+                let (chunk, rest) = write_buf.split_at_mut(0);
+            };
+            let encode_and_store = quote! {
+                (quicklog::serialize::Store::new_into(Self::decode_into, chunk), rest)
+            };
+
+            (initial_split, encode_and_store)
+        } else if field_accessors.len() > 1
+            || representation != Representation::Transparent
+            || !fields[0].2.is_plain()
+        {
             // Split off just large enough chunk to be kept in final Store
             let initial_split = quote! {
                 let (chunk, rest) = write_buf.split_at_mut(self.buffer_size_required());
@@ -114,7 +175,7 @@ pub(crate) fn derive(input: TokenStream) -> TokenStream {
                 #(#encode)*
 
                 assert!(chunk_rest.is_empty());
-                (quicklog::serialize::Store::new(Self::decode, chunk), rest)
+                (quicklog::serialize::Store::new_into(Self::decode_into, chunk), rest)
             };
 
             (initial_split, encode_and_store)
@@ -135,8 +196,7 @@ pub(crate) fn derive(input: TokenStream) -> TokenStream {
     // Combine decode implementations from all field types
     let field_tys: Vec<_> = fields
          .iter()
-         .enumerate()
-         .map(|(i, field)| {
+         .map(|&(i, field, ref format)| {
              let mut field_ty = field.ty.clone();
              if let Type::Reference(ty_ref) = &mut field_ty {
                  _ = ty_ref.lifetime.take();
@@ -146,26 +206,23 @@ pub(crate) fn derive(input: TokenStream) -> TokenStream {
              // Create a unique variable name for each decoded field
              let decoded_ident = if let Some(name) = &field.ident {
                  // Named field: use the field name
-                 Ident::new(&format!("{}", name), name.span())
+                 name.clone()
              } else {
                  // Unnamed field: use field_0, field_1, etc.
                  Ident::new(&format!("field_{}", i), proc_macro2::Span::call_site())
              };
 
-             quote! {
-                 let (#decoded_ident, read_buf) = <#field_ty as quicklog::serialize::Serialize>::decode(read_buf);
-             }
+             format.try_decode(&decoded_ident, &field_ty)
          })
          .collect();
 
     // Create variable names for the format string
     let decode_var_names: Vec<_> = fields
         .iter()
-        .enumerate()
-        .map(|(i, field)| {
+        .map(|&(i, field, _)| {
             if let Some(name) = &field.ident {
                 // Named field: use the field name
-                Ident::new(&format!("{}", name), name.span())
+                name.clone()
             } else {
                 // Unnamed field: use field_0, field_1, etc.
                 Ident::new(&format!("field_{}", i), proc_macro2::Span::call_site())
@@ -173,34 +230,585 @@ pub(crate) fn derive(input: TokenStream) -> TokenStream {
         })
         .collect();
 
-    // Assuming that each field in the output should just be separated by a space
-    // TODO: proper field naming?
-    let mut decode_fmt_str = String::new();
-    for _ in 0..fields.len() {
-        decode_fmt_str.push_str("{} ");
+    // Transparent structs separate each field in the output by a space, named
+    // structs are printed similar to their `Debug` representation, and fields
+    // are otherwise printed as `name=value`
+    let decode_fmt_str = match representation {
+        Representation::Transparent => vec!["{}"; fields.len()].join(" "),
+        Representation::Named => named_fmt_str(struct_name.to_string(), &fields),
+        Representation::Fields => fields
+            .iter()
+            .filter_map(|(_, field, _)| field.ident.as_ref())
+            .map(|ident| format!("{}={{}}", ident.unraw()))
+            .collect::<Vec<_>>()
+            .join(" "),
+    };
+
+    let size = if field_accessors.is_empty() {
+        quote! { 0 }
+    } else {
+        quote! { #(self.#field_accessors.buffer_size_required())+* }
+    };
+
+    let decode_into_fields = decode_into_fields(&decode_fmt_str, &fields);
+
+    quote! {
+        impl #impl_generics quicklog::serialize::Serialize for #struct_name #ty_generics #where_clause {
+            fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (quicklog::serialize::Store<'buf>, &'buf mut [u8]) {
+                // Perform initial split to get combined byte buffer that will be
+                // sufficient for all fields to be encoded in
+                #initial_chunk_split
+
+                #chunk_encode_and_store
+            }
+
+            fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+                Self::try_decode(read_buf).unwrap()
+            }
+
+            fn try_decode(read_buf: &[u8]) -> ::std::result::Result<(String, &[u8]), quicklog::Error> {
+                #(#field_tys)*
+
+                Ok((format!(#decode_fmt_str, #(#decode_var_names),*), read_buf))
+            }
+
+            fn buffer_size_required(&self) -> usize {
+                #size
+            }
+
+            fn decode_into<'__buf>(
+                read_buf: &'__buf [u8],
+                out: &mut dyn ::std::fmt::Write,
+            ) -> ::std::result::Result<&'__buf [u8], ::std::fmt::Error> {
+                #decode_into_fields
+
+                Ok(read_buf)
+            }
+        }
+    }
+}
+
+/// Generates a `Serialize` implementation for an enum.
+///
+/// The variant is encoded as its 1-byte index in declaration order, rather
+/// than its discriminant, followed by its fields:
+/// ```ignore
+/// #[derive(Serialize)]
+/// enum Event {
+///     Heartbeat,
+///     Fill(u64, f64),
+///     Cancel { id: u64 },
+/// }
+///
+/// // Generated code
+/// impl quicklog::serialize::Serialize for Event {
+///     fn encode<'buf>(
+///         &self,
+///         write_buf: &'buf mut [u8],
+///     ) -> (quicklog::serialize::Store<'buf>, &'buf mut [u8]) {
+///         let (chunk, rest) = write_buf.split_at_mut(self.buffer_size_required());
+///         let (index, chunk_rest) = chunk.split_at_mut(1);
+///         let chunk_rest = match self {
+///             Self::Heartbeat => {
+///                 index[0] = 0;
+///                 chunk_rest
+///             }
+///             Self::Fill(field_0, field_1) => {
+///                 index[0] = 1;
+///                 let (_, chunk_rest) = field_0.encode(chunk_rest);
+///                 let (_, chunk_rest) = field_1.encode(chunk_rest);
+///                 chunk_rest
+///             }
+///             // ...
+///         };
+///         assert!(chunk_rest.is_empty());
+///         (quicklog::serialize::Store::new_into(Self::decode_into, chunk), rest)
+///     }
+///     fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+///         Self::try_decode(read_buf).unwrap()
+///     }
+///     fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), quicklog::Error> {
+///         let (index, read_buf) = read_buf.split_first().ok_or(quicklog::Error::DecodeCorrupt)?;
+///         match *index {
+///             0 => Ok((format!("Heartbeat"), read_buf)),
+///             1 => {
+///                 let (field_0, read_buf) = <u64 as quicklog::serialize::Serialize>::try_decode(read_buf)?;
+///                 let (field_1, read_buf) = <f64 as quicklog::serialize::Serialize>::try_decode(read_buf)?;
+///                 Ok((format!("Fill({}, {})", field_0, field_1), read_buf))
+///             }
+///             // ...
+///             _ => Ok(("UnknownVariant".to_string(), read_buf)),
+///         }
+///     }
+///     fn buffer_size_required(&self) -> usize {
+///         1 + match self {
+///             Self::Heartbeat => 0,
+///             Self::Fill(field_0, field_1) => {
+///                 field_0.buffer_size_required() + field_1.buffer_size_required()
+///             }
+///             // ...
+///         }
+///     }
+/// }
+/// ```
+///
+/// Variants are decoded similar to their `Debug` representation, prefixed with
+/// the enum name for `#[serialize(named)]` enums, e.g. `Event::Heartbeat`.
+fn derive_enum(
+    input: &DeriveInput,
+    variants: &Punctuated<Variant, Token![,]>,
+    representation: Option<Representation>,
+) -> TokenStream2 {
+    let enum_name = &input.ident;
+    let representation = representation.unwrap_or(Representation::Transparent);
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    if variants.len() > usize::from(u8::MAX) + 1 {
+        return syn::Error::new_spanned(
+            input,
+            "Serialize can only be derived for enums with at most 256 variants",
+        )
+        .to_compile_error();
+    }
+
+    let mut encode_arms = Vec::with_capacity(variants.len());
+    let mut decode_arms = Vec::with_capacity(variants.len());
+    let mut size_arms = Vec::with_capacity(variants.len());
+    let mut decode_into_arms = Vec::with_capacity(variants.len());
+    for (index, variant) in variants.iter().enumerate() {
+        let index = index as u8;
+        let variant_name = &variant.ident;
+
+        let fields = match serialized_fields(&variant.fields) {
+            Ok(fields) => fields,
+            Err(err) => return err.to_compile_error(),
+        };
+
+        // Fields are bound as field_0, field_1, etc. so that they cannot
+        // shadow the locals of the generated functions, while skipped fields
+        // are not bound at all
+        let bindings: Vec<_> = fields
+            .iter()
+            .map(|&(i, _, _)| Ident::new(&format!("field_{}", i), proc_macro2::Span::call_site()))
+            .collect();
+        let pattern = match &variant.fields {
+            Fields::Named(_) => {
+                let names = fields.iter().map(|(_, field, _)| &field.ident);
+                quote! { Self::#variant_name { #(#names: #bindings,)* .. } }
+            }
+            Fields::Unnamed(unnamed) => {
+                let mut bindings = bindings.iter();
+                let elems = (0..unnamed.unnamed.len()).map(|i| {
+                    if fields.iter().any(|&(serialized, _, _)| serialized == i) {
+                        let binding = bindings.next();
+                        quote! { #binding }
+                    } else {
+                        quote! { _ }
+                    }
+                });
+                quote! { Self::#variant_name(#(#elems),*) }
+            }
+            Fields::Unit => quote! { Self::#variant_name },
+        };
+
+        encode_arms.push(quote! {
+            #pattern => {
+                index[0] = #index;
+                #(let (_, chunk_rest) = #bindings.encode(chunk_rest);)*
+                chunk_rest
+            }
+        });
+
+        let size = if bindings.is_empty() {
+            quote! { 0 }
+        } else {
+            quote! { #(#bindings.buffer_size_required())+* }
+        };
+        size_arms.push(quote! { #pattern => #size, });
+
+        let decode_fields = fields.iter().zip(&bindings).map(|((_, field, format), binding)| {
+            let mut field_ty = field.ty.clone();
+            if let Type::Reference(ty_ref) = &mut field_ty {
+                _ = ty_ref.lifetime.take();
+                _ = ty_ref.mutability.take();
+            }
+            format.try_decode(binding, &field_ty)
+        });
+
+        let decode_fmt_str = match representation {
+            Representation::Transparent | Representation::Fields => {
+                named_fmt_str(variant_name.to_string(), &fields)
+            }
+            Representation::Named => {
+                named_fmt_str(format!("{}::{}", enum_name, variant_name), &fields)
+            }
+        };
+
+        decode_arms.push(quote! {
+            #index => {
+                #(#decode_fields)*
+
+                Ok((format!(#decode_fmt_str, #(#bindings),*), read_buf))
+            }
+        });
+
+        let decode_into_fields = decode_into_fields(&decode_fmt_str, &fields);
+        decode_into_arms.push(quote! {
+            #index => {
+                #decode_into_fields
+
+                read_buf
+            }
+        });
+    }
+
+    // An enum without variants cannot be constructed, so there is nothing to
+    // encode
+    if variants.is_empty() {
+        return quote! {
+            impl #impl_generics quicklog::serialize::Serialize for #enum_name #ty_generics #where_clause {
+                fn encode<'buf>(&self, _write_buf: &'buf mut [u8]) -> (quicklog::serialize::Store<'buf>, &'buf mut [u8]) {
+                    match *self {}
+                }
+
+                fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+                    ("UnknownVariant".to_string(), read_buf)
+                }
+
+                fn buffer_size_required(&self) -> usize {
+                    match *self {}
+                }
+            }
+        };
     }
-    let decode_fmt_str = decode_fmt_str.trim_end();
 
     quote! {
-         impl #impl_generics quicklog::serialize::Serialize for #struct_name #ty_generics #where_clause {
-             fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (quicklog::serialize::Store<'buf>, &'buf mut [u8]) {
-                 // Perform initial split to get combined byte buffer that will be
-                 // sufficient for all fields to be encoded in
-                 #initial_chunk_split
+        impl #impl_generics quicklog::serialize::Serialize for #enum_name #ty_generics #where_clause {
+            fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (quicklog::serialize::Store<'buf>, &'buf mut [u8]) {
+                let (chunk, rest) = write_buf.split_at_mut(self.buffer_size_required());
+                let (index, chunk_rest) = chunk.split_at_mut(1);
+                let chunk_rest = match self {
+                    #(#encode_arms)*
+                };
 
-                 #chunk_encode_and_store
-             }
+                assert!(chunk_rest.is_empty());
+                (quicklog::serialize::Store::new_into(Self::decode_into, chunk), rest)
+            }
 
-             fn decode(read_buf: &[u8]) -> (String, &[u8]) {
-                 #(#field_tys)*
+            fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+                Self::try_decode(read_buf).unwrap()
+            }
 
-                 (format!(#decode_fmt_str, #(#decode_var_names),*), read_buf)
-             }
+            fn try_decode(read_buf: &[u8]) -> ::std::result::Result<(String, &[u8]), quicklog::Error> {
+                let (index, read_buf) = read_buf
+                    .split_first()
+                    .ok_or(quicklog::Error::DecodeCorrupt)?;
+                match *index {
+                    #(#decode_arms)*
+                    _ => Ok(("UnknownVariant".to_string(), read_buf)),
+                }
+            }
 
-             fn buffer_size_required(&self) -> usize {
-                 #(self.#field_accessors.buffer_size_required())+*
-             }
-         }
-     }
-     .into()
+            fn buffer_size_required(&self) -> usize {
+                1 + match self {
+                    #(#size_arms)*
+                }
+            }
+
+            fn decode_into<'__buf>(
+                read_buf: &'__buf [u8],
+                out: &mut dyn ::std::fmt::Write,
+            ) -> ::std::result::Result<&'__buf [u8], ::std::fmt::Error> {
+                let Some((index, read_buf)) = read_buf.split_first() else {
+                    return quicklog::serialize::write_corrupt(out);
+                };
+                let read_buf = match *index {
+                    #(#decode_into_arms)*
+                    _ => {
+                        out.write_str("UnknownVariant")?;
+                        read_buf
+                    }
+                };
+
+                Ok(read_buf)
+            }
+        }
+    }
+}
+
+/// Returns the fields not marked with `#[serialize(skip)]`, along with their
+/// index among all fields and how they are decoded
+fn serialized_fields(fields: &Fields) -> syn::Result<Vec<(usize, &Field, DecodeFormat)>> {
+    let mut serialized = Vec::with_capacity(fields.len());
+    for (i, field) in fields.iter().enumerate() {
+        let mut skip = false;
+        let mut format = DecodeFormat::default();
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("serialize"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("skip") {
+                    skip = true;
+                } else if meta.path.is_ident("unit") {
+                    format.unit = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("scale") {
+                    format.scale = Some(match meta.value()?.parse::<Lit>()? {
+                        Lit::Int(lit) => lit.base10_parse()?,
+                        Lit::Float(lit) => lit.base10_parse()?,
+                        lit => return Err(syn::Error::new_spanned(lit, "expected a number")),
+                    });
+                } else if meta.path.is_ident("precision") {
+                    format.precision = Some(meta.value()?.parse::<LitInt>()?.base10_parse()?);
+                } else if meta.path.is_ident("shortest") {
+                    format.shortest = true;
+                } else {
+                    return Err(meta.error(
+                        "unsupported `serialize` attribute on field, expected `skip`, `unit`, `scale`, `precision` or `shortest`",
+                    ));
+                }
+                Ok(())
+            })?;
+        }
+        if format.shortest && format.precision.is_some() {
+            return Err(syn::Error::new_spanned(
+                field,
+                "`shortest` and `precision` cannot both be set on a field",
+            ));
+        }
+
+        if !skip {
+            serialized.push((i, field, format));
+        }
+    }
+
+    Ok(serialized)
+}
+
+/// Format string for decoding `fields` similar to their `Debug`
+/// representation, e.g. `Order { id: {}, price: {} }` or `Level({}, {})`. Just
+/// `name` is printed if there are no fields to decode.
+fn named_fmt_str(name: String, fields: &[(usize, &Field, DecodeFormat)]) -> String {
+    if fields.is_empty() {
+        name
+    } else if fields.iter().all(|(_, field, _)| field.ident.is_some()) {
+        let fields = fields
+            .iter()
+            .filter_map(|(_, field, _)| field.ident.as_ref())
+            .map(|ident| format!("{}: {{}}", ident.unraw()))
+            .collect::<Vec<_>>()
+            .join(", ");
+        format!("{} {{{{ {} }}}}", name, fields)
+    } else {
+        format!("{}({})", name, vec!["{}"; fields.len()].join(", "))
+    }
+}
+
+/// Statements of `decode_into` writing the decoded `fields` into `out`,
+/// along with the text around them in `fmt_str`, the format string `decode`
+/// formats them with. Fields decoded as-is are written straight into `out`,
+/// the others are formatted by `DecodeFormat::decode` first.
+fn decode_into_fields(fmt_str: &str, fields: &[(usize, &Field, DecodeFormat)]) -> TokenStream2 {
+    let mut pieces = fmt_pieces(fmt_str).into_iter();
+    let first = pieces.next().unwrap_or_default();
+    let mut decode = vec![write_piece(&first)];
+    for ((i, field, format), piece) in fields.iter().zip(pieces) {
+        let mut ty = field.ty.clone();
+        if let Type::Reference(ty_ref) = &mut ty {
+            _ = ty_ref.lifetime.take();
+            _ = ty_ref.mutability.take();
+        }
+        if format.is_plain() {
+            decode.push(quote! {
+                let read_buf = <#ty as quicklog::serialize::Serialize>::decode_into(read_buf, out)?;
+            });
+        } else {
+            // bound by index, so that field names cannot shadow `out`
+            let binding = Ident::new(&format!("field_{}", i), proc_macro2::Span::call_site());
+            let decode_field = format.decode(&binding, &ty);
+            decode.push(quote! {
+                if <#ty as quicklog::serialize::Serialize>::try_decode(read_buf).is_err() {
+                    return quicklog::serialize::write_corrupt(out);
+                }
+                #decode_field
+                out.write_str(&#binding)?;
+            });
+        }
+        decode.push(write_piece(&piece));
+    }
+
+    quote! { #(#decode)* }
+}
+
+fn write_piece(piece: &str) -> TokenStream2 {
+    if piece.is_empty() {
+        quote! {}
+    } else {
+        quote! { out.write_str(#piece)?; }
+    }
+}
+
+/// Splits a format string with only `{}` placeholders into the text around
+/// them, unescaping `{{` and `}}`
+fn fmt_pieces(fmt_str: &str) -> Vec<String> {
+    let mut pieces = vec![String::new()];
+    let mut chars = fmt_str.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+            }
+            ('{', Some('}')) => {
+                chars.next();
+                pieces.push(String::new());
+                continue;
+            }
+            _ => {}
+        }
+        pieces.last_mut().expect("starts with a piece").push(c);
+    }
+    pieces
+}
+
+/// How a field is rendered once decoded, set with the `unit`, `scale`,
+/// `precision` and `shortest` options of `#[serialize(..)]` on the field.
+/// Encoding is not affected, so the raw value is still copied on the hot path.
+#[derive(Default)]
+struct DecodeFormat {
+    /// Suffix appended to the value, e.g. `bps`
+    unit: Option<String>,
+    /// Factor the value is multiplied by, e.g. `0.01` for cents to dollars
+    scale: Option<f64>,
+    /// Number of decimal places the value is printed with
+    precision: Option<usize>,
+    /// Whether the value is printed in its shortest representation, whatever
+    /// the global `FloatFormat`
+    shortest: bool,
+}
+
+impl DecodeFormat {
+    /// Whether the field is decoded as-is
+    fn is_plain(&self) -> bool {
+        self.unit.is_none() && self.scale.is_none() && self.precision.is_none() && !self.shortest
+    }
+
+    /// Like `decode`, but returns `Error::DecodeCorrupt` from the generated
+    /// `try_decode` if the bytes of the field are corrupt
+    fn try_decode(&self, binding: &Ident, ty: &Type) -> TokenStream2 {
+        if self.is_plain() {
+            return quote! {
+                let (#binding, read_buf) = <#ty as quicklog::serialize::Serialize>::try_decode(read_buf)?;
+            };
+        }
+
+        // checked as-is before being formatted
+        let decode = self.decode(binding, ty);
+        quote! {
+            <#ty as quicklog::serialize::Serialize>::try_decode(read_buf)?;
+            #decode
+        }
+    }
+
+    /// Decodes the field of type `ty` from `read_buf` into `binding`. Scaled
+    /// fields are decoded through `DecodeNumeric`, the others through their
+    /// `Serialize` implementation.
+    fn decode(&self, binding: &Ident, ty: &Type) -> TokenStream2 {
+        if self.is_plain() {
+            return quote! {
+                let (#binding, read_buf) = <#ty as quicklog::serialize::Serialize>::decode(read_buf);
+            };
+        }
+
+        let unit = self.unit.as_deref().unwrap_or_default();
+        if self.scale.is_none() && self.precision.is_none() {
+            let decode = if self.shortest {
+                quote! {
+                    <#ty as quicklog::serialize::DecodeNumeric>::decode_formatted(
+                        read_buf,
+                        quicklog::serialize::FloatFormat::Shortest,
+                    )
+                }
+            } else {
+                quote! { <#ty as quicklog::serialize::Serialize>::decode(read_buf) }
+            };
+            return quote! {
+                let (#binding, read_buf) = #decode;
+                let #binding = format!("{}{}", #binding, #unit);
+            };
+        }
+
+        let scale = self.scale.map(|scale| {
+            let scale = proc_macro2::Literal::f64_suffixed(scale);
+            quote! { * #scale }
+        });
+        // Scaled values are floats, so follow the global `FloatFormat` unless
+        // overridden
+        let value = match self.precision {
+            Some(precision) => quote! { format!("{:.*}{}", #precision, #binding #scale, #unit) },
+            None if self.shortest => quote! { format!("{}{}", #binding #scale, #unit) },
+            None => quote! {
+                format!(
+                    "{}{}",
+                    quicklog::serialize::FloatFormat::global().format(#binding #scale),
+                    #unit
+                )
+            },
+        };
+        quote! {
+            let (#binding, read_buf) = <#ty as quicklog::serialize::DecodeNumeric>::decode_f64(read_buf);
+            let #binding = #value;
+        }
+    }
+}
+
+/// How the decoded fields of a struct or enum variant are presented, chosen
+/// with the `#[serialize(transparent)]` or `#[serialize(named)]` attribute on
+/// the type
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Representation {
+    /// Fields separated by a space, e.g. `100` for `Timestamp(100)` (default
+    /// for tuple structs and enums)
+    Transparent,
+    /// Fields along with the struct name, e.g. `Timestamp(100)` or
+    /// `Order { id: 1, price: 100 }`
+    Named,
+    /// Fields along with their names, e.g. `id=1 price=100` (default for
+    /// structs with named fields)
+    Fields,
+}
+
+impl Representation {
+    /// Parses the representation given on the type, if any
+    fn parse(attrs: &[Attribute]) -> syn::Result<Option<Self>> {
+        let mut representation = None;
+        for attr in attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("serialize"))
+        {
+            attr.parse_nested_meta(|meta| {
+                let parsed = if meta.path.is_ident("transparent") {
+                    Representation::Transparent
+                } else if meta.path.is_ident("named") {
+                    Representation::Named
+                } else {
+                    return Err(meta.error(
+                        "unsupported `serialize` attribute, expected `transparent` or `named`",
+                    ));
+                };
+
+                match representation {
+                    Some(existing) if existing != parsed => {
+                        Err(meta.error("`transparent` and `named` cannot be used together"))
+                    }
+                    _ => {
+                        representation = Some(parsed);
+                        Ok(())
+                    }
+                }
+            })?;
+        }
+
+        Ok(representation)
+    }
 }
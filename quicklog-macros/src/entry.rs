@@ -0,0 +1,74 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    Expr, Ident, ItemFn, LitBool, Token,
+};
+
+/// `key = value` option of `#[quicklog::main]`
+struct EntryOption {
+    key: Ident,
+    value: Expr,
+}
+
+impl Parse for EntryOption {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key = input.parse()?;
+        input.parse::<Token![=]>()?;
+        Ok(Self {
+            key,
+            value: input.parse()?,
+        })
+    }
+}
+
+/// Initializes the logger at the start of the annotated function, and flushes
+/// it when the function returns or unwinds.
+///
+/// `flush = <expr>` sets the flusher and `panic_hook = false` leaves out the
+/// panic hook, while every other option is passed to `init_with_guard!`.
+pub(crate) fn main(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let options =
+        parse_macro_input!(attr with Punctuated::<EntryOption, Token![,]>::parse_terminated);
+    let mut item = parse_macro_input!(item as ItemFn);
+
+    let mut init_options = Vec::new();
+    let mut flush = None;
+    let mut panic_hook = true;
+    for EntryOption { key, value } in options {
+        if key == "flush" {
+            flush = Some(value);
+        } else if key == "panic_hook" {
+            match value {
+                Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Bool(LitBool { value, .. }),
+                    ..
+                }) => panic_hook = value,
+                _ => {
+                    return syn::Error::new_spanned(value, "expected `true` or `false`")
+                        .to_compile_error()
+                        .into()
+                }
+            }
+        } else {
+            init_options.push(quote! { #key = #value });
+        }
+    }
+
+    let flush: Option<TokenStream2> = flush.map(|flush| quote! { quicklog::with_flush!(#flush); });
+    let panic_hook = panic_hook.then(|| quote! { quicklog::guard::install_panic_hook(); });
+    let block = &item.block;
+    item.block = syn::parse_quote! {{
+        // dropped last, so that everything logged in the body is flushed,
+        // even when it returns early or unwinds
+        let __quicklog_guard = quicklog::init_with_guard!(#(#init_options),*);
+        #flush
+        #panic_hook
+        #block
+    }};
+
+    quote! { #item }.into()
+}
@@ -1,33 +1,145 @@
 use proc_macro::TokenStream;
-use proc_macro2::{Span, TokenStream as TokenStream2};
-use quote::{quote, ToTokens};
-use syn::{parse_macro_input, Ident};
+use proc_macro2::{Delimiter, Group, Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote, ToTokens};
+use syn::{parse_macro_input, parse_quote, Expr, ExprLit, Ident, Lifetime, Lit};
 
-use crate::args::{replace_fields_expr, Args, PrefixedArg};
+use crate::args::{replace_fields_expr, Args, BytesKind, FieldName, LazyKind, PrefixedArg};
+use crate::format_arg::FormatArg;
 use crate::Level;
 
 /// Parses token stream into the different components of `Args` and
 /// generates required tokens from the inputs
 pub(crate) fn expand(level: Level, input: TokenStream) -> TokenStream {
-    expand_parsed(level, parse_macro_input!(input as Args)).into()
+    disable_if_noop(expand_parsed(level, parse_macro_input!(input as Args), true))
 }
 
-/// Main function for expanding the components parsed from the macro call
-pub(crate) fn expand_parsed(level: Level, mut args: Args) -> TokenStream2 {
+/// Expands `audit!`, which logs at `INFO` into the audit channel, regardless
+/// of the level filter. Unlike the other logging macros, calls are kept with
+/// the `noop` feature, since audit records must never be lost.
+pub(crate) fn expand_audit(input: TokenStream) -> TokenStream {
+    let mut args = parse_macro_input!(input as Args);
+    if let Some(target) = &args.target {
+        return syn::Error::new_spanned(target, "`audit!` always logs into the audit channel")
+            .to_compile_error()
+            .into();
+    }
+    if let Some(option) = &args.skipping_option {
+        return syn::Error::new(
+            option.span(),
+            format!("`audit!` never skips records, so it does not take `{}`", option),
+        )
+        .to_compile_error()
+        .into();
+    }
+    args.target = Some(parse_quote! { quicklog::audit::__logger() });
+    expand_parsed(Level::Info, args, false).into()
+}
+
+fn disable_if_noop(expanded: TokenStream2) -> TokenStream {
+    // With the `noop` feature, calls are still type-checked, but compiled out
+    #[cfg(feature = "noop")]
+    let expanded = quote! { quicklog::__disabled_log!(#expanded) };
+
+    expanded.into()
+}
+
+/// Main function for expanding the components parsed from the macro call,
+/// checking the level of the call against the level filter if `filtered`
+pub(crate) fn expand_parsed(level: Level, mut args: Args, filtered: bool) -> TokenStream2 {
+    // Types of the arguments, in the order of their idents, returned by the
+    // trait checks when the argument is checked
+    let fmt_count = args.formatting_args.len();
+    let type_idents: Vec<_> = (0..fmt_count + args.prefixed_fields.len())
+        .map(|i| format_ident!("__quicklog_type_{}", i))
+        .collect();
     let args_traits_check: Vec<_> = args
         .prefixed_fields
         .iter()
-        .chain(args.formatting_args.iter())
-        .filter_map(|arg| match &arg.arg {
-            PrefixedArg::Debug(a) => Some(quote! { debug_check(&#a); }),
-            PrefixedArg::Display(a) => Some(quote! { display_check(&#a); }),
-            PrefixedArg::Serialize(a) => Some(quote! { serialize_check(&#a); }),
-            PrefixedArg::Normal(_) => None,
+        .zip(&type_idents[fmt_count..])
+        .chain(args.formatting_args.iter().zip(&type_idents[..fmt_count]))
+        .filter_map(|(arg, ty)| match &arg.arg {
+            PrefixedArg::Debug(a) => Some(quote! { let #ty = debug_check(&#a); }),
+            PrefixedArg::Display(a) => Some(quote! { let #ty = display_check(&#a); }),
+            PrefixedArg::Serialize(a) | PrefixedArg::Compressed(a) => {
+                Some(quote! { let #ty = serialize_check(&#a); })
+            }
+            // type-checked by the constructor of `Bytes`
+            PrefixedArg::Bytes(..) | PrefixedArg::Lazy(..) | PrefixedArg::Normal(_) => None,
         })
         .collect();
 
+    // Logged into the given instance instead of the global logger
+    let logger = match &args.target {
+        Some(target) => quote! { (#target) },
+        None => quote! { quicklog::logger() },
+    };
+
+    // Records whose `^` arguments cannot be encoded break out of the labeled
+    // block before being logged, see `EncodeErrorPolicy`
+    let encode_label = Lifetime::new("'__quicklog_log", Span::mixed_site());
+    let has_stores = args
+        .formatting_args
+        .iter()
+        .chain(args.prefixed_fields.iter())
+        .any(|arg| {
+            matches!(
+                arg.arg,
+                PrefixedArg::Serialize(_) | PrefixedArg::Compressed(_) | PrefixedArg::Bytes(..)
+            )
+        });
+    let on_encode_error = quote! {
+        #logger.record_encode_error(err);
+        break #encode_label Ok(());
+    };
+    let encode_label = has_stores.then(|| quote! { #encode_label: });
+
     let (new_idents_declaration, fmt_arg_idents, prefixed_field_idents) =
-        convert_args_to_idents(&args);
+        convert_args_to_idents(&args, &on_encode_error, &logger);
+
+    // Registered once per call site, see `quicklog::schema`
+    let arg_schemas: Vec<_> = args
+        .formatting_args
+        .iter()
+        .map(|arg| (arg, arg.name.as_ref().map(FieldName::value)))
+        .chain(
+            args.prefixed_fields
+                .iter()
+                .map(|field| (field, Some(field.name()))),
+        )
+        .zip(fmt_arg_idents.iter().chain(&prefixed_field_idents))
+        .zip(&type_idents)
+        .map(|(((arg, name), ident), ty)| {
+            let name = match name {
+                Some(name) => quote! { Some(#name) },
+                None => quote! { None },
+            };
+            let (kind, type_name) = match &arg.arg {
+                PrefixedArg::Debug(_) => (quote! { Debug }, quote! { #ty }),
+                PrefixedArg::Display(_) => (quote! { Display }, quote! { #ty }),
+                PrefixedArg::Serialize(_) => (quote! { Serialize }, quote! { #ty }),
+                PrefixedArg::Compressed(_) => (quote! { Compressed }, quote! { #ty }),
+                PrefixedArg::Bytes(kind, _) => {
+                    let kind = match kind {
+                        BytesKind::Hex => quote! { Hex },
+                        BytesKind::Base64 => quote! { Base64 },
+                        BytesKind::Len => quote! { ByteLen },
+                    };
+                    (kind, quote! { "[u8]" })
+                }
+                PrefixedArg::Lazy(..) => (
+                    quote! { Lazy },
+                    quote! { quicklog::schema::__output_type_name(&#ident.0) },
+                ),
+                PrefixedArg::Normal(_) => (
+                    quote! { Normal },
+                    quote! { quicklog::schema::__type_name_of(&#ident) },
+                ),
+            };
+            quote! {
+                quicklog::schema::ArgSchema::new(#name, quicklog::schema::ArgKind::#kind, #type_name)
+            }
+        })
+        .collect();
 
     let mut fmt_args = args.formatting_args;
     replace_fields_expr(
@@ -39,7 +151,7 @@ pub(crate) fn expand_parsed(level: Level, mut args: Args) -> TokenStream2 {
         .format_string
         .take()
         .map(|s| s.value())
-        .unwrap_or_else(String::new);
+        .unwrap_or_default();
     // Insert extra spacing between format string and format fields for prefixed fields
     // if prefixed fields exist
     // e.g. info!(?debug_struct, "hello world {}", a) -> format!("hello world {} debug_struct={:?}", a,
@@ -51,58 +163,207 @@ pub(crate) fn expand_parsed(level: Level, mut args: Args) -> TokenStream2 {
     }
     let special_fmt_str = special_fmt_str.trim_end();
 
-    // Conditionally capture trace context if feature is enabled at compile time
+    // When formatted with the alternate flag, the message and fields are
+    // written as logfmt pairs instead, e.g. ` msg="hello world" debug_struct=...`
+    let logfmt_message = if fmt_str.is_empty() {
+        quote! {}
+    } else {
+        quote! { quicklog::logfmt::__write_message(f, format_args!(#fmt_str, #fmt_args))?; }
+    };
+    // Fields are written one by one when decode hooks are registered, so that
+    // their values can be enriched
+    let hooked_fields = args
+        .prefixed_fields
+        .iter()
+        .zip(prefixed_field_idents.iter())
+        .enumerate()
+        .map(|(i, (field, ident))| {
+            let name = field.name();
+            let separator = if i == 0 && fmt_str.is_empty() { "" } else { " " };
+            let key = format!("{}{}=", separator, name);
+            let formatter = field.arg.formatter();
+            quote! {
+                f.write_str(#key)?;
+                quicklog::hooks::__write_field(f, #name, format_args!(#formatter, #ident))?;
+            }
+        })
+        .collect::<Vec<_>>();
+    // Summary of the calls skipped by a `throttle`, written after the fields
+    let write_suppressed = args.throttle.map(|_| {
+        quote! { quicklog::__write_suppressed(f, __quicklog_suppressed)?; }
+    });
+    let hooked_fields = if hooked_fields.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            if quicklog::hooks::__has_decode_hooks() {
+                #(#hooked_fields)*
+                #write_suppressed
+                return Ok(());
+            }
+        }
+    };
+
+    let logfmt_fields = args
+        .prefixed_fields
+        .iter()
+        .zip(prefixed_field_idents.iter())
+        .map(|(field, ident)| {
+            let name = field.name();
+            let formatter = field.arg.formatter();
+            quote! { quicklog::logfmt::__write_field(f, #name, format_args!(#formatter, #ident))?; }
+        });
+
+    // Conditionally capture trace context if feature is enabled at compile
+    // time, adding the record to the current span as an event
     #[cfg(feature = "trace")]
     let trace_capture = quote! {
-        let __quicklog_trace_id = {
-            if let Some(ctx) = quicklog::__FastraceSpanContext::current_local_parent() {
-                Some(ctx.trace_id.0)
-            } else {
-                None
-            }
-        };
+        let (__quicklog_trace_id, __quicklog_span_id, __quicklog_span_name) =
+            quicklog::__trace_event(#fmt_str, #level);
     };
 
     #[cfg(not(feature = "trace"))]
     let trace_capture = quote! {};
 
-    // Conditionally add trace_id field to LogRecord
+    // Conditionally add the trace context fields to LogRecord
     #[cfg(feature = "trace")]
     let trace_field = quote! {
         trace_id: __quicklog_trace_id,
+        span_id: __quicklog_span_id,
+        span_name: __quicklog_span_name,
     };
 
     #[cfg(not(feature = "trace"))]
     let trace_field = quote! {};
 
+    // Interned once per call site, and muted with the filter of the tag
+    let (tag_static, tag_check, tag) = match &args.tag {
+        Some(tag) => (
+            quote! {
+                static __QUICKLOG_TAG: quicklog::tag::__CallsiteTag =
+                    quicklog::tag::__CallsiteTag::new(#tag);
+            },
+            quote! { && __QUICKLOG_TAG.is_enabled(#level) },
+            quote! { Some(__QUICKLOG_TAG.get()) },
+        ),
+        None => (quote! {}, quote! {}, quote! { None }),
+    };
+
+    // Checked after the level, but before any argument is evaluated
+    let min_interval_check = args.min_interval.map(|nanos| {
+        quote! {
+            && {
+                static LAST_LOGGED: ::std::sync::atomic::AtomicU64 =
+                    ::std::sync::atomic::AtomicU64::new(quicklog::__NEVER_LOGGED);
+                quicklog::__min_interval_elapsed(&LAST_LOGGED, #nanos)
+            }
+        }
+    });
+
+    // Counted per call site before `min_interval` and `throttle`, so that
+    // they only see sampled calls, and before any argument is evaluated
+    let sample_check = args.sample.map(|rate| {
+        quote! {
+            && {
+                static SAMPLE_COUNT: ::std::sync::atomic::AtomicU64 =
+                    ::std::sync::atomic::AtomicU64::new(0);
+                quicklog::__sampled(&SAMPLE_COUNT, #rate)
+            }
+        }
+    });
+
+    // Checked last, so that the call site is only marked as logged once every
+    // other check passed
+    let once_check = args.once.then(|| {
+        quote! {
+            && {
+                static LOGGED: ::std::sync::atomic::AtomicBool =
+                    ::std::sync::atomic::AtomicBool::new(false);
+                !LOGGED.swap(true, ::std::sync::atomic::Ordering::Relaxed)
+            }
+        }
+    });
+
+    // Like `min_interval`, but also counts the skipped calls, which are
+    // summarized on the next logged line
+    let (throttle_static, throttle_check, throttle_suppressed) = match args.throttle {
+        Some(nanos) => (
+            quote! {
+                static __QUICKLOG_THROTTLE: quicklog::__Throttle = quicklog::__Throttle::new();
+            },
+            quote! { && __QUICKLOG_THROTTLE.allow(#nanos) },
+            quote! { let __quicklog_suppressed = __QUICKLOG_THROTTLE.take_suppressed(); },
+        ),
+        None => (quote! {}, quote! {}, quote! {}),
+    };
+
+    let level_check = if filtered {
+        quote! { quicklog::is_level_enabled!(#level) }
+    } else {
+        quote! { true }
+    };
+
     quote! {{
-        if quicklog::is_level_enabled!(#level) {
+        #tag_static
+        #throttle_static
+        if #level_check #tag_check #sample_check #min_interval_check #throttle_check #once_check { #encode_label {
             use quicklog::{Log, make_container, serialize::Serialize};
 
-            const fn debug_check<T: ::std::fmt::Debug + Clone>(_: &T) {}
-            const fn display_check<T: ::std::fmt::Display + Clone>(_: &T) {}
-            const fn serialize_check<T: Serialize>(_: &T) {}
+            fn debug_check<T: ::std::fmt::Debug + Clone>(_: &T) -> &'static str {
+                ::std::any::type_name::<T>()
+            }
+            fn display_check<T: ::std::fmt::Display + Clone>(_: &T) -> &'static str {
+                ::std::any::type_name::<T>()
+            }
+            fn serialize_check<T: Serialize>(_: &T) -> &'static str {
+                ::std::any::type_name::<T>()
+            }
 
             #(#args_traits_check)*
 
             #trace_capture
 
+            #throttle_suppressed
+
             #new_idents_declaration
 
+            static __QUICKLOG_CALLSITE: quicklog::callsite::__Callsite =
+                quicklog::callsite::__Callsite::new(
+                    quicklog::callsite::Metadata::new(#level, module_path!(), file!(), line!()),
+                );
+            let __quicklog_callsite = __QUICKLOG_CALLSITE.id();
+            static __QUICKLOG_SCHEMA: quicklog::schema::__SchemaSlot =
+                quicklog::schema::__SchemaSlot::new();
+            __QUICKLOG_SCHEMA.register(|| {
+                quicklog::schema::CallsiteSchema::new(
+                    __quicklog_callsite,
+                    #fmt_str,
+                    ::std::vec![#(#arg_schemas),*],
+                )
+            });
             let log_record = quicklog::LogRecord {
-                level: #level,
-                module_path: module_path!(),
-                file: file!(),
-                line: line!(),
+                callsite: __quicklog_callsite,
                 log_line: make_container!(quicklog::lazy_format::make_lazy_format!(|f| {
+                    if f.alternate() {
+                        #logfmt_message
+                        #(#logfmt_fields)*
+                        #write_suppressed
+                        return Ok(());
+                    }
                     write!(f, #fmt_str, #fmt_args)?;
-                    write!(f, #special_fmt_str, #(#prefixed_field_idents),*)
+                    #hooked_fields
+                    write!(f, #special_fmt_str, #(#prefixed_field_idents),*)?;
+                    #write_suppressed
+                    Ok(())
                 })),
+                tag: #tag,
+                sequence: 0,
+                metric: None,
                 #trace_field
             };
 
-            quicklog::logger().log(log_record)
-        } else {
+            #logger.log(log_record)
+        }} else {
             Ok(())
         }
         .unwrap_or(())
@@ -110,8 +371,13 @@ pub(crate) fn expand_parsed(level: Level, mut args: Args) -> TokenStream2 {
 }
 
 /// Generates new identifier tokens and their declarations for every special
-/// and formatting argument
-fn convert_args_to_idents(args: &Args) -> (TokenStream2, Vec<Ident>, Vec<Ident>) {
+/// and formatting argument, encoding `^` arguments into the byte buffer of
+/// `logger`
+fn convert_args_to_idents(
+    args: &Args,
+    on_encode_error: &TokenStream2,
+    logger: &TokenStream2,
+) -> (TokenStream2, Vec<Ident>, Vec<Ident>) {
     let mut args_to_own: Vec<TokenStream2> = Vec::new();
     let mut arg_count = 0;
 
@@ -120,19 +386,34 @@ fn convert_args_to_idents(args: &Args) -> (TokenStream2, Vec<Ident>, Vec<Ident>)
         Ident::new("x".repeat(arg_count).as_str(), Span::call_site())
     };
 
+    // Stores are only built once the argument was encoded successfully
+    let encoded = |store: TokenStream2| {
+        quote! {
+            match #store {
+                Ok(store) => store,
+                Err(err) => { #on_encode_error }
+            }
+        }
+    };
+
     let mut fmt_arg_idents = Vec::with_capacity(args.formatting_args.len());
     for fmt_arg in args.formatting_args.iter() {
         // Handle prefixes for format args
         match &fmt_arg.arg {
-            PrefixedArg::Serialize(i) => args_to_own.push(quote! {
-                quicklog::make_store!(#i)
-            }),
+            PrefixedArg::Serialize(i) => args_to_own.push(encoded(make_store(logger, i))),
+            PrefixedArg::Compressed(i) => args_to_own.push(encoded(quote! {
+                quicklog::make_compressed_store!(#logger, #i)
+            })),
+            PrefixedArg::Bytes(kind, i) => {
+                args_to_own.push(encoded(make_bytes_store(logger, *kind, i)))
+            }
             PrefixedArg::Debug(i) => args_to_own.push(quote! {
                 format!("{:?}", #i)
             }),
             PrefixedArg::Display(i) => args_to_own.push(quote! {
                 format!("{}", #i)
             }),
+            PrefixedArg::Lazy(kind, closure) => args_to_own.push(make_lazy(*kind, closure)),
             PrefixedArg::Normal(i) => args_to_own.push(i.to_token_stream()),
         }
         fmt_arg_idents.push(new_ident());
@@ -141,9 +422,14 @@ fn convert_args_to_idents(args: &Args) -> (TokenStream2, Vec<Ident>, Vec<Ident>)
     let mut prefixed_field_idents = Vec::with_capacity(args.prefixed_fields.len());
     for field in args.prefixed_fields.iter() {
         match &field.arg {
-            PrefixedArg::Serialize(i) => args_to_own.push(quote! {
-                quicklog::make_store!(#i)
-            }),
+            PrefixedArg::Serialize(i) => args_to_own.push(encoded(make_store(logger, i))),
+            PrefixedArg::Compressed(i) => args_to_own.push(encoded(quote! {
+                quicklog::make_compressed_store!(#logger, #i)
+            })),
+            PrefixedArg::Bytes(kind, i) => {
+                args_to_own.push(encoded(make_bytes_store(logger, *kind, i)))
+            }
+            PrefixedArg::Lazy(kind, closure) => args_to_own.push(make_lazy(*kind, closure)),
             _ => args_to_own.push(field.arg.to_token_stream()),
         }
         prefixed_field_idents.push(new_ident());
@@ -164,3 +450,39 @@ fn convert_args_to_idents(args: &Args) -> (TokenStream2, Vec<Ident>, Vec<Ident>)
         prefixed_field_idents,
     )
 }
+
+/// Encodes a `^` argument, queueing only the pointer and length of string
+/// literals since they live for the whole program
+fn make_store(logger: &TokenStream2, arg: &Expr) -> TokenStream2 {
+    match arg {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(_), ..
+        }) => quote! {
+            quicklog::make_store!(#logger, quicklog::serialize::StaticStr(#arg))
+        },
+        _ => quote! {
+            quicklog::make_store!(#logger, #arg)
+        },
+    }
+}
+
+/// Encodes the bytes of a `hex(^..)`, `base64(^..)` or `byte_len(^..)`
+/// argument along with how they are rendered
+fn make_bytes_store(logger: &TokenStream2, kind: BytesKind, arg: &Expr) -> TokenStream2 {
+    let constructor = kind.constructor();
+    // kept as a single operand of `&`, like a `$arg:expr` fragment
+    let arg = Group::new(Delimiter::None, arg.to_token_stream());
+    quote! {
+        quicklog::make_store!(#logger, #constructor(&#arg))
+    }
+}
+
+/// Wraps the closure of a `lazy` argument, which is only called when the
+/// record is flushed
+fn make_lazy(kind: LazyKind, closure: &Expr) -> TokenStream2 {
+    match kind {
+        LazyKind::Display => quote! { quicklog::__LazyDisplay(#closure) },
+        LazyKind::Debug => quote! { quicklog::__LazyDebug(#closure) },
+        LazyKind::Serialize => quote! { quicklog::__LazySerialize(#closure) },
+    }
+}
@@ -1,14 +1,16 @@
 use proc_macro::TokenStream;
 
 mod args;
+mod default_level;
 mod derive;
+mod entry;
 mod expand;
 mod format_arg;
 mod quicklog;
 mod selective_serialize;
 
 use derive::derive;
-use expand::expand;
+use expand::{expand, expand_audit};
 use quicklog::Level;
 
 #[proc_macro]
@@ -36,9 +38,105 @@ pub fn error(input: TokenStream) -> TokenStream {
     expand(Level::Error, input)
 }
 
+/// Logs a record into the audit channel, which never drops records, see
+/// `quicklog::audit`. Takes the same arguments as the other logging macros,
+/// except for `target:` and the options which skip calls (`min_interval:`,
+/// `throttle:`, `sample:` and `once:`), and logs at `INFO` regardless of the
+/// level filter. Calls are kept with the `noop` feature.
+///
+/// # Example
+///
+/// ```
+/// # let (order_id, qty) = (7, 100);
+/// quicklog::audit!(order_id, qty, "order filled");
+/// ```
+#[proc_macro]
+pub fn audit(input: TokenStream) -> TokenStream {
+    expand_audit(input)
+}
+
+/// Attribute macro which lowers all logging macro calls below the given
+/// level within the annotated module or function to no-ops at compile time.
+///
+/// Only the logging macros of quicklog (`trace!`, `debug!`, `info!`, `warn!`
+/// and `error!`) are lowered: calls qualified with the crate path, such as
+/// `quicklog::debug!`, and bare calls such as `debug!` when the macro is
+/// imported from quicklog by a `use` within the annotated item. Macros of the
+/// same name from `log`, `tracing` or the user are left alone. When quicklog
+/// is reached through another path, e.g. a re-export, it is given with
+/// `crate = path`, as in `#[default_level(Info, crate = my_log)]`.
+///
+/// # Example
+///
+/// ```
+/// #[quicklog::default_level(Info)]
+/// mod hot_path {
+///     pub fn run() {
+///         // compiled out
+///         quicklog::debug!("not logged");
+///         // unaffected
+///         quicklog::info!("logged");
+///     }
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn default_level(attr: TokenStream, item: TokenStream) -> TokenStream {
+    default_level::default_level(attr, item)
+}
+
+/// Attribute macro for `fn main` which initializes the logger, and flushes
+/// every queued record and the flushers' buffers when `main` returns, even
+/// early or by unwinding a panic.
+///
+/// Options are given as `key = value`: `flush` sets the flusher, every
+/// option of `init!` is supported, and the panic hook installed by default,
+/// see `quicklog::guard::install_panic_hook`, is left out with
+/// `panic_hook = false`.
+///
+/// # Example
+///
+/// ```no_run
+/// # use quicklog_flush::file_flusher::FileFlusher;
+/// #[quicklog::main(capacity = 8 * 1024, flush = FileFlusher::new("logs/app.log"))]
+/// fn main() {
+///     quicklog::info!("flushed when main returns");
+/// }
+/// ```
+#[proc_macro_attribute]
+pub fn main(attr: TokenStream, item: TokenStream) -> TokenStream {
+    entry::main(attr, item)
+}
+
 /// Derive macro for generating `quicklog` `Serialize`
 /// implementations.
-#[proc_macro_derive(Serialize)]
+///
+/// Fields of structs with named fields are decoded as `name=value` separated
+/// by a space, e.g. `id=1 price=100`, while fields of tuple structs are
+/// decoded as just their values, so a newtype such as `Timestamp(100)` is
+/// decoded as `100`. Marking the struct with `#[serialize(transparent)]`
+/// leaves out field names, decoding as `1 100`, while `#[serialize(named)]`
+/// includes the struct name instead, decoding as `Timestamp(100)`, or
+/// `Order { id: 1, price: 100 }` for structs with named fields.
+///
+/// Fields marked with `#[serialize(skip)]` are neither encoded nor decoded,
+/// e.g. large payloads or sensitive values.
+///
+/// Numeric fields can be rendered differently at decode time with
+/// `#[serialize(scale = 0.01, precision = 2, unit = " USD")]`: the value is
+/// multiplied by `scale`, printed with `precision` decimal places and followed
+/// by `unit`. Each option may be used on its own, and only `unit` works with
+/// non-numeric fields. The raw value is still what gets encoded.
+///
+/// Float fields, and scaled fields without `precision`, follow the global
+/// `FloatFormat`, e.g. fixed decimals for every price. `#[serialize(shortest)]`
+/// prints the field in its shortest representation regardless.
+///
+/// Enums with unit and data-carrying variants are supported as well. Variants
+/// are encoded as their 1-byte index in declaration order followed by their
+/// fields, and decode similar to their `Debug` representation, e.g. `Buy` or
+/// `Fill(1, 100.5)`. With `#[serialize(named)]`, the enum name is included, as
+/// in `Side::Buy`.
+#[proc_macro_derive(Serialize, attributes(serialize))]
 pub fn derive_serialize(input: TokenStream) -> TokenStream {
     derive(input)
 }
@@ -49,10 +147,56 @@ pub fn derive_serialize(input: TokenStream) -> TokenStream {
 /// This is useful for large structs where you only want to log specific fields
 /// to reduce serialization overhead in high-performance scenarios.
 ///
+/// Fields are encoded through `FixedSizeSerialize` by default. Fields whose
+/// type only implements the general `Serialize` trait, such as another struct
+/// deriving `SerializeSelective`, must be marked with `#[serialize(nested)]`.
+/// The derive cannot fall back to `Serialize` on its own: a derive macro only
+/// sees the name of the field type, not which traits it implements, and the
+/// choice decides both the encoded layout and whether `MAX_ENCODED_SIZE` can
+/// be computed at compile time. Tricks like autoref specialization would pick
+/// the `Serialize` path for every field of a generic type parameter, silently
+/// changing the layout of generic structs, so the opt-in keeps it explicit.
+/// Fields implementing neither trait can be marked with
+/// `#[serialize(with = "path")]`, naming a module with `buffer_size_required`,
+/// `encode` and `decode` functions for the field.
+///
+/// `Option` fields may be nested to any depth. Each level is encoded as a
+/// 1-byte marker, `1` for `Some` and `0` for `None`, and the value follows
+/// once every level is `Some`:
+///
+/// ```text
+/// Option<Option<u64>>
+///   None             -> [0]                  decoded as `None`
+///   Some(None)       -> [1, 0]               decoded as `Some(None)`
+///   Some(Some(5))    -> [1, 1, 5, 0, .., 0]  decoded as `Some(Some(5))`
+/// ```
+///
+/// A single `Option` keeps decoding as the value itself or `None`.
+///
+/// `f32` and `f64` fields follow the global `FloatFormat`, which can be
+/// overridden per field with `#[serialize(shortest)]` or
+/// `#[serialize(precision = 2)]`. Any fixed-size field can instead be rendered
+/// with a format string taking the decoded value, e.g. `#[serialize(fmt = "{:#x}")]`
+/// for flags. As with `derive(Serialize)`, numeric fields can be rendered
+/// with `#[serialize(scale = 0.01, precision = 2, unit = " USD")]`, the value
+/// being multiplied by `scale` and followed by `unit` when decoded only.
+///
+/// When every serialized field is fixed-size, the struct also gets an inherent
+/// `MAX_ENCODED_SIZE` constant: the encoded size with every `Option` being
+/// `Some`, which can be used to size buffers at compile time. Structs without
+/// `Option` fields return it from `buffer_size_required()` directly.
+///
+/// With `#[serialize(decode_typed)]` on the struct, a `<Name>Decoded` struct
+/// of the serialized fields is generated along with a `DecodeTyped`
+/// implementation, so that tools can reconstruct typed values from binary
+/// logs. Traits to derive for it can be listed with
+/// `#[serialize(decode_typed(derive(Debug, PartialEq)))]`.
+///
 /// # Example
 ///
-/// ```rust
+/// ```
 /// use quicklog::SerializeSelective;
+/// # pub enum OrderStatus { Open, Filled }
 ///
 /// #[derive(SerializeSelective)]
 /// pub struct Order {
@@ -64,6 +208,13 @@ pub fn derive_serialize(input: TokenStream) -> TokenStream {
 ///     pub status: OrderStatus,
 ///     pub filled_size: f64,
 /// }
+///
+/// #[derive(SerializeSelective)]
+/// pub struct Fill {
+///     // `Order` only implements `Serialize`
+///     #[serialize(nested)] pub order: Order,
+///     #[serialize] pub qty: u64,
+/// }
 /// ```
 #[proc_macro_derive(SerializeSelective, attributes(serialize))]
 pub fn derive_serialize_selective(input: TokenStream) -> TokenStream {
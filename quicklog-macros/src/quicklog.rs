@@ -6,6 +6,7 @@ use quote::{quote, ToTokens};
 // Alternative is to break out a subset of quicklog into a separate `quicklog-core`
 // crate or something similar, but that can be done in a future more general
 // refactor.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub(crate) enum Level {
     Trace = 0,
     Debug = 1,
@@ -14,6 +15,20 @@ pub(crate) enum Level {
     Error = 4,
 }
 
+impl Level {
+    /// Matches the name of a logging macro or level variant, e.g. `info` or `Info`
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "trace" | "Trace" => Some(Self::Trace),
+            "debug" | "Debug" => Some(Self::Debug),
+            "info" | "Info" => Some(Self::Info),
+            "warn" | "Warn" => Some(Self::Warn),
+            "error" | "Error" => Some(Self::Error),
+            _ => None,
+        }
+    }
+}
+
 impl ToTokens for Level {
     fn to_tokens(&self, tokens: &mut TokenStream2) {
         let tok = match self {
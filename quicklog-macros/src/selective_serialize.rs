@@ -1,6 +1,9 @@
 use proc_macro::TokenStream;
 use quote::quote;
-use syn::{parse_macro_input, Data, DeriveInput, Fields, FieldsNamed};
+use syn::{
+    parenthesized, parse_macro_input, punctuated::Punctuated, visit_mut::VisitMut, Data,
+    DeriveInput, Fields, FieldsNamed, Token,
+};
 
 /// Derives a selective Serialize implementation for structs.
 ///
@@ -14,9 +17,14 @@ use syn::{parse_macro_input, Data, DeriveInput, Fields, FieldsNamed};
 /// All primitive types (`u8`, `u16`, `u32`, `u64`, `u128`, `i8`, `i16`, `i32`, `i64`, `i128`,
 /// `usize`, `isize`, `f32`, `f64`) automatically implement this trait.
 ///
-/// For custom types, implement `FixedSizeSerialize<N>`:
+/// String-like fields (`&str`, `String` and `heapless::String<N>`, optionally
+/// wrapped in `Option`) are the exception, and are instead encoded with a length
+/// prefix through their `Serialize` implementation.
 ///
-/// ```rust
+/// For custom types, implement `FixedSizeSerialize<N>`, along with `Display`
+/// to render the decoded value:
+///
+/// ```
 /// use quicklog::serialize::FixedSizeSerialize;
 ///
 /// pub struct OrderId(u64);
@@ -29,12 +37,87 @@ use syn::{parse_macro_input, Data, DeriveInput, Fields, FieldsNamed};
 ///         Self(u64::from_le_bytes(bytes))
 ///     }
 /// }
+///
+/// impl std::fmt::Display for OrderId {
+///     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+///         write!(f, "#{}", self.0)
+///     }
+/// }
+/// ```
+///
+/// # Nested structs
+///
+/// A field whose type does not implement `FixedSizeSerialize`, but does implement
+/// the general `Serialize` trait (e.g. another struct deriving `SerializeSelective`
+/// or `Serialize`), can be marked with `#[serialize(nested)]`. Such fields are
+/// encoded through `Serialize::encode` with a variable length, and are decoded
+/// as `field={...}`.
+///
+/// ```
+/// use quicklog::SerializeSelective;
+///
+/// #[derive(SerializeSelective)]
+/// pub struct Price {
+///     #[serialize] pub px: f64,
+///     #[serialize] pub qty: u64,
+/// }
+///
+/// #[derive(SerializeSelective)]
+/// pub struct Order {
+///     #[serialize] pub oid: u64,
+///     #[serialize(nested)] pub price: Price,
+/// }
+/// ```
+///
+/// # Custom encoding
+///
+/// A field of any other type can be marked with `#[serialize(with = "path")]`,
+/// naming a module with the functions below, e.g. to truncate a `String`.
+/// Such fields are decoded as `field=...`, and as a `String` by typed decoding.
+///
+/// ```
+/// # use quicklog::SerializeSelective;
+/// mod truncated {
+///     /// Number of bytes `encode` writes: a length byte, followed by at
+///     /// most 8 bytes of the string
+///     pub fn buffer_size_required(value: &String) -> usize {
+///         1 + value.len().min(8)
+///     }
+///     /// Writes `value` into `write_buf`, which is exactly
+///     /// `buffer_size_required(value)` bytes long
+///     pub fn encode(value: &String, write_buf: &mut [u8]) {
+///         let (len, bytes) = write_buf.split_at_mut(1);
+///         len[0] = bytes.len() as u8;
+///         bytes.copy_from_slice(&value.as_bytes()[..bytes.len()]);
+///     }
+///     /// Decodes the value, returning the remainder of `read_buf`
+///     pub fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+///         let (bytes, rest) = read_buf[1..].split_at(read_buf[0] as usize);
+///         (String::from_utf8_lossy(bytes).into_owned(), rest)
+///     }
+/// }
+///
+/// #[derive(SerializeSelective)]
+/// pub struct Order {
+///     #[serialize] pub oid: u64,
+///     #[serialize(with = "truncated")] pub note: String,
+/// }
 /// ```
 ///
 /// # Example
 ///
-/// ```rust
+/// ```
 /// use quicklog::SerializeSelective;
+/// # use quicklog::serialize::FixedSizeSerialize;
+/// # pub struct OrderId(u64);
+/// # impl FixedSizeSerialize<8> for OrderId {
+/// #     fn to_le_bytes(&self) -> [u8; 8] { self.0.to_le_bytes() }
+/// #     fn from_le_bytes(bytes: [u8; 8]) -> Self { Self(u64::from_le_bytes(bytes)) }
+/// # }
+/// # impl std::fmt::Display for OrderId {
+/// #     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "#{}", self.0) }
+/// # }
+/// # pub enum OrderStatus { Open, Filled }
 ///
 /// #[derive(SerializeSelective)]
 /// pub struct Order {
@@ -55,6 +138,61 @@ use syn::{parse_macro_input, Data, DeriveInput, Fields, FieldsNamed};
 /// This approach achieves ~8-15x better encoding performance compared to individual
 /// `Serialize` trait calls, and ~111x better performance than Debug formatting.
 /// Buffer sizes are computed at compile time for optimal performance.
+///
+/// # Maximum size
+///
+/// If every serialized field is fixed-size, an inherent
+/// `pub const MAX_ENCODED_SIZE: usize` is generated, summing the `BYTE_SIZE` of
+/// each field plus one marker byte per `Option` level. String and nested fields
+/// have no upper bound, so no constant is generated for structs containing them.
+///
+/// ```
+/// # use quicklog::SerializeSelective;
+/// # #[derive(SerializeSelective)]
+/// # pub struct Order { #[serialize] pub oid: u64, #[serialize] pub price: Option<f64> }
+/// let mut buf = [0; Order::MAX_ENCODED_SIZE];
+/// # assert_eq!(buf.len(), 17);
+/// ```
+///
+/// # Typed decoding
+///
+/// With `#[serialize(decode_typed)]` on the struct, a struct named after it
+/// with a `Decoded` suffix is generated, holding the serialized fields, along
+/// with a `DecodeTyped` implementation returning it:
+///
+/// ```
+/// # use quicklog::SerializeSelective;
+/// # pub enum OrderStatus { Open, Filled }
+/// #[derive(SerializeSelective)]
+/// #[serialize(decode_typed(derive(Debug, PartialEq)))]
+/// pub struct Order<'a> {
+///     #[serialize] pub oid: u64,
+///     #[serialize] pub symbol: &'a str,
+///     pub status: OrderStatus,
+/// }
+/// ```
+///
+/// which generates:
+///
+/// ```ignore
+/// #[derive(Debug, PartialEq)]
+/// pub struct OrderDecoded {
+///     pub oid: u64,
+///     pub symbol: <&'static str as quicklog::serialize::DecodeTyped>::Decoded,
+/// }
+///
+/// impl<'a> quicklog::serialize::DecodeTyped for Order<'a> {
+///     type Decoded = OrderDecoded;
+///     fn decode_typed(read_buf: &[u8]) -> (OrderDecoded, &[u8]) { /* ... */ }
+///     fn try_decode_typed(read_buf: &[u8]) -> Result<(OrderDecoded, &[u8]), quicklog::Error> { /* ... */ }
+/// }
+/// ```
+///
+/// Fixed-size fields keep their type, while string and nested fields are
+/// decoded through their own `DecodeTyped` implementation. Only lifetime
+/// parameters are supported, as the decoded struct has no generics.
+/// `try_decode_typed` returns `Error::DecodeCorrupt` for a corrupt record
+/// instead of panicking like `decode_typed`.
 pub fn derive_selective_serialize(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
@@ -109,6 +247,37 @@ pub fn derive_selective_serialize(input: TokenStream) -> TokenStream {
         .map(|field| &field.ty)
         .collect();
 
+    let field_options = match serialize_fields
+        .iter()
+        .map(|field| parse_field_options(field))
+        .collect::<syn::Result<Vec<_>>>()
+    {
+        Ok(options) => options,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let (field_encodings, field_displays): (Vec<_>, Vec<_>) = field_options
+        .into_iter()
+        .map(|options| (options.encoding, options.display))
+        .unzip();
+
+    let decode_typed = match DecodeTypedOptions::parse(&input.attrs) {
+        Ok(options) => options,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let decode_typed_impl = match decode_typed {
+        Some(options) => match generate_decode_typed(
+            &input,
+            &options,
+            &field_names,
+            &field_types,
+            &field_encodings,
+        ) {
+            Ok(tokens) => Some(tokens),
+            Err(err) => return err.to_compile_error().into(),
+        },
+        None => None,
+    };
+
     // Split generics for impl signature
     // Note: We cannot add explicit FixedSizeSerialize<N> bounds in the where clause because:
     // 1. The const N parameter is type-dependent and cannot be expressed generically
@@ -117,15 +286,44 @@ pub fn derive_selective_serialize(input: TokenStream) -> TokenStream {
     let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
 
     // Generate encoding logic for each field
-    let encode_logic = generate_encode_logic(&field_names, &field_types);
+    let encode_logic = generate_encode_logic(&field_names, &field_types, &field_encodings);
 
     // Generate decoding logic for each field
-    let decode_logic = generate_decode_logic(&field_names, &field_types);
+    let decode_logic = generate_decode_logic(
+        &field_names,
+        &field_types,
+        &field_encodings,
+        &field_displays,
+    );
 
     // Generate buffer size calculation
-    let buffer_size_logic = generate_buffer_size_logic(&field_names, &field_types);
+    let buffer_size_logic = generate_buffer_size_logic(&field_names, &field_types, &field_encodings);
+
+    // Upper bound on the encoded size, only known when every field is fixed-size
+    let max_encoded_size = generate_max_encoded_size(&field_types, &field_encodings);
+    let max_encoded_size_impl = max_encoded_size.as_ref().map(|max_size| {
+        quote! {
+            impl #impl_generics #struct_name #ty_generics #where_clause {
+                /// Size in bytes of the encoding when every `Option` field is
+                /// `Some`, and an upper bound on `buffer_size_required()`
+                pub const MAX_ENCODED_SIZE: usize = #max_size;
+            }
+        }
+    });
+
+    // Without any Option field, the encoded size never changes
+    let has_option_field = field_types.iter().any(|ty| peel_options(ty).0 > 0);
+    let buffer_size_logic = if max_encoded_size.is_some() && !has_option_field {
+        quote! { total += Self::MAX_ENCODED_SIZE; }
+    } else {
+        buffer_size_logic
+    };
 
     let expanded = quote! {
+        #max_encoded_size_impl
+
+        #decode_typed_impl
+
         impl #impl_generics quicklog::serialize::Serialize for #struct_name #ty_generics #where_clause {
             fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (quicklog::serialize::Store<'buf>, &'buf mut [u8]) {
                 let total_size = self.buffer_size_required();
@@ -134,25 +332,29 @@ pub fn derive_selective_serialize(input: TokenStream) -> TokenStream {
                 let mut offset = 0;
                 #encode_logic
 
-                (quicklog::serialize::Store::new(Self::decode, chunk), rest)
+                (quicklog::serialize::Store::new_into(Self::decode_into, chunk), rest)
             }
 
             fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+                Self::try_decode(read_buf).unwrap()
+            }
+
+            fn buffer_size_required(&self) -> usize {
+                let mut total = 0;
+                #buffer_size_logic
+                total
+            }
+
+            fn try_decode(read_buf: &[u8]) -> ::std::result::Result<(String, &[u8]), quicklog::Error> {
                 let mut offset = 0;
                 let mut parts = Vec::new();
 
                 #decode_logic
 
                 let formatted = parts.join(" ");
-                let remaining = &read_buf[offset..];
+                let remaining = read_buf.get(offset..).ok_or(quicklog::Error::DecodeCorrupt)?;
 
-                (formatted, remaining)
-            }
-
-            fn buffer_size_required(&self) -> usize {
-                let mut total = 0;
-                #buffer_size_logic
-                total
+                Ok((formatted, remaining))
             }
         }
     };
@@ -160,39 +362,165 @@ pub fn derive_selective_serialize(input: TokenStream) -> TokenStream {
     TokenStream::from(expanded)
 }
 
+/// Describes how a `#[serialize]` field is written into the byte buffer
+enum FieldEncoding {
+    /// `#[serialize]`: fixed-size encoding through `FixedSizeSerialize`
+    FixedSize,
+    /// String-like fields (`&str`, `String`, `heapless::String<N>`), optionally
+    /// wrapped in `Option`: variable-length encoding through `Serialize`
+    Str,
+    /// `#[serialize(nested)]`: variable-length encoding through `Serialize`
+    Nested,
+    /// `#[serialize(with = "path")]`: variable-length encoding through the
+    /// `encode`, `decode` and `buffer_size_required` functions of a module
+    With(syn::Path),
+}
+
 fn has_serialize_attribute(field: &syn::Field) -> bool {
     field.attrs.iter().any(|attr| {
         attr.path().is_ident("serialize")
     })
 }
 
-fn generate_encode_logic(field_names: &[&syn::Ident], field_types: &[&syn::Type]) -> proc_macro2::TokenStream {
+/// How a fixed-size field is printed once decoded, overriding the global
+/// `FloatFormat` with `#[serialize(shortest)]` or `#[serialize(precision = N)]`,
+/// or `Display` with `#[serialize(fmt = "...")]`
+enum FieldFormat {
+    Shortest,
+    Fixed(usize),
+    /// Format string with a single argument, e.g. `{:#x}`
+    Spec(syn::LitStr),
+}
+
+/// How a fixed-size field is rendered once decoded. Encoding is not
+/// affected, so the raw value is still copied on the hot path.
+#[derive(Default)]
+struct FieldDisplay {
+    format: Option<FieldFormat>,
+    /// Factor the value is multiplied by, e.g. `0.01` for cents to dollars
+    scale: Option<f64>,
+    /// Suffix appended to the value, e.g. `bps`
+    unit: Option<String>,
+}
+
+/// Arguments of the `#[serialize]` attribute on a field
+struct FieldOptions {
+    encoding: FieldEncoding,
+    display: FieldDisplay,
+}
+
+/// Parses the arguments of the `#[serialize]` attribute on a field, if any
+fn parse_field_options(field: &syn::Field) -> syn::Result<FieldOptions> {
+    let is_string_field = is_string_type(peel_options(&field.ty).1);
+    let mut encoding = if is_string_field {
+        FieldEncoding::Str
+    } else {
+        FieldEncoding::FixedSize
+    };
+    let mut display = FieldDisplay::default();
+
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("serialize")) {
+        if let syn::Meta::Path(_) = attr.meta {
+            continue;
+        }
+
+        attr.parse_nested_meta(|meta| {
+            let format = if meta.path.is_ident("nested") || meta.path.is_ident("with") {
+                if matches!(encoding, FieldEncoding::Nested | FieldEncoding::With(_)) {
+                    return Err(meta.error("`nested` and `with` cannot both be set on a field"));
+                }
+                encoding = if meta.path.is_ident("with") {
+                    FieldEncoding::With(meta.value()?.parse::<syn::LitStr>()?.parse()?)
+                } else {
+                    FieldEncoding::Nested
+                };
+                return Ok(());
+            } else if meta.path.is_ident("shortest") {
+                FieldFormat::Shortest
+            } else if meta.path.is_ident("precision") {
+                FieldFormat::Fixed(meta.value()?.parse::<syn::LitInt>()?.base10_parse()?)
+            } else if meta.path.is_ident("fmt") {
+                FieldFormat::Spec(meta.value()?.parse()?)
+            } else if meta.path.is_ident("unit") {
+                display.unit = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+                return Ok(());
+            } else if meta.path.is_ident("scale") {
+                display.scale = Some(match meta.value()?.parse::<syn::Lit>()? {
+                    syn::Lit::Int(lit) => lit.base10_parse()?,
+                    syn::Lit::Float(lit) => lit.base10_parse()?,
+                    lit => return Err(syn::Error::new_spanned(lit, "expected a number")),
+                });
+                return Ok(());
+            } else {
+                return Err(meta.error(
+                    "unsupported `serialize` attribute, expected `nested`, `with`, `shortest`, `precision`, `fmt`, `unit` or `scale`",
+                ));
+            };
+            if display.format.replace(format).is_some() {
+                return Err(meta.error("only one of `shortest`, `precision` and `fmt` can be set on a field"));
+            }
+            Ok(())
+        })?;
+    }
+
+    let has_display =
+        display.format.is_some() || display.scale.is_some() || display.unit.is_some();
+    if has_display && !matches!(encoding, FieldEncoding::FixedSize) {
+        return Err(syn::Error::new_spanned(
+            field,
+            "`shortest`, `precision`, `fmt`, `unit` and `scale` only apply to fixed-size fields",
+        ));
+    }
+
+    Ok(FieldOptions { encoding, display })
+}
+
+fn generate_encode_logic(
+    field_names: &[&syn::Ident],
+    field_types: &[&syn::Type],
+    field_encodings: &[FieldEncoding],
+) -> proc_macro2::TokenStream {
     let mut tokens = proc_macro2::TokenStream::new();
 
-    for (name, ty) in field_names.iter().zip(field_types.iter()) {
-        let encode_field = generate_encode_field(name, ty);
+    for ((name, ty), encoding) in field_names.iter().zip(field_types.iter()).zip(field_encodings.iter()) {
+        let encode_field = generate_encode_field(name, ty, encoding);
         tokens.extend(encode_field);
     }
 
     tokens
 }
 
-fn generate_encode_field(field_name: &syn::Ident, field_type: &syn::Type) -> proc_macro2::TokenStream {
-    // Check if it's an Option type
-    if is_option_type(field_type) {
-        let inner_type = extract_option_inner_type(field_type).unwrap();
+fn generate_encode_field(
+    field_name: &syn::Ident,
+    field_type: &syn::Type,
+    encoding: &FieldEncoding,
+) -> proc_macro2::TokenStream {
+    if let FieldEncoding::With(path) = encoding {
+        return quote! {
+            // Encode field through the functions of the `with` module, into
+            // exactly the size it requires
+            let size = #path::buffer_size_required(&self.#field_name);
+            #path::encode(&self.#field_name, &mut chunk[offset..offset + size]);
+            offset += size;
+        };
+    }
+
+    if !matches!(encoding, FieldEncoding::FixedSize) {
+        return quote! {
+            // Encode variable-length field using the general Serialize trait
+            let field_buf = &mut chunk[offset..];
+            let available = field_buf.len();
+            let (_, field_rest) = quicklog::serialize::Serialize::encode(&self.#field_name, field_buf);
+            offset += available - field_rest.len();
+        };
+    }
+
+    let (depth, base_type) = peel_options(field_type);
+    if depth > 0 {
+        let encode_option = generate_encode_option(quote! { &self.#field_name }, depth, base_type);
         quote! {
-            // Encode Option<T> field using FixedSizeSerialize
-            if let Some(ref value) = self.#field_name {
-                chunk[offset] = 1; // Some marker
-                offset += 1;
-                let bytes = <#inner_type as quicklog::serialize::FixedSizeSerialize<_>>::to_le_bytes(value);
-                chunk[offset..offset + bytes.len()].copy_from_slice(&bytes);
-                offset += bytes.len();
-            } else {
-                chunk[offset] = 0; // None marker
-                offset += 1;
-            }
+            // Encode Option<T> field using FixedSizeSerialize, with a marker per Option level
+            #encode_option
         }
     } else {
         quote! {
@@ -204,67 +532,455 @@ fn generate_encode_field(field_name: &syn::Ident, field_type: &syn::Type) -> pro
     }
 }
 
-fn generate_decode_logic(field_names: &[&syn::Ident], field_types: &[&syn::Type]) -> proc_macro2::TokenStream {
+/// Encodes `value`, a reference to a value nested in `depth` Options, as a
+/// Some (1) or None (0) marker per Option level followed by the fixed-size
+/// bytes of `base_type` if every level is Some
+fn generate_encode_option(
+    value: proc_macro2::TokenStream,
+    depth: usize,
+    base_type: &syn::Type,
+) -> proc_macro2::TokenStream {
+    if depth == 0 {
+        return quote! {
+            let bytes = <#base_type as quicklog::serialize::FixedSizeSerialize<_>>::to_le_bytes(#value);
+            chunk[offset..offset + bytes.len()].copy_from_slice(&bytes);
+            offset += bytes.len();
+        };
+    }
+
+    let inner = generate_encode_option(quote! { value }, depth - 1, base_type);
+    quote! {
+        if let Some(value) = #value {
+            chunk[offset] = 1; // Some marker
+            offset += 1;
+            #inner
+        } else {
+            chunk[offset] = 0; // None marker
+            offset += 1;
+        }
+    }
+}
+
+fn generate_decode_logic(
+    field_names: &[&syn::Ident],
+    field_types: &[&syn::Type],
+    field_encodings: &[FieldEncoding],
+    field_displays: &[FieldDisplay],
+) -> proc_macro2::TokenStream {
     let mut tokens = proc_macro2::TokenStream::new();
 
-    for (name, ty) in field_names.iter().zip(field_types.iter()) {
+    for (((name, ty), encoding), display) in field_names
+        .iter()
+        .zip(field_types.iter())
+        .zip(field_encodings.iter())
+        .zip(field_displays.iter())
+    {
         let field_name_str = name.to_string();
-        let decode_field = generate_decode_field(&field_name_str, ty);
+        let decode_field = generate_decode_field(&field_name_str, ty, encoding, display);
         tokens.extend(decode_field);
     }
 
     tokens
 }
 
-fn generate_decode_field(field_name_str: &str, field_type: &syn::Type) -> proc_macro2::TokenStream {
-    if is_option_type(field_type) {
-        let inner_type = extract_option_inner_type(field_type).unwrap();
+fn generate_decode_field(
+    field_name_str: &str,
+    field_type: &syn::Type,
+    encoding: &FieldEncoding,
+    display: &FieldDisplay,
+) -> proc_macro2::TokenStream {
+    if let FieldEncoding::With(path) = encoding {
+        return quote! {
+            // Decode field through the `with` module
+            let field_buf = read_buf.get(offset..).ok_or(quicklog::Error::DecodeCorrupt)?;
+            let (value, field_rest) = #path::decode(field_buf);
+            parts.push(format!("{}={}", #field_name_str, value));
+            offset += field_buf.len() - field_rest.len();
+        };
+    }
+
+    if matches!(encoding, FieldEncoding::Nested) {
+        return quote! {
+            // Decode nested field using the general Serialize trait
+            let field_buf = read_buf.get(offset..).ok_or(quicklog::Error::DecodeCorrupt)?;
+            let (value, field_rest) = <#field_type as quicklog::serialize::Serialize>::try_decode(field_buf)?;
+            parts.push(format!("{}={{{}}}", #field_name_str, value));
+            offset += field_buf.len() - field_rest.len();
+        };
+    }
+
+    if matches!(encoding, FieldEncoding::Str) {
+        if peel_options(field_type).0 > 1 {
+            return quote! {
+                // Decode nested Options of strings through the Option<T>
+                // Serialize impl, which renders every level, e.g. `Some(None)`
+                let field_buf = read_buf.get(offset..).ok_or(quicklog::Error::DecodeCorrupt)?;
+                let (value, field_rest) = <#field_type as quicklog::serialize::Serialize>::try_decode(field_buf)?;
+                parts.push(format!("{}={}", #field_name_str, value));
+                offset += field_buf.len() - field_rest.len();
+            };
+        }
+
+        if let Some(inner_type) = extract_option_inner_type(field_type) {
+            return quote! {
+                // Decode Option<string> field, following the marker written by
+                // the Option<T> Serialize impl
+                let has_value = *read_buf.get(offset).ok_or(quicklog::Error::DecodeCorrupt)? != 0;
+                offset += 1;
+                if has_value {
+                    let field_buf = read_buf.get(offset..).ok_or(quicklog::Error::DecodeCorrupt)?;
+                    let (value, field_rest) = <#inner_type as quicklog::serialize::Serialize>::try_decode(field_buf)?;
+                    parts.push(format!("{}={}", #field_name_str, value));
+                    offset += field_buf.len() - field_rest.len();
+                } else {
+                    parts.push(format!("{}=None", #field_name_str));
+                }
+            };
+        }
+
+        return quote! {
+            // Decode string field using the general Serialize trait
+            let field_buf = read_buf.get(offset..).ok_or(quicklog::Error::DecodeCorrupt)?;
+            let (value, field_rest) = <#field_type as quicklog::serialize::Serialize>::try_decode(field_buf)?;
+            parts.push(format!("{}={}", #field_name_str, value));
+            offset += field_buf.len() - field_rest.len();
+        };
+    }
+
+    let (depth, base_type) = peel_options(field_type);
+    let formatted = generate_format_value(base_type, display);
+    let decode_value = generate_decode_value(base_type, &formatted);
+    if depth == 1 {
         quote! {
             // Decode Option<T> field using FixedSizeSerialize
-            let has_value = read_buf[offset] != 0;
+            let has_value = *read_buf.get(offset).ok_or(quicklog::Error::DecodeCorrupt)? != 0;
             offset += 1;
             if has_value {
-                let byte_size = <#inner_type as quicklog::serialize::FixedSizeSerialize<_>>::BYTE_SIZE;
-                let value = <#inner_type as quicklog::serialize::FixedSizeSerialize<_>>::from_le_bytes(
-                    read_buf[offset..offset + byte_size].try_into().unwrap()
-                );
+                let byte_size = <#base_type as quicklog::serialize::FixedSizeSerialize<_>>::BYTE_SIZE;
+                let value = #decode_value;
                 parts.push(format!("{}={}", #field_name_str, value));
                 offset += byte_size;
             } else {
                 parts.push(format!("{}=None", #field_name_str));
             }
         }
+    } else if depth > 1 {
+        let decode_option = generate_decode_option(depth, base_type, &decode_value);
+        quote! {
+            // Decode nested Options using FixedSizeSerialize, rendering every
+            // level, e.g. `Some(None)`
+            let value = #decode_option;
+            parts.push(format!("{}={}", #field_name_str, value));
+        }
     } else {
         quote! {
             // Decode direct field using FixedSizeSerialize
             let byte_size = <#field_type as quicklog::serialize::FixedSizeSerialize<_>>::BYTE_SIZE;
-            let value = <#field_type as quicklog::serialize::FixedSizeSerialize<_>>::from_le_bytes(
-                read_buf[offset..offset + byte_size].try_into().unwrap()
-            );
+            let value = #decode_value;
             parts.push(format!("{}={}", #field_name_str, value));
             offset += byte_size;
         }
     }
 }
 
-fn generate_buffer_size_logic(field_names: &[&syn::Ident], field_types: &[&syn::Type]) -> proc_macro2::TokenStream {
+/// Formats the decoded `value` of type `base_type` into a `String`. Floats,
+/// including scaled values, follow the global `FloatFormat`, and other values
+/// `Display`, unless the field overrides it.
+fn generate_format_value(
+    base_type: &syn::Type,
+    display: &FieldDisplay,
+) -> proc_macro2::TokenStream {
+    // Scaled values are read back as `f64` through `DecodeNumeric`
+    let scaled = display.scale.map(|scale| {
+        let scale = proc_macro2::Literal::f64_suffixed(scale);
+        quote! {
+            let value = <#base_type as quicklog::serialize::DecodeNumeric>::decode_f64(&bytes).0 * #scale;
+        }
+    });
+    let is_float = scaled.is_some() || is_float_type(base_type);
+    let formatted = match &display.format {
+        Some(FieldFormat::Shortest) => quote! {
+            quicklog::serialize::FloatFormat::Shortest.format(value)
+        },
+        Some(FieldFormat::Fixed(decimals)) => quote! {
+            quicklog::serialize::FloatFormat::Fixed(#decimals).format(value)
+        },
+        Some(FieldFormat::Spec(spec)) => quote! {
+            format!(#spec, value)
+        },
+        None if is_float => quote! {
+            quicklog::serialize::FloatFormat::global().format(value)
+        },
+        None => quote! { value.to_string() },
+    };
+    let formatted = match &display.unit {
+        Some(unit) => quote! { format!("{}{}", #formatted, #unit) },
+        None => formatted,
+    };
+    quote! {{
+        #scaled
+        #formatted
+    }}
+}
+
+/// Decodes the fixed-size `base_type` value at `offset` into a `String`,
+/// formatted with `formatted`, or rendered as `Unknown(..)` if its bytes are
+/// invalid, e.g. an unknown enum discriminant. Does not advance `offset`.
+fn generate_decode_value(
+    base_type: &syn::Type,
+    formatted: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    quote! {{
+        let byte_size = <#base_type as quicklog::serialize::FixedSizeSerialize<_>>::BYTE_SIZE;
+        let bytes = read_buf
+            .get(offset..offset + byte_size)
+            .ok_or(quicklog::Error::DecodeCorrupt)?
+            .try_into()
+            .unwrap();
+        match <#base_type as quicklog::serialize::FixedSizeSerialize<_>>::try_from_le_bytes(bytes) {
+            Some(value) => #formatted,
+            None => quicklog::serialize::format_unknown(&bytes),
+        }
+    }}
+}
+
+/// Decodes a value written by [`generate_encode_option`] into a `String`,
+/// decoding the innermost value with `decode_value`
+fn generate_decode_option(
+    depth: usize,
+    base_type: &syn::Type,
+    decode_value: &proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    if depth == 0 {
+        return quote! {{
+            let byte_size = <#base_type as quicklog::serialize::FixedSizeSerialize<_>>::BYTE_SIZE;
+            let value = #decode_value;
+            offset += byte_size;
+            value
+        }};
+    }
+
+    let inner = generate_decode_option(depth - 1, base_type, decode_value);
+    quote! {{
+        let has_value = *read_buf.get(offset).ok_or(quicklog::Error::DecodeCorrupt)? != 0;
+        offset += 1;
+        if has_value {
+            format!("Some({})", #inner)
+        } else {
+            "None".to_string()
+        }
+    }}
+}
+
+/// Options of the `#[serialize(decode_typed)]` attribute on the struct
+struct DecodeTypedOptions {
+    /// Traits derived for the decoded struct, given with
+    /// `#[serialize(decode_typed(derive(..)))]`
+    derives: Vec<syn::Path>,
+}
+
+impl DecodeTypedOptions {
+    /// Parses the `#[serialize]` attributes on the struct, returning `None`
+    /// if typed decoding is not enabled
+    fn parse(attrs: &[syn::Attribute]) -> syn::Result<Option<Self>> {
+        let mut options = None;
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("serialize")) {
+            attr.parse_nested_meta(|meta| {
+                if !meta.path.is_ident("decode_typed") {
+                    return Err(meta.error("unsupported `serialize` attribute, expected `decode_typed`"));
+                }
+
+                let options = options.get_or_insert(DecodeTypedOptions { derives: Vec::new() });
+                if meta.input.peek(syn::token::Paren) {
+                    meta.parse_nested_meta(|meta| {
+                        if !meta.path.is_ident("derive") {
+                            return Err(meta.error("unsupported `decode_typed` option, expected `derive`"));
+                        }
+                        let content;
+                        parenthesized!(content in meta.input);
+                        let derives = Punctuated::<syn::Path, Token![,]>::parse_terminated(&content)?;
+                        options.derives.extend(derives);
+                        Ok(())
+                    })?;
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(options)
+    }
+}
+
+/// Generates the decoded struct and `DecodeTyped` implementation enabled by
+/// `#[serialize(decode_typed)]`
+fn generate_decode_typed(
+    input: &DeriveInput,
+    options: &DecodeTypedOptions,
+    field_names: &[&syn::Ident],
+    field_types: &[&syn::Type],
+    field_encodings: &[FieldEncoding],
+) -> syn::Result<proc_macro2::TokenStream> {
+    if let Some(param) = input
+        .generics
+        .params
+        .iter()
+        .find(|param| !matches!(param, syn::GenericParam::Lifetime(_)))
+    {
+        return Err(syn::Error::new_spanned(
+            param,
+            "`decode_typed` only supports lifetime parameters",
+        ));
+    }
+
+    let struct_name = &input.ident;
+    let vis = &input.vis;
+    let decoded_name = syn::Ident::new(&format!("{}Decoded", struct_name), struct_name.span());
+    let derives = (!options.derives.is_empty()).then(|| {
+        let derives = &options.derives;
+        quote! { #[derive(#(#derives),*)] }
+    });
+    let doc = format!(
+        "Serialized fields of [`{}`], as decoded by `DecodeTyped::decode_typed`",
+        struct_name
+    );
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut decoded_types = Vec::with_capacity(field_types.len());
+    let mut decode_fields = Vec::with_capacity(field_types.len());
+    for (ty, encoding) in field_types.iter().zip(field_encodings) {
+        // The decoded struct has no lifetime parameters to borrow from
+        let mut ty = (*ty).clone();
+        StaticLifetimes.visit_type_mut(&mut ty);
+
+        if let FieldEncoding::With(path) = encoding {
+            // Only the display text of the field is known
+            decode_fields.push(quote! {{
+                let field_buf = read_buf.get(offset..).ok_or(quicklog::Error::DecodeCorrupt)?;
+                let (value, field_rest) = #path::decode(field_buf);
+                offset += field_buf.len() - field_rest.len();
+                value
+            }});
+            decoded_types.push(quote! { String });
+        } else if matches!(encoding, FieldEncoding::FixedSize) {
+            let (depth, base_type) = peel_options(&ty);
+            decode_fields.push(generate_decode_typed_option(depth, base_type));
+            decoded_types.push(quote! { #ty });
+        } else {
+            decode_fields.push(quote! {{
+                let field_buf = read_buf.get(offset..).ok_or(quicklog::Error::DecodeCorrupt)?;
+                let (value, field_rest) = <#ty as quicklog::serialize::DecodeTyped>::try_decode_typed(field_buf)?;
+                offset += field_buf.len() - field_rest.len();
+                value
+            }});
+            decoded_types.push(quote! { <#ty as quicklog::serialize::DecodeTyped>::Decoded });
+        }
+    }
+
+    Ok(quote! {
+        #[doc = #doc]
+        #derives
+        #vis struct #decoded_name {
+            #(pub #field_names: #decoded_types,)*
+        }
+
+        impl #impl_generics quicklog::serialize::DecodeTyped for #struct_name #ty_generics #where_clause {
+            type Decoded = #decoded_name;
+
+            fn decode_typed(read_buf: &[u8]) -> (Self::Decoded, &[u8]) {
+                Self::try_decode_typed(read_buf).unwrap()
+            }
+
+            fn try_decode_typed(
+                read_buf: &[u8],
+            ) -> ::std::result::Result<(Self::Decoded, &[u8]), quicklog::Error> {
+                let mut offset = 0;
+                #(let #field_names = #decode_fields;)*
+
+                let rest = read_buf.get(offset..).ok_or(quicklog::Error::DecodeCorrupt)?;
+                Ok((#decoded_name { #(#field_names),* }, rest))
+            }
+        }
+    })
+}
+
+/// Decodes a value written by [`generate_encode_option`] into its typed value
+fn generate_decode_typed_option(depth: usize, base_type: &syn::Type) -> proc_macro2::TokenStream {
+    if depth == 0 {
+        return quote! {{
+            let byte_size = <#base_type as quicklog::serialize::FixedSizeSerialize<_>>::BYTE_SIZE;
+            let bytes = read_buf
+                .get(offset..offset + byte_size)
+                .ok_or(quicklog::Error::DecodeCorrupt)?
+                .try_into()
+                .unwrap();
+            let value = <#base_type as quicklog::serialize::FixedSizeSerialize<_>>::from_le_bytes(bytes);
+            offset += byte_size;
+            value
+        }};
+    }
+
+    let inner = generate_decode_typed_option(depth - 1, base_type);
+    quote! {{
+        let has_value = *read_buf.get(offset).ok_or(quicklog::Error::DecodeCorrupt)? != 0;
+        offset += 1;
+        if has_value {
+            Some(#inner)
+        } else {
+            None
+        }
+    }}
+}
+
+/// Replaces every lifetime in a type with `'static`
+struct StaticLifetimes;
+
+impl VisitMut for StaticLifetimes {
+    fn visit_lifetime_mut(&mut self, lifetime: &mut syn::Lifetime) {
+        *lifetime = syn::Lifetime::new("'static", lifetime.span());
+    }
+}
+
+fn generate_buffer_size_logic(
+    field_names: &[&syn::Ident],
+    field_types: &[&syn::Type],
+    field_encodings: &[FieldEncoding],
+) -> proc_macro2::TokenStream {
     let mut tokens = proc_macro2::TokenStream::new();
 
-    for (name, ty) in field_names.iter().zip(field_types.iter()) {
-        let size_calc = generate_field_size_calc(name, ty);
+    for ((name, ty), encoding) in field_names.iter().zip(field_types.iter()).zip(field_encodings.iter()) {
+        let size_calc = generate_field_size_calc(name, ty, encoding);
         tokens.extend(size_calc);
     }
 
     tokens
 }
 
-fn generate_field_size_calc(field_name: &syn::Ident, field_type: &syn::Type) -> proc_macro2::TokenStream {
-    if is_option_type(field_type) {
-        let inner_type = extract_option_inner_type(field_type).unwrap();
+fn generate_field_size_calc(
+    field_name: &syn::Ident,
+    field_type: &syn::Type,
+    encoding: &FieldEncoding,
+) -> proc_macro2::TokenStream {
+    if let FieldEncoding::With(path) = encoding {
+        return quote! {
+            // Variable-length size: whatever the `with` module requires
+            total += #path::buffer_size_required(&self.#field_name);
+        };
+    }
+
+    if !matches!(encoding, FieldEncoding::FixedSize) {
+        return quote! {
+            // Variable-length size: whatever the Serialize impl requires
+            total += quicklog::serialize::Serialize::buffer_size_required(&self.#field_name);
+        };
+    }
+
+    let (depth, base_type) = peel_options(field_type);
+    if depth > 0 {
+        let option_size = generate_option_size(quote! { &self.#field_name }, depth, base_type);
         quote! {
-            // Option<T> size: 1 byte marker + 0 or BYTE_SIZE
-            // Use as_ref() to avoid moving non-Copy types
-            total += 1 + self.#field_name.as_ref().map_or(0, |_| <#inner_type as quicklog::serialize::FixedSizeSerialize<_>>::BYTE_SIZE);
+            // Option<T> size: 1 byte marker per Option level up to the first
+            // None, + BYTE_SIZE if every level is Some
+            total += #option_size;
         }
     } else {
         quote! {
@@ -274,13 +990,69 @@ fn generate_field_size_calc(field_name: &syn::Ident, field_type: &syn::Type) ->
     }
 }
 
-fn is_option_type(ty: &syn::Type) -> bool {
-    if let syn::Type::Path(type_path) = ty {
-        if let Some(segment) = type_path.path.segments.last() {
-            return segment.ident == "Option";
-        }
+/// Sum of the sizes of all fields with every Option level `Some`, or `None`
+/// if any field has a variable-length encoding
+fn generate_max_encoded_size(
+    field_types: &[&syn::Type],
+    field_encodings: &[FieldEncoding],
+) -> Option<proc_macro2::TokenStream> {
+    if field_encodings.iter().any(|encoding| !matches!(encoding, FieldEncoding::FixedSize)) {
+        return None;
+    }
+
+    let sizes = field_types.iter().map(|ty| {
+        let (depth, base_type) = peel_options(ty);
+        quote! { #depth + <#base_type as quicklog::serialize::FixedSizeSerialize<_>>::BYTE_SIZE }
+    });
+    Some(quote! { 0 #(+ #sizes)* })
+}
+
+/// Size of `value`, a reference to a value nested in `depth` Options, as
+/// encoded by [`generate_encode_option`]
+fn generate_option_size(
+    value: proc_macro2::TokenStream,
+    depth: usize,
+    base_type: &syn::Type,
+) -> proc_macro2::TokenStream {
+    if depth == 0 {
+        return quote! { <#base_type as quicklog::serialize::FixedSizeSerialize<_>>::BYTE_SIZE };
+    }
+
+    let inner = generate_option_size(quote! { value }, depth - 1, base_type);
+    // Use as_ref() to avoid moving non-Copy types
+    quote! { 1 + (#value).as_ref().map_or(0, |value| #inner) }
+}
+
+/// Matches `&str`, `String` and fixed-capacity strings such as `heapless::String<N>`
+fn is_string_type(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Reference(type_ref) => matches!(
+            &*type_ref.elem,
+            syn::Type::Path(type_path) if type_path.path.is_ident("str")
+        ),
+        syn::Type::Path(type_path) => type_path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "String"),
+        _ => false,
+    }
+}
+
+/// Matches `f32` and `f64`
+fn is_float_type(ty: &syn::Type) -> bool {
+    matches!(ty, syn::Type::Path(type_path) if type_path.path.is_ident("f32") || type_path.path.is_ident("f64"))
+}
+
+/// Strips every level of `Option` off `ty`, returning the number of levels
+/// along with the innermost type, e.g. `(2, u64)` for `Option<Option<u64>>`
+fn peel_options(mut ty: &syn::Type) -> (usize, &syn::Type) {
+    let mut depth = 0;
+    while let Some(inner_ty) = extract_option_inner_type(ty) {
+        depth += 1;
+        ty = inner_ty;
     }
-    false
+    (depth, ty)
 }
 
 fn extract_option_inner_type(ty: &syn::Type) -> Option<&syn::Type> {
@@ -0,0 +1,141 @@
+use std::fmt::Write;
+
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use criterion::{black_box, criterion_group, criterion_main, Bencher, Criterion};
+use quanta::Instant;
+use quicklog::layout::LayoutFormatter;
+use quicklog::timestamp::{CachedTimestamp, TimestampFormat};
+use quicklog::{info, with_flush, with_formatter, LogRecord, PatternFormatter};
+use quicklog_flush::noop_flusher::NoopFlusher;
+
+const RECORDS: u64 = 1_000;
+
+/// Timestamps 1µs apart, mostly within the same second
+fn times() -> Vec<DateTime<Utc>> {
+    let start = Utc.timestamp_opt(1_698_048_000, 0).unwrap();
+    (0..RECORDS as i64)
+        .map(|i| start + Duration::microseconds(i))
+        .collect()
+}
+
+/// Formats like `[{ts}]{msg}`, rendering the whole timestamp of every record
+struct UncachedFormatter {
+    timestamp_format: TimestampFormat,
+}
+
+impl PatternFormatter for UncachedFormatter {
+    fn custom_format(&mut self, time: DateTime<Utc>, log_record: LogRecord) -> String {
+        format!(
+            "[{}]{}\n",
+            self.timestamp_format.display(time),
+            log_record.log_line
+        )
+    }
+
+    fn set_timestamp_format(&mut self, format: TimestampFormat) {
+        self.timestamp_format = format;
+    }
+}
+
+fn bench_display_timestamp(b: &mut Bencher) {
+    let format = TimestampFormat::Rfc3339;
+    let times = times();
+    let mut times = times.iter().cycle();
+    let mut line = String::new();
+    b.iter(|| {
+        line.clear();
+        let _ = write!(line, "{}", format.display(*times.next().unwrap()));
+        black_box(line.len())
+    });
+}
+
+fn bench_cached_timestamp(b: &mut Bencher) {
+    let mut timestamp = CachedTimestamp::new(TimestampFormat::Rfc3339);
+    let times = times();
+    let mut times = times.iter().cycle();
+    let mut line = String::new();
+    b.iter(|| {
+        line.clear();
+        timestamp.write(&mut line, *times.next().unwrap());
+        black_box(line.len())
+    });
+}
+
+/// Times flushing `RECORDS` simple records, logged beforehand
+fn bench_flush(b: &mut Bencher) {
+    b.iter_custom(|iters| {
+        let mut elapsed = std::time::Duration::ZERO;
+        for _ in 0..iters {
+            for i in 0..RECORDS {
+                info!("order filled id={}", i);
+            }
+
+            let start = Instant::now();
+            black_box(quicklog::flush_all!());
+            elapsed += Instant::now() - start;
+        }
+        elapsed
+    });
+}
+
+/// Times logging `RECORDS` simple records into the queue, which is drained
+/// between iterations
+fn bench_enqueue(b: &mut Bencher) {
+    b.iter_custom(|iters| {
+        let mut elapsed = std::time::Duration::ZERO;
+        for _ in 0..iters {
+            let start = Instant::now();
+            for i in 0..RECORDS {
+                info!("order filled id={}", i);
+            }
+            elapsed += Instant::now() - start;
+
+            black_box(quicklog::flush_all!());
+        }
+        elapsed
+    });
+}
+
+fn bench_flush_uncached(b: &mut Bencher) {
+    with_formatter!(UncachedFormatter {
+        timestamp_format: TimestampFormat::default(),
+    });
+    bench_flush(b);
+}
+
+fn bench_flush_cached(b: &mut Bencher) {
+    with_formatter!(LayoutFormatter::new("[{ts}]{msg}").unwrap());
+    bench_flush(b);
+}
+
+fn bench_timestamps(c: &mut Criterion) {
+    quicklog::init!(capacity = 1024 * 1024);
+    with_flush!(NoopFlusher);
+
+    let mut group = c.benchmark_group("Timestamps");
+    group.bench_function("display rfc3339", bench_display_timestamp);
+    group.bench_function("cached rfc3339", bench_cached_timestamp);
+    group.finish();
+
+    // Queue layout history (median, noisy single-core machine):
+    // - `VecDeque` -> padded ring: enqueue 82.4 -> 85.4 µs, dequeue and
+    //   flush 247 -> 173 µs.
+    // - Same session re-measure of the padded ring (unsynchronized):
+    //   92.3 µs / 201 µs; atomic SPSC with padded head, released and tail:
+    //   96.6 µs / 250 µs; plus cached opposite positions: 89.1 µs / 218 µs.
+    // Both halves run on one thread here, so the cached positions only
+    // save the atomic loads; the cross-core traffic they avoid doesn't
+    // show up in this benchmark.
+    let mut group = c.benchmark_group("Queue 1000 records");
+    group.bench_function("enqueue", bench_enqueue);
+    group.bench_function("dequeue and flush", bench_flush_cached);
+    group.finish();
+
+    let mut group = c.benchmark_group("Flush 1000 records");
+    group.bench_function("uncached timestamps", bench_flush_uncached);
+    group.bench_function("cached timestamps", bench_flush_cached);
+    group.finish();
+}
+
+criterion_group!(benches, bench_timestamps);
+criterion_main!(benches);
@@ -1,10 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, Bencher, Criterion};
 use delog::render::DefaultRenderer;
 use quanta::Instant;
-use quicklog::{
-    serialize::{Serialize, Store},
-    with_flush,
-};
+use quicklog::with_flush;
 use quicklog_flush::noop_flusher::NoopFlusher;
 
 macro_rules! loop_with_cleanup {
@@ -31,12 +28,14 @@ macro_rules! loop_with_cleanup {
     }};
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone, Copy)]
 enum Side {
     Bid,
     Ask,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone, Copy)]
 struct SmallStruct {
     symbol: &'static str,
@@ -44,6 +43,7 @@ struct SmallStruct {
     side: Side,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone, Copy)]
 struct BigStruct {
     vec: [i32; 100],
@@ -27,6 +27,7 @@ macro_rules! loop_with_cleanup {
     }};
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Clone, Copy)]
 struct Order {
     id: u64,
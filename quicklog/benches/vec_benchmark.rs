@@ -54,7 +54,7 @@ fn bench_vec_u32_100_serialize(b: &mut Bencher) {
 
 fn bench_vec_string_10_clone(b: &mut Bencher) {
     let vec: Vec<String> = black_box(
-        vec!["AAPL", "GOOGL", "MSFT", "AMZN", "TSLA", "META", "NVDA", "AMD", "INTC", "NFLX"]
+        ["AAPL", "GOOGL", "MSFT", "AMZN", "TSLA", "META", "NVDA", "AMD", "INTC", "NFLX"]
             .iter()
             .map(|s| s.to_string())
             .collect(),
@@ -111,6 +111,7 @@ fn bench_vec_string_100_serialize(b: &mut Bencher) {
 // ============================================================================
 
 // Use the same Order struct from selective_serialization_benchmark for consistency
+#[allow(dead_code)]
 #[derive(Clone, Debug, SerializeSelective)]
 struct Order {
     #[serialize]
@@ -188,6 +189,7 @@ fn bench_vec_order_100_serialize(b: &mut Bencher) {
 // Benchmark 4: Vec<SelectiveOrder> (Selective Serialization)
 // ============================================================================
 
+#[allow(dead_code)]
 #[derive(Clone, Debug, SerializeSelective)]
 struct SelectiveOrder {
     #[serialize] id: u64,
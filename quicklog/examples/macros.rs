@@ -40,7 +40,11 @@ impl PatternFormatter for CustomFormatter {
     ) -> String {
         format!(
             "[{:?}][{}][{}][{}]{}\n",
-            time, log_record.file, log_record.line, log_record.level, log_record.log_line,
+            time,
+            log_record.file(),
+            log_record.line(),
+            log_record.level(),
+            log_record.log_line,
         )
     }
 }
@@ -0,0 +1,58 @@
+// Soak test which hammers the logging queue with the different producer
+// patterns under every queue full policy, and checks that every record is
+// accounted for.
+//
+// Run with:
+//   cargo run --release --example stress --features stress -- <records> <payload_len>
+
+use quicklog::queue::QueueFullPolicy;
+use quicklog::stress::{run, ProducerPattern, StressConfig};
+use quicklog::{init, with_flush};
+use quicklog_flush::noop_flusher::NoopFlusher;
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let records: usize = args
+        .next()
+        .map(|s| s.parse().expect("records should be a number"))
+        .unwrap_or(2 * quicklog::MAX_LOGGER_CAPACITY);
+    let payload_len: usize = args
+        .next()
+        .map(|s| s.parse().expect("payload_len should be a number"))
+        .unwrap_or(64);
+
+    init!();
+    // Swap this out for the flusher you want to validate
+    with_flush!(NoopFlusher);
+
+    let patterns = [
+        ProducerPattern::Burst { records },
+        ProducerPattern::Interleaved {
+            records,
+            logs_per_flush: 2,
+        },
+        ProducerPattern::Bursts {
+            bursts: 10,
+            burst_size: records / 10,
+            flushes_between: records / 20,
+        },
+    ];
+
+    let policies = [
+        QueueFullPolicy::DropNewest,
+        QueueFullPolicy::DropOldest,
+        QueueFullPolicy::Block,
+    ];
+
+    for policy in policies {
+        quicklog::logger().use_queue_full_policy(policy);
+        for pattern in patterns {
+            let report = run(&StressConfig {
+                pattern,
+                payload_len,
+            });
+            println!("{:?} {:?}\n  {}", policy, pattern, report);
+            assert!(report.is_consistent(), "records were lost: {}", report);
+        }
+    }
+}
@@ -26,7 +26,7 @@ fn main() {
     info!("Large vector: {}", ^large_vec);
 
     // Example 6: Vector of floats
-    let measurements: Vec<f64> = vec![1.5, 2.7, 3.14159, 4.2];
+    let measurements: Vec<f64> = vec![1.5, 2.7, 3.25, 4.2];
     info!("Measurements: {}", ^measurements);
 
     // Flush all log lines
@@ -0,0 +1,183 @@
+//! Defines [`AdaptiveVerbosity`], a policy which temporarily logs more
+//! verbosely from a target after a burst of errors from it, to capture the
+//! context leading up to the next ones.
+//!
+//! ```
+//! # use std::time::Duration;
+//! # use quicklog::{adaptive::AdaptiveVerbosity, init, level::{set_max_level, LevelFilter}, with_adaptive_verbosity};
+//! init!();
+//! set_max_level(LevelFilter::Info);
+//!
+//! // after 5 errors within 10s from a module, log its debug records for 30s
+//! with_adaptive_verbosity!(AdaptiveVerbosity::new(5, Duration::from_secs(10))
+//!     .with_boost(LevelFilter::Debug, Duration::from_secs(30)));
+//! ```
+//!
+//! Targets are module paths: errors are counted per module path, and records
+//! from the module and its submodules are boosted. While a target is boosted,
+//! the global level set with [`set_max_level`] is lowered to the boosted
+//! level, and records of other targets below the level the policy was
+//! installed with are dropped by the logger instead of at their call site.
+//! The global level is restored once no target is boosted anymore.
+//!
+//! Times are measured on the logger's clock.
+//!
+//! [`set_max_level`]: crate::level::set_max_level
+
+use std::{
+    collections::{HashMap, VecDeque},
+    time::Duration,
+};
+
+use crate::{
+    callsite::Metadata,
+    is_within_target,
+    level::{max_level, set_max_level, Level, LevelFilter},
+};
+
+/// Raises the verbosity of a target for a while after a burst of errors from
+/// it, see the [module docs](self)
+pub struct AdaptiveVerbosity {
+    /// Number of errors within `window` which boosts a target
+    errors: usize,
+    window: u64,
+    boost_level: LevelFilter,
+    boost_for: u64,
+    /// Global level when the policy was installed, restored once no target
+    /// is boosted
+    base: LevelFilter,
+    /// Times of the recent errors of every target, oldest first
+    recent_errors: HashMap<&'static str, VecDeque<u64>>,
+    /// Boosted targets along with the time their boost ends
+    boosted: Vec<(&'static str, u64)>,
+}
+
+impl AdaptiveVerbosity {
+    /// Boosts a target after `errors` errors from it within `window`, logging
+    /// its debug records for 60 seconds by default
+    pub fn new(errors: usize, window: Duration) -> Self {
+        Self {
+            errors: errors.max(1),
+            window: duration_nanos(window),
+            boost_level: LevelFilter::Debug,
+            boost_for: duration_nanos(Duration::from_secs(60)),
+            base: LevelFilter::Trace,
+            recent_errors: HashMap::new(),
+            boosted: Vec::new(),
+        }
+    }
+
+    /// Sets the level records of boosted targets are logged from, and for
+    /// how long
+    pub fn with_boost(mut self, level: LevelFilter, duration: Duration) -> Self {
+        self.boost_level = level;
+        self.boost_for = duration_nanos(duration);
+        self
+    }
+
+    /// Targets which are currently boosted, as of the last logged record
+    pub fn boosted_targets(&self) -> impl Iterator<Item = &'static str> + '_ {
+        self.boosted.iter().map(|(target, _)| *target)
+    }
+
+    /// Remembers the current global level as the one to restore
+    pub(crate) fn install(&mut self) {
+        self.base = max_level();
+    }
+
+    /// Restores the global level if a target is still boosted
+    pub(crate) fn uninstall(&mut self) {
+        if !self.boosted.is_empty() {
+            self.boosted.clear();
+            set_max_level(self.base);
+        }
+    }
+
+    /// Counts errors and updates boosts, returning whether a record from the
+    /// call site of `metadata` logged at `now` is kept
+    pub(crate) fn admit(&mut self, metadata: &Metadata, now: u64) -> bool {
+        let had_boosts = !self.boosted.is_empty();
+        self.boosted.retain(|&(_, until)| until > now);
+        if had_boosts && self.boosted.is_empty() {
+            set_max_level(self.base);
+        }
+
+        let level = metadata.level();
+        if level >= Level::Error {
+            self.record_error(metadata.module_path(), now);
+        }
+
+        self.base.enables(level)
+            || (self.boost_level.enables(level)
+                && self
+                    .boosted
+                    .iter()
+                    .any(|(target, _)| is_within_target(metadata.module_path(), target)))
+    }
+
+    fn record_error(&mut self, target: &'static str, now: u64) {
+        let errors = self.recent_errors.entry(target).or_default();
+        errors.push_back(now);
+        while errors
+            .front()
+            .is_some_and(|&time| now.saturating_sub(time) > self.window)
+        {
+            errors.pop_front();
+        }
+        if errors.len() < self.errors {
+            return;
+        }
+
+        errors.clear();
+        let until = now.saturating_add(self.boost_for);
+        match self.boosted.iter_mut().find(|(t, _)| *t == target) {
+            Some((_, boosted_until)) => *boosted_until = until,
+            None => self.boosted.push((target, until)),
+        }
+        if (self.boost_level as u8) < (max_level() as u8) {
+            set_max_level(self.boost_level);
+        }
+    }
+}
+
+fn duration_nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static GATEWAY_ERROR: Metadata = Metadata::new(Level::Error, "app::gateway", "", 0);
+    static GATEWAY_DEBUG: Metadata = Metadata::new(Level::Debug, "app::gateway::fix", "", 0);
+    static BOOK_DEBUG: Metadata = Metadata::new(Level::Debug, "app::book", "", 0);
+
+    const SECOND: u64 = 1_000_000_000;
+
+    #[test]
+    fn boosts_after_error_burst() {
+        let mut policy = AdaptiveVerbosity::new(3, Duration::from_secs(10))
+            .with_boost(LevelFilter::Debug, Duration::from_secs(30));
+        policy.base = LevelFilter::Info;
+
+        // errors outside of the window do not add up
+        assert!(policy.admit(&GATEWAY_ERROR, 0));
+        assert!(policy.admit(&GATEWAY_ERROR, 11 * SECOND));
+        assert!(policy.admit(&GATEWAY_ERROR, 12 * SECOND));
+        assert!(!policy.admit(&GATEWAY_DEBUG, 12 * SECOND));
+        assert_eq!(policy.boosted_targets().count(), 0);
+
+        assert!(policy.admit(&GATEWAY_ERROR, 13 * SECOND));
+        assert_eq!(
+            policy.boosted_targets().collect::<Vec<_>>(),
+            ["app::gateway"]
+        );
+        // submodules are boosted, other targets are not
+        assert!(policy.admit(&GATEWAY_DEBUG, 14 * SECOND));
+        assert!(!policy.admit(&BOOK_DEBUG, 14 * SECOND));
+
+        // reverts once the boost ends
+        assert!(!policy.admit(&GATEWAY_DEBUG, 43 * SECOND));
+        assert_eq!(policy.boosted_targets().count(), 0);
+    }
+}
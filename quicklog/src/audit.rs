@@ -0,0 +1,228 @@
+//! Audit channel, a second logger which never drops records, for regulatory
+//! order and trade audit trails which must not be lost even under extreme
+//! load, while the global logger keeps its lossy fast path.
+//!
+//! Records logged with [`audit!`] are queued separately from every other
+//! record, and flushed into their own sink, `logs/audit.log` unless set with
+//! [`with_audit_flush!`]. They are never filtered by level, and instead of
+//! dropping records when the queue is full, the producer blocks until audit
+//! records are flushed on another thread. Lines which fail to flush are kept,
+//! and written out first on the next flush, and once too many of them are
+//! kept, flushing blocks until the sink accepts them again.
+//!
+//! Unlike every other logging call, [`audit!`] is not compiled out by the
+//! `noop` feature, and [`with_audit_flush!`] and [`flush_audit!`] keep
+//! working, so that a build without logging still keeps its audit trail.
+//!
+//! ```
+//! # use quicklog::{audit, flush_audit, with_audit_flush};
+//! # use quicklog_flush::noop_flusher::NoopFlusher;
+//! with_audit_flush!(NoopFlusher);
+//!
+//! # let (order_id, qty) = (7, 100);
+//! audit!(order_id, qty, "order filled");
+//! assert_eq!(flush_audit!(), 1);
+//! ```
+//!
+//! [`audit!`]: crate::audit!
+//! [`with_audit_flush!`]: crate::with_audit_flush
+//! [`flush_audit!`]: crate::flush_audit
+
+use std::{collections::VecDeque, io, thread, time::Duration};
+
+use once_cell::unsync::Lazy;
+use quicklog_flush::{file_flusher::FileFlusher, Flush};
+
+use crate::{config::Config, level::Level, queue::QueueFullPolicy, Quicklog};
+
+/// Logger of the audit channel, created on first use
+static mut AUDIT_LOGGER: Lazy<Quicklog> = Lazy::new(|| {
+    Quicklog::new(
+        Config::new()
+            .with_queue_full_policy(QueueFullPolicy::Block)
+            .with_flush(RetryingFlusher::new(Box::new(FileFlusher::new(
+                "logs/audit.log",
+            )))),
+    )
+});
+
+/// **Internal API**
+///
+/// Returns a mut reference to the logger of the audit channel
+#[doc(hidden)]
+pub fn __logger() -> &'static mut Quicklog {
+    unsafe { &mut *std::ptr::addr_of_mut!(AUDIT_LOGGER) }
+}
+
+/// Sets the sink of the audit channel, used in [`with_audit_flush!`]
+///
+/// [`with_audit_flush!`]: crate::with_audit_flush
+#[doc(hidden)]
+pub fn __use_flush(flush: Box<dyn Flush>) {
+    __logger().use_flush(Box::new(RetryingFlusher::new(flush)))
+}
+
+/// Flushes the audit channel if anything was logged into it, used by
+/// [`FlushGuard`](crate::FlushGuard)
+pub(crate) fn flush_if_used() {
+    let logger = unsafe { &mut *std::ptr::addr_of_mut!(AUDIT_LOGGER) };
+    if let Some(logger) = Lazy::get_mut(logger) {
+        logger.flush_all();
+        let _ = logger.flush_buffered();
+    }
+}
+
+/// Number of lines which failed to flush kept by [`RetryingFlusher`], beyond
+/// which flushing blocks until the sink accepts lines again
+const MAX_PENDING_LINES: usize = 64 * 1024;
+
+/// Time waited between attempts to write the pending lines once there are
+/// [`MAX_PENDING_LINES`] of them
+const RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Keeps the lines which failed to flush, and writes them out before any
+/// later line, so that no audit record is lost to a transient I/O error
+struct RetryingFlusher {
+    inner: Box<dyn Flush>,
+    pending: VecDeque<(Level, String)>,
+    max_pending: usize,
+}
+
+impl RetryingFlusher {
+    fn new(inner: Box<dyn Flush>) -> RetryingFlusher {
+        RetryingFlusher::with_max_pending(inner, MAX_PENDING_LINES)
+    }
+
+    fn with_max_pending(inner: Box<dyn Flush>, max_pending: usize) -> RetryingFlusher {
+        RetryingFlusher {
+            inner,
+            pending: VecDeque::new(),
+            max_pending,
+        }
+    }
+
+    /// Writes the pending lines in order, stopping at the first failure
+    fn retry(&mut self) -> io::Result<()> {
+        while let Some((level, line)) = self.pending.front() {
+            // the line is only dropped once written
+            self.inner.try_flush_record(*level, line.clone())?;
+            self.pending.pop_front();
+        }
+        Ok(())
+    }
+}
+
+impl Flush for RetryingFlusher {
+    fn flush_one(&mut self, display: String) {
+        let _ = self.try_flush_one(display);
+    }
+
+    fn try_flush_one(&mut self, display: String) -> io::Result<()> {
+        self.try_flush_record(Level::Info, display)
+    }
+
+    /// Fails while lines are still pending, including `display`. Blocks
+    /// while `max_pending` lines are pending, rather than dropping any.
+    fn try_flush_record(&mut self, level: Level, display: String) -> io::Result<()> {
+        while self.pending.len() >= self.max_pending {
+            if self.retry().is_err() {
+                thread::sleep(RETRY_INTERVAL);
+            }
+        }
+        self.pending.push_back((level, display));
+        self.retry()
+    }
+
+    fn flush_buffered(&mut self) -> io::Result<()> {
+        self.retry()?;
+        self.inner.flush_buffered()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::*;
+
+    /// Fails to flush while `failing` is set
+    struct FlakyFlusher {
+        lines: Rc<RefCell<Vec<String>>>,
+        failing: Rc<RefCell<bool>>,
+    }
+
+    impl Flush for FlakyFlusher {
+        fn flush_one(&mut self, display: String) {
+            self.lines.borrow_mut().push(display)
+        }
+
+        fn try_flush_one(&mut self, display: String) -> io::Result<()> {
+            if *self.failing.borrow() {
+                return Err(io::ErrorKind::BrokenPipe.into());
+            }
+            self.flush_one(display);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn retries_failed_lines_in_order() {
+        let lines = Rc::new(RefCell::new(Vec::new()));
+        let failing = Rc::new(RefCell::new(true));
+        let mut flusher = RetryingFlusher::new(Box::new(FlakyFlusher {
+            lines: lines.clone(),
+            failing: failing.clone(),
+        }));
+
+        assert!(flusher.try_flush_one("first".to_string()).is_err());
+        assert!(flusher.try_flush_one("second".to_string()).is_err());
+        assert!(lines.borrow().is_empty());
+
+        *failing.borrow_mut() = false;
+        flusher.try_flush_one("third".to_string()).unwrap();
+        assert_eq!(*lines.borrow(), ["first", "second", "third"]);
+        assert!(flusher.pending.is_empty());
+    }
+
+    /// Fails to flush the first `failures` times
+    struct RecoveringFlusher {
+        lines: Rc<RefCell<Vec<String>>>,
+        failures: usize,
+    }
+
+    impl Flush for RecoveringFlusher {
+        fn flush_one(&mut self, display: String) {
+            self.lines.borrow_mut().push(display)
+        }
+
+        fn try_flush_one(&mut self, display: String) -> io::Result<()> {
+            if self.failures > 0 {
+                self.failures -= 1;
+                return Err(io::ErrorKind::BrokenPipe.into());
+            }
+            self.flush_one(display);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn blocks_once_too_many_lines_are_pending() {
+        let lines = Rc::new(RefCell::new(Vec::new()));
+        let mut flusher = RetryingFlusher::with_max_pending(
+            Box::new(RecoveringFlusher {
+                lines: lines.clone(),
+                failures: 4,
+            }),
+            2,
+        );
+
+        assert!(flusher.try_flush_one("first".to_string()).is_err());
+        assert!(flusher.try_flush_one("second".to_string()).is_err());
+        assert_eq!(flusher.pending.len(), 2);
+
+        // retried until the sink recovers instead of keeping a third line
+        flusher.try_flush_one("third".to_string()).unwrap();
+        assert_eq!(*lines.borrow(), ["first", "second", "third"]);
+        assert!(flusher.pending.is_empty());
+    }
+}
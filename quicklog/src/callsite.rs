@@ -0,0 +1,298 @@
+//! Static metadata of logging call sites, and filters over it.
+//!
+//! Every logging macro call embeds a [`Metadata`] static holding its level,
+//! module path, file and line, which is registered under a [`CallsiteId`] the
+//! first time the call site is reached. Records only carry that 4-byte id
+//! instead of the location, and formatters look the metadata up through
+//! [`LogRecord::metadata`]. [`with_filter!`] drops records by it before they
+//! are queued, and [`callsite_stats`] counts the records logged from every
+//! call site.
+//!
+//! ```
+//! # use quicklog::{callsite::Metadata, info, init, level::Level, with_filter};
+//! init!();
+//!
+//! // only keep records logged from the gateway
+//! with_filter!(|metadata: &Metadata| metadata.module_path().starts_with("gateway"));
+//!
+//! // dropped, logged from the doctest's module
+//! info!("hello");
+//! ```
+//!
+//! [`LogRecord::metadata`]: crate::LogRecord::metadata
+//! [`with_filter!`]: crate::with_filter
+//! [`callsite_stats`]: crate::callsite_stats
+
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    ptr,
+    sync::{
+        atomic::{AtomicPtr, AtomicU32, AtomicU64, Ordering},
+        Mutex, PoisonError,
+    },
+};
+
+use chrono::{DateTime, Utc};
+use quicklog_clock::to_system_time;
+
+use crate::level::Level;
+
+/// Number of call sites the registry holds
+pub const MAX_CALLSITES: usize = 1 << 16;
+
+/// Metadata of the registered call sites by id, null meaning not registered
+static CALLSITES: [AtomicPtr<Metadata>; MAX_CALLSITES] =
+    [const { AtomicPtr::new(ptr::null_mut()) }; MAX_CALLSITES];
+/// Ids of the registered call sites, by address of their metadata
+static IDS: Mutex<BTreeMap<usize, u32>> = Mutex::new(BTreeMap::new());
+/// Counters of the records logged from the registered call sites, by id
+static COUNTERS: [Counters; MAX_CALLSITES] = [const { Counters::new() }; MAX_CALLSITES];
+
+/// Counters of the records logged from a call site, see [`CallsiteStats`]
+struct Counters {
+    hits: AtomicU64,
+    dropped: AtomicU64,
+    /// Nanoseconds since the unix epoch, `0` if never hit
+    last_hit: AtomicU64,
+}
+
+impl Counters {
+    const fn new() -> Counters {
+        Counters {
+            hits: AtomicU64::new(0),
+            dropped: AtomicU64::new(0),
+            last_hit: AtomicU64::new(0),
+        }
+    }
+}
+
+/// Decides whether records from a call site are logged, see [`with_filter!`]
+///
+/// [`with_filter!`]: crate::with_filter
+pub type CallsiteFilter = Box<dyn Fn(&Metadata) -> bool>;
+
+/// Level and source location of a logging call site, known at compile time
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Metadata {
+    level: Level,
+    module_path: &'static str,
+    file: &'static str,
+    line: u32,
+}
+
+impl Metadata {
+    pub const fn new(
+        level: Level,
+        module_path: &'static str,
+        file: &'static str,
+        line: u32,
+    ) -> Self {
+        Self {
+            level,
+            module_path,
+            file,
+            line,
+        }
+    }
+
+    /// Level the call site logs at
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// Module path of the call site, e.g. `gateway::fix`
+    pub fn module_path(&self) -> &'static str {
+        self.module_path
+    }
+
+    /// Path of the source file of the call site
+    pub fn file(&self) -> &'static str {
+        self.file
+    }
+
+    /// Line of the call site in its source file
+    pub fn line(&self) -> u32 {
+        self.line
+    }
+}
+
+impl Display for Metadata {
+    /// Writes the location of the call site as `file:line`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.file, self.line)
+    }
+}
+
+/// Index of a call site in the registry, assigned in the order call sites
+/// are first reached, see the [module docs](self)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CallsiteId(u32);
+
+impl CallsiteId {
+    /// Id of the call site of `metadata`, which is registered under a new id
+    /// the first time
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`MAX_CALLSITES`] call sites are already registered.
+    pub fn of(metadata: &'static Metadata) -> CallsiteId {
+        let addr = metadata as *const Metadata;
+        let mut ids = IDS.lock().unwrap_or_else(PoisonError::into_inner);
+        let next = ids.len() as u32;
+        let index = *ids.entry(addr as usize).or_insert(next);
+        if index == next {
+            let slot = CALLSITES
+                .get(index as usize)
+                .unwrap_or_else(|| panic!("more than {} call sites registered", MAX_CALLSITES));
+            slot.store(addr.cast_mut(), Ordering::Release);
+        }
+        CallsiteId(index)
+    }
+
+    /// Metadata of the call site registered under this id
+    pub fn metadata(self) -> &'static Metadata {
+        let metadata = CALLSITES[self.0 as usize].load(Ordering::Acquire);
+        assert!(
+            !metadata.is_null(),
+            "call site {} is not registered",
+            self.0
+        );
+        // SAFETY: only references to `'static` metadata are stored in the
+        // registry, which are never written through, and ids are only handed
+        // out once their metadata is stored
+        unsafe { &*metadata }
+    }
+
+    /// Index of the call site, smaller than the number of call sites
+    /// registered so far
+    pub fn as_u32(self) -> u32 {
+        self.0
+    }
+
+    /// Counts a record logged from the call site at `time_logged`
+    pub(crate) fn record_hit(self, time_logged: u64) {
+        let counters = &COUNTERS[self.0 as usize];
+        counters.hits.fetch_add(1, Ordering::Relaxed);
+        counters.last_hit.store(time_logged, Ordering::Relaxed);
+    }
+
+    /// Counts a record logged from the call site which was never flushed
+    pub(crate) fn record_dropped(self) {
+        COUNTERS[self.0 as usize]
+            .dropped
+            .fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Records logged from a call site since the program started, see
+/// [`callsite_stats`]
+///
+/// [`callsite_stats`]: crate::callsite_stats
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CallsiteStats {
+    /// Call site the records were logged from
+    pub callsite: CallsiteId,
+    /// Number of records which passed the level filter and [`with_filter!`],
+    /// including dropped ones
+    ///
+    /// [`with_filter!`]: crate::with_filter
+    pub hits: u64,
+    /// Number of records which were dropped because the queue was full, or
+    /// because the logger was not initialized yet
+    pub dropped: u64,
+    /// Time the last record was logged, `None` if none was
+    pub last_hit: Option<DateTime<Utc>>,
+}
+
+impl CallsiteStats {
+    /// Level and source location of the call site
+    pub fn metadata(&self) -> &'static Metadata {
+        self.callsite.metadata()
+    }
+}
+
+/// Stats of every call site registered so far, see [`callsite_stats`]
+///
+/// [`callsite_stats`]: crate::callsite_stats
+pub fn stats() -> Vec<CallsiteStats> {
+    let registered = IDS.lock().unwrap_or_else(PoisonError::into_inner).len();
+    let mut stats: Vec<_> = COUNTERS[..registered]
+        .iter()
+        .enumerate()
+        .map(|(id, counters)| {
+            let last_hit = counters.last_hit.load(Ordering::Relaxed);
+            CallsiteStats {
+                callsite: CallsiteId(id as u32),
+                hits: counters.hits.load(Ordering::Relaxed),
+                dropped: counters.dropped.load(Ordering::Relaxed),
+                last_hit: (last_hit != 0).then(|| to_system_time(last_hit)),
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| b.hits.cmp(&a.hits));
+    stats
+}
+
+/// **Internal API**
+///
+/// Metadata of a call site and the id it was registered under, registered
+/// when the call site is first reached
+#[doc(hidden)]
+pub struct __Callsite {
+    metadata: Metadata,
+    id: AtomicU32,
+}
+
+impl __Callsite {
+    const UNREGISTERED: u32 = u32::MAX;
+
+    pub const fn new(metadata: Metadata) -> __Callsite {
+        __Callsite {
+            metadata,
+            id: AtomicU32::new(Self::UNREGISTERED),
+        }
+    }
+
+    /// Id of the call site, looked up in the registry only when it is first
+    /// reached
+    #[inline]
+    pub fn id(&'static self) -> CallsiteId {
+        let id = self.id.load(Ordering::Relaxed);
+        if id != Self::UNREGISTERED {
+            return CallsiteId(id);
+        }
+        let id = CallsiteId::of(&self.metadata);
+        self.id.store(id.0, Ordering::Relaxed);
+        id
+    }
+}
+
+/// Returns the id of a call site outside of quicklog's macros, e.g. of
+/// the `log` or `tracing` bridges, leaking its metadata once per distinct call
+/// site
+#[cfg(any(feature = "log", feature = "tracing-bridge"))]
+pub(crate) fn intern(
+    level: Level,
+    module_path: &'static str,
+    file: &'static str,
+    line: u32,
+) -> CallsiteId {
+    use std::collections::HashMap;
+
+    type Key = (u8, &'static str, &'static str, u32);
+    static INTERNED: Mutex<Option<HashMap<Key, CallsiteId>>> = Mutex::new(None);
+
+    let mut interned = INTERNED.lock().unwrap_or_else(PoisonError::into_inner);
+    *interned
+        .get_or_insert_with(HashMap::new)
+        .entry((level as u8, module_path, file, line))
+        .or_insert_with(|| {
+            CallsiteId::of(Box::leak(Box::new(Metadata::new(
+                level,
+                module_path,
+                file,
+                line,
+            ))))
+        })
+}
@@ -0,0 +1,118 @@
+//! Defines [`Config`], the options of a logger instance created with
+//! [`Quicklog::new`], which is independent of the global logger.
+//!
+//! Each instance has its own queue, clock, formatter and flusher, so that
+//! unrelated subsystems, e.g. market data and order entry, neither compete
+//! for queue capacity nor share a sink. Records are logged into an instance
+//! with the `target:` option of the logging macros, and flushed with its
+//! methods:
+//!
+//! ```
+//! # use quicklog::{config::Config, info, Quicklog};
+//! # use quicklog_flush::noop_flusher::NoopFlusher;
+//! let mut market_data_logger = Quicklog::new(
+//!     Config::new()
+//!         .with_capacity(64 * 1024)
+//!         .with_flush(NoopFlusher),
+//! );
+//!
+//! info!(target: market_data_logger, "book updated: {}", 42);
+//! assert_eq!(market_data_logger.flush_all(), 1);
+//! ```
+//!
+//! Like the global logger, an instance is meant to be used from a single
+//! thread, e.g. kept in a `thread_local!` by the thread owning it. The level
+//! filter set with [`set_max_level`] applies to every instance, and the
+//! statistics of the `stats` feature count the records of every instance.
+//! `^` arguments are encoded into the byte buffer of the instance the record
+//! is logged into, so that records of other loggers never overwrite them.
+//!
+//! [`Quicklog::new`]: crate::Quicklog::new
+//! [`set_max_level`]: crate::level::set_max_level
+
+use quicklog_clock::{quanta::QuantaClock, Clock};
+use quicklog_flush::{file_flusher::FileFlusher, Flush};
+
+use crate::{
+    constants::MAX_LOGGER_CAPACITY, queue::QueueFullPolicy, serialize::EncodeErrorPolicy,
+    timestamp::TimestampFormat, PatternFormatter, QuickLogFormatter,
+};
+
+/// Options of a logger instance, mirroring the options of [`init!`], with the
+/// same defaults as the global logger
+///
+/// [`init!`]: crate::init
+pub struct Config {
+    pub(crate) capacity: usize,
+    pub(crate) timestamp_format: TimestampFormat,
+    pub(crate) queue_full_policy: QueueFullPolicy,
+    pub(crate) encode_error_policy: EncodeErrorPolicy,
+    pub(crate) formatter: Box<dyn PatternFormatter>,
+    pub(crate) flusher: Box<dyn Flush>,
+    pub(crate) clock: Box<dyn Clock>,
+}
+
+impl Config {
+    pub fn new() -> Config {
+        Config::default()
+    }
+
+    /// Number of records the queue holds, defaults to [`MAX_LOGGER_CAPACITY`]
+    pub fn with_capacity(mut self, capacity: usize) -> Config {
+        self.capacity = capacity;
+        self
+    }
+
+    /// How timestamps are formatted
+    pub fn with_timestamp_format(mut self, format: TimestampFormat) -> Config {
+        self.timestamp_format = format;
+        self
+    }
+
+    /// What happens when logging into a full queue, defaults to dropping the
+    /// new record
+    pub fn with_queue_full_policy(mut self, policy: QueueFullPolicy) -> Config {
+        self.queue_full_policy = policy;
+        self
+    }
+
+    /// What happens when a `^` argument cannot be encoded, defaults to
+    /// dropping the record
+    pub fn with_encode_error_policy(mut self, policy: EncodeErrorPolicy) -> Config {
+        self.encode_error_policy = policy;
+        self
+    }
+
+    /// Formatter of the flushed lines
+    pub fn with_formatter(mut self, formatter: impl PatternFormatter + 'static) -> Config {
+        self.formatter = Box::new(formatter);
+        self
+    }
+
+    /// Flusher the lines are written into, defaults to the file
+    /// `logs/quicklog.log`
+    pub fn with_flush(mut self, flush: impl Flush + 'static) -> Config {
+        self.flusher = Box::new(flush);
+        self
+    }
+
+    /// Clock the records are timestamped with
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Config {
+        self.clock = Box::new(clock);
+        self
+    }
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            capacity: MAX_LOGGER_CAPACITY,
+            timestamp_format: TimestampFormat::default(),
+            queue_full_policy: QueueFullPolicy::default(),
+            encode_error_policy: EncodeErrorPolicy::default(),
+            formatter: Box::new(QuickLogFormatter::new()),
+            flusher: Box::new(FileFlusher::new("logs/quicklog.log")),
+            clock: Box::new(QuantaClock::new()),
+        }
+    }
+}
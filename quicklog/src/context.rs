@@ -0,0 +1,255 @@
+//! Scoped context fields, enabled with the `scoped-context` feature, which
+//! are appended to every record logged while they are entered.
+//!
+//! ```
+//! # use quicklog::{context::{self, Context}, info, init};
+//! init!();
+//! let _guard = context::enter(Context::new().with("order_id", 42).with("venue", "XNAS"));
+//! info!("order sent");
+//! // [...]order sent order_id=42 venue=XNAS
+//! ```
+//!
+//! Entered contexts are kept per thread, so they would be lost when an async
+//! task moves to another worker after an `.await`. Futures wrapped with
+//! [`Instrument::in_context`] or [`Instrument::in_current_context`] carry
+//! their context along, and enter it every time they are polled, whichever
+//! thread polls them. This works with any executor, including multi-threaded
+//! runtimes such as tokio:
+//!
+//! ```
+//! # use quicklog::{context::{Context, Instrument}, info};
+//! async fn handle(id: u64) {
+//!     info!("handling");
+//! }
+//!
+//! let task = handle(7).in_context(Context::new().with("request_id", 7));
+//! // spawn `task` on any executor, records logged by `handle` get `request_id=7`
+//! ```
+
+use std::{
+    cell::RefCell,
+    fmt::{self, Display, Formatter},
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    sync::Arc,
+    task::{self, Poll},
+};
+
+use crate::logfmt;
+
+thread_local! {
+    /// Context entered on this thread, including every enclosing one
+    static CURRENT: RefCell<Context> = RefCell::new(Context::new());
+}
+
+/// Fields appended to the records logged while the context is entered, with
+/// values formatted when they are added
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Context {
+    fields: Arc<Vec<(&'static str, String)>>,
+}
+
+impl Context {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Context entered on this thread, including every enclosing one
+    pub fn current() -> Self {
+        CURRENT.with(|current| current.borrow().clone())
+    }
+
+    /// Adds the field `key`, replacing any field with the same key
+    pub fn with(mut self, key: &'static str, value: impl Display) -> Self {
+        let fields = Arc::make_mut(&mut self.fields);
+        let value = value.to_string();
+        match fields.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, v)) => *v = value,
+            None => fields.push((key, value)),
+        }
+        self
+    }
+
+    /// Fields of this context, in the order they were added
+    pub fn fields(&self) -> impl Iterator<Item = (&'static str, &str)> + '_ {
+        self.fields.iter().map(|(key, value)| (*key, value.as_str()))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Fields of `self` followed by those of `inner`, which take precedence
+    fn merge(&self, inner: &Context) -> Context {
+        if self.is_empty() {
+            return inner.clone();
+        }
+        inner
+            .fields()
+            .fold(self.clone(), |merged, (key, value)| merged.with(key, value))
+    }
+}
+
+/// Restores the previously entered context when dropped, returned by
+/// [`enter`]
+#[must_use = "the context is exited as soon as the guard is dropped"]
+pub struct ContextGuard {
+    previous: Option<Context>,
+    /// Contexts are entered per thread, so the guard cannot be sent to
+    /// another one
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ContextGuard {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            CURRENT.with(|current| *current.borrow_mut() = previous);
+        }
+    }
+}
+
+/// Enters `context` on this thread until the returned guard is dropped,
+/// adding its fields to those of the context already entered
+pub fn enter(context: Context) -> ContextGuard {
+    let previous = CURRENT.with(|current| {
+        let merged = current.borrow().merge(&context);
+        current.replace(merged)
+    });
+    ContextGuard {
+        previous: Some(previous),
+        _not_send: PhantomData,
+    }
+}
+
+/// Log line of a record logged with a context entered, followed by the
+/// fields of the context
+pub(crate) struct ContextLine {
+    pub(crate) log_line: Box<dyn Display>,
+    pub(crate) context: Context,
+}
+
+impl Display for ContextLine {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        // forwards the formatter as-is, keeping the alternate flag for logfmt
+        self.log_line.fmt(f)?;
+        for (key, value) in self.context.fields() {
+            if f.alternate() {
+                logfmt::__write_field(f, key, value)?;
+            } else {
+                write!(f, " {}={}", key, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Context to add to a record logged now, if any
+pub(crate) fn current_if_entered() -> Option<Context> {
+    CURRENT.with(|current| {
+        let current = current.borrow();
+        (!current.is_empty()).then(|| current.clone())
+    })
+}
+
+/// Future which enters its context whenever it is polled, returned by
+/// [`Instrument::in_context`]
+pub struct InstrumentedFuture<F> {
+    inner: F,
+    context: Context,
+}
+
+impl<F> InstrumentedFuture<F> {
+    /// Context entered when polling the future
+    pub fn context(&self) -> &Context {
+        &self.context
+    }
+
+    pub fn into_inner(self) -> F {
+        self.inner
+    }
+}
+
+impl<F: Future> Future for InstrumentedFuture<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context<'_>) -> Poll<Self::Output> {
+        // SAFETY: `inner` is never moved out of the pinned future, and
+        // `InstrumentedFuture` does not implement `Drop` or `Unpin` manually
+        let this = unsafe { self.get_unchecked_mut() };
+        let _guard = enter(this.context.clone());
+        unsafe { Pin::new_unchecked(&mut this.inner) }.poll(cx)
+    }
+}
+
+/// Attaches a [`Context`] to a future, analogous to `tracing::Instrument`
+pub trait Instrument: Future + Sized {
+    /// Enters `context` whenever the future is polled
+    fn in_context(self, context: Context) -> InstrumentedFuture<Self> {
+        InstrumentedFuture {
+            inner: self,
+            context,
+        }
+    }
+
+    /// Enters the context entered on this thread right now whenever the
+    /// future is polled
+    fn in_current_context(self) -> InstrumentedFuture<Self> {
+        self.in_context(Context::current())
+    }
+}
+
+impl<F: Future> Instrument for F {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_contexts() {
+        let outer = enter(Context::new().with("order_id", 42).with("venue", "XNAS"));
+        {
+            let _inner = enter(Context::new().with("venue", "XLON").with("leg", 2));
+            assert_eq!(
+                Context::current().fields().collect::<Vec<_>>(),
+                [("order_id", "42"), ("venue", "XLON"), ("leg", "2")]
+            );
+        }
+        assert_eq!(
+            Context::current().fields().collect::<Vec<_>>(),
+            [("order_id", "42"), ("venue", "XNAS")]
+        );
+        drop(outer);
+        assert!(current_if_entered().is_none());
+    }
+
+    #[test]
+    fn context_line() {
+        let line = ContextLine {
+            log_line: Box::new("order sent"),
+            context: Context::new().with("order_id", 42).with("venue", "X Y"),
+        };
+        assert_eq!(line.to_string(), "order sent order_id=42 venue=X Y");
+        assert_eq!(format!("{:#}", line), "order sent order_id=42 venue=\"X Y\"");
+    }
+
+    #[test]
+    fn instrumented_future_enters_context_when_polled() {
+        let context = Context::new().with("request_id", 7);
+        let mut future = std::pin::pin!(async {
+            let before = Context::current();
+            std::future::ready(()).await;
+            (before, Context::current())
+        }
+        .in_context(context.clone()));
+
+        let mut cx = task::Context::from_waker(task::Waker::noop());
+        // polled outside of the context, which is only entered while polling
+        let Poll::Ready((before, after)) = future.as_mut().poll(&mut cx) else {
+            panic!("future is ready right away");
+        };
+        assert_eq!(before, context);
+        assert_eq!(after, context);
+        assert!(current_if_entered().is_none());
+    }
+}
@@ -0,0 +1,89 @@
+//! Defines [`Error`], the error type returned by the fallible APIs of quicklog.
+//!
+//! ```
+//! # use quicklog::{try_init, try_flush, Error};
+//! try_init!().unwrap();
+//!
+//! match try_flush!() {
+//!     Ok(()) => println!("flushed one log line"),
+//!     Err(Error::Empty) => println!("nothing to flush"),
+//!     Err(Error::FlushFailed(err)) => eprintln!("failed to flush: {}", err),
+//!     Err(err) => eprintln!("{}", err),
+//! }
+//! ```
+
+use std::fmt::Display;
+
+/// Errors that can be returned by quicklog.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Logging queue is full, the log record was dropped
+    QueueFull,
+    /// Logging queue is empty, there is nothing to flush
+    Empty,
+    /// Logger was used before [`init!`](crate::init) was called
+    NotInitialized,
+    /// [`try_init!`](crate::try_init) was called more than once
+    AlreadyInitialized,
+    /// Flusher failed to perform I/O
+    FlushFailed(std::io::Error),
+    /// Buffer passed in is too small to encode a value into
+    BufferTooSmall {
+        /// Number of bytes required to encode the value
+        required: usize,
+        /// Number of bytes available in the buffer
+        available: usize,
+    },
+    /// Encoded bytes could not be decoded back into a value
+    DecodeCorrupt,
+    /// Decode function of a `^` argument cannot be queued, as the decode
+    /// table already holds [`MAX_DECODERS`] functions
+    ///
+    /// [`MAX_DECODERS`]: crate::serialize::decode_table::MAX_DECODERS
+    DecodeTableFull,
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::QueueFull => write!(f, "logging queue is full"),
+            Self::Empty => write!(f, "logging queue is empty"),
+            Self::NotInitialized => write!(
+                f,
+                "logger is not initialized, `init!()` needs to be called at the entry point of your application"
+            ),
+            Self::AlreadyInitialized => write!(f, "logger is already initialized"),
+            Self::FlushFailed(err) => write!(f, "failed to flush log line: {}", err),
+            Self::BufferTooSmall {
+                required,
+                available,
+            } => write!(
+                f,
+                "buffer too small to encode value, required {} bytes but only {} available",
+                required, available
+            ),
+            Self::DecodeCorrupt => write!(f, "encoded bytes are corrupt and cannot be decoded"),
+            Self::DecodeTableFull => write!(
+                f,
+                "decode table is full, at most {} decode functions can be queued",
+                crate::serialize::decode_table::MAX_DECODERS
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::FlushFailed(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Self::FlushFailed(err)
+    }
+}
@@ -0,0 +1,134 @@
+use std::{
+    panic,
+    sync::OnceLock,
+    thread::{self, ThreadId},
+};
+
+use crate::{
+    audit,
+    callsite::{Metadata, __Callsite},
+    level::Level,
+    logfmt, logger, Log, LogRecord,
+};
+
+/// Flushes the logger when dropped, returned by [`init_with_guard!`].
+///
+/// On drop, every record still queued is flushed and the flushers write out
+/// anything they buffered, see [`Flush::flush_buffered`], including those of
+/// the [`audit`](crate::audit) channel. Keep the guard
+/// alive until the end of `main`, as records logged after it is dropped are
+/// only written by a later flush.
+///
+/// The guard has to be bound to a name: `let _ = init_with_guard!();` drops
+/// it right away, while `let _guard = init_with_guard!();` keeps it until the
+/// end of the scope.
+///
+/// ```
+/// # use quicklog::{info, init_with_guard};
+/// fn main() {
+///     let _guard = init_with_guard!();
+///
+///     info!("flushed when main returns");
+/// }
+/// ```
+///
+/// [`init_with_guard!`]: crate::init_with_guard
+/// [`Flush::flush_buffered`]: quicklog_flush::Flush::flush_buffered
+#[must_use = "the logger is flushed as soon as the guard is dropped"]
+pub struct FlushGuard {
+    _private: (),
+}
+
+impl FlushGuard {
+    /// Creates a guard for the global logger, see [`init_with_guard!`] to
+    /// also initialize it
+    ///
+    /// [`init_with_guard!`]: crate::init_with_guard
+    pub fn new() -> FlushGuard {
+        FlushGuard { _private: () }
+    }
+}
+
+impl Default for FlushGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for FlushGuard {
+    fn drop(&mut self) {
+        let logger = logger();
+        logger.flush_all();
+        // nowhere left to report errors to while shutting down
+        let _ = logger.flush_buffered();
+        audit::flush_if_used();
+    }
+}
+
+/// Thread which installed the panic hook, the only one which flushes from it
+static HOOK_THREAD: OnceLock<ThreadId> = OnceLock::new();
+
+/// Installs a panic hook which logs panics of the current thread as `ERROR`
+/// records and flushes the logger, before calling the previous hook. Done by
+/// [`#[quicklog::main]`](crate::main).
+///
+/// Records are then written even when panics abort rather than unwind, and
+/// [`FlushGuard`] is never dropped. Panics of other threads are left to the
+/// previous hook, since the logger belongs to the thread which initialized
+/// it. Installing the hook more than once has no effect.
+pub fn install_panic_hook() {
+    if HOOK_THREAD.set(thread::current().id()).is_err() {
+        return;
+    }
+
+    let previous = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        if HOOK_THREAD.get() == Some(&thread::current().id()) {
+            let message = info
+                .payload()
+                .downcast_ref::<&str>()
+                .copied()
+                .or_else(|| info.payload().downcast_ref::<String>().map(String::as_str))
+                .unwrap_or("Box<dyn Any>");
+            let message = match info.location() {
+                Some(location) => format!("panicked at {}: {}", location, message),
+                None => format!("panicked: {}", message),
+            };
+            let logger = logger();
+            let _ = logger.log(panic_record(message));
+            logger.flush_all();
+            let _ = logger.flush_buffered();
+        }
+        previous(info);
+    }));
+}
+
+/// Record of a panic caught by the hook installed with [`install_panic_hook`]
+fn panic_record(message: String) -> LogRecord {
+    static CALLSITE: __Callsite = __Callsite::new(Metadata::new(
+        Level::Error,
+        module_path!(),
+        file!(),
+        line!(),
+    ));
+
+    LogRecord {
+        callsite: CALLSITE.id(),
+        log_line: Box::new(lazy_format::make_lazy_format!(|f| {
+            if f.alternate() {
+                logfmt::__write_message(f, format_args!("{}", message))
+            } else {
+                f.write_str(&message)
+            }
+        })),
+        tag: None,
+        sequence: 0,
+        metric: None,
+        #[cfg(feature = "trace")]
+        trace_id: None,
+        #[cfg(feature = "trace")]
+        span_id: None,
+        #[cfg(feature = "trace")]
+        span_name: None,
+    }
+}
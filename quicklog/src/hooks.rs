@@ -0,0 +1,150 @@
+//! Decode hooks, which enrich the values of named fields when records are
+//! flushed.
+//!
+//! Hooks are keyed by field name and receive the formatted value of the
+//! field. This keeps the hot path cheap, e.g. logging a numeric symbol id,
+//! while flushed log lines still contain human readable names. Returning
+//! `None` from a hook keeps the original value.
+//!
+//! ```
+//! # use std::collections::HashMap;
+//! # use quicklog::{flush, info, init, hooks::{lookup, register_decode_hook}};
+//! init!();
+//!
+//! let symbols = HashMap::from([(1_u32, "BTCUSD"), (2, "ETHUSD")]);
+//! register_decode_hook("symbol", lookup(symbols));
+//!
+//! let symbol_id = 1_u32;
+//! info!(symbol = symbol_id, "order filled");
+//! // flushed as "order filled symbol=BTCUSD"
+//! flush!();
+//! ```
+
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    hash::Hash,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        PoisonError, RwLock,
+    },
+};
+
+/// Maps the formatted value of a field to its enriched value
+pub type DecodeHook = Box<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+static DECODE_HOOKS: RwLock<Vec<(&'static str, DecodeHook)>> = RwLock::new(Vec::new());
+static HAS_DECODE_HOOKS: AtomicBool = AtomicBool::new(false);
+
+/// Registers `hook` for fields named `field`, replacing any hook previously
+/// registered for it. Nested field names are matched in full, e.g.
+/// `order.symbol`.
+pub fn register_decode_hook<F>(field: &'static str, hook: F)
+where
+    F: Fn(&str) -> Option<String> + Send + Sync + 'static,
+{
+    let mut hooks = DECODE_HOOKS.write().unwrap_or_else(PoisonError::into_inner);
+    hooks.retain(|(name, _)| *name != field);
+    hooks.push((field, Box::new(hook)));
+    HAS_DECODE_HOOKS.store(true, Ordering::Release);
+}
+
+/// Removes the hook registered for fields named `field`, returning whether
+/// there was one
+pub fn remove_decode_hook(field: &str) -> bool {
+    let mut hooks = DECODE_HOOKS.write().unwrap_or_else(PoisonError::into_inner);
+    let len = hooks.len();
+    hooks.retain(|(name, _)| *name != field);
+    HAS_DECODE_HOOKS.store(!hooks.is_empty(), Ordering::Release);
+    hooks.len() != len
+}
+
+/// Removes all registered hooks
+pub fn clear_decode_hooks() {
+    let mut hooks = DECODE_HOOKS.write().unwrap_or_else(PoisonError::into_inner);
+    hooks.clear();
+    HAS_DECODE_HOOKS.store(false, Ordering::Release);
+}
+
+/// Creates a hook which parses the value of a field as `K` and looks it up in
+/// `table`. Values which fail to parse or are missing from `table` are kept.
+pub fn lookup<K, V>(table: HashMap<K, V>) -> impl Fn(&str) -> Option<String> + Send + Sync
+where
+    K: FromStr + Eq + Hash + Send + Sync,
+    V: Display + Send + Sync,
+{
+    move |value| {
+        let key = value.parse::<K>().ok()?;
+        table.get(&key).map(|v| v.to_string())
+    }
+}
+
+/// Returns the enriched value of `field`, if a hook is registered for it and
+/// it maps `value`
+pub(crate) fn enrich(field: &str, value: &str) -> Option<String> {
+    if !__has_decode_hooks() {
+        return None;
+    }
+
+    let hooks = DECODE_HOOKS.read().unwrap_or_else(PoisonError::into_inner);
+    let (_, hook) = hooks.iter().find(|(name, _)| *name == field)?;
+    hook(value)
+}
+
+/// **Internal API**
+///
+/// Checks if any hooks are registered, so that log lines without hooks can be
+/// written in one go
+#[doc(hidden)]
+pub fn __has_decode_hooks() -> bool {
+    HAS_DECODE_HOOKS.load(Ordering::Acquire)
+}
+
+/// **Internal API**
+///
+/// Writes the value of `field`, enriched by its hook if there is one
+#[doc(hidden)]
+pub fn __write_field(f: &mut Formatter<'_>, field: &str, value: impl Display) -> fmt::Result {
+    let value = value.to_string();
+    match enrich(field, &value) {
+        Some(enriched) => f.write_str(&enriched),
+        None => f.write_str(&value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::{clear_decode_hooks, enrich, lookup, register_decode_hook, remove_decode_hook};
+
+    #[test]
+    fn decode_hooks() {
+        assert_eq!(enrich("symbol", "1"), None);
+
+        register_decode_hook("symbol", lookup(HashMap::from([(1_u32, "BTCUSD")])));
+        register_decode_hook("side", |value| match value {
+            "0" => Some("buy".to_string()),
+            "1" => Some("sell".to_string()),
+            _ => None,
+        });
+        assert_eq!(enrich("symbol", "1").as_deref(), Some("BTCUSD"));
+        assert_eq!(enrich("symbol", "2"), None);
+        assert_eq!(enrich("symbol", "not a number"), None);
+        assert_eq!(enrich("side", "1").as_deref(), Some("sell"));
+        assert_eq!(enrich("venue", "1"), None);
+
+        // registering again replaces the previous hook
+        register_decode_hook("symbol", lookup(HashMap::from([(1_u32, "ETHUSD")])));
+        assert_eq!(enrich("symbol", "1").as_deref(), Some("ETHUSD"));
+
+        assert!(remove_decode_hook("symbol"));
+        assert!(!remove_decode_hook("symbol"));
+        assert_eq!(enrich("symbol", "1"), None);
+        assert_eq!(enrich("side", "0").as_deref(), Some("buy"));
+
+        clear_decode_hooks();
+        assert_eq!(enrich("side", "0"), None);
+    }
+}
@@ -0,0 +1,267 @@
+//! Defines [`JournaldFormatter`], which formats records into journald entries
+//! sent by a [`JournaldFlusher`], for services deployed under systemd.
+//! Enabled on Linux with the `journald` feature.
+//!
+//! ```no_run
+//! # use quicklog::{init, info, journald::{JournaldFlusher, JournaldFormatter}, with_flush};
+//! init!(formatter = JournaldFormatter::new().with_syslog_identifier("trader"));
+//! with_flush!(JournaldFlusher::new().unwrap());
+//!
+//! info!(symbol = "BTC-USD", qty = 5, "order filled");
+//! // PRIORITY=6
+//! // SYSLOG_IDENTIFIER=trader
+//! // MESSAGE=order filled
+//! // CODE_FILE=src/main.rs
+//! // CODE_LINE=7
+//! // TARGET=trader
+//! // SYMBOL=BTC-USD
+//! // QTY=5
+//! ```
+//!
+//! Structured fields, including those of the entered context and spans, are
+//! written as fields of their own, named after their key in uppercase with
+//! any character other than `A-Z`, `0-9` and `_` replaced by `_`, e.g.
+//! `order.id` as `ORDER_ID`. journald stamps entries with the time it
+//! receives them, so the timestamp of records is not written.
+//!
+//! [`JournaldFlusher`]: quicklog_flush::journald_flusher::JournaldFlusher
+
+use std::{borrow::Cow, fmt::Write};
+
+use chrono::{DateTime, Utc};
+
+use quicklog_flush::journald_flusher::priority;
+pub use quicklog_flush::journald_flusher::JournaldFlusher;
+
+use crate::{LogRecord, PatternFormatter};
+
+/// Formats records as journald entries, with one `KEY=value` field per line,
+/// see [`JournaldFlusher`] for the escaping of values
+#[derive(Clone, Debug, Default)]
+pub struct JournaldFormatter {
+    syslog_identifier: Option<String>,
+}
+
+impl JournaldFormatter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Writes `identifier` as the `SYSLOG_IDENTIFIER` of entries, which
+    /// journald otherwise takes from the name of the process
+    pub fn with_syslog_identifier(mut self, identifier: impl Into<String>) -> Self {
+        self.syslog_identifier = Some(identifier.into());
+        self
+    }
+}
+
+impl PatternFormatter for JournaldFormatter {
+    fn custom_format(&mut self, time: DateTime<Utc>, log_record: LogRecord) -> String {
+        let mut line = String::new();
+        self.format_into(time, log_record, &mut line);
+        line
+    }
+
+    fn format_into(&mut self, _time: DateTime<Utc>, log_record: LogRecord, line: &mut String) {
+        let _ = writeln!(line, "PRIORITY={}", priority(log_record.level()));
+        if let Some(identifier) = &self.syslog_identifier {
+            write_field(line, "SYSLOG_IDENTIFIER", identifier);
+        }
+
+        // the message and fields are split from their logfmt pairs
+        let pairs = format!("{:#}", log_record.log_line);
+        let pairs = parse_logfmt(&pairs);
+        let message = pairs
+            .iter()
+            .find(|(key, _)| *key == "msg")
+            .map_or("", |(_, message)| message);
+        write_field(line, "MESSAGE", message);
+
+        write_field(line, "CODE_FILE", log_record.file());
+        let _ = writeln!(line, "CODE_LINE={}", log_record.line());
+        write_field(line, "TARGET", log_record.module_path());
+        if let Some(tag) = log_record.tag {
+            write_field(line, "TAG", tag.name());
+        }
+        #[cfg(feature = "trace")]
+        if let Some(trace_id) = log_record.trace_id {
+            let _ = writeln!(line, "TRACE_ID={:032x}", trace_id);
+        }
+        #[cfg(feature = "trace")]
+        if let Some(span_id) = log_record.span_id {
+            let _ = writeln!(line, "SPAN_ID={:016x}", span_id);
+        }
+
+        for (key, value) in pairs.iter().filter(|(key, _)| *key != "msg") {
+            let Some(name) = field_name(key) else {
+                continue;
+            };
+            write_field(line, &name, value);
+        }
+    }
+}
+
+/// Writes `KEY=value` on a line of its own, escaping newlines and
+/// backslashes within the value
+fn write_field(line: &mut String, key: &str, value: &str) {
+    line.push_str(key);
+    line.push('=');
+    for c in value.chars() {
+        match c {
+            '\n' => line.push_str("\\n"),
+            '\\' => line.push_str("\\\\"),
+            c => line.push(c),
+        }
+    }
+    line.push('\n');
+}
+
+/// Name of the journald field for the structured field `key`, which only
+/// contains `A-Z`, `0-9` and `_`, and neither starts with `_`, reserved for
+/// fields set by journald, nor with a digit
+fn field_name(key: &str) -> Option<String> {
+    let name = key
+        .chars()
+        .map(|c| match c {
+            'a'..='z' => c.to_ascii_uppercase(),
+            'A'..='Z' | '0'..='9' => c,
+            _ => '_',
+        })
+        .collect::<String>();
+    let name = name.trim_start_matches(|c: char| c == '_' || c.is_ascii_digit());
+    // journald ignores longer names
+    (!name.is_empty()).then(|| name.chars().take(64).collect())
+}
+
+/// Splits the logfmt pairs written by the alternate formatting of a log line,
+/// unquoting quoted values
+fn parse_logfmt(pairs: &str) -> Vec<(&str, Cow<'_, str>)> {
+    let mut parsed = Vec::new();
+    let mut rest = pairs.trim_start();
+    while let Some((key, value)) = rest.split_once('=') {
+        let (value, remaining) = match value.strip_prefix('"') {
+            Some(quoted) => unquote(quoted),
+            None => {
+                let end = value.find(' ').unwrap_or(value.len());
+                (Cow::Borrowed(&value[..end]), &value[end..])
+            }
+        };
+        parsed.push((key, value));
+        rest = remaining.trim_start();
+    }
+    parsed
+}
+
+/// Unescapes a value quoted with `{:?}`, given without its opening quote,
+/// returning the value and what follows its closing quote
+fn unquote(quoted: &str) -> (Cow<'_, str>, &str) {
+    let mut value = String::new();
+    let mut chars = quoted.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return (Cow::Owned(value), &quoted[i + 1..]),
+            '\\' => match chars.next() {
+                Some((_, 'n')) => value.push('\n'),
+                Some((_, 'r')) => value.push('\r'),
+                Some((_, 't')) => value.push('\t'),
+                Some((_, '0')) => value.push('\0'),
+                Some((_, 'u')) => {
+                    // `\u{..}`
+                    let digits = chars
+                        .by_ref()
+                        .map(|(_, c)| c)
+                        .skip(1)
+                        .take_while(|&c| c != '}')
+                        .collect::<String>();
+                    if let Some(c) = u32::from_str_radix(&digits, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                    {
+                        value.push(c);
+                    }
+                }
+                Some((_, c)) => value.push(c),
+                None => {}
+            },
+            c => value.push(c),
+        }
+    }
+    (Cow::Owned(value), "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        callsite::{Metadata, __Callsite},
+        level::Level,
+        logfmt,
+    };
+
+    #[test]
+    fn parses_logfmt_pairs() {
+        assert_eq!(
+            parse_logfmt(r#" msg="order filled" symbol=BTC-USD note="a \"b\"\nc\u{1b}""#),
+            [
+                ("msg", Cow::Borrowed("order filled")),
+                ("symbol", Cow::Borrowed("BTC-USD")),
+                ("note", Cow::Borrowed("a \"b\"\nc\u{1b}")),
+            ]
+        );
+        assert!(parse_logfmt("").is_empty());
+    }
+
+    #[test]
+    fn field_names() {
+        assert_eq!(field_name("order.id").as_deref(), Some("ORDER_ID"));
+        assert_eq!(field_name("_private").as_deref(), Some("PRIVATE"));
+        assert_eq!(field_name("2fa").as_deref(), Some("FA"));
+        assert_eq!(field_name("__"), None);
+    }
+
+    #[test]
+    fn formats_entries() {
+        static CALLSITE: __Callsite = __Callsite::new(Metadata::new(
+            Level::Warn,
+            "trader::orders",
+            "src/orders.rs",
+            7,
+        ));
+        let record = LogRecord {
+            callsite: CALLSITE.id(),
+            log_line: Box::new(lazy_format::make_lazy_format!(|f| {
+                if f.alternate() {
+                    logfmt::__write_message(f, format_args!("order rejected"))?;
+                    logfmt::__write_field(f, "order.id", 42)?;
+                    logfmt::__write_field(f, "reason", "price\\band\nlimit")
+                } else {
+                    f.write_str("order rejected order.id=42")
+                }
+            })),
+            tag: None,
+            sequence: 0,
+            metric: None,
+            #[cfg(feature = "trace")]
+            trace_id: None,
+            #[cfg(feature = "trace")]
+            span_id: None,
+            #[cfg(feature = "trace")]
+            span_name: None,
+        };
+
+        let mut formatter = JournaldFormatter::new().with_syslog_identifier("trader");
+        assert_eq!(
+            formatter.custom_format(Utc::now(), record),
+            concat!(
+                "PRIORITY=4\n",
+                "SYSLOG_IDENTIFIER=trader\n",
+                "MESSAGE=order rejected\n",
+                "CODE_FILE=src/orders.rs\n",
+                "CODE_LINE=7\n",
+                "TARGET=trader::orders\n",
+                "ORDER_ID=42\n",
+                "REASON=price\\\\band\\nlimit\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! Defines [`LayoutFormatter`], which lays records out according to a pattern
+//! string instead of the fixed layout of the default formatter.
+//!
+//! ```
+//! # use quicklog::{init, info, layout::LayoutFormatter};
+//! init!(formatter = LayoutFormatter::new("{ts} [{level}] {module} - {msg}").unwrap());
+//!
+//! info!(symbol = "BTC-USD", "order filled");
+//! // 2023-10-23T08:00:00.123456789Z [INFO] my_app::orders - order filled symbol=BTC-USD
+//! ```
+//!
+//! Placeholders are written in braces, everything else is copied as-is, with
+//! `{{` and `}}` standing for literal braces:
+//!
+//! * `{ts}`: timestamp, in the [`TimestampFormat`] of the logger
+//! * `{level}`: level, e.g. `INFO`
+//! * `{module}`: module path of the call site
+//! * `{file}` and `{line}`: source location of the call site
+//! * `{msg}`: message followed by the fields
+//! * `{tag}`: tag of the record, empty for untagged records
+//! * `{seq}`: sequence number of the record, see [`LogRecord::sequence`]
+//! * `{trace_id}`: trace id with the `trace` feature, empty otherwise
+//! * `{span_id}`: id of the fastrace span with the `trace` feature, empty
+//!   otherwise
+//!
+//! A line break is appended to every record.
+
+use std::fmt::{self, Display, Write};
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    timestamp::{CachedTimestamp, TimestampFormat},
+    LogRecord, PatternFormatter,
+};
+
+/// Part of a layout pattern
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Timestamp,
+    Level,
+    Module,
+    File,
+    Line,
+    Message,
+    Tag,
+    Sequence,
+    TraceId,
+    SpanId,
+}
+
+impl Segment {
+    fn from_placeholder(name: &str) -> Option<Self> {
+        match name {
+            "ts" => Some(Self::Timestamp),
+            "level" => Some(Self::Level),
+            "module" => Some(Self::Module),
+            "file" => Some(Self::File),
+            "line" => Some(Self::Line),
+            "msg" => Some(Self::Message),
+            "tag" => Some(Self::Tag),
+            "seq" => Some(Self::Sequence),
+            "trace_id" => Some(Self::TraceId),
+            "span_id" => Some(Self::SpanId),
+            _ => None,
+        }
+    }
+}
+
+/// Error returned when a layout pattern cannot be parsed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LayoutParseError {
+    /// Placeholder which is not supported, e.g. `{thread}`
+    UnknownPlaceholder(String),
+    /// `{` without a matching `}`
+    UnclosedPlaceholder,
+    /// `}` which neither closes a placeholder nor is escaped as `}}`
+    UnmatchedBrace,
+}
+
+impl Display for LayoutParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownPlaceholder(name) => write!(f, "unknown placeholder `{{{}}}`", name),
+            Self::UnclosedPlaceholder => write!(f, "placeholder is missing a closing `}}`"),
+            Self::UnmatchedBrace => write!(f, "unmatched `}}`, use `}}}}` for a literal brace"),
+        }
+    }
+}
+
+impl std::error::Error for LayoutParseError {}
+
+/// Formats records according to a pattern such as
+/// `"{ts} [{level}] {module} - {msg}"`, see the [module docs](self) for the
+/// supported placeholders
+#[derive(Clone, Debug)]
+pub struct LayoutFormatter {
+    segments: Vec<Segment>,
+    timestamp: CachedTimestamp,
+}
+
+impl LayoutFormatter {
+    /// Parses `pattern`, failing on unknown placeholders and unbalanced
+    /// braces
+    pub fn new(pattern: &str) -> Result<Self, LayoutParseError> {
+        Ok(Self {
+            segments: parse(pattern)?,
+            timestamp: CachedTimestamp::default(),
+        })
+    }
+}
+
+impl PatternFormatter for LayoutFormatter {
+    fn custom_format(&mut self, time: DateTime<Utc>, log_record: LogRecord) -> String {
+        let mut line = String::new();
+        self.format_into(time, log_record, &mut line);
+        line
+    }
+
+    fn format_into(&mut self, time: DateTime<Utc>, log_record: LogRecord, line: &mut String) {
+        for segment in &self.segments {
+            let _ = match segment {
+                Segment::Literal(literal) => line.write_str(literal),
+                Segment::Timestamp => {
+                    self.timestamp.write(line, time);
+                    Ok(())
+                }
+                Segment::Level => write!(line, "{}", log_record.level()),
+                Segment::Module => line.write_str(log_record.module_path()),
+                Segment::File => line.write_str(log_record.file()),
+                Segment::Line => write!(line, "{}", log_record.line()),
+                Segment::Message => write!(line, "{}", log_record.log_line),
+                Segment::Tag => match log_record.tag {
+                    Some(tag) => write!(line, "{}", tag),
+                    None => Ok(()),
+                },
+                Segment::Sequence => write!(line, "{}", log_record.sequence),
+                #[cfg(feature = "trace")]
+                Segment::TraceId => match log_record.trace_id {
+                    Some(trace_id) => write!(line, "{:032x}", trace_id),
+                    None => Ok(()),
+                },
+                #[cfg(feature = "trace")]
+                Segment::SpanId => match log_record.span_id {
+                    Some(span_id) => write!(line, "{:016x}", span_id),
+                    None => Ok(()),
+                },
+                #[cfg(not(feature = "trace"))]
+                Segment::TraceId | Segment::SpanId => Ok(()),
+            };
+        }
+        line.push('\n');
+    }
+
+    fn set_timestamp_format(&mut self, format: TimestampFormat) {
+        self.timestamp.set_format(format);
+    }
+}
+
+fn parse(pattern: &str) -> Result<Vec<Segment>, LayoutParseError> {
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => return Err(LayoutParseError::UnclosedPlaceholder),
+                    }
+                }
+                let segment = Segment::from_placeholder(name.trim())
+                    .ok_or(LayoutParseError::UnknownPlaceholder(name))?;
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(segment);
+            }
+            '}' => return Err(LayoutParseError::UnmatchedBrace),
+            c => literal.push(c),
+        }
+    }
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+
+    Ok(segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, LayoutParseError, Segment};
+
+    #[test]
+    fn parses_patterns() {
+        assert_eq!(
+            parse("{ts} [{level}] {module} - {msg}").unwrap(),
+            [
+                Segment::Timestamp,
+                Segment::Literal(" [".to_string()),
+                Segment::Level,
+                Segment::Literal("] ".to_string()),
+                Segment::Module,
+                Segment::Literal(" - ".to_string()),
+                Segment::Message,
+            ]
+        );
+        assert_eq!(
+            parse("{{{file}:{line}}}").unwrap(),
+            [
+                Segment::Literal("{".to_string()),
+                Segment::File,
+                Segment::Literal(":".to_string()),
+                Segment::Line,
+                Segment::Literal("}".to_string()),
+            ]
+        );
+        assert_eq!(parse("").unwrap(), []);
+    }
+
+    #[test]
+    fn rejects_invalid_patterns() {
+        assert_eq!(
+            parse("{ts} {thread}"),
+            Err(LayoutParseError::UnknownPlaceholder("thread".to_string()))
+        );
+        assert_eq!(parse("{ts"), Err(LayoutParseError::UnclosedPlaceholder));
+        assert_eq!(parse("ts}"), Err(LayoutParseError::UnmatchedBrace));
+    }
+}
@@ -27,45 +27,16 @@
 //! [`Level`]: crate::level::Level
 //! [`LevelFilter`]: crate::level::LevelFilter
 
-#[repr(u8)]
-#[derive(Clone, Copy, Eq, PartialEq, PartialOrd)]
-pub enum Level {
-    /// Designates trace information, which is of very low priority
-    Trace = 0,
-    /// Designates debug information, which is of low priority
-    Debug = 1,
-    /// Designates useful information
-    Info = 2,
-    /// Designates potentially hazardous situations
-    Warn = 3,
-    /// Designates serious errors
-    Error = 4,
-}
-
-impl std::fmt::Display for Level {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let level = match self {
-            Self::Trace => "TRACE",
-            Self::Debug => "DEBUG",
-            Self::Info => "INFO",
-            Self::Warn => "WARN",
-            Self::Error => "ERROR",
-        };
-        write!(f, "{}", level)
-    }
-}
-
-impl std::fmt::Debug for Level {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        std::fmt::Display::fmt(self, f)
-    }
-}
+pub use quicklog_flush::Level;
 
 /// `LevelFilter` represents the different [`Level`] of logging we have,
 /// with the addition of `Off`.
+///
+/// Filters apply globally through [`set_max_level`], or to the records of a
+/// single tag through [`Tag::set_filter`](crate::tag::Tag::set_filter).
+/// `LevelFilter::Off` disables every level.
 #[repr(u8)]
 #[derive(Clone, Copy, Eq, PartialEq, PartialOrd)]
-#[doc(hidden)]
 pub enum LevelFilter {
     /// Enables trace and above
     Trace = 0,
@@ -86,6 +57,26 @@ pub enum LevelFilter {
     Off = 6,
 }
 
+impl LevelFilter {
+    /// Whether records at `level` pass this filter
+    #[inline]
+    pub fn enables(self, level: Level) -> bool {
+        level as u8 >= self as u8
+    }
+
+    pub(crate) fn from_u8(filter: u8) -> Self {
+        match filter {
+            0 => Self::Trace,
+            1 => Self::Debug,
+            2 => Self::Info,
+            3 => Self::Warn,
+            4 => Self::Error,
+            5 => Self::Event,
+            _ => Self::Off,
+        }
+    }
+}
+
 impl std::fmt::Display for LevelFilter {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let level_filter = match self {
@@ -129,6 +120,7 @@ impl std::str::FromStr for LevelFilter {
 
 static mut MAX_LOG_LEVEL_FILTER: LevelFilter = LevelFilter::Trace;
 
+/// Sets the global [`LevelFilter`], `LevelFilter::Off` disables all logging
 #[inline]
 pub fn set_max_level(level: LevelFilter) {
     unsafe {
@@ -136,6 +128,7 @@ pub fn set_max_level(level: LevelFilter) {
     }
 }
 
+/// Returns the global [`LevelFilter`]
 #[inline(always)]
 pub fn max_level() -> LevelFilter {
     unsafe { MAX_LOG_LEVEL_FILTER }
@@ -183,7 +176,9 @@ mod tests {
                 } else {
                     assert!(level_val >= filter_val);
                 }
+                assert_eq!(filter.enables(level), level_idx >= filter_idx);
             }
+            assert_eq!(LevelFilter::from_u8(filter as u8), filter);
         }
     }
 }
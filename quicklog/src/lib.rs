@@ -29,6 +29,15 @@
 //! `init!()` macro needs to be called to initialize the logger before we can
 //! start logging, probably near the entry point of your application.
 //!
+//! Records still queued when `main` returns are never flushed, unless the
+//! logger is initialized with `init_with_guard!()` instead, which returns a
+//! [`FlushGuard`] flushing them when dropped, or `main` is annotated with
+//! [`#[quicklog::main]`](main), which also flushes on panics.
+//!
+//! Records logged before `init!()`, e.g. by a library while the binary is
+//! still setting up, are dropped rather than queued, and counted in
+//! [`pre_init_dropped_count`].
+//!
 //! ## Example Usage
 //!
 //! ```
@@ -61,6 +70,77 @@
 //! * [`warn!`]
 //! * [`error!`]
 //!
+//! #### Compile-time level filtering
+//!
+//! [`default_level`] can be placed on a module or function to compile out all
+//! logging calls below a given level within it. Only quicklog's macros are
+//! affected: calls qualified as `quicklog::debug!`, or bare calls of macros
+//! imported from quicklog by a `use` within the annotated item.
+//!
+//! ```
+//! # use quicklog::init;
+//! #[quicklog::default_level(Warn)]
+//! mod order_book {
+//!     use quicklog::{debug, warn};
+//!
+//!     pub fn update() {
+//!         // no-op, lower than `Warn`
+//!         debug!("book updated");
+//!         warn!("book crossed");
+//!     }
+//! }
+//! # fn main() {
+//! # init!();
+//! # order_book::update();
+//! # }
+//! ```
+//!
+//! #### Disabling logging entirely
+//!
+//! With the `noop` feature, every logging call is compiled out as if disabled
+//! through [`default_level`], and [`init!`], [`flush!`] and the other setup
+//! and flush macros do nothing, without touching any call site. Bridges from
+//! `log` and `tracing` report every level as disabled. Records of the
+//! [`audit`] channel are still logged and flushed.
+//!
+//! #### Minimum interval per call site
+//!
+//! Hot call sites can be limited to logging at most once per interval, measured
+//! on the logger's [`Clock`], by starting the macro call with
+//! `min_interval: <duration>`. The duration is an integer literal in `ns`, `us`,
+//! `ms` or `s`. Calls within the interval are skipped before any argument is
+//! evaluated.
+//!
+//! ```
+//! # use quicklog::{init, info};
+//! # init!();
+//! # let book = [1, 2, 3];
+//! for level in book {
+//!     // logged at most once every 100ms
+//!     info!(min_interval: 100ms, "book level {}", level);
+//! }
+//! ```
+//!
+//! #### Tags
+//!
+//! Records can be tagged by starting the macro call with `tag: <name>`, where
+//! the name is an identifier or a string literal. Tags can be combined with
+//! `min_interval`, and are used to route records to a different flusher with
+//! [`with_tag_flush!`], see [`tag`].
+//!
+//! ```
+//! # use quicklog::{init, info};
+//! # init!();
+//! # let id = 1;
+//! info!(tag: audit, "order {} accepted", id);
+//! ```
+//!
+//! #### Logger instances
+//!
+//! Starting the macro call with `target: <logger>` logs into a [`Quicklog`]
+//! instance created with [`Quicklog::new`] instead of the global logger, see
+//! [`config`].
+//!
 //! ## Setup Macros
 //!
 //! Quicklog allows a user specified [`Clock`] or [`Flush`] to be implemented by
@@ -70,6 +150,18 @@
 //! * [`with_clock!`]: Specify the Clock Quicklog uses
 //! * [`with_flush!`]: Specify the Flusher Quicklog uses
 //! * [`with_flush_into_file`]: Specify path to flush log lines into
+//! * [`with_tag_flush!`]: Specify the Flusher for records with a given tag
+//! * [`with_audit_flush!`]: Specify the Flusher of the [`audit`] channel
+//! * [`with_filter!`]: Specify which records are logged by the level and
+//!   location of their call site
+//! * [`with_timestamp_format!`]: Specify how timestamps are formatted
+//! * [`with_output!`]: Add a formatter and flusher every record is also
+//!   written into, e.g. to write two formats at once
+//! * [`with_formatter!`]: Specify the formatter Quicklog uses, e.g. a
+//!   [`LogfmtFormatter`] for logfmt output, or a [`LayoutFormatter`] for a
+//!   custom layout
+//! * [`with_metrics_report!`], [`with_metrics_sink!`]: Specify how often and
+//!   where the metrics recorded with [`metric!`] are reported
 //!
 //! ## Macro prefix for partial serialization
 //!
@@ -81,16 +173,28 @@
 //! form, as you could simply pass the buffer directly into the decode fn, eliminiating any
 //! need to copy.
 //!
-//! ```ignore
+//! ```
 //! # use quicklog::{init, info, serialize::{Serialize, Store}};
 //! struct SomeStruct {
 //!     num: i64
 //! }
 //!
 //! impl Serialize for SomeStruct {
-//!    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut[u8]) { /* some impl */ }
-//!    fn decode(read_buf: &[u8]) -> (String, &[u8]) { /* some impl */ }
-//!    fn buffer_size_required(&self) -> usize { /* some impl */ }
+//!     fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+//!         let (chunk, rest) = write_buf.split_at_mut(8);
+//!         chunk.copy_from_slice(&self.num.to_le_bytes());
+//!         (Store::new(Self::decode, chunk), rest)
+//!     }
+//!
+//!     fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+//!         let (chunk, rest) = read_buf.split_at(8);
+//!         let num = i64::from_le_bytes(chunk.try_into().unwrap());
+//!         (format!("SomeStruct {{ num: {} }}", num), rest)
+//!     }
+//!
+//!     fn buffer_size_required(&self) -> usize {
+//!         8
+//!     }
 //! }
 //!
 //! fn main() {
@@ -100,6 +204,16 @@
 //! }
 //! ```
 //!
+//! Large arguments can be wrapped in `compressed(^...)`, e.g.
+//! `info!(book = compressed(^snapshot))`. With the `compression` feature, their
+//! encoded bytes are LZ4-compressed before being written into the queue, and
+//! decompressed when flushing. Without the feature, they are serialized like
+//! `^snapshot`.
+//!
+//! Byte buffers can be wrapped in `hex(^...)`, `base64(^...)` or
+//! `byte_len(^...)` to pick how they are rendered, see
+//! [`BytesFormat`](serialize::BytesFormat).
+//!
 //! ## Macro prefix for eager evaluation
 //!
 //! There are two prefixes you can use for variables, `%` and `?`. This works the same
@@ -119,10 +233,37 @@
 //! # }
 //! ```
 //!
+//! ## Lazy arguments
+//!
+//! Expensive arguments can be given as a closure prefixed by `lazy`, e.g.
+//! `info!("state: {}", lazy || summary())`. The closure is moved into the
+//! record and only called when it is flushed, so it is never called for
+//! records which are filtered out or dropped. Its value is formatted with
+//! `Display`, or with `Debug` and `Serialize` for `?lazy` and `^lazy`. Since
+//! it runs on the flushing thread, it may only capture owned values, e.g. with
+//! `move`, and is called once for every [`output`](crate::with_output)
+//! writing the record.
+//!
+//! ```
+//! # use quicklog::{init, info};
+//! # fn summary() -> String { String::new() }
+//! # fn main() {
+//! # init!();
+//! let levels = vec![101.5, 101.75];
+//! info!(
+//!     depth = lazy move || levels.len(),
+//!     ?lazy || summary(),
+//!     "book state: {}",
+//!     lazy || summary()
+//! );
+//! # }
+//! ```
+//!
 //! ## Structured fields
 //!
 //! Structured fields in log lines can be specified using `field_name = field_value`
-//! syntax. `field_name` can be a literal or a bunch of idents. This can also
+//! syntax. `field_name` can be dot-delimited idents, which may be keywords as
+//! in `order.type`, or a string literal such as `"http.status-code"`. This can also
 //! be used in combination with `%` and `?` prefix on args to eagerly evaluate
 //! expressions into format strings.
 //!
@@ -141,40 +282,59 @@
 //! There are two environment variables you can set:
 //!
 //! 1. `QUICKLOG_MAX_LOGGER_CAPACITY`
-//!     - sets the size of the spsc ring buffer used for logging
+//!     - sets the default number of records the logging queue holds, which can
+//!       also be set at runtime with `init!(capacity = ...)`
 //! 2. `QUICKLOG_MAX_SERIALIZE_BUFFER_CAPACITY`
 //!     - sets the size of the byte buffer used for static serialization
 //!     - this can be increased when you run into issues out of memory in debug
-//!     when conducting load testing
+//!       when conducting load testing
 //!
 //! # Components
 //!
 //! ## quicklog-clock
 //!
 //! [`Clock`] is the trait for a clock that can be used with [`Quicklog`]. Clocks can
-//! be swapped out at runtime with a different implementation.
+//! be swapped out at runtime with a different implementation, which only needs
+//! to return the current time in nanoseconds since the unix epoch. By default,
+//! the TSC based [`QuantaClock`] is used, while [`SystemClock`] reads
+//! `std::time::SystemTime` instead.
 //!
 //! This swap should be done at the init stage of your application to ensure
 //! that timings are consistent.
 //!
 //! ### Example
 //!
-//! ```ignore
-//! struct SomeClock;
-//!
-//! impl Clock for SomeClock { /* impl methods */ }
+//! ```
+//! use std::sync::atomic::{AtomicU64, Ordering};
+//! use quicklog::{flush, info, init, with_clock};
+//! use quicklog_clock::Clock;
 //!
-//! fn main() {
-//!     init!();
+//! // simulated clock, e.g. driven by market data timestamps when backtesting
+//! static SIMULATED_TIME: AtomicU64 = AtomicU64::new(0);
 //!
-//!     with_clock!(SomeClock::new());
+//! struct SimulatedClock;
 //!
-//!     // logger now uses SomeClock for timestamping
-//!     info!("Hello, world!");
-//!     flush!();
+//! impl Clock for SimulatedClock {
+//!     fn get_time(&self) -> u64 {
+//!         SIMULATED_TIME.load(Ordering::Relaxed)
+//!     }
 //! }
+//!
+//! # fn main() {
+//! init!();
+//!
+//! with_clock!(SimulatedClock);
+//!
+//! // logger now uses SimulatedClock for timestamping
+//! SIMULATED_TIME.store(1_698_048_000_000_000_000, Ordering::Relaxed);
+//! info!("Hello, world!");
+//! flush!();
+//! # }
 //! ```
 //!
+//! [`QuantaClock`]: quicklog_clock::quanta::QuantaClock
+//! [`SystemClock`]: quicklog_clock::system::SystemClock
+//!
 //! ## quicklog-flush
 //!
 //! [`Flush`] is the trait that defines how the log messages would be flushed.
@@ -200,39 +360,113 @@
 //! [`Serialize`]: serialize::Serialize
 //! [`StdoutFlusher`]: quicklog_flush::stdout_flusher::StdoutFlusher
 //! [`FileFlusher`]: quicklog_flush::file_flusher::FileFlusher
+//! [`LogfmtFormatter`]: logfmt::LogfmtFormatter
+//! [`LayoutFormatter`]: layout::LayoutFormatter
 
-use heapless::spsc::Queue;
+use adaptive::AdaptiveVerbosity;
+use callsite::{CallsiteFilter, CallsiteId, Metadata, __Callsite};
+use config::Config;
 use level::Level;
+use metric::{MetricSample, Metrics, MetricsLine, MetricsReport, MetricsSink};
 use once_cell::unsync::Lazy;
-use quanta::Instant;
-use serialize::buffer::ByteBuffer;
+use queue::{Consumer, Producer, QueueFullPolicy};
+use serialize::{buffer::ByteBuffer, EncodeErrorPolicy};
 use std::cell::OnceCell;
 use std::fmt::Display;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use tag::Tag;
+use timestamp::{CachedTimestamp, TimestampFormat};
 
 pub use std::{file, line, module_path};
 
 use chrono::{DateTime, Utc};
-use quicklog_clock::{quanta::QuantaClock, Clock};
-use quicklog_flush::{file_flusher::FileFlusher, Flush};
+use quicklog_clock::{quanta::QuantaClock, to_system_time, Clock};
+use quicklog_flush::{file_flusher::FileFlusher, AsyncFlush, Flush};
 
 /// re-export of crates, for use in macros
 pub use lazy_format;
 pub use quicklog_flush;
 
+/// contains the policy raising the verbosity of targets after error bursts
+pub mod adaptive;
+/// contains the audit channel, which never drops records
+pub mod audit;
+/// contains the static metadata of call sites and filters over it
+pub mod callsite;
+/// contains scoped context fields, which follow instrumented futures
+#[cfg(feature = "scoped-context")]
+pub mod context;
+/// contains the options of logger instances independent of the global logger
+pub mod config;
+/// contains the error type returned by fallible APIs
+pub mod error;
+/// contains the guard flushing the logger on drop
+pub mod guard;
+/// contains hooks which enrich field values when flushing
+pub mod hooks;
+/// contains the formatter writing journald entries
+#[cfg(all(target_os = "linux", feature = "journald"))]
+pub mod journald;
 /// contains logging levels and filters
 pub mod level;
+/// contains the bridge from the `log` crate facade
+#[cfg(feature = "log")]
+pub mod log_bridge;
+/// contains the formatter laying records out by a pattern string
+pub mod layout;
+/// contains the logfmt formatter
+pub mod logfmt;
 /// contains macros
 pub mod macros;
+/// contains counters, gauges and histograms queued along with records
+pub mod metric;
+/// contains the buffer for merging records from several producers in time order
+pub mod ordering;
+/// contains the policies for handling a full logging queue
+pub mod queue;
+/// contains the reader for querying flushed log files by time range
+pub mod reader;
+/// contains the registry of call site schemas, for decoding logs out of process
+pub mod schema;
 /// contains trait for serialization and pre-generated impl for common types and buffer
 pub mod serialize;
+/// contains spans, which prepend their context to the records logged within them
+pub mod span;
+/// contains live statistics about the logger
+#[cfg(feature = "stats")]
+pub mod stats;
+/// contains the soak-test harness for the logging queue
+#[cfg(feature = "stress")]
+pub mod stress;
+/// contains tags for routing records when flushing
+pub mod tag;
+/// contains helpers for testing what is logged
+pub mod test_utils;
+/// contains timestamp formatting options
+pub mod timestamp;
+/// contains startup validation of decode functions
+pub mod validate;
+/// contains the versioned binary layout of record headers
+pub mod wire;
+/// contains the bridge from the `tracing` ecosystem
+#[cfg(feature = "tracing-bridge")]
+pub mod tracing_bridge;
 
 include!("constants.rs");
 /// `constants.rs` is generated from `build.rs`, should not be modified manually
 pub mod constants;
 
-pub use quicklog_macros::{debug, error, info, trace, warn, Serialize, SerializeSelective};
+pub use error::Error;
+pub use guard::FlushGuard;
+pub use quicklog_macros::{
+    audit, debug, default_level, error, info, main, trace, warn, Serialize, SerializeSelective,
+};
 pub use serialize::FixedSizeSerialize;
 
+#[cfg(feature = "log")]
+pub use log_bridge::{init_log_bridge, LogBridge};
+
 /// Re-export fastrace types when trace feature is enabled
 #[cfg(feature = "trace")]
 pub use fastrace::prelude::SpanContext as __FastraceSpanContext;
@@ -241,20 +475,37 @@ pub use fastrace::prelude::SpanContext as __FastraceSpanContext;
 ///
 /// timed log item being stored into logging queue
 #[doc(hidden)]
-pub type TimedLogRecord = (Instant, LogRecord);
+pub type TimedLogRecord = (u64, LogRecord);
 
 /// Logger initialized to Quicklog
 #[doc(hidden)]
 static mut LOGGER: Lazy<Quicklog> = Lazy::new(Quicklog::default);
 
-/// Producer side of queue
-pub type Sender = heapless::spsc::Producer<'static, TimedLogRecord, MAX_LOGGER_CAPACITY>;
 /// Result from pushing onto queue
-pub type SendResult = Result<(), TimedLogRecord>;
-/// Consumer side of queue
-pub type Receiver = heapless::spsc::Consumer<'static, TimedLogRecord, MAX_LOGGER_CAPACITY>;
+pub type SendResult = Result<(), Error>;
 /// Result from trying to pop from logging queue
-pub type RecvResult = Result<(), FlushError>;
+pub type RecvResult = Result<(), Error>;
+
+/// Formatter and flusher of an output added with [`with_output!`]
+type Output = (Box<dyn PatternFormatter>, Box<dyn Flush>);
+
+/// Statistics about a flush, returned by [`flush_with_result!`] and
+/// [`flush_with_deadline!`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FlushResult {
+    /// Records written by the flush
+    pub events_flushed: usize,
+    /// Bytes of the log lines written by the flush, including those written
+    /// into outputs added with [`with_output!`] and record sinks added with
+    /// [`with_record_sink!`]
+    pub bytes_written: usize,
+    /// Records left in the queue after the flush, e.g. those logged while
+    /// flushing
+    pub events_remaining: usize,
+    /// Records dropped because the queue was full since the previous call to
+    /// [`flush_with_result!`], always 0 for [`flush_with_deadline!`]
+    pub dropped_since_last: usize,
+}
 
 /// Log is the base trait that Quicklog will implement.
 /// Flushing and formatting is deferred while logging.
@@ -265,69 +516,543 @@ pub trait Log {
     fn log(&mut self, record: LogRecord) -> SendResult;
 }
 
-/// Errors that can be presented when flushing
-#[derive(Debug)]
-pub enum FlushError {
-    /// Queue is empty
-    Empty,
-}
-
 ///  ha**Internal API**
 ///
 /// Returns a mut reference to the globally static logger [`LOGGER`]
 #[doc(hidden)]
 pub fn logger() -> &'static mut Quicklog {
-    unsafe { &mut LOGGER }
+    unsafe { &mut *std::ptr::addr_of_mut!(LOGGER) }
+}
+
+/// Flushes records logged from the module `target`, or any of its
+/// submodules, into `flush` instead of the default flusher. Records are
+/// matched by their module path, e.g. `gateway::fix` matches records logged
+/// from `gateway::fix` and `gateway::fix::session`, and the most specific
+/// target wins. Flushers for tags set with [`with_tag_flush!`] still take
+/// precedence.
+///
+/// ```
+/// # use quicklog::{info, init, route_target};
+/// # use quicklog_flush::file_flusher::FileFlusher;
+/// init!();
+/// route_target("gateway::fix", FileFlusher::new("logs/fix.log"));
+/// ```
+pub fn route_target<F: Flush + 'static>(target: &str, flush: F) {
+    logger().use_target_flush(target, Box::new(flush))
+}
+
+/// Returns the number of records dropped so far because the logging queue
+/// was full, according to the configured [`QueueFullPolicy`]
+pub fn dropped_count() -> usize {
+    logger().dropped_count()
+}
+
+/// Returns the number of records logged and dropped from every call site
+/// reached so far, along with when each last logged, starting with the call
+/// sites which logged the most records
+///
+/// ```
+/// # use quicklog::{callsite_stats, info, init};
+/// init!();
+/// for i in 0..3 {
+///     info!("tick {}", i);
+/// }
+///
+/// let busiest = &callsite_stats()[0];
+/// assert_eq!(busiest.hits, 3);
+/// assert_eq!(busiest.dropped, 0);
+/// assert!(busiest.last_hit.is_some());
+/// ```
+pub fn callsite_stats() -> Vec<callsite::CallsiteStats> {
+    callsite::stats()
+}
+
+/// Returns the number of records spilled so far into the overflow segment
+/// because the logging queue was full, with [`QueueFullPolicy::Spill`].
+/// Spilled records are still flushed, unlike the ones counted by
+/// [`dropped_count`].
+pub fn spilled_count() -> usize {
+    logger().spilled_count()
+}
+
+/// Returns the number of records dropped so far because one of their `^`
+/// arguments could not be encoded, according to the configured
+/// [`EncodeErrorPolicy`]
+///
+/// [`EncodeErrorPolicy`]: serialize::EncodeErrorPolicy
+pub fn encode_error_count() -> usize {
+    logger().encode_error_count()
+}
+
+/// Returns the number of records dropped because they were logged before the
+/// logger was initialized with [`init!`], e.g. by a library logging while the
+/// binary is still setting up. Such records are never flushed.
+pub fn pre_init_dropped_count() -> usize {
+    logger().pre_init_dropped_count()
+}
+
+/// **Internal API**
+///
+/// Flushes every record queued at the time of calling like [`flush_all!`],
+/// except that records going to the default flusher are written into the
+/// flusher set with [`with_async_flush!`], if any, returning how many records
+/// were written. Used in [`flush_async!`].
+#[doc(hidden)]
+pub async fn __flush_async() -> usize {
+    let Some(mut flusher) = logger().async_flusher.take() else {
+        return logger().flush_all();
+    };
+    let pending = logger().receiver.get().map_or(0, |receiver| receiver.len());
+
+    // the logger is not borrowed across awaits, so the line of the default
+    // flusher is formatted into a buffer of the future instead, reused for
+    // every record
+    let mut line = String::new();
+    let mut flushed = 0;
+    for _ in 0..pending {
+        let next = match logger().flush_next_with(Some(&mut line)) {
+            Ok(Next::Flushed(bytes)) => Ok(bytes),
+            Ok(Next::Deferred(deferred)) => {
+                let result = flusher.flush_str(deferred.level, &line).await;
+                logger().finish_deferred(deferred, result)
+            }
+            Err(err) => Err(err),
+        };
+        match next {
+            Ok(Some(_)) => flushed += 1,
+            Ok(None) | Err(Error::FlushFailed(_)) => {}
+            Err(_) => break,
+        }
+    }
+    logger().report_metrics_if_due();
+    // unless another flusher was set while flushing
+    logger().async_flusher.get_or_insert(flusher);
+    flushed
+}
+
+/// **Internal API**
+///
+/// Initial value of the last logged time of a call site with a
+/// `min_interval`, meaning nothing was logged from it yet
+#[doc(hidden)]
+pub const __NEVER_LOGGED: u64 = u64::MAX;
+
+/// **Internal API**
+///
+/// Adds a record logged within a fastrace span to the span as an event named
+/// after its format string, so that traces include log lines, returning the
+/// trace and span ids, and the span name, to stamp the record with.
+/// Arguments are not formatted, keeping formatting off the logging thread.
+#[cfg(feature = "trace")]
+#[doc(hidden)]
+#[inline]
+pub fn __trace_event(
+    format: &'static str,
+    level: Level,
+) -> (Option<u128>, Option<u64>, Option<&'static str>) {
+    let Some(context) = __FastraceSpanContext::current_local_parent() else {
+        return (None, None, None);
+    };
+    fastrace::Event::add_to_local_parent(format, || {
+        [(
+            std::borrow::Cow::Borrowed("level"),
+            std::borrow::Cow::Owned(level.to_string()),
+        )]
+    });
+    (
+        Some(context.trace_id.0),
+        Some(context.span_id.0),
+        span::name_of_fastrace_span(context.span_id.0),
+    )
+}
+
+/// **Internal API**
+///
+/// Checks whether at least `interval` nanoseconds passed on the logger's clock
+/// since a call site last logged, updating its last logged time if so
+#[doc(hidden)]
+pub fn __min_interval_elapsed(last_logged: &AtomicU64, interval: u64) -> bool {
+    let now = logger().clock.get_time();
+    let last = last_logged.load(Ordering::Relaxed);
+    if last != __NEVER_LOGGED && now.saturating_sub(last) < interval {
+        return false;
+    }
+    last_logged.store(now, Ordering::Relaxed);
+    true
+}
+
+/// **Internal API**
+///
+/// Counts a call of a call site with a `sample` rate, returning whether it is
+/// one of every `rate` calls, starting with the first
+#[doc(hidden)]
+pub fn __sampled(count: &AtomicU64, rate: u64) -> bool {
+    count.fetch_add(1, Ordering::Relaxed).is_multiple_of(rate)
+}
+
+/// **Internal API**
+///
+/// State of a call site with a `throttle`, counting the calls skipped since
+/// it last logged
+#[doc(hidden)]
+pub struct __Throttle {
+    last_logged: AtomicU64,
+    suppressed: AtomicU64,
+}
+
+impl __Throttle {
+    pub const fn new() -> Self {
+        Self {
+            last_logged: AtomicU64::new(__NEVER_LOGGED),
+            suppressed: AtomicU64::new(0),
+        }
+    }
+
+    /// Whether at least `interval` nanoseconds passed since the call site
+    /// last logged, counting the call as suppressed otherwise
+    pub fn allow(&self, interval: u64) -> bool {
+        let allowed = __min_interval_elapsed(&self.last_logged, interval);
+        if !allowed {
+            self.suppressed.fetch_add(1, Ordering::Relaxed);
+        }
+        allowed
+    }
+
+    /// Number of calls suppressed since the last call to this method
+    pub fn take_suppressed(&self) -> u64 {
+        self.suppressed.swap(0, Ordering::Relaxed)
+    }
+}
+
+impl Default for __Throttle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// **Internal API**
+///
+/// Argument given as `lazy || ...`, formatting the value returned by the
+/// closure with `Display` when the record is flushed
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct __LazyDisplay<F>(pub F);
+
+impl<F: Fn() -> T, T: Display> Display for __LazyDisplay<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&(self.0)(), f)
+    }
+}
+
+/// **Internal API**
+///
+/// Argument given as `?lazy || ...`, formatting the value returned by the
+/// closure with `Debug` when the record is flushed
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct __LazyDebug<F>(pub F);
+
+impl<F: Fn() -> T, T: std::fmt::Debug> Display for __LazyDebug<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&(self.0)(), f)
+    }
+}
+
+/// **Internal API**
+///
+/// Argument given as `^lazy || ...`, encoding and decoding the value returned
+/// by the closure when the record is flushed
+#[doc(hidden)]
+#[derive(Clone)]
+pub struct __LazySerialize<F>(pub F);
+
+impl<F: Fn() -> T, T: serialize::Serialize> Display for __LazySerialize<F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = (self.0)();
+        let mut buf = vec![0; value.buffer_size_required()];
+        let (store, _) = value.encode(&mut buf);
+        Display::fmt(&store, f)
+    }
+}
+
+/// **Internal API**
+///
+/// Writes how many calls of a throttled call site were suppressed since it
+/// last logged, if any, as a `suppressed` pair with the alternate flag
+#[doc(hidden)]
+pub fn __write_suppressed(f: &mut std::fmt::Formatter<'_>, suppressed: u64) -> std::fmt::Result {
+    match suppressed {
+        0 => Ok(()),
+        _ if f.alternate() => logfmt::__write_field(f, "suppressed", suppressed),
+        1 => f.write_str(" (suppressed 1 similar message)"),
+        _ => write!(f, " (suppressed {} similar messages)", suppressed),
+    }
 }
 
 pub struct LogRecord {
-    /// Level
-    pub level: Level,
-    /// Module path
-    pub module_path: &'static str,
-    /// File
-    pub file: &'static str,
-    /// Line
-    pub line: u32,
+    /// Call site the record was logged from, whose level and source location
+    /// are looked up with [`LogRecord::metadata`], see [`callsite`]
+    pub callsite: CallsiteId,
     /// Log line captured by using LazyFormat which implements Display trait.
+    /// Formatting it with the alternate flag (`{:#}`) writes the message and
+    /// fields as logfmt pairs, see [`logfmt`].
     pub log_line: Box<dyn Display>,
+    /// Tag given with the `tag:` option of the logging macros, see [`tag`]
+    pub tag: Option<Tag>,
+    /// Number of records logged before this one since the logger was
+    /// initialized, including dropped ones. Assigned by the logger, so records
+    /// are created with `0`.
+    pub sequence: u64,
+    /// Sample recorded with [`metric!`] instead of a log line, which is
+    /// aggregated into [`Quicklog::metrics`] rather than flushed. Boxed, so
+    /// that queued records fit in a cache line, see [`queue`]
+    pub metric: Option<Box<MetricSample>>,
     /// Trace ID (when trace feature is enabled)
     #[cfg(feature = "trace")]
     pub trace_id: Option<u128>,
+    /// ID of the fastrace span the record was logged in (when trace feature
+    /// is enabled)
+    #[cfg(feature = "trace")]
+    pub span_id: Option<u64>,
+    /// Name of the span of [`LogRecord::span_id`], when it was entered with
+    /// [`span::Span::enter`] (when trace feature is enabled)
+    #[cfg(feature = "trace")]
+    pub span_name: Option<&'static str>,
+}
+
+impl LogRecord {
+    /// Level and source location of the call site
+    pub fn metadata(&self) -> &'static Metadata {
+        self.callsite.metadata()
+    }
+
+    /// Level the record was logged at
+    pub fn level(&self) -> Level {
+        self.metadata().level()
+    }
+
+    /// Module path of the call site
+    pub fn module_path(&self) -> &'static str {
+        self.metadata().module_path()
+    }
+
+    /// Source file of the call site
+    pub fn file(&self) -> &'static str {
+        self.metadata().file()
+    }
+
+    /// Line of the call site
+    pub fn line(&self) -> u32 {
+        self.metadata().line()
+    }
+
+    /// Splits off `copies` records sharing the log line of this record, one
+    /// for every output added with [`with_output!`], so that the arguments
+    /// are decoded once rather than captured again
+    fn share(self, copies: usize) -> (LogRecord, Vec<LogRecord>) {
+        if copies == 0 {
+            return (self, Vec::new());
+        }
+
+        let log_line: Rc<dyn Display> = Rc::from(self.log_line);
+        let copy = |log_line: Rc<dyn Display>| LogRecord {
+            callsite: self.callsite,
+            log_line: Box::new(SharedLogLine(log_line)),
+            tag: self.tag,
+            sequence: self.sequence,
+            metric: self.metric.clone(),
+            #[cfg(feature = "trace")]
+            trace_id: self.trace_id,
+            #[cfg(feature = "trace")]
+            span_id: self.span_id,
+            #[cfg(feature = "trace")]
+            span_name: self.span_name,
+        };
+        let copies = (0..copies).map(|_| copy(log_line.clone())).collect();
+        (copy(log_line), copies)
+    }
+}
+
+/// Whether `module_path` is the module `target` or one of its submodules
+pub(crate) fn is_within_target(module_path: &str, target: &str) -> bool {
+    module_path
+        .strip_prefix(target)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+}
+
+/// Log line shared by the copies of a record made by [`LogRecord::share`]
+struct SharedLogLine(Rc<dyn Display>);
+
+impl Display for SharedLogLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // forwards the formatter as-is, keeping the alternate flag for logfmt
+        self.0.fmt(f)
+    }
+}
+
+/// Log line of a record spilled while the queue was full, formatted when
+/// spilled rather than when flushed, see [`QueueFullPolicy::Spill`]
+struct SpilledLine {
+    plain: String,
+    /// Formatted with the alternate flag, for logfmt
+    alternate: String,
+}
+
+impl SpilledLine {
+    fn new(log_line: &dyn Display) -> SpilledLine {
+        SpilledLine {
+            plain: log_line.to_string(),
+            alternate: format!("{:#}", log_line),
+        }
+    }
+}
+
+impl Display for SpilledLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            f.write_str(&self.alternate)
+        } else {
+            f.write_str(&self.plain)
+        }
+    }
 }
 
 pub trait PatternFormatter {
     fn custom_format(&mut self, time: DateTime<Utc>, log_record: LogRecord) -> String;
+
+    /// Appends the formatted record to `line`, a buffer the logger clears
+    /// and reuses for every record, so that flushing needs no allocation.
+    /// Defaults to appending the line returned by [`custom_format`].
+    ///
+    /// [`custom_format`]: PatternFormatter::custom_format
+    fn format_into(&mut self, time: DateTime<Utc>, log_record: LogRecord, line: &mut String) {
+        line.push_str(&self.custom_format(time, log_record));
+    }
+
+    /// Called whenever the [`TimestampFormat`] of the logger changes, as well as
+    /// when this formatter is attached to the logger. Formatters which format
+    /// timestamps should store the format and use it in [`custom_format`].
+    ///
+    /// [`custom_format`]: PatternFormatter::custom_format
+    fn set_timestamp_format(&mut self, _format: TimestampFormat) {}
+
+    /// Called whenever deterministic mode of the logger is toggled, as well
+    /// as when this formatter is attached to the logger, see
+    /// [`Quicklog::use_deterministic`]. Formatters should then write
+    /// [`LogRecord::sequence`], and leave out anything which differs between
+    /// runs.
+    fn set_deterministic(&mut self, _deterministic: bool) {}
+}
+
+/// Receives every record as it is flushed, before any formatting, e.g. to
+/// write them in binary form next to the text log, see [`wire::WireWriter`].
+/// Added with [`with_record_sink!`].
+pub trait RecordSink {
+    /// Writes `log_record`, logged at `time`, returning how many bytes were
+    /// written
+    fn write_record(
+        &mut self,
+        time: DateTime<Utc>,
+        log_record: &LogRecord,
+    ) -> std::io::Result<usize>;
+
+    /// Writes out anything buffered, see [`Flush::flush_buffered`]
+    fn flush_buffered(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
-pub struct QuickLogFormatter;
+pub struct QuickLogFormatter {
+    timestamp: CachedTimestamp,
+    deterministic: bool,
+}
 
 impl QuickLogFormatter {
     fn new() -> Self {
-        Self {}
+        Self {
+            timestamp: CachedTimestamp::default(),
+            deterministic: false,
+        }
     }
 }
 
 impl PatternFormatter for QuickLogFormatter {
     fn custom_format(&mut self, time: DateTime<Utc>, object: LogRecord) -> String {
+        let mut line = String::new();
+        self.format_into(time, object, &mut line);
+        line
+    }
+
+    fn format_into(&mut self, time: DateTime<Utc>, object: LogRecord, line: &mut String) {
+        use std::fmt::Write;
+
         #[cfg(feature = "trace")]
-        {
-            if let Some(trace_id) = object.trace_id {
-                return format!("[trace_id={:032x}] [{:?}]{}\n", trace_id, time, object.log_line);
+        if let Some(trace_id) = object.trace_id {
+            if !self.deterministic {
+                let _ = write!(line, "[trace_id={:032x}", trace_id);
+                if let Some(span_id) = object.span_id {
+                    let _ = write!(line, " span_id={:016x}", span_id);
+                }
+                if let Some(span_name) = object.span_name {
+                    let _ = write!(line, " span_name={}", span_name);
+                }
+                line.push_str("] ");
             }
         }
-        format!("[{:?}]{}\n", time, object.log_line)
+        line.push('[');
+        self.timestamp.write(line, time);
+        line.push(']');
+        if self.deterministic {
+            let _ = write!(line, "[{}]", object.sequence);
+        }
+        let _ = writeln!(line, "{}", object.log_line);
+    }
+
+    fn set_timestamp_format(&mut self, format: TimestampFormat) {
+        self.timestamp.set_format(format);
+    }
+
+    fn set_deterministic(&mut self, deterministic: bool) {
+        self.deterministic = deterministic;
     }
 }
 
 /// Quicklog implements the Log trait, to provide logging
 pub struct Quicklog {
     flusher: Box<dyn Flush>,
+    /// Taken out while [`flush_async!`] awaits it
+    async_flusher: Option<Box<dyn AsyncFlush>>,
+    tag_flushers: Vec<(Tag, Box<dyn Flush>)>,
+    target_flushers: Vec<(String, Box<dyn Flush>)>,
     clock: Box<dyn Clock>,
+    filter: Option<CallsiteFilter>,
+    adaptive: Option<AdaptiveVerbosity>,
     formatter: Box<dyn PatternFormatter>,
-    sender: OnceCell<Sender>,
-    receiver: OnceCell<Receiver>,
+    outputs: Vec<Output>,
+    record_sinks: Vec<Box<dyn RecordSink>>,
+    /// Formatted line of the record being flushed, reused for every record
+    line: String,
+    timestamp_format: TimestampFormat,
+    queue_full_policy: QueueFullPolicy,
+    encode_error_policy: EncodeErrorPolicy,
+    /// Counters written when logging, and read when flushing, possibly on
+    /// another thread
+    dropped: AtomicUsize,
+    encode_errors: AtomicUsize,
+    spilled: AtomicUsize,
+    /// `dropped` as of the last call to `flush_with_result`
+    dropped_at_last_result: usize,
+    pre_init_dropped: AtomicUsize,
+    deterministic: bool,
+    sequence: u64,
+    capacity: usize,
+    /// Logging side of the queue, only used by the thread logging
+    sender: OnceCell<Producer<TimedLogRecord>>,
+    /// Flushing side of the queue, only used by the thread flushing
+    receiver: OnceCell<Consumer<TimedLogRecord>>,
     byte_buffer: ByteBuffer,
+    metrics: Metrics,
+    metrics_report: Option<MetricsReport>,
 }
 
 impl Quicklog {
@@ -337,24 +1062,363 @@ impl Quicklog {
         self.flusher = flush
     }
 
-    pub fn use_formatter(&mut self, formatter: Box<dyn PatternFormatter>) {
+    /// Sets which flusher [`flush_async!`] flushes into, used in
+    /// [`with_async_flush!`]
+    #[doc(hidden)]
+    pub fn use_async_flush(&mut self, flush: Box<dyn AsyncFlush>) {
+        self.async_flusher = Some(flush)
+    }
+
+    /// Sets which flusher to be used, returning the previous one, used by
+    /// [`test_utils::capture`]
+    #[doc(hidden)]
+    pub fn replace_flush(&mut self, flush: Box<dyn Flush>) -> Box<dyn Flush> {
+        std::mem::replace(&mut self.flusher, flush)
+    }
+
+    /// Sets which flusher records tagged with `tag` are flushed into instead
+    /// of the default flusher, used in [`with_tag_flush!`]
+    #[doc(hidden)]
+    pub fn use_tag_flush(&mut self, tag: Tag, flush: Box<dyn Flush>) {
+        match self.tag_flushers.iter_mut().find(|(t, _)| *t == tag) {
+            Some((_, flusher)) => *flusher = flush,
+            None => self.tag_flushers.push((tag, flush)),
+        }
+    }
+
+    /// Sets which flusher records logged from the module `target` or its
+    /// submodules are flushed into, used in [`route_target`]
+    #[doc(hidden)]
+    pub fn use_target_flush(&mut self, target: &str, flush: Box<dyn Flush>) {
+        match self.target_flushers.iter_mut().find(|(t, _)| t == target) {
+            Some((_, flusher)) => *flusher = flush,
+            None => self.target_flushers.push((target.to_string(), flush)),
+        }
+    }
+
+    /// Sets which records are logged by the metadata of their call site,
+    /// used in [`with_filter!`]
+    #[doc(hidden)]
+    pub fn use_filter(&mut self, filter: CallsiteFilter) {
+        self.filter = Some(filter);
+    }
+
+    /// Removes the filter set with [`with_filter!`], logging every record
+    /// again
+    pub fn clear_filter(&mut self) {
+        self.filter = None;
+    }
+
+    /// Sets the policy raising the verbosity of targets after error bursts,
+    /// used in [`with_adaptive_verbosity!`]
+    #[doc(hidden)]
+    pub fn use_adaptive_verbosity(&mut self, mut policy: AdaptiveVerbosity) {
+        self.clear_adaptive_verbosity();
+        policy.install();
+        self.adaptive = Some(policy);
+    }
+
+    /// Removes the policy set with [`with_adaptive_verbosity!`], restoring
+    /// the global level if a target was boosted
+    pub fn clear_adaptive_verbosity(&mut self) {
+        if let Some(mut policy) = self.adaptive.take() {
+            policy.uninstall();
+        }
+    }
+
+    /// Policy set with [`with_adaptive_verbosity!`], if any
+    pub fn adaptive_verbosity(&self) -> Option<&AdaptiveVerbosity> {
+        self.adaptive.as_ref()
+    }
+
+    pub fn use_formatter(&mut self, mut formatter: Box<dyn PatternFormatter>) {
+        formatter.set_timestamp_format(self.timestamp_format.clone());
+        formatter.set_deterministic(self.deterministic);
         self.formatter = formatter
     }
 
+    /// Sets which formatter to be used, returning the previous one, used by
+    /// [`test_utils::capture`]
+    #[doc(hidden)]
+    pub fn replace_formatter(
+        &mut self,
+        mut formatter: Box<dyn PatternFormatter>,
+    ) -> Box<dyn PatternFormatter> {
+        formatter.set_timestamp_format(self.timestamp_format.clone());
+        formatter.set_deterministic(self.deterministic);
+        std::mem::replace(&mut self.formatter, formatter)
+    }
+
+    /// Adds an output which every record is also formatted and flushed into,
+    /// used in [`with_output!`]
+    #[doc(hidden)]
+    pub fn add_output(&mut self, mut formatter: Box<dyn PatternFormatter>, flush: Box<dyn Flush>) {
+        formatter.set_timestamp_format(self.timestamp_format.clone());
+        formatter.set_deterministic(self.deterministic);
+        self.outputs.push((formatter, flush));
+    }
+
+    /// Adds a sink which every record is also written into, used in
+    /// [`with_record_sink!`]
+    #[doc(hidden)]
+    pub fn add_record_sink(&mut self, sink: Box<dyn RecordSink>) {
+        self.record_sinks.push(sink);
+    }
+
+    /// Sets how timestamps are formatted, used in [`with_timestamp_format!`]
+    #[doc(hidden)]
+    pub fn use_timestamp_format(&mut self, format: TimestampFormat) {
+        self.formatter.set_timestamp_format(format.clone());
+        for (formatter, _) in &mut self.outputs {
+            formatter.set_timestamp_format(format.clone());
+        }
+        self.timestamp_format = format;
+    }
+
+    /// Makes the flushed output depend only on the logged records and on
+    /// `clock`, so that replaying the same input, e.g. in a backtest, flushes
+    /// byte-identical lines across runs. Used in [`init!`] with the
+    /// `deterministic` option.
+    ///
+    /// * timestamps are read from `clock`, which replaces the current clock
+    /// * formatters are told to write [`LogRecord::sequence`], numbering
+    ///   records from 0 in the order they were logged
+    /// * trace ids are not flushed
+    ///
+    /// Fields are always flushed in the order they are given at the call
+    /// site, and no thread ids or wall-clock times are captured.
+    #[doc(hidden)]
+    pub fn use_deterministic(&mut self, clock: Box<dyn Clock>) {
+        self.clock = clock;
+        self.deterministic = true;
+        self.formatter.set_deterministic(true);
+        for (formatter, _) in &mut self.outputs {
+            formatter.set_deterministic(true);
+        }
+    }
+
+    /// Whether the logger is in deterministic mode, see
+    /// [`Quicklog::use_deterministic`]
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic
+    }
+
+    /// Sets what happens when logging into a full queue, used in [`init!`]
+    #[doc(hidden)]
+    pub fn use_queue_full_policy(&mut self, policy: QueueFullPolicy) {
+        self.queue_full_policy = policy;
+    }
+
+    /// Returns the policy used when logging into a full queue
+    pub fn queue_full_policy(&self) -> QueueFullPolicy {
+        self.queue_full_policy
+    }
+
+    /// Sets what happens when a `^` argument cannot be encoded, used in
+    /// [`init!`]
+    #[doc(hidden)]
+    pub fn use_encode_error_policy(&mut self, policy: EncodeErrorPolicy) {
+        self.encode_error_policy = policy;
+    }
+
+    /// Returns the policy used when a `^` argument cannot be encoded
+    pub fn encode_error_policy(&self) -> EncodeErrorPolicy {
+        self.encode_error_policy
+    }
+
+    /// Checks whether the queue is initialized and full
+    #[cfg(feature = "stress")]
+    pub(crate) fn is_queue_full(&self) -> bool {
+        self.sender.get().is_some_and(|sender| sender.is_full())
+    }
+
+    /// Sets the number of records the queue holds, used in [`init!`]. Only
+    /// takes effect if called before the logger is initialized.
+    #[doc(hidden)]
+    pub fn use_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+    }
+
+    /// Returns the number of records the queue holds
+    pub fn capacity(&self) -> usize {
+        self.sender
+            .get()
+            .map_or(self.capacity, |sender| sender.capacity())
+    }
+
+    /// Returns the number of records dropped because the queue was full, see
+    /// [`dropped_count`]
+    pub fn dropped_count(&self) -> usize {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of records dropped because one of their `^`
+    /// arguments could not be encoded, see [`encode_error_count`]
+    pub fn encode_error_count(&self) -> usize {
+        self.encode_errors.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of records spilled into the overflow segment while
+    /// the queue was full, see [`spilled_count`]
+    pub fn spilled_count(&self) -> usize {
+        self.spilled.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of records dropped because they were logged before
+    /// the logger was initialized, see [`pre_init_dropped_count`]
+    pub fn pre_init_dropped_count(&self) -> usize {
+        self.pre_init_dropped.load(Ordering::Relaxed)
+    }
+
+    /// Flushes the records which were queued when this was called, returning
+    /// how many of them were written, used in [`flush_all!`].
+    ///
+    /// Records logged while flushing, e.g. by a producer which keeps logging,
+    /// are left in the queue for the next flush, so this always terminates.
+    /// Records which fail to flush are skipped.
+    pub fn flush_all(&mut self) -> usize {
+        self.flush_queued().events_flushed
+    }
+
+    /// Flushes like [`flush_all`](Quicklog::flush_all), returning statistics
+    /// about the flush and the health of the queue, used in
+    /// [`flush_with_result!`]
+    pub fn flush_with_result(&mut self) -> FlushResult {
+        let dropped = self.dropped_count();
+        let dropped_since_last = dropped - self.dropped_at_last_result;
+        self.dropped_at_last_result = dropped;
+        FlushResult {
+            dropped_since_last,
+            ..self.flush_queued()
+        }
+    }
+
+    /// Flushes like [`flush_all`](Quicklog::flush_all), but stops once
+    /// `budget` has elapsed, leaving the rest of the queue for the next
+    /// flush, used in [`flush_with_deadline!`]. At least one record is
+    /// flushed if any is queued, so that the queue keeps draining even with
+    /// a budget which is too short for a single record.
+    pub fn flush_with_deadline(&mut self, budget: std::time::Duration) -> FlushResult {
+        let deadline = std::time::Instant::now().checked_add(budget);
+        self.flush_queued_until(deadline)
+    }
+
+    fn flush_queued(&mut self) -> FlushResult {
+        self.flush_queued_until(None)
+    }
+
+    fn flush_queued_until(&mut self, deadline: Option<std::time::Instant>) -> FlushResult {
+        let mut result = FlushResult::default();
+        let Some(pending) = self.receiver.get().map(|receiver| receiver.len()) else {
+            return result;
+        };
+
+        for _ in 0..pending {
+            match self.flush_next() {
+                Ok(Some(bytes)) => {
+                    result.events_flushed += 1;
+                    result.bytes_written += bytes;
+                }
+                Ok(None) | Err(Error::FlushFailed(_)) => {}
+                Err(_) => break,
+            }
+            if deadline.is_some_and(|deadline| std::time::Instant::now() >= deadline) {
+                break;
+            }
+        }
+        self.report_metrics_if_due();
+        result.events_remaining = self.receiver.get().map_or(0, |receiver| receiver.len());
+        result
+    }
+
+    /// Writes out anything buffered by the default flusher, the flushers of
+    /// tags and targets, the outputs and the record sinks, see
+    /// [`Flush::flush_buffered`]. Every
+    /// flusher is written out even if another one failed, returning the
+    /// first error encountered.
+    pub fn flush_buffered(&mut self) -> std::io::Result<()> {
+        let tag_flushers = self.tag_flushers.iter_mut().map(|(_, flusher)| flusher);
+        let target_flushers = self.target_flushers.iter_mut().map(|(_, flusher)| flusher);
+        let outputs = self.outputs.iter_mut().map(|(_, flusher)| flusher);
+        let mut result = Ok(());
+        for flusher in std::iter::once(&mut self.flusher)
+            .chain(tag_flushers)
+            .chain(target_flushers)
+            .chain(outputs)
+        {
+            result = result.and(flusher.flush_buffered());
+        }
+        for sink in &mut self.record_sinks {
+            result = result.and(sink.flush_buffered());
+        }
+        result
+    }
+
+    /// Applies the [`EncodeErrorPolicy`] to a record whose `^` argument could
+    /// not be encoded, used by the logging macros
+    #[doc(hidden)]
+    pub fn record_encode_error(&mut self, err: Error) {
+        match self.encode_error_policy {
+            EncodeErrorPolicy::Drop => {
+                self.encode_errors.fetch_add(1, Ordering::Relaxed);
+            }
+            EncodeErrorPolicy::Panic => panic!("failed to encode log argument: {}", err),
+        }
+    }
+
+    fn record_dropped(&mut self, callsite: CallsiteId) {
+        callsite.record_dropped();
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "stats")]
+        stats::STATS.record_dropped();
+    }
+
     /// Sets which clock to be used, used in [`with_clock!`]
     #[doc(hidden)]
     pub fn use_clock(&mut self, clock: Box<dyn Clock>) {
         self.clock = clock
     }
 
+    /// Creates a logger instance independent of the global logger, with its
+    /// own queue, clock, formatter and flusher, which is initialized right
+    /// away. Records are logged into it with the `target:` option of the
+    /// logging macros, e.g. `info!(target: market_data_logger, "...")`, see
+    /// [`config`].
+    pub fn new(config: Config) -> Quicklog {
+        let mut logger = Quicklog {
+            capacity: config.capacity,
+            queue_full_policy: config.queue_full_policy,
+            encode_error_policy: config.encode_error_policy,
+            clock: config.clock,
+            flusher: config.flusher,
+            ..Quicklog::default()
+        };
+        logger.use_timestamp_format(config.timestamp_format);
+        logger.use_formatter(config.formatter);
+        logger.init();
+        logger
+    }
+
     /// Initializes channel inside of quicklog, can be called
-    /// through [`init!`] macro
+    /// through [`init!`] macro. Subsequent calls are ignored.
     pub fn init(&mut self) {
-        static mut QUEUE: Queue<TimedLogRecord, MAX_LOGGER_CAPACITY> = Queue::new();
-        let (sender, receiver): (Sender, Receiver) = unsafe { QUEUE.split() };
+        self.try_init().ok();
+    }
 
+    /// Initializes channel inside of quicklog, can be called through
+    /// [`try_init!`] macro. Returns [`Error::AlreadyInitialized`] if the
+    /// logger was already initialized.
+    pub fn try_init(&mut self) -> Result<(), Error> {
+        if self.sender.get().is_some() {
+            return Err(Error::AlreadyInitialized);
+        }
+        let (sender, receiver) = queue::channel(self.capacity);
         self.sender.set(sender).ok();
         self.receiver.set(receiver).ok();
+        #[cfg(feature = "stats")]
+        stats::STATS.record_init(self.capacity());
+
+        Ok(())
     }
 
     /// Internal API to get a chunk from buffer
@@ -370,52 +1434,460 @@ impl Quicklog {
     pub fn get_chunk_as_mut(&mut self, chunk_size: usize) -> &mut [u8] {
         self.byte_buffer.get_chunk_as_mut(chunk_size)
     }
+
+    /// Internal API to get a chunk from buffer, returning
+    /// [`Error::BufferTooSmall`] instead of panicking when `chunk_size`
+    /// exceeds the capacity of the buffer
+    #[doc(hidden)]
+    pub fn try_get_chunk_as_mut(&mut self, chunk_size: usize) -> Result<&mut [u8], Error> {
+        self.byte_buffer.try_get_chunk_as_mut(chunk_size)
+    }
+
+    /// Internal API to get the chunk a `^` argument of a record logged into
+    /// this logger is encoded into, like [`Quicklog::try_get_chunk_as_mut`].
+    /// The chunk outlives the borrow of the logger, since records only hold
+    /// onto it until they are flushed by this same logger.
+    #[doc(hidden)]
+    pub fn try_get_record_chunk(&mut self, chunk_size: usize) -> Result<&'static mut [u8], Error> {
+        let chunk = self.byte_buffer.try_get_chunk_as_mut(chunk_size)?;
+        // SAFETY: the chunk lives in the heap allocation of the byte buffer,
+        // which is neither moved nor freed before the logger is dropped, and
+        // the queued records reading it are dropped along with the logger
+        Ok(unsafe { std::slice::from_raw_parts_mut(chunk.as_mut_ptr(), chunk.len()) })
+    }
 }
 
 impl Default for Quicklog {
     fn default() -> Self {
         Quicklog {
             flusher: Box::new(FileFlusher::new("logs/quicklog.log")),
+            async_flusher: None,
+            tag_flushers: Vec::new(),
+            target_flushers: Vec::new(),
             clock: Box::new(QuantaClock::new()),
+            filter: None,
+            adaptive: None,
             formatter: Box::new(QuickLogFormatter::new()),
+            outputs: Vec::new(),
+            record_sinks: Vec::new(),
+            line: String::new(),
+            timestamp_format: TimestampFormat::default(),
+            queue_full_policy: QueueFullPolicy::default(),
+            encode_error_policy: EncodeErrorPolicy::default(),
+            dropped: AtomicUsize::new(0),
+            encode_errors: AtomicUsize::new(0),
+            spilled: AtomicUsize::new(0),
+            dropped_at_last_result: 0,
+            pre_init_dropped: AtomicUsize::new(0),
+            deterministic: false,
+            sequence: 0,
+            capacity: MAX_LOGGER_CAPACITY,
             sender: OnceCell::new(),
             receiver: OnceCell::new(),
             byte_buffer: ByteBuffer::new(),
+            metrics: Metrics::default(),
+            metrics_report: None,
         }
     }
 }
 
 impl Log for Quicklog {
     fn log(&mut self, record: LogRecord) -> SendResult {
-        match
-            self.sender
-                .get_mut()
-                .expect("Sender is not initialized, `Quicklog::init()` needs to be called at the entry point of your application")
-                .enqueue((self.clock.get_instant(), record))
+        if self
+            .filter
+            .as_ref()
+            .is_some_and(|filter| !filter(record.metadata()))
         {
-            Ok(_) => Ok(()),
-            Err(err) => Err(err),
+            return Ok(());
         }
+        let time_logged = self.clock.get_time();
+        if self
+            .adaptive
+            .as_mut()
+            .is_some_and(|policy| !policy.admit(record.metadata(), time_logged))
+        {
+            return Ok(());
+        }
+        #[cfg(feature = "scoped-context")]
+        let record = match context::current_if_entered() {
+            Some(context) => LogRecord {
+                log_line: Box::new(context::ContextLine {
+                    log_line: record.log_line,
+                    context,
+                }),
+                ..record
+            },
+            None => record,
+        };
+        let record = match span::current_if_entered() {
+            Some(spans) => LogRecord {
+                log_line: Box::new(span::SpanLine {
+                    log_line: record.log_line,
+                    spans,
+                }),
+                ..record
+            },
+            None => record,
+        };
+        self.enqueue(time_logged, record)
     }
 
     fn flush_one(&mut self) -> RecvResult {
-        match
-            self.receiver
-                    .get_mut()
-                    .expect("RECEIVER is not initialized, `Quicklog::init()` needs to be called at the entry point of your application")
-                    .dequeue()
-        {
-            Some((time_logged, record)) => {
-                let log_line = self.formatter.custom_format(
-                    self.clock
-                        .compute_system_time_from_instant(time_logged)
-                        .expect("Unable to get time from instant"),
-                    record,
-                );
-                self.flusher.flush_one(log_line);
+        self.flush_next().map(|_| ())
+    }
+}
+
+impl Quicklog {
+    /// Queues a sample recorded with [`metric!`], which is not filtered like
+    /// log records
+    #[doc(hidden)]
+    pub fn log_metric(&mut self, callsite: CallsiteId, sample: MetricSample) -> SendResult {
+        let record = LogRecord {
+            callsite,
+            log_line: Box::new(MetricsLine(Metrics::default())),
+            tag: None,
+            sequence: 0,
+            metric: Some(Box::new(sample)),
+            #[cfg(feature = "trace")]
+            trace_id: None,
+            #[cfg(feature = "trace")]
+            span_id: None,
+            #[cfg(feature = "trace")]
+            span_name: None,
+        };
+        let time_logged = self.clock.get_time();
+        self.enqueue(time_logged, record)
+    }
+
+    /// Metrics aggregated from the samples flushed so far, see [`metric!`]
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Reports the aggregated metrics every `interval`, into `sink` or as a
+    /// log line if there is none, used in [`with_metrics_report!`] and
+    /// [`with_metrics_sink!`]
+    #[doc(hidden)]
+    pub fn use_metrics_report(
+        &mut self,
+        interval: std::time::Duration,
+        sink: Option<Box<dyn MetricsSink>>,
+    ) {
+        self.metrics_report = Some(MetricsReport {
+            interval: u64::try_from(interval.as_nanos()).unwrap_or(u64::MAX),
+            last_report: None,
+            sink,
+        });
+    }
+
+    /// Reports the aggregated metrics right away, then starts a new period,
+    /// clearing histograms. Metrics are written as an `INFO` log line with the
+    /// default formatter and flusher unless a sink was set with
+    /// [`with_metrics_sink!`].
+    pub fn report_metrics(&mut self) -> RecvResult {
+        static CALLSITE: __Callsite =
+            __Callsite::new(Metadata::new(Level::Info, module_path!(), file!(), line!()));
+
+        let now = self.clock.get_time();
+        let time = to_system_time(now);
+        let result = match self.metrics_report.as_mut() {
+            Some(report) => {
+                report.last_report = Some(now);
+                match report.sink.as_mut() {
+                    Some(sink) => {
+                        sink.report(time, &self.metrics);
+                        Ok(())
+                    }
+                    None => self.write_metrics(time, CALLSITE.id()),
+                }
+            }
+            None => self.write_metrics(time, CALLSITE.id()),
+        };
+        self.metrics.end_period();
+        result
+    }
+
+    fn write_metrics(&mut self, time: DateTime<Utc>, callsite: CallsiteId) -> RecvResult {
+        let record = LogRecord {
+            callsite,
+            log_line: Box::new(MetricsLine(self.metrics.clone())),
+            tag: None,
+            sequence: self.sequence,
+            metric: None,
+            #[cfg(feature = "trace")]
+            trace_id: None,
+            #[cfg(feature = "trace")]
+            span_id: None,
+            #[cfg(feature = "trace")]
+            span_name: None,
+        };
+        let log_line = self.formatter.custom_format(time, record);
+        self.flusher
+            .try_flush_record(Level::Info, log_line)
+            .map_err(Error::FlushFailed)
+    }
+
+    fn report_metrics_if_due(&mut self) {
+        let Some(report) = self.metrics_report.as_mut() else {
+            return;
+        };
+        if report.is_due(self.clock.get_time()) {
+            let _ = self.report_metrics();
+        }
+    }
+
+    fn enqueue(&mut self, time_logged: u64, mut record: LogRecord) -> SendResult {
+        #[cfg(feature = "stats")]
+        let level = record.level();
+        let callsite = record.callsite;
+        callsite.record_hit(time_logged);
+        let Some(sender) = self.sender.get_mut() else {
+            // there is nowhere to keep the record until `init!` is called
+            callsite.record_dropped();
+            self.pre_init_dropped.fetch_add(1, Ordering::Relaxed);
+            #[cfg(feature = "stats")]
+            stats::STATS.record_pre_init_dropped();
+            return Err(Error::NotInitialized);
+        };
+        record.sequence = self.sequence;
+        self.sequence += 1;
+        let record = match sender.enqueue((time_logged, record)) {
+            Ok(_) => {
+                #[cfg(feature = "stats")]
+                stats::STATS.record_logged(level, sender.len());
+                return Ok(());
+            }
+            Err(record) => record,
+        };
+
+        // queue is full, so make room for the new record according to the policy
+        match self.queue_full_policy {
+            QueueFullPolicy::DropNewest => {}
+            QueueFullPolicy::DropOldest => {
+                // nothing is dropped if the consumer made room meanwhile
+                if let Some((_, oldest)) = sender.drop_oldest() {
+                    self.record_dropped(oldest.callsite);
+                }
+            }
+            QueueFullPolicy::Block => {
+                sender.enqueue_blocking(record);
+                #[cfg(feature = "stats")]
+                stats::STATS.record_logged(level, self.capacity());
+                return Ok(());
+            }
+            QueueFullPolicy::Spill { max_records } => {
+                let (time_logged, record) = record;
+                let record = LogRecord {
+                    log_line: Box::new(SpilledLine::new(&*record.log_line)),
+                    ..record
+                };
+                return match sender.spill((time_logged, record), max_records) {
+                    Ok(_) => {
+                        self.spilled.fetch_add(1, Ordering::Relaxed);
+                        #[cfg(feature = "stats")]
+                        stats::STATS.record_logged(level, self.capacity());
+                        Ok(())
+                    }
+                    Err(_) => {
+                        self.record_dropped(callsite);
+                        Err(Error::QueueFull)
+                    }
+                };
+            }
+        }
+
+        match self.sender.get_mut().map(|sender| sender.enqueue(record)) {
+            Some(Ok(_)) => {
+                #[cfg(feature = "stats")]
+                stats::STATS.record_logged(level, self.capacity());
                 Ok(())
             }
-            None => Err(FlushError::Empty),
+            _ => {
+                self.record_dropped(callsite);
+                Err(Error::QueueFull)
+            }
+        }
+    }
+
+    /// Flushes the oldest record, returning how many bytes were written, or
+    /// `None` if a metric sample was aggregated rather than a log line written
+    fn flush_next(&mut self) -> Result<Option<usize>, Error> {
+        match self.flush_next_with(None)? {
+            Next::Flushed(bytes) => Ok(bytes),
+            Next::Deferred(_) => unreachable!("only deferred into a line passed in"),
+        }
+    }
+
+    /// Flushes the oldest record like [`Quicklog::flush_next`], routed to the
+    /// flushers of its tag or target and written to the outputs. If it goes
+    /// to the default flusher and `deferred` is set, its line is formatted
+    /// into `deferred` instead, for the caller to flush, e.g. into the async
+    /// flusher, before passing the result to [`Quicklog::finish_deferred`].
+    fn flush_next_with(&mut self, deferred: Option<&mut String>) -> Result<Next, Error> {
+        let receiver = self.receiver.get_mut().ok_or(Error::NotInitialized)?;
+        match receiver.dequeue() {
+            Some((_, LogRecord {
+                metric: Some(sample),
+                ..
+            })) => {
+                self.metrics.record(*sample);
+                self.report_metrics_if_due();
+                Ok(Next::Flushed(None))
+            }
+            Some((time_logged, record)) => {
+                #[cfg(feature = "stats")]
+                let (start, queue_len) = (std::time::Instant::now(), receiver.len());
+                let flusher = match record
+                    .tag
+                    .and_then(|tag| self.tag_flushers.iter_mut().find(|(t, _)| *t == tag))
+                {
+                    Some((_, flusher)) => Some(flusher),
+                    None => self
+                        .target_flushers
+                        .iter_mut()
+                        .filter(|(target, _)| is_within_target(record.module_path(), target))
+                        .max_by_key(|(target, _)| target.len())
+                        .map(|(_, flusher)| flusher),
+                };
+                let level = record.level();
+                // trace and span ids are random, so they would differ between runs
+                #[cfg(feature = "trace")]
+                let record = LogRecord {
+                    trace_id: record.trace_id.filter(|_| !self.deterministic),
+                    span_id: record.span_id.filter(|_| !self.deterministic),
+                    ..record
+                };
+                let time = to_system_time(time_logged);
+                let (sinks_written, sinks_flushed) =
+                    Self::write_record_sinks(&mut self.record_sinks, time, &record);
+                let (record, copies) = record.share(self.outputs.len());
+                let line = &mut self.line;
+                if let (None, Some(deferred)) = (&flusher, deferred) {
+                    deferred.clear();
+                    self.formatter.format_into(time, record, deferred);
+                    let (outputs_written, outputs_flushed) =
+                        Self::flush_outputs(&mut self.outputs, copies, line, time, level);
+                    return Ok(Next::Deferred(DeferredLine {
+                        level,
+                        bytes: deferred.len(),
+                        outputs_written: sinks_written + outputs_written,
+                        outputs_flushed: sinks_flushed.and(outputs_flushed),
+                        #[cfg(feature = "stats")]
+                        start,
+                        #[cfg(feature = "stats")]
+                        queue_len,
+                    }));
+                }
+                let flusher = flusher.unwrap_or(&mut self.flusher);
+                line.clear();
+                self.formatter.format_into(time, record, line);
+                let bytes = line.len();
+                let flushed = flusher
+                    .try_flush_str(level, line)
+                    .map_err(Error::FlushFailed);
+                #[cfg(feature = "stats")]
+                stats::STATS.record_flushed(start.elapsed(), bytes, flushed.is_ok(), queue_len);
+                let bytes_written = if flushed.is_ok() { bytes } else { 0 };
+                let (outputs_written, outputs_flushed) =
+                    Self::flush_outputs(&mut self.outputs, copies, line, time, level);
+                flushed
+                    .and(sinks_flushed)
+                    .and(outputs_flushed)
+                    .map(|()| Next::Flushed(Some(bytes_written + sinks_written + outputs_written)))
+            }
+            None => {
+                self.report_metrics_if_due();
+                Err(Error::Empty)
+            }
         }
     }
+
+    /// Writes the copies of a record to the outputs, returning how many bytes
+    /// were written. Every output is written to even if another one failed,
+    /// returning the first error encountered.
+    fn flush_outputs(
+        outputs: &mut [Output],
+        copies: Vec<LogRecord>,
+        line: &mut String,
+        time: DateTime<Utc>,
+        level: Level,
+    ) -> (usize, Result<(), Error>) {
+        let mut bytes_written = 0;
+        let mut flushed = Ok(());
+        for ((formatter, flusher), record) in outputs.iter_mut().zip(copies) {
+            line.clear();
+            formatter.format_into(time, record, line);
+            let bytes = line.len();
+            let output_flushed = flusher
+                .try_flush_str(level, line)
+                .map_err(Error::FlushFailed);
+            if output_flushed.is_ok() {
+                bytes_written += bytes;
+            }
+            flushed = flushed.and(output_flushed);
+        }
+        (bytes_written, flushed)
+    }
+
+    /// Writes a record into the record sinks, returning how many bytes were
+    /// written. Like the outputs, every sink is written to even if another one
+    /// failed, returning the first error encountered.
+    fn write_record_sinks(
+        sinks: &mut [Box<dyn RecordSink>],
+        time: DateTime<Utc>,
+        record: &LogRecord,
+    ) -> (usize, Result<(), Error>) {
+        let mut bytes_written = 0;
+        let mut written = Ok(());
+        for sink in sinks {
+            match sink.write_record(time, record) {
+                Ok(bytes) => bytes_written += bytes,
+                Err(err) => written = written.and(Err(Error::FlushFailed(err))),
+            }
+        }
+        (bytes_written, written)
+    }
+
+    /// Completes a record left to the caller by [`Quicklog::flush_next_with`],
+    /// given the result of flushing its line, like [`Quicklog::flush_next`]
+    /// returns it
+    fn finish_deferred(
+        &mut self,
+        deferred: DeferredLine,
+        flushed: std::io::Result<()>,
+    ) -> Result<Option<usize>, Error> {
+        #[cfg(feature = "stats")]
+        stats::STATS.record_flushed(
+            deferred.start.elapsed(),
+            deferred.bytes,
+            flushed.is_ok(),
+            deferred.queue_len,
+        );
+        let bytes_written = if flushed.is_ok() { deferred.bytes } else { 0 };
+        flushed
+            .map_err(Error::FlushFailed)
+            .and(deferred.outputs_flushed)
+            .map(|()| Some(bytes_written + deferred.outputs_written))
+    }
+}
+
+/// Outcome of [`Quicklog::flush_next_with`]
+enum Next {
+    /// Bytes written, or `None` if a metric sample was aggregated
+    Flushed(Option<usize>),
+    /// Line left for the caller to flush into the default flusher
+    Deferred(DeferredLine),
+}
+
+/// Record whose line is left for the caller of [`Quicklog::flush_next_with`]
+/// to flush, once written to the record sinks and outputs
+struct DeferredLine {
+    level: Level,
+    bytes: usize,
+    outputs_written: usize,
+    outputs_flushed: Result<(), Error>,
+    #[cfg(feature = "stats")]
+    start: std::time::Instant,
+    #[cfg(feature = "stats")]
+    queue_len: usize,
 }
@@ -0,0 +1,128 @@
+//! Bridge from the [`log`] crate facade into quicklog, enabled with the `log`
+//! feature.
+//!
+//! Once installed with [`init_log_bridge`], records logged by dependencies
+//! through `log::info!` and friends are funneled into quicklog's queue. The
+//! arguments of these records are formatted eagerly, as [`log::Record`] only
+//! borrows them, but flushing is still deferred until [`flush!`] is called.
+//!
+//! As with quicklog's own macros, records should be logged on the thread which
+//! owns the logger.
+//!
+//! ```
+//! quicklog::init!();
+//! quicklog::init_log_bridge().unwrap();
+//!
+//! // e.g. logged from within a dependency
+//! log::info!("connected to {}", "exchange");
+//! quicklog::flush!();
+//! ```
+//!
+//! [`flush!`]: crate::flush
+
+use crate::{
+    callsite,
+    level::{self, Level, LevelFilter},
+    logfmt::FormattedLine,
+    logger, Log, LogRecord,
+};
+
+/// Implements [`log::Log`] by pushing records onto quicklog's queue
+pub struct LogBridge;
+
+static LOG_BRIDGE: LogBridge = LogBridge;
+
+/// Installs [`LogBridge`] as the global [`log`] logger, with its max level set
+/// to quicklog's current max level. Fails if a [`log`] logger was already
+/// installed.
+pub fn init_log_bridge() -> Result<(), log::SetLoggerError> {
+    log::set_logger(&LOG_BRIDGE)?;
+    log::set_max_level(match level::max_level() {
+        LevelFilter::Trace => log::LevelFilter::Trace,
+        LevelFilter::Debug => log::LevelFilter::Debug,
+        LevelFilter::Info => log::LevelFilter::Info,
+        LevelFilter::Warn => log::LevelFilter::Warn,
+        LevelFilter::Error => log::LevelFilter::Error,
+        LevelFilter::Event | LevelFilter::Off => log::LevelFilter::Off,
+    });
+    Ok(())
+}
+
+fn to_level(level: log::Level) -> Level {
+    match level {
+        log::Level::Trace => Level::Trace,
+        log::Level::Debug => Level::Debug,
+        log::Level::Info => Level::Info,
+        log::Level::Warn => Level::Warn,
+        log::Level::Error => Level::Error,
+    }
+}
+
+impl log::Log for LogBridge {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        crate::is_level_enabled!(to_level(metadata.level()))
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let log_record = LogRecord {
+            callsite: callsite::intern(
+                to_level(record.level()),
+                record.module_path_static().unwrap_or_default(),
+                record.file_static().unwrap_or_default(),
+                record.line().unwrap_or_default(),
+            ),
+            log_line: Box::new(FormattedLine::new(format!("{}", record.args()))),
+            tag: None,
+            sequence: 0,
+            metric: None,
+            #[cfg(feature = "trace")]
+            trace_id: None,
+            #[cfg(feature = "trace")]
+            span_id: None,
+            #[cfg(feature = "trace")]
+            span_name: None,
+        };
+        logger().log(log_record).unwrap_or(())
+    }
+
+    fn flush(&self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use quicklog_flush::Flush;
+
+    use crate::{flush_all, with_flush};
+
+    struct VecFlusher(Rc<RefCell<Vec<String>>>);
+
+    impl Flush for VecFlusher {
+        fn flush_one(&mut self, display: String) {
+            self.0.borrow_mut().push(display)
+        }
+    }
+
+    #[test]
+    fn log_records_are_queued() {
+        let lines = Rc::new(RefCell::new(Vec::new()));
+        crate::init!();
+        with_flush!(VecFlusher(lines.clone()));
+        super::init_log_bridge().unwrap();
+
+        log::info!("hello {}", "world");
+        log::warn!(target: "dependency", "careful");
+        assert!(lines.borrow().is_empty());
+
+        flush_all!();
+        let lines = lines.borrow();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("]hello world\n"));
+        assert!(lines[1].ends_with("]careful\n"));
+    }
+}
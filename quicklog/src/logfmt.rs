@@ -0,0 +1,210 @@
+//! Defines [`LogfmtFormatter`], which flushes records as [logfmt] key-value
+//! pairs instead of a positional log line.
+//!
+//! ```
+//! # use quicklog::{init, info, logfmt::LogfmtFormatter};
+//! init!(formatter = LogfmtFormatter::new());
+//!
+//! let price = 101.5;
+//! info!(symbol = "BTC-USD", price = price, "order filled");
+//! // ts=2023-10-23T08:00:00.123456789Z level=INFO msg="order filled" symbol=BTC-USD price=101.5
+//! ```
+//!
+//! The message and fields of a record are written as logfmt pairs when its
+//! [`LogRecord::log_line`] is formatted with the alternate flag (`{:#}`), so
+//! custom [`PatternFormatter`]s can emit logfmt as well. Each pair is preceded
+//! by a single space.
+//!
+//! [logfmt]: https://brandur.org/logfmt
+
+use std::fmt::{self, Display, Formatter, Write};
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    hooks,
+    timestamp::{CachedTimestamp, TimestampFormat},
+    LogRecord, PatternFormatter,
+};
+
+/// Formats records as `ts=... level=INFO msg="..." key=value ...`, with
+/// `tag=...` after the level for tagged records, preceded by `seq=...` in
+/// deterministic mode
+pub struct LogfmtFormatter {
+    timestamp: CachedTimestamp,
+    deterministic: bool,
+}
+
+impl LogfmtFormatter {
+    pub fn new() -> Self {
+        Self {
+            timestamp: CachedTimestamp::default(),
+            deterministic: false,
+        }
+    }
+}
+
+impl Default for LogfmtFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PatternFormatter for LogfmtFormatter {
+    fn custom_format(&mut self, time: DateTime<Utc>, log_record: LogRecord) -> String {
+        let mut line = String::new();
+        self.format_into(time, log_record, &mut line);
+        line
+    }
+
+    fn format_into(&mut self, time: DateTime<Utc>, log_record: LogRecord, line: &mut String) {
+        line.push_str("ts=");
+        match self.timestamp.format() {
+            // may need quoting
+            TimestampFormat::Custom(_) => {
+                let _ = write_value(line, self.timestamp.format().display(time));
+            }
+            _ => self.timestamp.write(line, time),
+        }
+        let _ = write!(line, " level={}", log_record.level());
+        if self.deterministic {
+            let _ = write!(line, " seq={}", log_record.sequence);
+        }
+        if let Some(tag) = log_record.tag {
+            line.push_str(" tag=");
+            let _ = write_value(line, tag);
+        }
+        #[cfg(feature = "trace")]
+        if let Some(trace_id) = log_record.trace_id {
+            let _ = write!(line, " trace_id={:032x}", trace_id);
+        }
+        #[cfg(feature = "trace")]
+        if let Some(span_id) = log_record.span_id {
+            let _ = write!(line, " span_id={:016x}", span_id);
+        }
+        let _ = writeln!(line, "{:#}", log_record.log_line);
+    }
+
+    fn set_timestamp_format(&mut self, format: TimestampFormat) {
+        self.timestamp.set_format(format);
+    }
+
+    fn set_deterministic(&mut self, deterministic: bool) {
+        self.deterministic = deterministic;
+    }
+}
+
+/// Writes `value`, quoting and escaping it if it would otherwise not be
+/// parsed back as a single logfmt value
+fn write_value<W: Write>(w: &mut W, value: impl Display) -> fmt::Result {
+    let value = value.to_string();
+    let needs_quotes = value.is_empty()
+        || value
+            .chars()
+            .any(|c| c == ' ' || c == '=' || c == '"' || c == '\\' || c.is_control());
+
+    if needs_quotes {
+        write!(w, "{:?}", value)
+    } else {
+        w.write_str(&value)
+    }
+}
+
+/// **Internal API**
+///
+/// Writes the message of a record as a ` msg=...` pair
+#[doc(hidden)]
+pub fn __write_message(f: &mut Formatter<'_>, message: fmt::Arguments<'_>) -> fmt::Result {
+    f.write_str(" msg=")?;
+    write_value(f, message)
+}
+
+/// **Internal API**
+///
+/// Writes a field of a record as a ` key=value` pair
+#[doc(hidden)]
+pub fn __write_field(f: &mut Formatter<'_>, key: &str, value: impl Display) -> fmt::Result {
+    write!(f, " {}=", key)?;
+    let value = value.to_string();
+    match hooks::enrich(key, &value) {
+        Some(enriched) => write_value(f, enriched),
+        None => write_value(f, value),
+    }
+}
+
+/// Log line of a record which was already formatted, such as one received
+/// from a bridged logging facade, together with its fields
+#[cfg(any(feature = "log", feature = "tracing-bridge"))]
+pub(crate) struct FormattedLine {
+    pub(crate) message: String,
+    pub(crate) fields: Vec<(&'static str, String)>,
+}
+
+#[cfg(any(feature = "log", feature = "tracing-bridge"))]
+impl FormattedLine {
+    pub(crate) fn new(message: String) -> Self {
+        Self {
+            message,
+            fields: Vec::new(),
+        }
+    }
+}
+
+#[cfg(any(feature = "log", feature = "tracing-bridge"))]
+impl Display for FormattedLine {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            if !self.message.is_empty() {
+                __write_message(f, format_args!("{}", self.message))?;
+            }
+            for (key, value) in &self.fields {
+                __write_field(f, key, value)?;
+            }
+            return Ok(());
+        }
+
+        f.write_str(&self.message)?;
+        for (key, value) in &self.fields {
+            write!(f, " {}={}", key, value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write_value;
+
+    fn value(value: &str) -> String {
+        let mut out = String::new();
+        write_value(&mut out, value).unwrap();
+        out
+    }
+
+    #[test]
+    fn values_are_quoted_when_needed() {
+        assert_eq!(value("BTC-USD"), "BTC-USD");
+        assert_eq!(value("101.5"), "101.5");
+        assert_eq!(value(""), "\"\"");
+        assert_eq!(value("order filled"), "\"order filled\"");
+        assert_eq!(value("a=b"), "\"a=b\"");
+        assert_eq!(value("say \"hi\""), "\"say \\\"hi\\\"\"");
+        assert_eq!(value("two\nlines"), "\"two\\nlines\"");
+    }
+
+    #[cfg(any(feature = "log", feature = "tracing-bridge"))]
+    #[test]
+    fn formatted_line() {
+        use super::FormattedLine;
+
+        let mut line = FormattedLine::new("order filled".to_string());
+        line.fields.push(("side", "buy".to_string()));
+        line.fields.push(("venue", "x y".to_string()));
+
+        assert_eq!(line.to_string(), "order filled side=buy venue=x y");
+        assert_eq!(
+            format!("{:#}", line),
+            " msg=\"order filled\" side=buy venue=\"x y\""
+        );
+    }
+}
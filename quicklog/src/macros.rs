@@ -4,7 +4,36 @@
 #[macro_export]
 macro_rules! with_flush {
     ($flush:expr) => {{
-        $crate::logger().use_flush($crate::make_container!($flush))
+        $crate::__unless_noop!(
+            $crate::logger().use_flush($crate::make_container!($flush)),
+            ()
+        )
+    }};
+}
+
+/// Used to amend which `Flush` records tagged with the given [`Tag`] are
+/// flushed into, instead of the flusher set with [`with_flush!`]. The tag can
+/// be given as a `Tag` or by its name.
+///
+/// ```
+/// # use quicklog::{info, init, with_tag_flush};
+/// # use quicklog_flush::file_flusher::FileFlusher;
+/// init!();
+/// with_tag_flush!("audit", FileFlusher::new("logs/audit.log"));
+/// info!(tag: audit, "order accepted");
+/// ```
+///
+/// [`Tag`]: crate::tag::Tag
+#[macro_export]
+macro_rules! with_tag_flush {
+    ($tag:expr, $flush:expr) => {{
+        $crate::__unless_noop!(
+            $crate::logger().use_tag_flush(
+                $crate::tag::Tag::from($tag),
+                $crate::make_container!($flush)
+            ),
+            ()
+        )
     }};
 }
 
@@ -14,7 +43,63 @@ macro_rules! with_flush {
 #[macro_export]
 macro_rules! with_formatter {
     ($formatter:expr) => {{
-        $crate::logger().use_formatter($crate::make_container!($formatter))
+        $crate::__unless_noop!(
+            $crate::logger().use_formatter($crate::make_container!($formatter)),
+            ()
+        )
+    }};
+}
+
+/// Adds an output to `Quicklog`, made of a `PatternFormatter` and a `Flush`.
+/// Every record is formatted and flushed into each output as well as into
+/// the flusher set with [`with_flush!`], from a single drain of the queue,
+/// e.g. to write a legacy text file next to a new format during a migration.
+///
+/// ```
+/// # use quicklog::{info, init, flush, with_output, logfmt::LogfmtFormatter};
+/// # use quicklog_flush::file_flusher::FileFlusher;
+/// init!();
+/// with_output!(LogfmtFormatter::new(), FileFlusher::new("logs/quicklog.logfmt"));
+/// info!("written to both files");
+/// flush!();
+/// ```
+#[macro_export]
+macro_rules! with_output {
+    ($formatter:expr, $flush:expr) => {{
+        $crate::__unless_noop!(
+            $crate::logger().add_output(
+                $crate::make_container!($formatter),
+                $crate::make_container!($flush)
+            ),
+            ()
+        )
+    }};
+}
+
+/// Adds a [`RecordSink`] to `Quicklog`, which every record is written into
+/// before being formatted, from the same drain of the queue as the flusher
+/// set with [`with_flush!`] and the outputs, e.g. to write binary segments
+/// next to the legacy text file with a [`WireWriter`].
+///
+/// ```
+/// # use quicklog::{info, init, flush, with_record_sink, wire::WireWriter};
+/// # use std::fs::File;
+/// init!();
+/// # std::fs::create_dir_all("logs").unwrap();
+/// with_record_sink!(WireWriter::new(File::create("logs/quicklog.bin").unwrap()));
+/// info!("written as text and in binary form");
+/// flush!();
+/// ```
+///
+/// [`RecordSink`]: crate::RecordSink
+/// [`WireWriter`]: crate::wire::WireWriter
+#[macro_export]
+macro_rules! with_record_sink {
+    ($sink:expr) => {{
+        $crate::__unless_noop!(
+            $crate::logger().add_record_sink($crate::make_container!($sink)),
+            ()
+        )
     }};
 }
 
@@ -22,19 +107,314 @@ macro_rules! with_formatter {
 #[macro_export]
 macro_rules! with_flush_into_file {
     ($file_path:expr) => {{
-        let flusher = $crate::quicklog_flush::file_flusher::FileFlusher::new($file_path);
-        $crate::logger().use_flush($crate::make_container!(flusher));
+        $crate::__unless_noop!(
+            {
+                let flusher = $crate::quicklog_flush::file_flusher::FileFlusher::new($file_path);
+                $crate::logger().use_flush($crate::make_container!(flusher));
+            },
+            ()
+        )
     }};
 }
 
 /// Initializes Quicklog by calling [`Quicklog::init()`]
 /// Should only be called once in the application
 ///
+/// Options can optionally be passed in as `key = value` pairs:
+///
+/// * `capacity`: number of records the logging queue holds, defaults to
+///   [`MAX_LOGGER_CAPACITY`]
+/// * `timestamp_format`: a [`TimestampFormat`], see [`with_timestamp_format!`]
+/// * `float_format`: a [`FloatFormat`], see [`with_float_format!`]
+/// * `queue_full_policy`: a [`QueueFullPolicy`], defaults to dropping the new record
+/// * `encode_error_policy`: an [`EncodeErrorPolicy`], defaults to dropping
+///   records whose `^` arguments cannot be encoded
+/// * `formatter`: a [`PatternFormatter`], e.g. [`LogfmtFormatter`], see [`with_formatter!`]
+/// * `deterministic`: a [`Clock`] replaying recorded timestamps, which makes
+///   the flushed output identical across runs, see
+///   [`Quicklog::use_deterministic()`]
+///
+/// ```
+/// # use quicklog::{init, timestamp::TimestampFormat};
+/// init!(capacity = 8 * 1024, timestamp_format = TimestampFormat::Rfc3339);
+/// ```
+///
 /// [`Quicklog::init()`]: crate::Quicklog::init
+/// [`Quicklog::use_deterministic()`]: crate::Quicklog::use_deterministic
+/// [`Clock`]: quicklog_clock::Clock
+/// [`MAX_LOGGER_CAPACITY`]: crate::constants::MAX_LOGGER_CAPACITY
+/// [`TimestampFormat`]: crate::timestamp::TimestampFormat
+/// [`FloatFormat`]: crate::serialize::FloatFormat
+/// [`QueueFullPolicy`]: crate::queue::QueueFullPolicy
+/// [`EncodeErrorPolicy`]: crate::serialize::EncodeErrorPolicy
+/// [`PatternFormatter`]: crate::PatternFormatter
+/// [`LogfmtFormatter`]: crate::logfmt::LogfmtFormatter
 #[macro_export]
 macro_rules! init {
     () => {
-        $crate::logger().init();
+        $crate::__unless_noop!($crate::logger().init(), ());
+    };
+    ($($key:ident = $value:expr),+ $(,)?) => {{
+        $crate::__unless_noop!(
+            {
+                $($crate::__init_option!($key = $value);)+
+                $crate::logger().init();
+            },
+            ()
+        )
+    }};
+}
+
+/// Initializes Quicklog like [`init!`], returning a [`FlushGuard`] which
+/// flushes every queued record and the flushers' buffers when dropped. Takes
+/// the same options as [`init!`].
+///
+/// Bind the guard to a name such as `_guard`, as `let _ = ...` drops it
+/// right away.
+///
+/// ```
+/// # use quicklog::{info, init_with_guard};
+/// fn main() {
+///     let _guard = init_with_guard!(capacity = 8 * 1024);
+///
+///     info!("flushed when main returns");
+/// }
+/// ```
+///
+/// [`FlushGuard`]: crate::FlushGuard
+#[macro_export]
+macro_rules! init_with_guard {
+    ($($key:ident = $value:expr),* $(,)?) => {{
+        $crate::init!($($key = $value),*);
+        $crate::FlushGuard::new()
+    }};
+}
+
+/// Applies a single `key = value` option passed to [`init!`]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __init_option {
+    (capacity = $value:expr) => {
+        $crate::logger().use_capacity($value)
+    };
+    (timestamp_format = $value:expr) => {
+        $crate::logger().use_timestamp_format($value)
+    };
+    (float_format = $value:expr) => {
+        $crate::serialize::set_float_format($value)
+    };
+    (queue_full_policy = $value:expr) => {
+        $crate::logger().use_queue_full_policy($value)
+    };
+    (encode_error_policy = $value:expr) => {
+        $crate::logger().use_encode_error_policy($value)
+    };
+    (formatter = $value:expr) => {
+        $crate::logger().use_formatter($crate::make_container!($value))
+    };
+    (deterministic = $value:expr) => {
+        $crate::logger().use_deterministic($crate::make_container!($value))
+    };
+}
+
+/// Used to amend which records are logged, takes in a closure deciding by
+/// the [`Metadata`] of their call site. Records which are filtered out are
+/// neither queued nor counted as dropped.
+///
+/// ```
+/// # use quicklog::{callsite::Metadata, init, level::Level, with_filter};
+/// init!();
+/// // keep everything from the gateway, and only warnings from elsewhere
+/// with_filter!(|metadata: &Metadata| {
+///     metadata.module_path().starts_with("gateway") || metadata.level() >= Level::Warn
+/// });
+/// ```
+///
+/// [`Metadata`]: crate::callsite::Metadata
+#[macro_export]
+macro_rules! with_filter {
+    ($filter:expr) => {{
+        $crate::__unless_noop!($crate::logger().use_filter(Box::new($filter)), ())
+    }};
+}
+
+/// Used to set the policy raising the verbosity of a target after a burst of
+/// errors from it, takes in an [`AdaptiveVerbosity`]
+///
+/// [`AdaptiveVerbosity`]: crate::adaptive::AdaptiveVerbosity
+#[macro_export]
+macro_rules! with_adaptive_verbosity {
+    ($policy:expr) => {{
+        $crate::__unless_noop!($crate::logger().use_adaptive_verbosity($policy), ())
+    }};
+}
+
+/// Used to report the metrics recorded with [`metric!`] as an `INFO` log line
+/// every interval, takes in a [`Duration`]. Reports are written while
+/// flushing, once the interval elapsed on the clock of the logger.
+///
+/// [`Duration`]: std::time::Duration
+#[macro_export]
+macro_rules! with_metrics_report {
+    ($interval:expr) => {{
+        $crate::__unless_noop!($crate::logger().use_metrics_report($interval, None), ())
+    }};
+}
+
+/// Used to report the metrics recorded with [`metric!`] into a
+/// [`MetricsSink`] every interval instead of as a log line, takes in a
+/// [`Duration`] and the sink
+///
+/// ```
+/// # use std::time::Duration;
+/// # use quicklog::{init, metric::Metrics, with_metrics_sink};
+/// init!();
+/// with_metrics_sink!(Duration::from_secs(1), |_time, metrics: &Metrics| {
+///     println!("{}", metrics);
+/// });
+/// ```
+///
+/// [`Duration`]: std::time::Duration
+/// [`MetricsSink`]: crate::metric::MetricsSink
+#[macro_export]
+macro_rules! with_metrics_sink {
+    ($interval:expr, $sink:expr) => {{
+        $crate::__unless_noop!(
+            $crate::logger()
+                .use_metrics_report($interval, Some($crate::make_container!($sink))),
+            ()
+        )
+    }};
+}
+
+/// Records a counter, gauge or histogram sample, given as
+/// `metric!(counter: "name", value)`, `metric!(gauge: "name", value)` or
+/// `metric!(histogram: "name", value)`, where the value is any primitive
+/// number. The sample is queued like a log record and aggregated when flushed,
+/// see [`metric`](crate::metric).
+///
+/// ```
+/// # use quicklog::{init, metric};
+/// init!();
+/// # let latency_us = 12;
+/// metric!(counter: "orders.sent", 1);
+/// metric!(histogram: "ack.latency_us", latency_us);
+/// ```
+#[macro_export]
+macro_rules! metric {
+    (counter: $name:expr, $value:expr $(,)?) => {
+        $crate::__metric!(Counter, $name, $value)
+    };
+    (gauge: $name:expr, $value:expr $(,)?) => {
+        $crate::__metric!(Gauge, $name, $value)
+    };
+    (histogram: $name:expr, $value:expr $(,)?) => {
+        $crate::__metric!(Histogram, $name, $value)
+    };
+}
+
+/// Expansion of [`metric!`]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __metric {
+    ($kind:ident, $name:expr, $value:expr) => {{
+        static __QUICKLOG_CALLSITE: $crate::callsite::__Callsite =
+            $crate::callsite::__Callsite::new($crate::callsite::Metadata::new(
+                $crate::level::Level::Info,
+                module_path!(),
+                file!(),
+                line!(),
+            ));
+        let sample = $crate::metric::MetricSample {
+            kind: $crate::metric::MetricKind::$kind,
+            name: $name,
+            value: $value as f64,
+        };
+        $crate::__unless_noop!(
+            $crate::logger()
+                .log_metric(__QUICKLOG_CALLSITE.id(), sample)
+                .unwrap_or(()),
+            ()
+        )
+    }};
+}
+
+/// Creates a [`Span`] from its name and fields, given as `key = value` where
+/// the value implements [`Serialize`]. The span is entered with [`enter!`].
+///
+/// ```
+/// # use quicklog::span;
+/// # let (order_id, side) = (42_u64, "buy");
+/// let order = span!("order", id = order_id, side = side);
+/// assert_eq!(order.name(), "order");
+/// assert_eq!(order.fields().len(), 2);
+/// ```
+///
+/// [`Span`]: crate::span::Span
+/// [`Serialize`]: crate::serialize::Serialize
+#[macro_export]
+macro_rules! span {
+    ($name:expr $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::span::Span::new($name)$(.with(stringify!($key), &$value))*
+    };
+}
+
+/// Enters a span on this thread until the returned [`SpanGuard`] is dropped,
+/// prepending the span to every record logged meanwhile. Takes a span created
+/// with [`span!`], or the name and fields of a new span.
+///
+/// ```
+/// # use quicklog::{enter, info, init, span};
+/// init!();
+/// # let (order_id, qty) = (42_u64, 10_u32);
+/// let _order = enter!("order", id = order_id);
+/// let _fill = enter!(span!("fill", qty = qty));
+/// info!("filled");
+/// // [...]order{id=42}:fill{qty=10}: filled
+/// ```
+///
+/// [`SpanGuard`]: crate::span::SpanGuard
+#[macro_export]
+macro_rules! enter {
+    ($name:literal $(, $key:ident = $value:expr)* $(,)?) => {
+        $crate::span!($name $(, $key = $value)*).enter()
+    };
+    ($span:expr) => {
+        $crate::span::Span::enter($span)
+    };
+}
+
+/// Used to amend how timestamps are formatted when flushing, takes in a
+/// [`TimestampFormat`]
+///
+/// [`TimestampFormat`]: crate::timestamp::TimestampFormat
+#[macro_export]
+macro_rules! with_timestamp_format {
+    ($format:expr) => {{
+        $crate::__unless_noop!($crate::logger().use_timestamp_format($format), ())
+    }};
+}
+
+/// Used to amend how floats are formatted when flushing, takes in a
+/// [`FloatFormat`]
+///
+/// [`FloatFormat`]: crate::serialize::FloatFormat
+#[macro_export]
+macro_rules! with_float_format {
+    ($format:expr) => {{
+        $crate::__unless_noop!($crate::serialize::set_float_format($format), ())
+    }};
+}
+
+/// Initializes Quicklog by calling [`Quicklog::try_init()`], returning
+/// [`Error::AlreadyInitialized`] if the logger was already initialized
+///
+/// [`Quicklog::try_init()`]: crate::Quicklog::try_init
+/// [`Error::AlreadyInitialized`]: crate::Error::AlreadyInitialized
+#[macro_export]
+macro_rules! try_init {
+    () => {
+        $crate::__unless_noop!($crate::logger().try_init(), Ok::<(), $crate::Error>(()))
     };
 }
 
@@ -44,7 +424,10 @@ macro_rules! init {
 #[macro_export]
 macro_rules! with_clock {
     ($clock:expr) => {{
-        $crate::logger().use_clock($crate::make_container!($clock))
+        $crate::__unless_noop!(
+            $crate::logger().use_clock($crate::make_container!($clock)),
+            ()
+        )
     }};
 }
 
@@ -58,7 +441,50 @@ macro_rules! make_container {
     };
 }
 
+/// Expansion of a logging macro call disabled through [`default_level`] or the
+/// `noop` feature.
+/// Arguments are still type-checked, but the call is never executed.
+///
+/// [`default_level`]: crate::default_level
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __disabled_log {
+    ($($call:tt)*) => {
+        if false {
+            $($call)*
+        }
+    };
+}
+
+/// Expansion of the setup and flush macros. With the `noop` feature, `$enabled`
+/// is still type-checked, but is never executed and `$disabled` is returned
+/// instead.
+#[cfg(not(feature = "noop"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __unless_noop {
+    ($enabled:expr, $disabled:expr) => {
+        $enabled
+    };
+}
+
+/// Expansion of the setup and flush macros. With the `noop` feature, `$enabled`
+/// is still type-checked, but is never executed and `$disabled` is returned
+/// instead.
+#[cfg(feature = "noop")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __unless_noop {
+    ($enabled:expr, $disabled:expr) => {{
+        if false {
+            let _ = $enabled;
+        }
+        $disabled
+    }};
+}
+
 /// Checks if the current level we are trying to log is enabled
+#[cfg(not(feature = "noop"))]
 #[doc(hidden)]
 #[macro_export]
 macro_rules! is_level_enabled {
@@ -67,22 +493,72 @@ macro_rules! is_level_enabled {
     };
 }
 
-// in debug, without clone, we have to make a Arc of Store, this ensures
-// we are able to properly keep track of the stores we are using
-//
-// in release, we have a clonable store, so we remove the overhead of Arc
+/// Checks if the current level we are trying to log is enabled, which is never
+/// the case with the `noop` feature
+#[cfg(feature = "noop")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! is_level_enabled {
+    ($level:expr) => {{
+        let _ = $level;
+        false
+    }};
+}
+
+// the decode function of the store is registered once per call site, and
+// the record only carries its id, see `serialize::decode_table`. The argument
+// is encoded into the byte buffer of `$logger`, which the record is logged
+// into. Returns an error instead of panicking when the argument cannot be
+// encoded, which the logging macros handle according to the
+// `EncodeErrorPolicy`
 #[doc(hidden)]
 #[macro_export]
 macro_rules! make_store {
-    ($serializable:expr) => {{
+    ($serializable:expr) => {
+        $crate::make_store!($crate::logger(), $serializable)
+    };
+    ($logger:expr, $serializable:expr) => {{
         use $crate::serialize::Serialize;
-        let (store, _) = $serializable
-            .encode($crate::logger().get_chunk_as_mut($serializable.buffer_size_required()));
+        static __QUICKLOG_DECODE_SITE: $crate::serialize::decode_table::DecodeSite =
+            $crate::serialize::decode_table::DecodeSite::new();
+        match $logger.try_get_record_chunk($serializable.buffer_size_required()) {
+            Ok(chunk) => $serializable.try_encode(chunk).and_then(|(store, _)| {
+                $crate::serialize::decode_table::CompactStore::new(store, &__QUICKLOG_DECODE_SITE)
+            }),
+            Err(err) => Err(err),
+        }
+    }};
+}
 
-        store
+// `compressed(^arg)` with the `compression` feature: the encoded bytes are
+// LZ4-compressed on the hot path and decompressed when flushing
+#[cfg(feature = "compression")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! make_compressed_store {
+    ($serializable:expr) => {
+        $crate::make_compressed_store!($crate::logger(), $serializable)
+    };
+    ($logger:expr, $serializable:expr) => {{
+        $crate::serialize::compress::try_encode_compressed(&$serializable, |size| {
+            $logger.try_get_record_chunk(size)
+        })
     }};
 }
 
+// `compressed(^arg)` without the `compression` feature is serialized as `^arg`
+#[cfg(not(feature = "compression"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! make_compressed_store {
+    ($serializable:expr) => {
+        $crate::make_store!($serializable)
+    };
+    ($logger:expr, $serializable:expr) => {
+        $crate::make_store!($logger, $serializable)
+    };
+}
+
 /// Allows flushing onto an implementor of [`Flush`], which can be modified with
 /// [`with_flush!`] macro and returns [`RecvResult`]
 ///
@@ -91,8 +567,13 @@ macro_rules! make_store {
 #[macro_export]
 macro_rules! try_flush {
     () => {{
-        use $crate::Log;
-        $crate::logger().flush_one()
+        $crate::__unless_noop!(
+            {
+                use $crate::Log;
+                $crate::logger().flush_one()
+            },
+            $crate::RecvResult::Err($crate::Error::Empty)
+        )
     }};
 }
 
@@ -108,13 +589,254 @@ macro_rules! flush {
 }
 
 /// Allows flushing onto an implementor of [`Flush`], which can be modified with
-/// [`with_flush!`] macro and continues trying to flush until no more lines need flushing.
+/// [`with_flush!`] macro and flushes every record queued at the time of calling,
+/// returning how many records were written.
+///
+/// Records logged while flushing are left for the next flush, see
+/// [`Quicklog::flush_all()`].
 ///
 /// [`Flush`]: `quicklog_flush::Flush`
+/// [`Quicklog::flush_all()`]: crate::Quicklog::flush_all
 #[macro_export]
 macro_rules! flush_all {
     () => {
-        while let Ok(()) = $crate::try_flush!() {}
+        $crate::__unless_noop!($crate::logger().flush_all(), 0_usize)
+    };
+}
+
+/// Flushes like [`flush_all!`], returning a [`FlushResult`] with how many
+/// records and bytes were written, how many records are left in the queue,
+/// and how many were dropped since the previous call, to monitor queue
+/// pressure
+///
+/// ```
+/// # use quicklog::{flush_with_result, info, init, with_flush};
+/// # use quicklog_flush::noop_flusher::NoopFlusher;
+/// init!();
+/// # with_flush!(NoopFlusher);
+/// info!("hello world");
+/// let result = flush_with_result!();
+/// assert_eq!(result.events_flushed, 1);
+/// if result.dropped_since_last > 0 {
+///     // flush more often, or increase the capacity
+/// }
+/// ```
+///
+/// [`FlushResult`]: crate::FlushResult
+#[macro_export]
+macro_rules! flush_with_result {
+    () => {
+        $crate::__unless_noop!(
+            $crate::logger().flush_with_result(),
+            $crate::FlushResult::default()
+        )
+    };
+}
+
+/// Sets the [`AsyncFlush`] which [`flush_async!`] flushes into, e.g. an
+/// [`AsyncTcpFlusher`] with the `tokio` feature
+///
+/// [`AsyncFlush`]: quicklog_flush::AsyncFlush
+/// [`AsyncTcpFlusher`]: quicklog_flush::async_tcp_flusher::AsyncTcpFlusher
+#[macro_export]
+macro_rules! with_async_flush {
+    ($flush:expr) => {{
+        $crate::__unless_noop!($crate::logger().use_async_flush(Box::new($flush)), ())
+    }};
+}
+
+/// Returns a future flushing every record queued at the time of calling into
+/// the flusher set with [`with_async_flush!`], awaiting its I/O, and
+/// resolving to how many records were written. Without an async flusher,
+/// records are flushed like [`flush_all!`].
+///
+/// Records are routed like [`flush_all!`]: those going to the default
+/// flusher are written into the async flusher instead, while flushers set
+/// for tags and targets, and the outputs, are still written to. Like
+/// [`flush_all!`], only one flush may run at a time, so the future can be
+/// spawned as long as nothing else flushes the logger meanwhile.
+///
+/// ```
+/// # use quicklog::{flush_async, info, init, with_async_flush};
+/// # use quicklog_flush::{AsyncFlush, FlushFuture, Level};
+/// # struct Collector;
+/// # impl AsyncFlush for Collector {
+/// #     fn flush_record(&mut self, _: Level, _: String) -> FlushFuture<'_> {
+/// #         Box::pin(async { Ok(()) })
+/// #     }
+/// # }
+/// # async fn run() {
+/// init!();
+/// with_async_flush!(Collector);
+/// info!("hello world");
+/// assert_eq!(flush_async!().await, 1);
+/// # }
+/// ```
+#[macro_export]
+macro_rules! flush_async {
+    () => {
+        $crate::__unless_noop!($crate::__flush_async(), async { 0_usize })
+    };
+}
+
+/// Sets the flusher of the [`audit`] channel, which records logged with
+/// [`audit!`] are flushed into instead of `logs/audit.log`. Not disabled by
+/// the `noop` feature, like [`audit!`].
+///
+/// [`audit`]: crate::audit
+/// [`audit!`]: crate::audit!
+#[macro_export]
+macro_rules! with_audit_flush {
+    ($flush:expr) => {{
+        $crate::audit::__use_flush(Box::new($flush))
+    }};
+}
+
+/// Flushes every record queued in the [`audit`] channel at the time of
+/// calling, returning how many records were written. Not disabled by the
+/// `noop` feature, like [`audit!`].
+///
+/// [`audit`]: crate::audit
+/// [`audit!`]: crate::audit!
+#[macro_export]
+macro_rules! flush_audit {
+    () => {
+        $crate::audit::__logger().flush_all()
+    };
+}
+
+/// Flushes like [`flush_all!`] until the given [`Duration`] has elapsed,
+/// returning a [`FlushResult`] whose `events_remaining` tells how many
+/// records are left for the next flush. Bounds the time spent flushing on a
+/// thread shared with other work, e.g. while a burst of records is queued.
+///
+/// ```
+/// # use std::time::Duration;
+/// # use quicklog::{flush_with_deadline, info, init, with_flush};
+/// # use quicklog_flush::noop_flusher::NoopFlusher;
+/// init!();
+/// # with_flush!(NoopFlusher);
+/// info!("hello world");
+/// let result = flush_with_deadline!(Duration::from_micros(50));
+/// if result.events_remaining > 0 {
+///     // yield to other work, and flush again later
+/// }
+/// ```
+///
+/// [`Duration`]: std::time::Duration
+/// [`FlushResult`]: crate::FlushResult
+#[macro_export]
+macro_rules! flush_with_deadline {
+    ($budget:expr) => {
+        $crate::__unless_noop!(
+            $crate::logger().flush_with_deadline($budget),
+            $crate::FlushResult::default()
+        )
+    };
+}
+
+/// Logs at the `TRACE` level at most once per interval, see [`info_throttled!`]
+#[macro_export]
+macro_rules! trace_throttled {
+    ($interval:tt, $($args:tt)+) => {
+        $crate::trace!(throttle: $interval, $($args)+)
+    };
+}
+
+/// Logs at the `DEBUG` level at most once per interval, see [`info_throttled!`]
+#[macro_export]
+macro_rules! debug_throttled {
+    ($interval:tt, $($args:tt)+) => {
+        $crate::debug!(throttle: $interval, $($args)+)
+    };
+}
+
+/// Logs at the `INFO` level at most once per interval, a shorthand for
+/// `info!(throttle: <interval>, ...)`. Calls within the interval are skipped
+/// before any argument is evaluated, and the next logged line of the call
+/// site says how many were suppressed.
+///
+/// ```
+/// # use quicklog::{info_throttled, init};
+/// # init!();
+/// # let seq = 1;
+/// // e.g. "gap in sequence 42 (suppressed 17 similar messages)"
+/// info_throttled!(1s, "gap in sequence {}", seq);
+/// ```
+#[macro_export]
+macro_rules! info_throttled {
+    ($interval:tt, $($args:tt)+) => {
+        $crate::info!(throttle: $interval, $($args)+)
+    };
+}
+
+/// Logs at the `WARN` level at most once per interval, see [`info_throttled!`]
+#[macro_export]
+macro_rules! warn_throttled {
+    ($interval:tt, $($args:tt)+) => {
+        $crate::warn!(throttle: $interval, $($args)+)
+    };
+}
+
+/// Logs at the `ERROR` level at most once per interval, see [`info_throttled!`]
+#[macro_export]
+macro_rules! error_throttled {
+    ($interval:tt, $($args:tt)+) => {
+        $crate::error!(throttle: $interval, $($args)+)
     };
 }
 
+/// Logs at the `TRACE` level only the first time the call site is reached,
+/// see [`warn_once!`]
+#[macro_export]
+macro_rules! trace_once {
+    ($($args:tt)+) => {
+        $crate::trace!(once: true, $($args)+)
+    };
+}
+
+/// Logs at the `DEBUG` level only the first time the call site is reached,
+/// see [`warn_once!`]
+#[macro_export]
+macro_rules! debug_once {
+    ($($args:tt)+) => {
+        $crate::debug!(once: true, $($args)+)
+    };
+}
+
+/// Logs at the `INFO` level only the first time the call site is reached,
+/// see [`warn_once!`]
+#[macro_export]
+macro_rules! info_once {
+    ($($args:tt)+) => {
+        $crate::info!(once: true, $($args)+)
+    };
+}
+
+/// Logs at the `WARN` level only the first time the call site is reached, a
+/// shorthand for `warn!(once: true, ...)`, e.g. for configuration warnings
+/// and deprecation notices in hot loops. Calls made while the level is
+/// disabled do not count.
+///
+/// ```
+/// # use quicklog::{init, warn_once};
+/// # init!();
+/// for _ in 0..1000 {
+///     warn_once!("`max_depth` is deprecated, use `depth_limit`");
+/// }
+/// ```
+#[macro_export]
+macro_rules! warn_once {
+    ($($args:tt)+) => {
+        $crate::warn!(once: true, $($args)+)
+    };
+}
+
+/// Logs at the `ERROR` level only the first time the call site is reached,
+/// see [`warn_once!`]
+#[macro_export]
+macro_rules! error_once {
+    ($($args:tt)+) => {
+        $crate::error!(once: true, $($args)+)
+    };
+}
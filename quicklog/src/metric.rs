@@ -0,0 +1,277 @@
+//! Defines counters, gauges and histograms recorded with [`metric!`], which
+//! share the queue of log records, so that the hot path is instrumented
+//! through a single pipeline.
+//!
+//! Recording a metric only queues its name and value. Samples are aggregated
+//! into [`Metrics`] when they are flushed, and reported periodically as a log
+//! line, or into a [`MetricsSink`]:
+//!
+//! ```
+//! # use std::time::Duration;
+//! # use quicklog::{flush_all, init, metric, with_metrics_report};
+//! init!();
+//! // writes `metrics orders.filled=2 position=-3 ...` every 10 seconds
+//! with_metrics_report!(Duration::from_secs(10));
+//!
+//! metric!(counter: "orders.filled", 1);
+//! metric!(counter: "orders.filled", 1);
+//! metric!(gauge: "position", -3);
+//! metric!(histogram: "fill.latency_us", 12.5);
+//! flush_all!();
+//!
+//! let metrics = quicklog::logger().metrics();
+//! assert_eq!(metrics.get("orders.filled"), Some(quicklog::metric::MetricValue::Counter(2.0)));
+//! ```
+//!
+//! Counters are totals since the logger was initialized, gauges hold their
+//! last value, and histograms summarize the values recorded since the
+//! previous report. Metrics are not filtered by level, but are dropped like
+//! records when the queue is full.
+//!
+//! [`metric!`]: crate::metric!
+
+use std::{
+    collections::BTreeMap,
+    fmt::{self, Display, Formatter},
+};
+
+use chrono::{DateTime, Utc};
+
+use crate::logfmt;
+
+/// Kind of a metric, given as the first argument of [`metric!`](crate::metric!)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MetricKind {
+    /// Adds up every recorded value
+    Counter,
+    /// Keeps the last recorded value
+    Gauge,
+    /// Summarizes the distribution of the recorded values
+    Histogram,
+}
+
+/// Value recorded by a call to [`metric!`](crate::metric!), queued along with
+/// log records
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MetricSample {
+    pub kind: MetricKind,
+    pub name: &'static str,
+    pub value: f64,
+}
+
+/// Count, sum and bounds of the values of a histogram
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HistogramSummary {
+    pub count: u64,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl HistogramSummary {
+    fn new(value: f64) -> Self {
+        Self {
+            count: 1,
+            sum: value,
+            min: value,
+            max: value,
+        }
+    }
+
+    fn record(&mut self, value: f64) {
+        self.count += 1;
+        self.sum += value;
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    /// Average of the recorded values
+    pub fn mean(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+}
+
+/// Aggregated value of a metric
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MetricValue {
+    /// Sum of every recorded value
+    Counter(f64),
+    /// Last recorded value
+    Gauge(f64),
+    /// Values recorded since the previous report
+    Histogram(HistogramSummary),
+}
+
+/// Metrics aggregated when flushing, ordered by name. Displayed as
+/// `name=value` pairs, with `name.count`, `name.min`, `name.max` and
+/// `name.mean` pairs for histograms.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Metrics {
+    values: BTreeMap<&'static str, MetricValue>,
+}
+
+impl Metrics {
+    /// Aggregated value of the metric `name`, if it was recorded
+    pub fn get(&self, name: &str) -> Option<MetricValue> {
+        self.values.get(name).copied()
+    }
+
+    /// Every metric along with its aggregated value, ordered by name
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, MetricValue)> + '_ {
+        self.values.iter().map(|(name, value)| (*name, *value))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Adds `sample` to the value of its metric, which is reset if it was
+    /// recorded as another kind before
+    pub(crate) fn record(&mut self, sample: MetricSample) {
+        let value = self.values.get_mut(sample.name);
+        match (sample.kind, value) {
+            (MetricKind::Counter, Some(MetricValue::Counter(total))) => *total += sample.value,
+            (MetricKind::Gauge, Some(MetricValue::Gauge(last))) => *last = sample.value,
+            (MetricKind::Histogram, Some(MetricValue::Histogram(summary))) => {
+                summary.record(sample.value)
+            }
+            (kind, _) => {
+                let value = match kind {
+                    MetricKind::Counter => MetricValue::Counter(sample.value),
+                    MetricKind::Gauge => MetricValue::Gauge(sample.value),
+                    MetricKind::Histogram => {
+                        MetricValue::Histogram(HistogramSummary::new(sample.value))
+                    }
+                };
+                self.values.insert(sample.name, value);
+            }
+        }
+    }
+
+    /// Starts a new reporting period, clearing histograms
+    pub(crate) fn end_period(&mut self) {
+        self.values
+            .retain(|_, value| !matches!(value, MetricValue::Histogram(_)));
+    }
+}
+
+impl Display for Metrics {
+    /// Writes `name=value` pairs separated by spaces, or preceded by a space
+    /// each with the alternate flag, like the fields of a record
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let alternate = f.alternate();
+        let mut first = true;
+        let mut pair = |f: &mut Formatter<'_>, name: &str, suffix: &str, value: &dyn Display| {
+            let key = format!("{}{}", name, suffix);
+            if alternate {
+                return logfmt::__write_field(f, &key, value);
+            }
+            if !std::mem::take(&mut first) {
+                f.write_str(" ")?;
+            }
+            write!(f, "{}={}", key, value)
+        };
+        for (name, value) in self.iter() {
+            match value {
+                MetricValue::Counter(value) | MetricValue::Gauge(value) => {
+                    pair(f, name, "", &value)?
+                }
+                MetricValue::Histogram(summary) => {
+                    pair(f, name, ".count", &summary.count)?;
+                    pair(f, name, ".min", &summary.min)?;
+                    pair(f, name, ".max", &summary.max)?;
+                    pair(f, name, ".mean", &summary.mean())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Receives the aggregated metrics periodically, see
+/// [`with_metrics_sink!`](crate::with_metrics_sink!)
+pub trait MetricsSink {
+    /// Called with the metrics aggregated as of `time`
+    fn report(&mut self, time: DateTime<Utc>, metrics: &Metrics);
+}
+
+impl<F: FnMut(DateTime<Utc>, &Metrics)> MetricsSink for F {
+    fn report(&mut self, time: DateTime<Utc>, metrics: &Metrics) {
+        self(time, metrics)
+    }
+}
+
+/// When and where metrics are reported
+pub(crate) struct MetricsReport {
+    /// Nanoseconds between two reports
+    pub(crate) interval: u64,
+    /// Time of the last report, on the clock of the logger
+    pub(crate) last_report: Option<u64>,
+    /// Written as a log line when there is no sink
+    pub(crate) sink: Option<Box<dyn MetricsSink>>,
+}
+
+impl MetricsReport {
+    /// Whether a report is due at `now`, counting the first period from the
+    /// first check
+    pub(crate) fn is_due(&mut self, now: u64) -> bool {
+        let last_report = *self.last_report.get_or_insert(now);
+        now.saturating_sub(last_report) >= self.interval
+    }
+}
+
+/// Log line of a metrics report, written as `metrics name=value ...`, or as
+/// `msg=metrics name=value ...` pairs with the alternate flag
+pub(crate) struct MetricsLine(pub(crate) Metrics);
+
+impl Display for MetricsLine {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            logfmt::__write_message(f, format_args!("metrics"))?;
+            return write!(f, "{:#}", self.0);
+        }
+        write!(f, "metrics {}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(kind: MetricKind, name: &'static str, value: f64) -> MetricSample {
+        MetricSample { kind, name, value }
+    }
+
+    #[test]
+    fn aggregates_samples() {
+        let mut metrics = Metrics::default();
+        metrics.record(sample(MetricKind::Counter, "orders", 1.0));
+        metrics.record(sample(MetricKind::Counter, "orders", 2.0));
+        metrics.record(sample(MetricKind::Gauge, "position", 5.0));
+        metrics.record(sample(MetricKind::Gauge, "position", -3.0));
+        metrics.record(sample(MetricKind::Histogram, "latency", 4.0));
+        metrics.record(sample(MetricKind::Histogram, "latency", 2.0));
+
+        assert_eq!(metrics.get("orders"), Some(MetricValue::Counter(3.0)));
+        assert_eq!(metrics.get("position"), Some(MetricValue::Gauge(-3.0)));
+        assert_eq!(
+            metrics.to_string(),
+            "latency.count=2 latency.min=2 latency.max=4 latency.mean=3 orders=3 position=-3"
+        );
+        assert_eq!(
+            format!("{:#}", MetricsLine(metrics.clone())),
+            " msg=metrics latency.count=2 latency.min=2 latency.max=4 latency.mean=3 orders=3 position=-3"
+        );
+
+        metrics.end_period();
+        assert_eq!(metrics.to_string(), "orders=3 position=-3");
+    }
+
+    #[test]
+    fn changing_kind_resets_value() {
+        let mut metrics = Metrics::default();
+        metrics.record(sample(MetricKind::Counter, "depth", 4.0));
+        metrics.record(sample(MetricKind::Gauge, "depth", 2.0));
+        assert_eq!(metrics.get("depth"), Some(MetricValue::Gauge(2.0)));
+    }
+}
@@ -0,0 +1,214 @@
+//! Defines [`ReorderBuffer`], which restores the global time order of
+//! timestamped records arriving from several producers.
+//!
+//! Records produced on different threads, or read back from the log files of
+//! different processes, are only ordered per producer. Merging them requires
+//! holding records back for a bounded window: once a record with timestamp `t`
+//! has been seen, every record older than `t - tolerance` is assumed to have
+//! arrived and can be released in order.
+//!
+//! The logger itself is single-producer, so its records are already ordered by
+//! their timestamps. The buffer is meant for merging the output of several
+//! producers, such as log files written by different processes.
+//!
+//! ```
+//! # use quicklog::ordering::ReorderBuffer;
+//! let mut buffer = ReorderBuffer::new(100);
+//! buffer.push(1_000, "a1");
+//! buffer.push(950, "b1");
+//! buffer.push(1_200, "a2");
+//!
+//! // records older than 1_200 - 100 are released
+//! assert_eq!(buffer.pop_ready(), Some((950, "b1")));
+//! assert_eq!(buffer.pop_ready(), Some((1_000, "a1")));
+//! assert_eq!(buffer.pop_ready(), None);
+//!
+//! // remaining records are released once all producers are done
+//! assert_eq!(buffer.drain().collect::<Vec<_>>(), vec![(1_200, "a2")]);
+//! ```
+
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+/// Buffers timestamped records, releasing them in timestamp order once they
+/// fall out of the reordering window.
+///
+/// Records with equal timestamps are released in the order they were pushed.
+/// Records arriving older than the last released record can no longer be
+/// ordered, so they are released as soon as possible and counted, see
+/// [`ReorderBuffer::late_count`].
+pub struct ReorderBuffer<T> {
+    records: BinaryHeap<Pending<T>>,
+    tolerance: u64,
+    newest: Option<u64>,
+    last_released: Option<u64>,
+    pushed: u64,
+    late: usize,
+}
+
+impl<T> ReorderBuffer<T> {
+    /// Creates a buffer which holds records back until a record `tolerance`
+    /// nanoseconds newer has been pushed
+    pub fn new(tolerance: u64) -> Self {
+        Self {
+            records: BinaryHeap::new(),
+            tolerance,
+            newest: None,
+            last_released: None,
+            pushed: 0,
+            late: 0,
+        }
+    }
+
+    /// Reordering window in nanoseconds
+    pub fn tolerance(&self) -> u64 {
+        self.tolerance
+    }
+
+    /// Pushes a record with its timestamp in nanoseconds
+    pub fn push(&mut self, timestamp: u64, record: T) {
+        if self.last_released.is_some_and(|last| timestamp < last) {
+            self.late += 1;
+        }
+        self.newest = Some(
+            self.newest
+                .map_or(timestamp, |newest| newest.max(timestamp)),
+        );
+        self.records.push(Pending {
+            timestamp,
+            sequence: self.pushed,
+            record,
+        });
+        self.pushed += 1;
+    }
+
+    /// Pops the oldest record if it fell out of the reordering window
+    pub fn pop_ready(&mut self) -> Option<(u64, T)> {
+        let newest = self.newest?;
+        let oldest = self.records.peek()?.timestamp;
+        if newest.saturating_sub(oldest) < self.tolerance {
+            return None;
+        }
+        self.release()
+    }
+
+    /// Pops all buffered records in timestamp order, regardless of the
+    /// reordering window
+    pub fn drain(&mut self) -> impl Iterator<Item = (u64, T)> + '_ {
+        std::iter::from_fn(move || self.release())
+    }
+
+    /// Number of buffered records
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Number of records which arrived older than an already released record,
+    /// i.e. outside of the reordering window
+    pub fn late_count(&self) -> usize {
+        self.late
+    }
+
+    fn release(&mut self) -> Option<(u64, T)> {
+        let Pending {
+            timestamp, record, ..
+        } = self.records.pop()?;
+        self.last_released = Some(
+            self.last_released
+                .map_or(timestamp, |last| last.max(timestamp)),
+        );
+        Some((timestamp, record))
+    }
+}
+
+/// Buffered record, ordered so that the oldest record is at the top of the
+/// max-heap
+struct Pending<T> {
+    timestamp: u64,
+    sequence: u64,
+    record: T,
+}
+
+impl<T> Pending<T> {
+    fn key(&self) -> (u64, u64) {
+        (self.timestamp, self.sequence)
+    }
+}
+
+impl<T> PartialEq for Pending<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl<T> Eq for Pending<T> {}
+
+impl<T> PartialOrd for Pending<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Pending<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.key().cmp(&self.key())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReorderBuffer;
+
+    #[test]
+    fn releases_in_timestamp_order() {
+        let mut buffer = ReorderBuffer::new(10);
+        let mut released = Vec::new();
+
+        // two producers, each ordered, interleaved with bounded skew
+        let a = [100, 105, 112, 120, 131];
+        let b = [98, 103, 110, 118, 125];
+        for (ta, tb) in a.into_iter().zip(b) {
+            buffer.push(ta, 'a');
+            buffer.push(tb, 'b');
+            while let Some((timestamp, _)) = buffer.pop_ready() {
+                released.push(timestamp);
+            }
+        }
+        assert!(!buffer.is_empty());
+        released.extend(buffer.drain().map(|(timestamp, _)| timestamp));
+
+        let mut expected = a.into_iter().chain(b).collect::<Vec<_>>();
+        expected.sort();
+        assert_eq!(released, expected);
+        assert_eq!(buffer.late_count(), 0);
+    }
+
+    #[test]
+    fn equal_timestamps_keep_push_order() {
+        let mut buffer = ReorderBuffer::new(0);
+        buffer.push(5, "first");
+        buffer.push(5, "second");
+        buffer.push(5, "third");
+
+        let released = buffer.drain().map(|(_, r)| r).collect::<Vec<_>>();
+        assert_eq!(released, vec!["first", "second", "third"]);
+    }
+
+    #[test]
+    fn late_records() {
+        let mut buffer = ReorderBuffer::new(10);
+        buffer.push(100, 0);
+        buffer.push(120, 1);
+        assert_eq!(buffer.pop_ready(), Some((100, 0)));
+        assert_eq!(buffer.pop_ready(), None);
+
+        // older than the released record, so it can no longer be ordered
+        buffer.push(90, 2);
+        assert_eq!(buffer.late_count(), 1);
+        assert_eq!(buffer.pop_ready(), Some((90, 2)));
+        assert_eq!(buffer.len(), 1);
+    }
+}
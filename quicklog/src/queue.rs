@@ -0,0 +1,597 @@
+//! Defines [`QueueFullPolicy`], which controls what happens when a record is
+//! logged while the logging queue is full.
+//!
+//! The number of records the queue holds defaults to [`MAX_LOGGER_CAPACITY`],
+//! and can be changed when initializing the logger.
+//!
+//! ```
+//! # use quicklog::{init, queue::QueueFullPolicy};
+//! // overwrite the oldest unflushed record instead of dropping the new one
+//! init!(capacity = 64 * 1024, queue_full_policy = QueueFullPolicy::DropOldest);
+//! ```
+//!
+//! [`MAX_LOGGER_CAPACITY`]: crate::constants::MAX_LOGGER_CAPACITY
+
+use std::{
+    cell::UnsafeCell,
+    collections::VecDeque,
+    mem::MaybeUninit,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// Size of a cache line on the targets quicklog is tuned for
+pub(crate) const CACHE_LINE_SIZE: usize = 64;
+
+/// Behavior of the logger when a record is logged while the queue is full.
+///
+/// Records dropped under any policy are counted, see [`dropped_count`].
+///
+/// [`dropped_count`]: crate::dropped_count
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum QueueFullPolicy {
+    /// Drops the new record, returning [`Error::QueueFull`] from [`Log::log`]
+    ///
+    /// [`Error::QueueFull`]: crate::Error::QueueFull
+    /// [`Log::log`]: crate::Log::log
+    #[default]
+    DropNewest,
+    /// Drops the oldest unflushed record to make room for the new record
+    DropOldest,
+    /// Blocks the producer until the records flushed on another thread make
+    /// room for the new record. Nothing is flushed on the logging thread, so
+    /// records must be flushed on another thread: a thread both logging and
+    /// flushing blocks forever once the queue is full.
+    Block,
+    /// Spills the new record into a heap-allocated overflow segment, keeping
+    /// up to `max_records` records on top of those the queue holds, and only
+    /// drops it once the segment is full too. Spilled records are flushed
+    /// after the records in the queue, in the order they were logged.
+    ///
+    /// This is a slower path meant to absorb bursts: the arguments of a
+    /// spilled record are formatted on the logging thread, as its encoded
+    /// arguments could otherwise be overwritten in the byte buffer before it
+    /// is flushed. The segment is freed once drained.
+    Spill { max_records: usize },
+}
+
+/// Value aligned to its own cache line, so that writing it never invalidates
+/// the cache line holding its neighbours
+#[repr(align(64))]
+struct CachePadded<T>(T);
+
+/// Slot of the ring buffer, aligned to a cache line so that a record never
+/// straddles two lines, and writing a record never touches the line of the
+/// record being flushed
+#[repr(align(64))]
+struct Slot<T>(UnsafeCell<MaybeUninit<T>>);
+
+const _: () = assert!(std::mem::align_of::<Slot<u8>>() == CACHE_LINE_SIZE);
+
+/// Creates a bounded single-producer single-consumer queue of records,
+/// holding at most `capacity` records, which is at least 1.
+///
+/// The queue is a ring buffer of cache-line aligned slots, allocated once when
+/// the queue is created, and never grown. The capacity is a runtime parameter
+/// rather than a const generic one, as it is chosen by
+/// `init!(capacity = ...)`. The [`Producer`] and the [`Consumer`] can be used
+/// from different threads, e.g. logging on the hot thread while flushing on
+/// another one: the positions in the ring buffer are atomics living on
+/// separate cache lines, and publish the records in their slots with
+/// release/acquire ordering.
+///
+/// Enqueueing is what commits a record: the logging macros encode every
+/// argument into the byte buffer first, and only then build the record and
+/// enqueue it. A producer panicking mid-encode therefore never enqueues its
+/// record, and the bytes it partially wrote are never read by the flusher, so
+/// no per-record commit flag is needed.
+pub(crate) fn channel<T>(capacity: usize) -> (Producer<T>, Consumer<T>) {
+    let slots = (0..capacity.max(1))
+        .map(|_| Slot(UnsafeCell::new(MaybeUninit::uninit())))
+        .collect();
+    let ring = Arc::new(Ring {
+        head: CachePadded(AtomicU64::new(0)),
+        released: CachePadded(AtomicU64::new(0)),
+        tail: CachePadded(AtomicU64::new(0)),
+        slots,
+        spilled: Mutex::new(VecDeque::new()),
+        spilled_len: AtomicUsize::new(0),
+    });
+    let producer = Producer {
+        ring: ring.clone(),
+        tail: 0,
+        released: 0,
+    };
+    let consumer = Consumer {
+        ring,
+        head: 0,
+        tail: 0,
+    };
+    (producer, consumer)
+}
+
+/// Ring buffer shared by the [`Producer`] and the [`Consumer`]
+struct Ring<T> {
+    /// Number of records ever taken out of the ring buffer, i.e. the position
+    /// of the front. Records are claimed by moving it with a compare and swap,
+    /// either by the consumer, or by the producer dropping the oldest record,
+    /// see [`Producer::drop_oldest`].
+    head: CachePadded<AtomicU64>,
+    /// Position past the last record read out of its slot once claimed, i.e.
+    /// before which the producer may write slots again
+    released: CachePadded<AtomicU64>,
+    /// Number of records ever moved into the ring buffer, i.e. the position
+    /// past its back, only written by the producer
+    tail: CachePadded<AtomicU64>,
+    slots: Box<[Slot<T>]>,
+    /// Records spilled while the queue was full, see
+    /// [`QueueFullPolicy::Spill`]. The producer keeps spilling while any is
+    /// left, and the consumer only takes them once the ring buffer is
+    /// drained, so that records are flushed in the order they were logged.
+    spilled: Mutex<VecDeque<T>>,
+    /// Length of `spilled`, checked without locking it
+    spilled_len: AtomicUsize,
+}
+
+impl<T> Ring<T> {
+    /// Slot of the record at the given position
+    #[inline]
+    fn slot(&self, position: u64) -> *mut MaybeUninit<T> {
+        let index = (position % self.slots.len() as u64) as usize;
+        self.slots[index].0.get()
+    }
+
+    fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Claims the record at `head`, returning whether it was still there
+    #[inline]
+    fn claim(&self, head: u64) -> Result<(), u64> {
+        self.head
+            .0
+            .compare_exchange_weak(head, head + 1, Ordering::AcqRel, Ordering::Acquire)
+            .map(|_| ())
+    }
+
+    /// Reads out the record at `head`, once claimed
+    ///
+    /// # Safety
+    ///
+    /// The record must have been claimed with [`Ring::claim`]
+    #[inline]
+    unsafe fn read(&self, head: u64) -> T {
+        // SAFETY: slots between the head and the tail hold records written by
+        // the producer, published by the acquire load of the tail, and the
+        // record was claimed, so it is only read once. The producer does not
+        // write the slot again until the record is released.
+        let record = unsafe { (*self.slot(head)).assume_init_read() };
+        self.released.0.fetch_max(head + 1, Ordering::Release);
+        record
+    }
+}
+
+impl<T> Drop for Ring<T> {
+    fn drop(&mut self) {
+        let head = *self.head.0.get_mut();
+        let tail = *self.tail.0.get_mut();
+        for position in head..tail {
+            // SAFETY: slots between the head and the tail hold records written
+            // by the producer, and the ring is no longer shared
+            unsafe { (*self.slot(position)).assume_init_drop() };
+        }
+    }
+}
+
+/// Waits for the other side of the queue, spinning at first, then yielding
+/// to the scheduler, e.g. when both sides share a single core
+#[derive(Default)]
+struct Backoff {
+    spins: u32,
+}
+
+impl Backoff {
+    const SPIN_LIMIT: u32 = 64;
+
+    fn snooze(&mut self) {
+        if self.spins < Self::SPIN_LIMIT {
+            self.spins += 1;
+            std::hint::spin_loop();
+        } else {
+            std::thread::yield_now();
+        }
+    }
+}
+
+/// Logging side of a queue created with [`channel`]
+pub(crate) struct Producer<T> {
+    ring: Arc<Ring<T>>,
+    /// Position past the back, which only the producer writes
+    tail: u64,
+    /// Last position read of [`Ring::released`], only read again once the
+    /// ring buffer looks full, so that enqueueing does not touch the cache
+    /// line written by the consumer
+    released: u64,
+}
+
+// SAFETY: the producer only writes slots past the back, which the consumer
+// does not read until the tail is published
+unsafe impl<T: Send> Send for Producer<T> {}
+
+impl<T> Producer<T> {
+    /// Pushes a record onto the back of the queue, returning the record if the
+    /// queue is full, or if spilled records are still waiting to be flushed
+    #[inline]
+    pub(crate) fn enqueue(&mut self, record: T) -> Result<(), T> {
+        let tail = self.tail;
+        if tail - self.released >= self.capacity() as u64 {
+            self.released = self.ring.released.0.load(Ordering::Acquire);
+            if tail - self.released >= self.capacity() as u64 {
+                return Err(record);
+            }
+        }
+        if self.ring.spilled_len.load(Ordering::Acquire) > 0 {
+            return Err(record);
+        }
+        // SAFETY: the slot is past the back, so the consumer does not read it,
+        // and every record before the back which was in it was released
+        unsafe { (*self.ring.slot(tail)).write(record) };
+        self.tail = tail + 1;
+        self.ring.tail.0.store(self.tail, Ordering::Release);
+        Ok(())
+    }
+
+    /// Pushes a record onto the back of the queue, waiting for the consumer,
+    /// flushing on another thread, to make room if the queue is full
+    pub(crate) fn enqueue_blocking(&mut self, mut record: T) {
+        let mut backoff = Backoff::default();
+        while let Err(refused) = self.enqueue(record) {
+            record = refused;
+            backoff.snooze();
+        }
+    }
+
+    /// Takes the oldest record out of the ring buffer to make room for a new
+    /// one, returning `None` if the consumer emptied it meanwhile. The record
+    /// is claimed like the consumer does, so that it is taken out by exactly
+    /// one of them.
+    pub(crate) fn drop_oldest(&mut self) -> Option<T> {
+        let tail = self.tail;
+        let mut backoff = Backoff::default();
+        loop {
+            let head = self.ring.head.0.load(Ordering::Acquire);
+            if head == tail {
+                return None;
+            }
+            // the slot freed by the oldest record is written right away, so
+            // the consumer must not be reading out a record it claimed
+            if self.ring.released.0.load(Ordering::Acquire) != head {
+                backoff.snooze();
+                continue;
+            }
+            if self.ring.claim(head).is_ok() {
+                // SAFETY: claimed above
+                return Some(unsafe { self.ring.read(head) });
+            }
+        }
+    }
+
+    /// Pushes a record onto the back of the overflow segment, while the queue
+    /// is full, returning the record if the queue and the segment already
+    /// hold `max_records` records more than the capacity of the queue
+    pub(crate) fn spill(&mut self, record: T, max_records: usize) -> Result<(), T> {
+        let mut spilled = self.ring.spilled.lock().unwrap_or_else(|e| e.into_inner());
+        // slots freed while records are spilled are made up for by the segment
+        let free = self.capacity() - self.len();
+        if spilled.len() >= max_records + free {
+            return Err(record);
+        }
+        spilled.push_back(record);
+        self.ring
+            .spilled_len
+            .store(spilled.len(), Ordering::Release);
+        Ok(())
+    }
+
+    /// Number of slots of the ring buffer taken, not counting spilled records
+    pub(crate) fn len(&self) -> usize {
+        (self.tail - self.ring.released.0.load(Ordering::Acquire)) as usize
+    }
+
+    /// Checks whether a record would be refused by [`Producer::enqueue`],
+    /// reading the positions written by the consumer
+    #[cfg(any(test, feature = "stress"))]
+    pub(crate) fn is_full(&self) -> bool {
+        self.ring.spilled_len.load(Ordering::Acquire) > 0 || self.len() >= self.capacity()
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.ring.capacity()
+    }
+}
+
+/// Flushing side of a queue created with [`channel`]
+pub(crate) struct Consumer<T> {
+    ring: Arc<Ring<T>>,
+    /// Position of the front as of the last record claimed, which is behind
+    /// [`Ring::head`] if the producer dropped the oldest records since
+    head: u64,
+    /// Last position read of [`Ring::tail`], only read again once the ring
+    /// buffer looks empty, so that dequeueing does not touch the cache line
+    /// written by the producer
+    tail: u64,
+}
+
+// SAFETY: the consumer only reads slots before the back, which the producer
+// does not write until the head is published
+unsafe impl<T: Send> Send for Consumer<T> {}
+
+impl<T> Consumer<T> {
+    /// Pops the oldest record off the front of the queue, followed by the
+    /// spilled records once the ring buffer is drained
+    #[inline]
+    pub(crate) fn dequeue(&mut self) -> Option<T> {
+        let mut head = self.head;
+        loop {
+            // the producer dropping the oldest records can move the head past
+            // the cached tail, so it is never claimed before checking the
+            // real tail
+            if head >= self.tail {
+                self.tail = self.ring.tail.0.load(Ordering::Acquire);
+            }
+            if head >= self.tail {
+                if self.ring.spilled_len.load(Ordering::Acquire) == 0 {
+                    return None;
+                }
+                // records moved into the ring buffer before the first record
+                // was spilled, and published along with it, are flushed first
+                self.tail = self.ring.tail.0.load(Ordering::Acquire);
+                if head >= self.tail {
+                    return self.dequeue_spilled();
+                }
+            }
+            match self.ring.claim(head) {
+                Ok(()) => break,
+                // the producer dropped the oldest records
+                Err(current) => head = current,
+            }
+        }
+        self.head = head + 1;
+        // SAFETY: claimed above
+        Some(unsafe { self.ring.read(head) })
+    }
+
+    #[cold]
+    fn dequeue_spilled(&mut self) -> Option<T> {
+        let mut spilled = self.ring.spilled.lock().unwrap_or_else(|e| e.into_inner());
+        let record = spilled.pop_front();
+        if spilled.is_empty() {
+            // the segment is freed once drained
+            *spilled = VecDeque::new();
+        }
+        self.ring
+            .spilled_len
+            .store(spilled.len(), Ordering::Release);
+        record
+    }
+
+    /// Number of records in the queue, including spilled records
+    pub(crate) fn len(&self) -> usize {
+        let head = self.ring.head.0.load(Ordering::Acquire);
+        let tail = self.ring.tail.0.load(Ordering::Acquire);
+        tail.saturating_sub(head) as usize + self.ring.spilled_len.load(Ordering::Acquire)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounded_fifo() {
+        let (mut producer, mut consumer) = channel(2);
+        assert_eq!(producer.capacity(), 2);
+        assert_eq!(producer.enqueue(1), Ok(()));
+        assert_eq!(producer.enqueue(2), Ok(()));
+        assert!(producer.is_full());
+        assert_eq!(producer.enqueue(3), Err(3));
+        assert_eq!(consumer.dequeue(), Some(1));
+        assert_eq!(producer.enqueue(3), Ok(()));
+        assert_eq!(consumer.dequeue(), Some(2));
+        assert_eq!(consumer.dequeue(), Some(3));
+        assert_eq!(consumer.dequeue(), None);
+    }
+
+    #[test]
+    fn lengths() {
+        let (mut producer, mut consumer) = channel(2);
+        assert_eq!((producer.len(), consumer.len()), (0, 0));
+        producer.enqueue(1).unwrap();
+        producer.enqueue(2).unwrap();
+        assert!(producer.enqueue(3).is_err());
+        assert_eq!((producer.len(), consumer.len()), (2, 2));
+        consumer.dequeue();
+        producer.enqueue(3).unwrap();
+        assert_eq!((producer.len(), consumer.len()), (2, 2));
+        consumer.dequeue();
+        consumer.dequeue();
+        assert_eq!(consumer.dequeue(), None);
+        assert_eq!((producer.len(), consumer.len()), (0, 0));
+    }
+
+    #[test]
+    fn spilled_records_follow_the_queue() {
+        let (mut producer, mut consumer) = channel(2);
+        producer.enqueue(1).unwrap();
+        producer.enqueue(2).unwrap();
+        assert_eq!(producer.spill(3, 2), Ok(()));
+        assert_eq!(producer.spill(4, 2), Ok(()));
+        assert_eq!(producer.spill(5, 2), Err(5));
+        assert_eq!((producer.len(), consumer.len()), (2, 4));
+
+        // the queue stays full until the segment is drained
+        assert_eq!(consumer.dequeue(), Some(1));
+        assert!(producer.is_full());
+        assert_eq!(producer.enqueue(5), Err(5));
+        assert_eq!(producer.spill(5, 2), Ok(()));
+        assert_eq!(consumer.dequeue(), Some(2));
+        assert_eq!(consumer.dequeue(), Some(3));
+        assert_eq!(consumer.dequeue(), Some(4));
+        assert!(producer.is_full());
+        assert_eq!(consumer.dequeue(), Some(5));
+        assert!(!producer.is_full());
+        assert_eq!(consumer.dequeue(), None);
+        assert_eq!(consumer.len(), 0);
+    }
+
+    #[test]
+    fn drops_queued_records() {
+        let record = std::rc::Rc::new(());
+        let (mut producer, mut consumer) = channel(3);
+        for _ in 0..3 {
+            producer.enqueue(record.clone()).unwrap();
+        }
+        producer.spill(record.clone(), 1).unwrap();
+        drop(consumer.dequeue());
+        assert_eq!(std::rc::Rc::strong_count(&record), 4);
+        drop((producer, consumer));
+        assert_eq!(std::rc::Rc::strong_count(&record), 1);
+    }
+
+    #[test]
+    fn producer_and_consumer_threads() {
+        const RECORDS: u64 = 100_000;
+        let (mut producer, mut consumer) = channel(64);
+        let logging = std::thread::spawn(move || {
+            let mut next = 0;
+            while next < RECORDS {
+                // spilled records are interleaved with the ring buffer
+                let queued = producer.enqueue(next).or_else(|next| match next % 3 {
+                    0 => producer.spill(next, 8),
+                    _ => Err(next),
+                });
+                match queued {
+                    Ok(()) => next += 1,
+                    Err(_) => std::thread::yield_now(),
+                }
+            }
+        });
+
+        let mut expected = 0;
+        while expected < RECORDS {
+            match consumer.dequeue() {
+                Some(record) => {
+                    assert_eq!(record, expected);
+                    expected += 1;
+                }
+                None => std::thread::yield_now(),
+            }
+        }
+        logging.join().unwrap();
+        assert_eq!(consumer.dequeue(), None);
+    }
+
+    #[test]
+    fn drop_oldest() {
+        let (mut producer, mut consumer) = channel(2);
+        producer.enqueue(1).unwrap();
+        producer.enqueue(2).unwrap();
+        assert_eq!(producer.drop_oldest(), Some(1));
+        producer.enqueue(3).unwrap();
+        assert_eq!(consumer.dequeue(), Some(2));
+        assert_eq!(consumer.dequeue(), Some(3));
+        // the consumer took every record first
+        assert_eq!(producer.drop_oldest(), None);
+    }
+
+    #[test]
+    fn dequeue_after_drop_oldest_past_cached_tail() {
+        // every record is moved out once, so each clone is dropped once
+        let record = Arc::new(());
+        let (mut producer, mut consumer) = channel(2);
+        producer.enqueue(record.clone()).unwrap();
+        producer.enqueue(record.clone()).unwrap();
+        assert!(consumer.dequeue().is_some());
+
+        // the producer drops every record left, moving the head past the
+        // tail the consumer last read
+        producer.enqueue(record.clone()).unwrap();
+        assert!(producer.drop_oldest().is_some());
+        assert!(producer.drop_oldest().is_some());
+        assert!(consumer.dequeue().is_none());
+        assert!(consumer.dequeue().is_none());
+        assert_eq!(Arc::strong_count(&record), 1);
+
+        producer.enqueue(record.clone()).unwrap();
+        producer.enqueue(record.clone()).unwrap();
+        assert!(producer.drop_oldest().is_some());
+        producer.enqueue(record.clone()).unwrap();
+        assert!(consumer.dequeue().is_some());
+        assert!(consumer.dequeue().is_some());
+        assert!(consumer.dequeue().is_none());
+        assert_eq!(Arc::strong_count(&record), 1);
+        assert_eq!(producer.enqueue(record.clone()), Ok(()));
+        assert_eq!(producer.len(), 1);
+    }
+
+    #[test]
+    fn drop_oldest_and_block_across_threads() {
+        const RECORDS: u64 = 100_000;
+        let (mut producer, mut consumer) = channel(16);
+        let logging = std::thread::spawn(move || {
+            let mut dropped = 0;
+            for record in 0..RECORDS {
+                if record % 2 == 0 {
+                    producer.enqueue_blocking(record);
+                    continue;
+                }
+                if let Err(record) = producer.enqueue(record) {
+                    dropped += producer.drop_oldest().map_or(0, |_| 1);
+                    producer.enqueue(record).unwrap();
+                }
+            }
+            dropped
+        });
+
+        let mut dequeued = Vec::new();
+        while !logging.is_finished() || consumer.len() > 0 {
+            match consumer.dequeue() {
+                Some(record) => dequeued.push(record),
+                None => std::thread::yield_now(),
+            }
+        }
+        let dropped = logging.join().unwrap();
+        assert_eq!(dequeued.len() as u64 + dropped, RECORDS);
+        assert!(dequeued.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(dequeued.last(), Some(&(RECORDS - 1)));
+    }
+
+    #[test]
+    fn cache_line_layout() {
+        use std::mem::offset_of;
+
+        let (producer, _consumer) = channel::<u8>(4);
+        let start = producer.ring.slots.as_ptr() as usize;
+        assert_eq!(start % CACHE_LINE_SIZE, 0);
+        // the positions written by the consumer and by the producer
+        let positions = [
+            offset_of!(Ring<u8>, head),
+            offset_of!(Ring<u8>, released),
+            offset_of!(Ring<u8>, tail),
+        ];
+        for (i, a) in positions.iter().enumerate() {
+            for b in &positions[i + 1..] {
+                assert!(a.abs_diff(*b) >= CACHE_LINE_SIZE);
+            }
+        }
+
+        // a queued record takes a single cache line
+        #[cfg(not(feature = "trace"))]
+        assert_eq!(
+            std::mem::size_of::<Slot<crate::TimedLogRecord>>(),
+            CACHE_LINE_SIZE
+        );
+    }
+}
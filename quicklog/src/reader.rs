@@ -0,0 +1,229 @@
+//! Reads back log lines flushed into files, such as a set of rotated log files.
+//!
+//! [`SegmentIndex`] records the first and last timestamp of every file, or
+//! segment, so that [`SegmentIndex::read_range`] only opens the segments which
+//! overlap with the requested time range instead of scanning every file.
+//!
+//! Timestamps are parsed from the start of each log line as written by the
+//! default formatter, i.e. `[<timestamp>]...`, in the [`TimestampFormat`] the
+//! files were written with.
+//!
+//! ```no_run
+//! use chrono::{TimeZone, Utc};
+//! use quicklog::{reader::SegmentIndex, timestamp::TimestampFormat};
+//!
+//! let index = SegmentIndex::build(
+//!     ["logs/app.log.1", "logs/app.log.2", "logs/app.log"],
+//!     TimestampFormat::Rfc3339,
+//! )?;
+//!
+//! let from = Utc.with_ymd_and_hms(2023, 10, 23, 8, 0, 0).unwrap();
+//! let to = Utc.with_ymd_and_hms(2023, 10, 23, 8, 5, 0).unwrap();
+//! for line in index.read_range(from, to)? {
+//!     println!("{}", line);
+//! }
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Utc};
+
+use crate::timestamp::TimestampFormat;
+
+/// Number of bytes read from the end of a segment to find its last timestamp
+const TAIL_LEN: u64 = 64 * 1024;
+
+/// A single log file, along with the range of timestamps it contains
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Segment {
+    /// Path to the log file
+    pub path: PathBuf,
+    /// Timestamp of the first log line in the file
+    pub first: DateTime<Utc>,
+    /// Timestamp of the last log line in the file
+    pub last: DateTime<Utc>,
+}
+
+impl Segment {
+    /// Checks whether any log line of this segment could fall within `from..=to`
+    pub fn overlaps(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> bool {
+        self.first <= to && self.last >= from
+    }
+}
+
+/// Index over a set of log files, ordered by their first timestamp
+#[derive(Clone, Debug)]
+pub struct SegmentIndex {
+    segments: Vec<Segment>,
+    format: TimestampFormat,
+}
+
+impl SegmentIndex {
+    /// Builds the index by reading the first and last timestamp of every file
+    /// in `paths`. Files without any timestamped log lines are skipped.
+    pub fn build<P: AsRef<Path>>(
+        paths: impl IntoIterator<Item = P>,
+        format: TimestampFormat,
+    ) -> io::Result<SegmentIndex> {
+        let mut segments = Vec::new();
+        for path in paths {
+            if let Some(segment) = index_segment(path.as_ref(), &format)? {
+                segments.push(segment);
+            }
+        }
+        segments.sort_by_key(|segment| segment.first);
+
+        Ok(SegmentIndex { segments, format })
+    }
+
+    /// Returns the indexed segments, ordered by their first timestamp
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Returns every log line with a timestamp within `from..=to`, opening
+    /// only the segments which overlap with the range. Lines without a
+    /// timestamp are treated as a continuation of the preceding log line.
+    pub fn read_range(&self, from: DateTime<Utc>, to: DateTime<Utc>) -> io::Result<Vec<String>> {
+        let mut lines = Vec::new();
+        for segment in self.segments.iter().filter(|s| s.overlaps(from, to)) {
+            let mut in_range = false;
+            for line in BufReader::new(File::open(&segment.path)?).lines() {
+                let line = line?;
+                if let Some(time) = parse_line_timestamp(&line, &self.format) {
+                    in_range = from <= time && time <= to;
+                }
+                if in_range {
+                    lines.push(line);
+                }
+            }
+        }
+
+        Ok(lines)
+    }
+}
+
+/// Reads the first and last timestamp of the file at `path`
+fn index_segment(path: &Path, format: &TimestampFormat) -> io::Result<Option<Segment>> {
+    let mut file = File::open(path)?;
+    let mut first = None;
+    for line in BufReader::new(&mut file).lines() {
+        first = parse_line_timestamp(&line?, format);
+        if first.is_some() {
+            break;
+        }
+    }
+    let Some(first) = first else {
+        return Ok(None);
+    };
+
+    // only the tail of the file needs to be read to find the last timestamp,
+    // unless the tail does not contain a single timestamped line
+    let len = file.metadata()?.len();
+    let mut last = None;
+    for start in [len.saturating_sub(TAIL_LEN), 0] {
+        let mut tail = String::new();
+        file.seek(SeekFrom::Start(start))?;
+        BufReader::new(&mut file).read_to_string(&mut tail)?;
+        last = tail
+            .lines()
+            .rev()
+            .find_map(|line| parse_line_timestamp(line, format));
+        if last.is_some() || start == 0 {
+            break;
+        }
+    }
+
+    Ok(Some(Segment {
+        path: path.to_path_buf(),
+        first,
+        last: last.unwrap_or(first),
+    }))
+}
+
+/// Parses the timestamp of a log line written by the default formatter, e.g.
+/// `[<timestamp>]...` or `[trace_id=<id>] [<timestamp>]...`
+fn parse_line_timestamp(line: &str, format: &TimestampFormat) -> Option<DateTime<Utc>> {
+    let line = match line.strip_prefix("[trace_id=") {
+        Some(rest) => rest.split_once("] ")?.1,
+        None => line,
+    };
+    let (timestamp, _) = line.strip_prefix('[')?.split_once(']')?;
+    format.parse(timestamp)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn time(secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(1_698_048_000 + secs, 0).unwrap()
+    }
+
+    fn write_segment(dir: &Path, name: &str, secs: &[i64]) -> PathBuf {
+        let path = dir.join(name);
+        let mut file = File::create(&path).unwrap();
+        for &s in secs {
+            writeln!(
+                file,
+                "[{}] line {}",
+                TimestampFormat::Rfc3339.display(time(s)),
+                s
+            )
+            .unwrap();
+        }
+        path
+    }
+
+    #[test]
+    fn read_range_over_segments() {
+        let dir = std::env::temp_dir().join(format!("quicklog-reader-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let paths = [
+            write_segment(&dir, "app.log", &[20, 21, 22]),
+            write_segment(&dir, "app.log.1", &[10, 11, 12]),
+            write_segment(&dir, "app.log.2", &[0, 1, 2]),
+        ];
+
+        let index = SegmentIndex::build(&paths, TimestampFormat::Rfc3339).unwrap();
+        let segments = index.segments();
+        assert_eq!(segments.len(), 3);
+        assert_eq!((segments[0].first, segments[0].last), (time(0), time(2)));
+        assert_eq!(segments[2].path, paths[0]);
+
+        let lines = index.read_range(time(2), time(11)).unwrap();
+        let lines = lines
+            .iter()
+            .map(|line| line.split_once("] ").unwrap().1)
+            .collect::<Vec<_>>();
+        assert_eq!(lines, ["line 2", "line 10", "line 11"]);
+
+        assert!(index.read_range(time(3), time(9)).unwrap().is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn parse_timestamps_from_lines() {
+        let format = TimestampFormat::EpochNanos;
+        let expected = Some(time(0));
+        assert_eq!(
+            parse_line_timestamp("[1698048000000000000]hello", &format),
+            expected
+        );
+        assert_eq!(
+            parse_line_timestamp("[trace_id=00ff] [1698048000000000000]hello", &format),
+            expected
+        );
+        assert_eq!(parse_line_timestamp("continued line", &format), None);
+    }
+}
@@ -0,0 +1,283 @@
+//! Registry of the schemas of logging call sites, for tools decoding logs
+//! outside of the process.
+//!
+//! Every call of a logging macro registers the [`CallsiteSchema`] of its call
+//! site the first time it is reached with its level enabled: its level and
+//! location, its format string, and the name, kind and type of every
+//! argument. [`to_json`] exports the registered schemas, e.g. once on
+//! shutdown, so that they can be stored alongside the logs and compared
+//! between two versions of a binary.
+//!
+//! ```
+//! # use quicklog::{info, init, schema};
+//! init!();
+//! # let (id, price) = (1_u64, 101.5_f64);
+//! info!(id = ^id, "filled at {}", ^price);
+//!
+//! let schemas = schema::schemas();
+//! let args = schemas[0].args();
+//! assert_eq!(schemas[0].format(), "filled at {}");
+//! assert_eq!((args[0].name(), args[0].type_name()), (None, "f64"));
+//! assert_eq!((args[1].name(), args[1].type_name()), (Some("id"), "u64"));
+//! ```
+//!
+//! Call sites only register once, so call sites within generic functions
+//! record the types of their first call. Type names are those given by
+//! [`std::any::type_name`], which may change between versions of the
+//! compiler.
+
+use std::{
+    fmt::Write,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex, PoisonError,
+    },
+};
+
+use crate::callsite::{CallsiteId, Metadata};
+
+/// Version of the layout written by [`to_json`]
+pub const SCHEMA_VERSION: u8 = 1;
+
+/// Schemas of the call sites reached so far, in the order they were reached
+static SCHEMAS: Mutex<Vec<CallsiteSchema>> = Mutex::new(Vec::new());
+
+/// How an argument is passed to a logging macro, which decides how its value
+/// is queued and rendered
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArgKind {
+    /// `?arg`, formatted with `Debug` on the logging thread
+    Debug,
+    /// `%arg`, formatted with `Display` on the logging thread
+    Display,
+    /// `^arg`, encoded and decoded with `Serialize`
+    Serialize,
+    /// `compressed(^arg)`, encoded with `Serialize` and compressed
+    Compressed,
+    /// `hex(^arg)`, bytes rendered as hex
+    Hex,
+    /// `base64(^arg)`, bytes rendered as base64
+    Base64,
+    /// `byte_len(^arg)`, bytes rendered as their length
+    ByteLen,
+    /// `lazy || ..`, closure called when flushing
+    Lazy,
+    /// `arg`, cloned and formatted with `Display` when flushing
+    Normal,
+}
+
+impl ArgKind {
+    /// Name of the kind in the exported schema, e.g. `serialize`
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Debug => "debug",
+            Self::Display => "display",
+            Self::Serialize => "serialize",
+            Self::Compressed => "compressed",
+            Self::Hex => "hex",
+            Self::Base64 => "base64",
+            Self::ByteLen => "byte_len",
+            Self::Lazy => "lazy",
+            Self::Normal => "normal",
+        }
+    }
+}
+
+/// Name, kind and type of an argument of a logging macro
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArgSchema {
+    name: Option<&'static str>,
+    kind: ArgKind,
+    type_name: &'static str,
+}
+
+impl ArgSchema {
+    pub fn new(name: Option<&'static str>, kind: ArgKind, type_name: &'static str) -> Self {
+        Self {
+            name,
+            kind,
+            type_name,
+        }
+    }
+
+    /// Name of the field, or `None` for unnamed format arguments
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    /// How the argument is passed
+    pub fn kind(&self) -> ArgKind {
+        self.kind
+    }
+
+    /// Type of the argument, the type returned by the closure of `lazy`
+    /// arguments and `[u8]` for bytes
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+}
+
+/// Level, location, format string and arguments of a logging call site
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallsiteSchema {
+    callsite: CallsiteId,
+    format: &'static str,
+    args: Vec<ArgSchema>,
+}
+
+impl CallsiteSchema {
+    pub fn new(callsite: CallsiteId, format: &'static str, args: Vec<ArgSchema>) -> Self {
+        Self {
+            callsite,
+            format,
+            args,
+        }
+    }
+
+    /// Id of the call site, which records logged from it carry
+    pub fn callsite(&self) -> CallsiteId {
+        self.callsite
+    }
+
+    /// Level and source location of the call site
+    pub fn metadata(&self) -> &'static Metadata {
+        self.callsite.metadata()
+    }
+
+    /// Format string of the call, without the fields
+    pub fn format(&self) -> &'static str {
+        self.format
+    }
+
+    /// Format arguments, in order, followed by the fields
+    pub fn args(&self) -> &[ArgSchema] {
+        &self.args
+    }
+
+    /// Serializes the schema into a JSON object
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        self.write_json(&mut json);
+        json
+    }
+
+    fn write_json(&self, json: &mut String) {
+        let metadata = self.metadata();
+        let _ = write!(
+            json,
+            concat!(
+                "{{\"id\":{},\"level\":\"{}\",\"module_path\":{},\"file\":{},\"line\":{},",
+                "\"format\":{},\"args\":["
+            ),
+            self.callsite.as_u32(),
+            metadata.level(),
+            JsonStr(metadata.module_path()),
+            JsonStr(metadata.file()),
+            metadata.line(),
+            JsonStr(self.format)
+        );
+        for (i, arg) in self.args.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            json.push_str("{\"name\":");
+            match arg.name {
+                Some(name) => {
+                    let _ = write!(json, "{}", JsonStr(name));
+                }
+                None => json.push_str("null"),
+            }
+            let _ = write!(
+                json,
+                ",\"kind\":\"{}\",\"type\":{}}}",
+                arg.kind.as_str(),
+                JsonStr(arg.type_name)
+            );
+        }
+        json.push_str("]}");
+    }
+}
+
+/// Schemas of the call sites reached so far, in the order they were reached
+pub fn schemas() -> Vec<CallsiteSchema> {
+    SCHEMAS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+}
+
+/// Serializes the schemas of the call sites reached so far into JSON, as
+/// `{"version":1,"callsites":[..]}`, see [`CallsiteSchema::to_json`]
+pub fn to_json() -> String {
+    let schemas = SCHEMAS.lock().unwrap_or_else(PoisonError::into_inner);
+    let mut json = format!("{{\"version\":{},\"callsites\":[", SCHEMA_VERSION);
+    for (i, schema) in schemas.iter().enumerate() {
+        if i > 0 {
+            json.push(',');
+        }
+        schema.write_json(&mut json);
+    }
+    json.push_str("]}");
+    json
+}
+
+/// Writes a string as a quoted JSON string
+struct JsonStr<'a>(&'a str);
+
+impl std::fmt::Display for JsonStr<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_char('"')?;
+        for c in self.0.chars() {
+            match c {
+                '"' => f.write_str("\\\"")?,
+                '\\' => f.write_str("\\\\")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                '\t' => f.write_str("\\t")?,
+                c if c.is_control() => write!(f, "\\u{:04x}", c as u32)?,
+                c => f.write_char(c)?,
+            }
+        }
+        f.write_char('"')
+    }
+}
+
+/// **Internal API**
+///
+/// Registers the schema of a call site the first time it is reached
+#[doc(hidden)]
+pub struct __SchemaSlot(AtomicBool);
+
+impl __SchemaSlot {
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self(AtomicBool::new(false))
+    }
+
+    #[inline]
+    pub fn register(&self, schema: impl FnOnce() -> CallsiteSchema) {
+        if !self.0.load(Ordering::Relaxed) && !self.0.swap(true, Ordering::Relaxed) {
+            let schema = schema();
+            SCHEMAS
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(schema);
+        }
+    }
+}
+
+/// **Internal API**
+///
+/// Type of a value, used for arguments which are not otherwise checked
+#[doc(hidden)]
+pub fn __type_name_of<T>(_: &T) -> &'static str {
+    std::any::type_name::<T>()
+}
+
+/// **Internal API**
+///
+/// Type returned by the closure of a `lazy` argument
+#[doc(hidden)]
+pub fn __output_type_name<F: Fn() -> T, T>(_: &F) -> &'static str {
+    std::any::type_name::<T>()
+}
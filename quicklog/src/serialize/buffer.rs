@@ -1,4 +1,4 @@
-use crate::constants::MAX_SERIALIZE_BUFFER_CAPACITY;
+use crate::{constants::MAX_SERIALIZE_BUFFER_CAPACITY, Error};
 
 /// Bytebuffer to provide byte chunks for store
 pub struct ByteBuffer {
@@ -8,11 +8,13 @@ pub struct ByteBuffer {
 
 impl ByteBuffer {
     pub fn new() -> Self {
-        let mut data = Vec::new();
-        data.resize(MAX_SERIALIZE_BUFFER_CAPACITY, 0);
+        let data = vec![0; MAX_SERIALIZE_BUFFER_CAPACITY];
         Self { data, write_idx: 0 }
     }
 
+    /// Reserves the next `chunk_size` bytes. A chunk is only read once the
+    /// record referencing it is queued, so a chunk left partially written by a
+    /// panicking `encode` is simply skipped over.
     pub fn get_chunk_as_mut(&mut self, chunk_size: usize) -> &mut [u8] {
         let curr_idx = self.write_idx;
         if chunk_size > MAX_SERIALIZE_BUFFER_CAPACITY {
@@ -36,6 +38,19 @@ impl ByteBuffer {
             &mut self.data[curr_idx..curr_idx + chunk_size]
         }
     }
+
+    /// Like `get_chunk_as_mut`, but returns [`Error::BufferTooSmall`] instead
+    /// of panicking when `chunk_size` exceeds the capacity of the buffer
+    pub fn try_get_chunk_as_mut(&mut self, chunk_size: usize) -> Result<&mut [u8], Error> {
+        if chunk_size > MAX_SERIALIZE_BUFFER_CAPACITY {
+            return Err(Error::BufferTooSmall {
+                required: chunk_size,
+                available: MAX_SERIALIZE_BUFFER_CAPACITY,
+            });
+        }
+
+        Ok(self.get_chunk_as_mut(chunk_size))
+    }
 }
 
 impl Default for ByteBuffer {
@@ -0,0 +1,221 @@
+use std::{
+    fmt::{self, Write},
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+use super::{split_checked, write_corrupt, Serialize, Store, SIZE_LENGTH};
+use crate::Error;
+
+/// How byte buffers logged with `^` are rendered when records are decoded,
+/// e.g. the payloads of wire protocols.
+///
+/// `&[u8]` arguments follow the format set globally with
+/// [`set_bytes_format`], hex by default, while the `hex(^..)`, `base64(^..)`
+/// and `byte_len(^..)` arguments of the logging macros pick one per argument,
+/// for any value implementing `AsRef<[u8]>`:
+///
+/// ```
+/// # use quicklog::info;
+/// let payload = vec![0xde, 0xad, 0xbe, 0xef];
+///
+/// info!(payload = hex(^payload), "sent"); // "sent payload=deadbeef"
+/// info!(payload = base64(^payload), "sent"); // "sent payload=3q2+7w=="
+/// // only the length is queued
+/// info!(payload = byte_len(^payload), "sent"); // "sent payload=<4 bytes>"
+/// ```
+///
+/// The bytes are copied as-is when logging, and only rendered when flushing,
+/// except with [`BytesFormat::Len`] which does not copy them at all.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[repr(u8)]
+pub enum BytesFormat {
+    /// Lowercase hex digits, e.g. `deadbeef` (default)
+    #[default]
+    Hex = 0,
+    /// Standard base64 with padding, e.g. `3q2+7w==`
+    Base64 = 1,
+    /// Just the number of bytes, e.g. `<4 bytes>`
+    Len = 2,
+}
+
+impl BytesFormat {
+    /// Format applied to `&[u8]` arguments
+    pub fn global() -> BytesFormat {
+        BytesFormat::from_u8(BYTES_FORMAT.load(Ordering::Relaxed))
+    }
+
+    fn from_u8(value: u8) -> BytesFormat {
+        match value {
+            1 => BytesFormat::Base64,
+            2 => BytesFormat::Len,
+            _ => BytesFormat::Hex,
+        }
+    }
+}
+
+static BYTES_FORMAT: AtomicU8 = AtomicU8::new(BytesFormat::Hex as u8);
+
+/// Sets how `&[u8]` arguments logged from now on are rendered
+pub fn set_bytes_format(format: BytesFormat) {
+    BYTES_FORMAT.store(format as u8, Ordering::Relaxed);
+}
+
+/// Byte buffer along with how it is rendered, see [`BytesFormat`]. Created by
+/// the `hex(^..)`, `base64(^..)` and `byte_len(^..)` arguments of the logging
+/// macros.
+#[derive(Clone, Copy, Debug)]
+pub struct Bytes<'a> {
+    bytes: &'a [u8],
+    format: BytesFormat,
+}
+
+impl<'a> Bytes<'a> {
+    pub fn new<B: AsRef<[u8]> + ?Sized>(bytes: &'a B, format: BytesFormat) -> Bytes<'a> {
+        Bytes {
+            bytes: bytes.as_ref(),
+            format,
+        }
+    }
+
+    pub fn hex<B: AsRef<[u8]> + ?Sized>(bytes: &'a B) -> Bytes<'a> {
+        Bytes::new(bytes, BytesFormat::Hex)
+    }
+
+    pub fn base64<B: AsRef<[u8]> + ?Sized>(bytes: &'a B) -> Bytes<'a> {
+        Bytes::new(bytes, BytesFormat::Base64)
+    }
+
+    pub fn len_only<B: AsRef<[u8]> + ?Sized>(bytes: &'a B) -> Bytes<'a> {
+        Bytes::new(bytes, BytesFormat::Len)
+    }
+}
+
+/// Encodes the format, followed by the length and the bytes, which are left
+/// out with [`BytesFormat::Len`]
+impl Serialize for Bytes<'_> {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        let (chunk, rest) = write_buf.split_at_mut(self.buffer_size_required());
+        let (header, bytes) = chunk.split_at_mut(1 + SIZE_LENGTH);
+        header[0] = self.format as u8;
+        header[1..].copy_from_slice(&self.bytes.len().to_le_bytes());
+        bytes.copy_from_slice(&self.bytes[..bytes.len()]);
+
+        (Store::new_into(Self::decode_into, chunk), rest)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        Self::try_decode(read_buf).unwrap()
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        1 + SIZE_LENGTH
+            + match self.format {
+                BytesFormat::Len => 0,
+                _ => self.bytes.len(),
+            }
+    }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        let Ok((encoded, rest)) = Encoded::read(read_buf) else {
+            return write_corrupt(out);
+        };
+        encoded.write(out)?;
+        Ok(rest)
+    }
+
+    fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+        let (encoded, rest) = Encoded::read(read_buf)?;
+        let mut s = String::new();
+        let _ = encoded.write(&mut s);
+        Ok((s, rest))
+    }
+}
+
+/// Bytes as encoded by `Bytes`, borrowed from the read buffer
+struct Encoded<'a> {
+    format: BytesFormat,
+    len: usize,
+    /// Empty with [`BytesFormat::Len`]
+    bytes: &'a [u8],
+}
+
+impl<'a> Encoded<'a> {
+    /// Reads the encoded bytes, along with the remainder of `read_buf`
+    fn read(read_buf: &'a [u8]) -> Result<(Encoded<'a>, &'a [u8]), Error> {
+        let (header, read_buf) = split_checked(read_buf, 1 + SIZE_LENGTH)?;
+        let format = BytesFormat::from_u8(header[0]);
+        let len = usize::from_le_bytes(header[1..].try_into().unwrap());
+        if format == BytesFormat::Len {
+            return Ok((
+                Encoded {
+                    format,
+                    len,
+                    bytes: &[],
+                },
+                read_buf,
+            ));
+        }
+
+        let (bytes, rest) = split_checked(read_buf, len)?;
+        Ok((Encoded { format, len, bytes }, rest))
+    }
+
+    fn write(&self, out: &mut dyn Write) -> fmt::Result {
+        match self.format {
+            BytesFormat::Len => write!(out, "<{} bytes>", self.len),
+            BytesFormat::Base64 => write_base64(out, self.bytes),
+            _ => write_hex(out, self.bytes),
+        }
+    }
+}
+
+/// Rendered with the format set with [`set_bytes_format`]
+impl Serialize for [u8] {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        Bytes::new(self, BytesFormat::global()).encode(write_buf)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        Bytes::decode(read_buf)
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        Bytes::new(self, BytesFormat::global()).buffer_size_required()
+    }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        Bytes::decode_into(read_buf, out)
+    }
+
+    fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+        Bytes::try_decode(read_buf)
+    }
+}
+
+fn write_hex(out: &mut dyn Write, bytes: &[u8]) -> fmt::Result {
+    for byte in bytes {
+        write!(out, "{:02x}", byte)?;
+    }
+    Ok(())
+}
+
+fn write_base64(out: &mut dyn Write, bytes: &[u8]) -> fmt::Result {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0_u32, |n, (i, &byte)| n | u32::from(byte) << (16 - 8 * i));
+        // 2 to 4 digits for 1 to 3 bytes, padded with `=`
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let digit = (n >> (18 - 6 * i)) & 0x3f;
+                out.write_char(ALPHABET[digit as usize] as char)?;
+            } else {
+                out.write_char('=')?;
+            }
+        }
+    }
+    Ok(())
+}
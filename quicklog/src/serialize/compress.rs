@@ -0,0 +1,83 @@
+//! LZ4 compression of serialized arguments, used by `compressed(^arg)` in the
+//! logging macros when the `compression` feature is enabled.
+//!
+//! The argument is first encoded through its [`Serialize`] implementation into
+//! a thread-local scratch buffer, then compressed into the logger's byte
+//! buffer as:
+//!
+//! ```text
+//! [compressed size: u32][encoded size: u32][compressed bytes]
+//! ```
+//!
+//! Decompression only happens when the record is flushed.
+
+use std::cell::RefCell;
+
+use super::{Serialize, Store};
+use crate::Error;
+
+/// Number of bytes taken by the compressed and encoded sizes
+const HEADER_SIZE: usize = 2 * std::mem::size_of::<u32>();
+
+thread_local! {
+    /// Scratch buffers holding the encoded and compressed bytes, reused
+    /// across calls to avoid allocating on the hot path
+    static SCRATCH: RefCell<(Vec<u8>, Vec<u8>)> = const { RefCell::new((Vec::new(), Vec::new())) };
+}
+
+/// Encodes `value` and compresses it into a chunk of exactly the required
+/// size, obtained from `get_chunk`
+pub fn encode_compressed<'buf, T: Serialize>(
+    value: &T,
+    get_chunk: impl FnOnce(usize) -> &'buf mut [u8],
+) -> Store<'buf> {
+    match try_encode_compressed(value, |size| Ok(get_chunk(size))) {
+        Ok(store) => store,
+        Err(err) => unreachable!("chunk is always obtained: {}", err),
+    }
+}
+
+/// Like [`encode_compressed`], but returns the error of `get_chunk` instead of
+/// panicking when no chunk can be obtained for the compressed bytes
+pub fn try_encode_compressed<'buf, T: Serialize>(
+    value: &T,
+    get_chunk: impl FnOnce(usize) -> Result<&'buf mut [u8], Error>,
+) -> Result<Store<'buf>, Error> {
+    SCRATCH.with(|scratch| {
+        let (encoded, compressed) = &mut *scratch.borrow_mut();
+
+        let encoded_size = value.buffer_size_required();
+        encoded.clear();
+        encoded.resize(encoded_size, 0);
+        value.encode(encoded);
+
+        compressed.clear();
+        compressed.resize(lz4_flex::block::get_maximum_output_size(encoded_size), 0);
+        let compressed_size = lz4_flex::block::compress_into(encoded, compressed)
+            .expect("output buffer is sized for the worst case");
+
+        let chunk = get_chunk(HEADER_SIZE + compressed_size)?;
+        let (header, bytes) = chunk.split_at_mut(HEADER_SIZE);
+        header[..4].copy_from_slice(&(compressed_size as u32).to_le_bytes());
+        header[4..].copy_from_slice(&(encoded_size as u32).to_le_bytes());
+        bytes.copy_from_slice(&compressed[..compressed_size]);
+
+        Ok(Store::new(decode_compressed::<T>, chunk))
+    })
+}
+
+/// Decompresses the bytes written by [`encode_compressed`] and decodes them
+/// with the [`Serialize`] implementation of `T`
+pub fn decode_compressed<T: Serialize>(read_buf: &[u8]) -> (String, &[u8]) {
+    let (header, read_buf) = read_buf.split_at(HEADER_SIZE);
+    let compressed_size = u32::from_le_bytes(header[..4].try_into().unwrap()) as usize;
+    let encoded_size = u32::from_le_bytes(header[4..].try_into().unwrap()) as usize;
+    let (compressed, rest) = read_buf.split_at(compressed_size);
+
+    let decoded = match lz4_flex::block::decompress(compressed, encoded_size) {
+        Ok(encoded) => T::decode(&encoded).0,
+        Err(err) => format!("<corrupted compressed argument: {}>", err),
+    };
+
+    (decoded, rest)
+}
@@ -0,0 +1,143 @@
+use std::fmt::{self, Display, Write};
+
+use super::{FixedSizeSerialize, Serialize, Store};
+
+/// Fixed-point number stored as an `i64` count of `10^-SCALE` units, e.g. a
+/// price in ticks, decoded with exactly `SCALE` decimal places.
+///
+/// ```
+/// # use quicklog::{info, serialize::FixedPoint};
+/// // 101.25 with 4 decimal places
+/// let price = FixedPoint::<4>(1_012_500);
+/// assert_eq!(price.to_string(), "101.2500");
+///
+/// info!(price = ^price, "order sent"); // "order sent price=101.2500"
+/// ```
+///
+/// `SCALE` is at most 18.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct FixedPoint<const SCALE: u32>(pub i64);
+
+impl<const SCALE: u32> FixedPoint<SCALE> {
+    const FACTOR: u64 = {
+        assert!(SCALE <= 18, "FixedPoint supports at most 18 decimal places");
+        10_u64.pow(SCALE)
+    };
+}
+
+impl<const SCALE: u32> Display for FixedPoint<SCALE> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let units = self.0.unsigned_abs();
+        let (int, frac) = (units / Self::FACTOR, units % Self::FACTOR);
+        if SCALE == 0 {
+            write!(f, "{}{}", sign, int)
+        } else {
+            write!(
+                f,
+                "{}{}.{:0width$}",
+                sign,
+                int,
+                frac,
+                width = SCALE as usize
+            )
+        }
+    }
+}
+
+impl<const SCALE: u32> Serialize for FixedPoint<SCALE> {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        let (chunk, rest) = write_buf.split_at_mut(self.buffer_size_required());
+        chunk.copy_from_slice(&self.0.to_le_bytes());
+
+        (Store::new_into(Self::decode_into, chunk), rest)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        let (value, rest) = read_fixed_point::<SCALE>(read_buf);
+        (value.to_string(), rest)
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        std::mem::size_of::<i64>()
+    }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        let (value, rest) = read_fixed_point::<SCALE>(read_buf);
+        write!(out, "{}", value)?;
+        Ok(rest)
+    }
+}
+
+impl<const SCALE: u32> FixedSizeSerialize<8> for FixedPoint<SCALE> {
+    fn to_le_bytes(&self) -> [u8; 8] {
+        self.0.to_le_bytes()
+    }
+
+    fn from_le_bytes(bytes: [u8; 8]) -> Self {
+        Self(i64::from_le_bytes(bytes))
+    }
+}
+
+fn read_fixed_point<const SCALE: u32>(read_buf: &[u8]) -> (FixedPoint<SCALE>, &[u8]) {
+    let (chunk, rest) = read_buf.split_at(std::mem::size_of::<i64>());
+    (
+        FixedPoint(i64::from_le_bytes(chunk.try_into().unwrap())),
+        rest,
+    )
+}
+
+#[cfg(feature = "rust_decimal")]
+mod rust_decimal_impl {
+    use std::fmt::{self, Write};
+
+    use rust_decimal::Decimal;
+
+    use crate::serialize::{FixedSizeSerialize, Serialize, Store};
+
+    const DECIMAL_SIZE: usize = 16;
+
+    /// Encodes the 16 bytes of the decimal, decoded like its `Display`
+    /// representation, e.g. `101.25`
+    impl Serialize for Decimal {
+        fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+            let (chunk, rest) = write_buf.split_at_mut(DECIMAL_SIZE);
+            chunk.copy_from_slice(&self.serialize());
+
+            (Store::new_into(Self::decode_into, chunk), rest)
+        }
+
+        fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+            let (value, rest) = read_decimal(read_buf);
+            (value.to_string(), rest)
+        }
+
+        fn buffer_size_required(&self) -> usize {
+            DECIMAL_SIZE
+        }
+
+        fn decode_into<'a>(
+            read_buf: &'a [u8],
+            out: &mut dyn Write,
+        ) -> Result<&'a [u8], fmt::Error> {
+            let (value, rest) = read_decimal(read_buf);
+            write!(out, "{}", value)?;
+            Ok(rest)
+        }
+    }
+
+    impl FixedSizeSerialize<DECIMAL_SIZE> for Decimal {
+        fn to_le_bytes(&self) -> [u8; DECIMAL_SIZE] {
+            self.serialize()
+        }
+
+        fn from_le_bytes(bytes: [u8; DECIMAL_SIZE]) -> Self {
+            Decimal::deserialize(bytes)
+        }
+    }
+
+    fn read_decimal(read_buf: &[u8]) -> (Decimal, &[u8]) {
+        let (bytes, rest) = read_buf.split_at(DECIMAL_SIZE);
+        (Decimal::deserialize(bytes.try_into().unwrap()), rest)
+    }
+}
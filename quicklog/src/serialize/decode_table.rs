@@ -0,0 +1,251 @@
+//! Table of the decode functions of `^` arguments, so that a queued record
+//! carries a small [`DecodeId`] per argument instead of a function pointer.
+//!
+//! Each call site of a `^` argument has a [`DecodeSite`], which registers the
+//! decode function in the table the first time it is used, and remembers its
+//! id from then on. The encoded argument is then queued as a
+//! [`CompactStore`], which takes 16 bytes instead of the 32 bytes of a
+//! [`Store`].
+
+use std::{
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+    ptr,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        OnceLock,
+    },
+};
+
+use super::{Decoder, Store};
+use crate::Error;
+
+/// Maximum number of distinct decode functions the table holds
+pub const MAX_DECODERS: usize = 4096;
+
+/// Decode functions registered so far, indexed by [`DecodeId`]
+static DECODERS: [OnceLock<Decoder>; MAX_DECODERS] = [const { OnceLock::new() }; MAX_DECODERS];
+
+/// Number of ids handed out
+static REGISTERED: AtomicU32 = AtomicU32::new(0);
+
+impl Decoder {
+    /// Whether both decoders call the same function
+    fn same_fn(self, other: Decoder) -> bool {
+        match (self, other) {
+            (Decoder::Owned(a), Decoder::Owned(b)) => ptr::fn_addr_eq(a, b),
+            (Decoder::Into(a), Decoder::Into(b)) => ptr::fn_addr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+/// Index of a decode function in the table
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecodeId(u32);
+
+impl DecodeId {
+    /// Id of `decoder`, registering it if no call site did yet, or
+    /// [`Error::DecodeTableFull`] once [`MAX_DECODERS`] are registered
+    fn register(decoder: Decoder) -> Result<DecodeId, Error> {
+        if let Some(id) = DecodeId::lookup(decoder) {
+            return Ok(id);
+        }
+        let id = REGISTERED
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |registered| {
+                (registered < MAX_DECODERS as u32).then_some(registered + 1)
+            })
+            .map_err(|_| Error::DecodeTableFull)?;
+        // the id was just handed out, so nothing else sets it
+        let _ = DECODERS[id as usize].set(decoder);
+        Ok(DecodeId(id))
+    }
+
+    /// Id `decoder` was registered with. Two threads registering the same
+    /// function at once may both give it an id, which is harmless.
+    fn lookup(decoder: Decoder) -> Option<DecodeId> {
+        let registered = REGISTERED.load(Ordering::Acquire);
+        (0..registered)
+            .find(|&id| {
+                DECODERS[id as usize]
+                    .get()
+                    .is_some_and(|registered| registered.same_fn(decoder))
+            })
+            .map(DecodeId)
+    }
+
+    /// Decode function this id was registered with
+    pub fn decoder(self) -> Decoder {
+        // ids are only handed out by `DecodeId::register` once set
+        *DECODERS[self.0 as usize]
+            .get()
+            .expect("decode id without a decoder")
+    }
+}
+
+/// Ids of the decode functions used at a call site of a `^` argument, so
+/// that the table is only searched the first time each is used.
+///
+/// A call site within a generic function is shared by every instance of the
+/// function, so it remembers the ids of up to [`DecodeSite::WAYS`] decode
+/// functions, one per type it logs.
+pub struct DecodeSite {
+    /// Ids plus one, `0` for ways not used yet, which always come last
+    ids: [AtomicU32; DecodeSite::WAYS],
+}
+
+impl DecodeSite {
+    /// Number of decode functions a call site remembers
+    pub const WAYS: usize = 4;
+
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> DecodeSite {
+        DecodeSite {
+            ids: [const { AtomicU32::new(0) }; DecodeSite::WAYS],
+        }
+    }
+
+    /// Id of `decoder`, registering it the first time it is used here
+    #[inline]
+    pub fn id(&self, decoder: Decoder) -> Result<DecodeId, Error> {
+        for way in &self.ids {
+            match way.load(Ordering::Acquire) {
+                0 => break,
+                id => {
+                    let id = DecodeId(id - 1);
+                    if id.decoder().same_fn(decoder) {
+                        return Ok(id);
+                    }
+                }
+            }
+        }
+        self.register(decoder)
+    }
+
+    #[cold]
+    fn register(&self, decoder: Decoder) -> Result<DecodeId, Error> {
+        let id = DecodeId::register(decoder)?;
+        // remembered in the first free way, or in place of the last one
+        let way = self
+            .ids
+            .iter()
+            .find(|way| way.load(Ordering::Relaxed) == 0)
+            .unwrap_or(&self.ids[DecodeSite::WAYS - 1]);
+        way.store(id.0 + 1, Ordering::Release);
+        Ok(id)
+    }
+}
+
+/// Encoded argument as queued along with its record, decoded through its
+/// [`DecodeId`] when displayed
+#[derive(Clone, Copy)]
+pub struct CompactStore<'buf> {
+    decode_id: DecodeId,
+    len: u32,
+    ptr: *const u8,
+    _buffer: PhantomData<&'buf [u8]>,
+}
+
+impl<'buf> CompactStore<'buf> {
+    /// Queues `store` logged at `site`, failing if the decode table is full
+    #[inline]
+    pub fn new(store: Store<'buf>, site: &DecodeSite) -> Result<CompactStore<'buf>, Error> {
+        let buffer = store.buffer;
+        Ok(CompactStore {
+            decode_id: site.id(store.decoder)?,
+            len: u32::try_from(buffer.len()).expect("encoded argument larger than 4 GiB"),
+            ptr: buffer.as_ptr(),
+            _buffer: PhantomData,
+        })
+    }
+
+    /// Id of the decode function of the argument
+    pub fn decode_id(&self) -> DecodeId {
+        self.decode_id
+    }
+
+    pub fn as_string(&self) -> String {
+        self.decode_id.decoder().decode(self.buffer())
+    }
+
+    /// Decodes the argument straight into `w`, see [`Store::write_to`]
+    pub fn write_to<W: fmt::Write + ?Sized>(&self, mut w: &mut W) -> fmt::Result {
+        self.decode_id
+            .decoder()
+            .decode_into(self.buffer(), &mut w)
+            .map(|_| ())
+    }
+
+    fn buffer(&self) -> &'buf [u8] {
+        // SAFETY: built from a slice borrowed for `'buf`
+        unsafe { std::slice::from_raw_parts(self.ptr, self.len as usize) }
+    }
+}
+
+impl Display for CompactStore<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write_to(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::serialize::Serialize;
+
+    #[test]
+    fn compact_store_decodes_through_id() {
+        static SITE: DecodeSite = DecodeSite::new();
+        static OTHER_SITE: DecodeSite = DecodeSite::new();
+
+        let mut buf = [0; 16];
+        let (store, _) = 42_u64.encode(&mut buf);
+        let compact = CompactStore::new(store.clone(), &SITE).unwrap();
+        assert_eq!(compact.to_string(), "42");
+        assert_eq!(std::mem::size_of::<CompactStore>(), 16);
+
+        // the same decode function always gets the same id, at any call site
+        let other = CompactStore::new(store.clone(), &SITE).unwrap();
+        assert_eq!(other.decode_id(), compact.decode_id());
+        let other = CompactStore::new(store, &OTHER_SITE).unwrap();
+        assert_eq!(other.decode_id(), compact.decode_id());
+
+        // and every type its own
+        let mut str_buf = [0; 16];
+        let (store, _) = "abc".encode(&mut str_buf);
+        let compact = CompactStore::new(store, &OTHER_SITE).unwrap();
+        assert_eq!(compact.to_string(), "abc");
+        let mut line = String::from("symbol=");
+        compact.write_to(&mut line).unwrap();
+        assert_eq!(line, "symbol=abc");
+        assert_ne!(compact.decode_id(), other.decode_id());
+        assert_eq!(
+            OTHER_SITE.id(Decoder::Into(u64::decode_into)).unwrap(),
+            other.decode_id()
+        );
+        // functions returning a `String` keep their kind
+        let owned = SITE.id(Decoder::Owned(u64::decode)).unwrap();
+        assert_ne!(owned, other.decode_id());
+        assert!(matches!(owned.decoder(), Decoder::Owned(_)));
+        assert_eq!(owned.decoder().decode(&buf), "42");
+    }
+
+    #[test]
+    fn call_sites_in_generic_functions() {
+        fn log<T: Serialize>(value: T) -> String {
+            static SITE: DecodeSite = DecodeSite::new();
+            let mut buf = [0; 32];
+            let (store, _) = value.encode(&mut buf);
+            CompactStore::new(store, &SITE).unwrap().to_string()
+        }
+        assert_eq!(log(7_u32), "7");
+        assert_eq!(log("abc"), "abc");
+        assert_eq!(log(7_u32), "7");
+        // more types than the site remembers are still decoded
+        assert_eq!(log(1_u8), "1");
+        assert_eq!(log(2_i16), "2");
+        assert_eq!(log(true), "true");
+        assert_eq!(log("def"), "def");
+        assert_eq!(log(3_u64), "3");
+    }
+}
@@ -0,0 +1,98 @@
+use std::fmt::Display;
+
+use super::{DecodeFn, Serialize, Store};
+
+/// Object-safe counterpart of [`Serialize`], implemented for every type which
+/// implements [`Serialize`].
+///
+/// As [`Serialize::decode`] is an associated function, `dyn Serialize` is not
+/// allowed. `dyn AnySerialize` instead exposes the decode function through
+/// [`AnySerialize::decode_fn`], so that heterogeneous values can be passed
+/// through a common interface.
+///
+/// ```
+/// use quicklog::serialize::{AnySerialize, ErasedSerialize};
+///
+/// let fields: Vec<(&str, Box<dyn AnySerialize>)> = vec![
+///     ("id", Box::new(42_u64)),
+///     ("symbol", Box::new("BTC-USD")),
+///     ("price", Box::new(Some(100.5_f64))),
+/// ];
+///
+/// let erased = fields
+///     .iter()
+///     .map(|(name, value)| format!("{}={}", name, ErasedSerialize::from_dyn(value.as_ref())))
+///     .collect::<Vec<_>>();
+/// assert_eq!(erased, ["id=42", "symbol=BTC-USD", "price=Some(100.5)"]);
+/// ```
+pub trait AnySerialize {
+    /// Encodes the value into `write_buf`, see [`Serialize::encode`]
+    fn encode_any<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]);
+    /// Returns the function which decodes the bytes written by
+    /// [`AnySerialize::encode_any`], see [`Serialize::decode`]
+    fn decode_fn(&self) -> DecodeFn;
+    /// The number of bytes required to encode the value, see
+    /// [`Serialize::buffer_size_required`]
+    fn any_buffer_size_required(&self) -> usize;
+}
+
+impl<T: Serialize> AnySerialize for T {
+    fn encode_any<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        self.encode(write_buf)
+    }
+
+    fn decode_fn(&self) -> DecodeFn {
+        T::decode
+    }
+
+    fn any_buffer_size_required(&self) -> usize {
+        self.buffer_size_required()
+    }
+}
+
+/// Owned, type-erased encoding of a value, storing the encoded bytes along
+/// with the function to decode them.
+#[derive(Clone)]
+pub struct ErasedSerialize {
+    decode_fn: DecodeFn,
+    bytes: Box<[u8]>,
+}
+
+impl ErasedSerialize {
+    /// Encodes `value` into a newly allocated buffer
+    pub fn new<T: Serialize>(value: &T) -> ErasedSerialize {
+        Self::from_dyn(value)
+    }
+
+    /// Encodes a `dyn AnySerialize` into a newly allocated buffer
+    pub fn from_dyn(value: &dyn AnySerialize) -> ErasedSerialize {
+        let mut bytes = vec![0; value.any_buffer_size_required()].into_boxed_slice();
+        value.encode_any(&mut bytes);
+        ErasedSerialize {
+            decode_fn: value.decode_fn(),
+            bytes,
+        }
+    }
+
+    /// Returns the encoded bytes
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the function which decodes [`ErasedSerialize::bytes`]
+    pub fn decode_fn(&self) -> DecodeFn {
+        self.decode_fn
+    }
+
+    /// Returns a [`Store`] over the encoded bytes, which decodes them when
+    /// formatted
+    pub fn as_store(&self) -> Store<'_> {
+        Store::new(self.decode_fn, &self.bytes)
+    }
+}
+
+impl Display for ErasedSerialize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_store().write_to(f)
+    }
+}
@@ -0,0 +1,135 @@
+use super::{DecodeFn, ErasedSerialize, Serialize, Store, SIZE_LENGTH};
+
+/// Set of key/value pairs built at runtime, which can be logged with `^`.
+///
+/// Keys are `&'static str`, so only values are encoded when inserted. Values
+/// are stored as an [`ErasedSerialize`], so values of different types can be
+/// mixed without formatting them through `Debug` on the hot path.
+///
+/// ```
+/// use quicklog::{info, serialize::FieldMap};
+///
+/// # quicklog::init!();
+/// let fields = FieldMap::new()
+///     .with("strategy", &"momentum")
+///     .with("window", &20_u32)
+///     .with("threshold", &0.25_f64);
+///
+/// // logs "config: fields={strategy=momentum window=20 threshold=0.25}"
+/// info!(^fields, "config:");
+/// ```
+#[derive(Clone, Default)]
+pub struct FieldMap {
+    fields: Vec<(&'static str, ErasedSerialize)>,
+}
+
+impl FieldMap {
+    pub fn new() -> FieldMap {
+        FieldMap { fields: Vec::new() }
+    }
+
+    /// Creates an empty map with space for `capacity` fields
+    pub fn with_capacity(capacity: usize) -> FieldMap {
+        FieldMap {
+            fields: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Inserts a field, replacing the value of an existing field with the same
+    /// key. Fields keep the order in which they were first inserted.
+    pub fn insert<T: Serialize>(&mut self, key: &'static str, value: &T) -> &mut FieldMap {
+        let value = ErasedSerialize::new(value);
+        match self.fields.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => *existing = value,
+            None => self.fields.push((key, value)),
+        }
+        self
+    }
+
+    /// Builder-style version of [`FieldMap::insert`]
+    pub fn with<T: Serialize>(mut self, key: &'static str, value: &T) -> FieldMap {
+        self.insert(key, value);
+        self
+    }
+
+    /// Returns the value of the field with `key`
+    pub fn get(&self, key: &str) -> Option<&ErasedSerialize> {
+        self.fields.iter().find(|(k, _)| *k == key).map(|(_, v)| v)
+    }
+
+    /// Iterates over the fields in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &ErasedSerialize)> {
+        self.fields.iter().map(|(k, v)| (*k, v))
+    }
+
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+/// Encoded as the number of fields, followed by the key, decode function,
+/// value length and value bytes of every field.
+///
+/// The decode function is written as its address, which is only valid within
+/// the process that encoded it. This holds for the logging buffer, which is
+/// decoded by the same process when flushing.
+impl Serialize for FieldMap {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        let (chunk, rest) = write_buf.split_at_mut(self.buffer_size_required());
+
+        chunk[..SIZE_LENGTH].copy_from_slice(&self.fields.len().to_le_bytes());
+        let mut offset = SIZE_LENGTH;
+        for (key, value) in self.fields.iter() {
+            key.encode(&mut chunk[offset..]);
+            offset += key.buffer_size_required();
+
+            let decode_fn = value.decode_fn() as usize;
+            let bytes = value.bytes();
+            for word in [decode_fn, bytes.len()] {
+                chunk[offset..offset + SIZE_LENGTH].copy_from_slice(&word.to_le_bytes());
+                offset += SIZE_LENGTH;
+            }
+            chunk[offset..offset + bytes.len()].copy_from_slice(bytes);
+            offset += bytes.len();
+        }
+
+        (Store::new(Self::decode, chunk), rest)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        let read_word = |buf: &[u8]| usize::from_le_bytes(buf[..SIZE_LENGTH].try_into().unwrap());
+
+        let len = read_word(read_buf);
+        let mut rest = &read_buf[SIZE_LENGTH..];
+        let mut fields = Vec::with_capacity(len);
+        for _ in 0..len {
+            let (key, remaining) = <&str as Serialize>::decode(rest);
+            let decode_fn = read_word(remaining);
+            let value_len = read_word(&remaining[SIZE_LENGTH..]);
+            let (value, remaining) = remaining[2 * SIZE_LENGTH..].split_at(value_len);
+
+            // SAFETY: written by `encode` from a valid `DecodeFn` within this
+            // process
+            let decode_fn = unsafe { std::mem::transmute::<usize, DecodeFn>(decode_fn) };
+            fields.push(format!("{}={}", key, decode_fn(value).0));
+            rest = remaining;
+        }
+
+        (format!("{{{}}}", fields.join(" ")), rest)
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        SIZE_LENGTH
+            + self
+                .fields
+                .iter()
+                .map(|(key, value)| {
+                    key.buffer_size_required() + 2 * SIZE_LENGTH + value.bytes().len()
+                })
+                .sum::<usize>()
+    }
+}
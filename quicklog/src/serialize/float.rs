@@ -0,0 +1,77 @@
+use std::{
+    fmt::{self, Display},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// How floats are formatted when records are decoded, set globally with
+/// [`with_float_format!`] or the `float_format` option of [`init!`].
+///
+/// Applies to `f32` and `f64` arguments logged with `^`, and to the float
+/// fields of `#[derive(Serialize)]` and `#[derive(SerializeSelective)]`
+/// structs, which can override it per field with `#[serialize(shortest)]` or
+/// `#[serialize(precision = N)]`. Only decoding is affected, the raw bits are
+/// still copied when logging.
+///
+/// ```
+/// # use quicklog::{info, init, serialize::FloatFormat, with_float_format};
+/// init!(float_format = FloatFormat::Fixed(2));
+///
+/// info!(px = ^101.5_f64, "filled"); // "filled px=101.50"
+///
+/// with_float_format!(FloatFormat::Shortest);
+/// info!(px = ^101.5_f64, "filled"); // "filled px=101.5"
+/// ```
+///
+/// [`with_float_format!`]: crate::with_float_format
+/// [`init!`]: crate::init
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FloatFormat {
+    /// Shortest representation which parses back to the same value, e.g.
+    /// `0.1` or `101.5` (default)
+    #[default]
+    Shortest,
+    /// Fixed number of decimal places, e.g. `101.50` with `Fixed(2)`
+    Fixed(usize),
+}
+
+/// `FloatFormat::Shortest` in [`FLOAT_FORMAT`], every other value being the
+/// number of decimal places of `FloatFormat::Fixed`
+const SHORTEST: usize = usize::MAX;
+
+static FLOAT_FORMAT: AtomicUsize = AtomicUsize::new(SHORTEST);
+
+impl FloatFormat {
+    /// Format applied to floats which do not override it
+    pub fn global() -> FloatFormat {
+        match FLOAT_FORMAT.load(Ordering::Relaxed) {
+            SHORTEST => FloatFormat::Shortest,
+            decimals => FloatFormat::Fixed(decimals),
+        }
+    }
+
+    /// Formats `value`, which is printed with its `Display` implementation
+    /// given the number of decimal places as precision
+    pub fn format<T: Display>(self, value: T) -> String {
+        let mut s = String::new();
+        let _ = self.write(&mut s, value);
+        s
+    }
+
+    /// Writes `value` with this format into `out`
+    pub fn write<T: Display>(self, out: &mut dyn fmt::Write, value: T) -> fmt::Result {
+        match self {
+            FloatFormat::Shortest => write!(out, "{}", value),
+            FloatFormat::Fixed(decimals) => write!(out, "{:.*}", decimals, value),
+        }
+    }
+}
+
+/// Sets the format applied to floats which do not override it, see
+/// [`FloatFormat`]
+pub fn set_float_format(format: FloatFormat) {
+    let value = match format {
+        FloatFormat::Shortest => SHORTEST,
+        FloatFormat::Fixed(decimals) => decimals.min(SHORTEST - 1),
+    };
+    FLOAT_FORMAT.store(value, Ordering::Relaxed);
+}
@@ -0,0 +1,87 @@
+//! Vectors storing their elements inline, encoded the same way as `Vec<T>` so
+//! that only the used length is copied
+
+use std::fmt::{self, Write};
+
+use super::{encode_seq, seq_size_required, DecodeTyped, Serialize, Store};
+use crate::Error;
+
+#[cfg(feature = "smallvec")]
+impl<A> Serialize for smallvec::SmallVec<A>
+where
+    A: smallvec::Array,
+    A::Item: Serialize,
+{
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        encode_seq(self, write_buf)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        Vec::<A::Item>::decode(read_buf)
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        seq_size_required(self)
+    }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        Vec::<A::Item>::decode_into(read_buf, out)
+    }
+
+    fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+        Vec::<A::Item>::try_decode(read_buf)
+    }
+}
+
+#[cfg(feature = "smallvec")]
+impl<A> DecodeTyped for smallvec::SmallVec<A>
+where
+    A: smallvec::Array,
+    A::Item: DecodeTyped,
+{
+    type Decoded = Vec<<A::Item as DecodeTyped>::Decoded>;
+
+    fn decode_typed(read_buf: &[u8]) -> (Self::Decoded, &[u8]) {
+        Vec::<A::Item>::decode_typed(read_buf)
+    }
+
+    fn try_decode_typed(read_buf: &[u8]) -> Result<(Self::Decoded, &[u8]), Error> {
+        Vec::<A::Item>::try_decode_typed(read_buf)
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<T: Serialize, const N: usize> Serialize for arrayvec::ArrayVec<T, N> {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        encode_seq(self, write_buf)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        Vec::<T>::decode(read_buf)
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        seq_size_required(self)
+    }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        Vec::<T>::decode_into(read_buf, out)
+    }
+
+    fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+        Vec::<T>::try_decode(read_buf)
+    }
+}
+
+#[cfg(feature = "arrayvec")]
+impl<T: DecodeTyped, const N: usize> DecodeTyped for arrayvec::ArrayVec<T, N> {
+    type Decoded = Vec<T::Decoded>;
+
+    fn decode_typed(read_buf: &[u8]) -> (Self::Decoded, &[u8]) {
+        Vec::<T>::decode_typed(read_buf)
+    }
+
+    fn try_decode_typed(read_buf: &[u8]) -> Result<(Self::Decoded, &[u8]), Error> {
+        Vec::<T>::try_decode_typed(read_buf)
+    }
+}
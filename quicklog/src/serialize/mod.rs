@@ -1,6 +1,37 @@
-use std::{fmt::Display, str::from_utf8};
+use std::{
+    fmt::{self, Display, Write},
+    str::from_utf8,
+};
+
+use crate::Error;
 
 pub mod buffer;
+mod bytes;
+#[cfg(feature = "compression")]
+pub mod compress;
+pub mod decode_table;
+mod decimal;
+mod erased;
+mod field_map;
+mod float;
+#[cfg(any(feature = "smallvec", feature = "arrayvec"))]
+mod inline_vec;
+mod net;
+mod num;
+mod smart_ptr;
+mod static_str;
+mod time;
+mod typed;
+#[cfg(feature = "uuid")]
+mod uuid;
+
+pub use bytes::{set_bytes_format, Bytes, BytesFormat};
+pub use decimal::FixedPoint;
+pub use erased::{AnySerialize, ErasedSerialize};
+pub use field_map::FieldMap;
+pub use float::{set_float_format, FloatFormat};
+pub use static_str::StaticStr;
+pub use typed::DecodeTyped;
 
 /// Allows specification of a custom way to serialize the Struct.
 ///
@@ -59,6 +90,79 @@ pub trait Serialize {
     fn decode(read_buf: &[u8]) -> (String, &[u8]);
     /// The number of bytes required to `encode` the type into a byte buffer.
     fn buffer_size_required(&self) -> usize;
+
+    /// Describes how to decode the implementing type from a byte buffer
+    /// straight into `out`, which the logger reuses for every record when
+    /// flushing, instead of allocating a `String`.
+    ///
+    /// Returns the remainder of `read_buf` that was not read. Defaults to
+    /// writing the `String` returned by `try_decode`, so only types overriding
+    /// it, along with [`Store::new_into`] in `encode`, are decoded without
+    /// allocating. Corrupt bytes are written as [`CORRUPT`] rather than
+    /// panicking the flusher, see [`write_corrupt`].
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        match Self::try_decode(read_buf) {
+            Ok((s, rest)) => {
+                out.write_str(&s)?;
+                Ok(rest)
+            }
+            Err(_) => write_corrupt(out),
+        }
+    }
+
+    /// Fallible version of `decode`, returning [`Error::DecodeCorrupt`] for
+    /// bytes which were not encoded by `encode`, e.g. a truncated buffer or
+    /// a corrupted length prefix, instead of panicking. Defaults to calling
+    /// `decode`.
+    ///
+    /// Built-in types and derived implementations check their input, so that
+    /// a single corrupted record does not panic the consumer.
+    fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+        Ok(Self::decode(read_buf))
+    }
+
+    /// Like `encode`, but returns [`Error::BufferTooSmall`] instead of
+    /// panicking when `write_buf` is too small, see [`try_encode`]. Used by the
+    /// logging macros for `^` arguments.
+    fn try_encode<'buf>(
+        &self,
+        write_buf: &'buf mut [u8],
+    ) -> Result<(Store<'buf>, &'buf mut [u8]), Error> {
+        try_encode(self, write_buf)
+    }
+}
+
+/// Numeric types whose decoded value can be rescaled, used by the
+/// `#[serialize(scale = ..)]` and `#[serialize(precision = ..)]` field
+/// attributes of `#[derive(Serialize)]`.
+///
+/// Only decoding is affected: the raw value is still copied as-is when
+/// logging, and converted to a float once the record is flushed.
+pub trait DecodeNumeric: Serialize {
+    /// Decodes the value from a byte buffer as a float.
+    ///
+    /// Returns the value as well as the remainder of `read_buf` that was not
+    /// read.
+    fn decode_f64(read_buf: &[u8]) -> (f64, &[u8]) {
+        Self::try_decode_f64(read_buf).unwrap()
+    }
+
+    /// Fallible version of `decode_f64`, returning [`Error::DecodeCorrupt`]
+    /// if `read_buf` is too short instead of panicking.
+    fn try_decode_f64(read_buf: &[u8]) -> Result<(f64, &[u8]), Error>;
+
+    /// Decodes the value formatted with `format` instead of the global
+    /// [`FloatFormat`], used by the `#[serialize(shortest)]` field attribute.
+    /// Integers are printed as-is whatever the format, and corrupt bytes as
+    /// [`CORRUPT`], with nothing left to read.
+    ///
+    /// Defaults to formatting the value returned by `try_decode_f64`.
+    fn decode_formatted(read_buf: &[u8], format: FloatFormat) -> (String, &[u8]) {
+        match Self::try_decode_f64(read_buf) {
+            Ok((x, rest)) => (format.format(x), rest),
+            Err(_) => (CORRUPT.to_string(), &[]),
+        }
+    }
 }
 
 /// High-performance, fixed-size serialization for primitive-like types.
@@ -121,6 +225,20 @@ pub trait FixedSizeSerialize<const N: usize> {
     /// bytes produced by `to_le_bytes()`.
     fn from_le_bytes(bytes: [u8; N]) -> Self;
 
+    /// Fallible version of [`FixedSizeSerialize::from_le_bytes`], returning
+    /// `None` for bytes which do not represent a value, e.g. an unknown enum
+    /// discriminant in corrupted or version-skewed data. Defaults to calling
+    /// `from_le_bytes`.
+    ///
+    /// `SerializeSelective` structs decode their fields through this method,
+    /// rendering invalid bytes with [`format_unknown`] instead of panicking.
+    fn try_from_le_bytes(bytes: [u8; N]) -> Option<Self>
+    where
+        Self: Sized,
+    {
+        Some(Self::from_le_bytes(bytes))
+    }
+
     /// The number of bytes required for serialization (always N).
     ///
     /// This is provided as a const for generic programming convenience.
@@ -130,69 +248,312 @@ pub trait FixedSizeSerialize<const N: usize> {
 /// Function pointer which decodes a byte buffer back into `String` representation
 pub type DecodeFn = fn(&[u8]) -> (String, &[u8]);
 
+/// Function pointer which decodes a byte buffer into a writer, see
+/// [`Serialize::decode_into`]
+pub type DecodeIntoFn = for<'a> fn(&'a [u8], &mut dyn Write) -> Result<&'a [u8], fmt::Error>;
+
+/// How the encoded bytes of a [`Store`] are decoded
+#[derive(Clone, Copy)]
+pub enum Decoder {
+    /// Decoded into a newly allocated `String`
+    Owned(DecodeFn),
+    /// Decoded into a writer
+    Into(DecodeIntoFn),
+}
+
+impl Decoder {
+    /// Decodes `read_buf` into `out`, returning the remainder of `read_buf`
+    /// that was not read
+    pub fn decode_into<'a>(
+        self,
+        read_buf: &'a [u8],
+        out: &mut dyn Write,
+    ) -> Result<&'a [u8], fmt::Error> {
+        match self {
+            Decoder::Owned(decode_fn) => {
+                let (s, rest) = decode_fn(read_buf);
+                out.write_str(&s)?;
+                Ok(rest)
+            }
+            Decoder::Into(decode_into) => decode_into(read_buf, out),
+        }
+    }
+
+    /// Decodes `read_buf` into a `String`
+    pub fn decode(self, read_buf: &[u8]) -> String {
+        match self {
+            Decoder::Owned(decode_fn) => decode_fn(read_buf).0,
+            Decoder::Into(decode_into) => {
+                let mut s = String::new();
+                // writing into a `String` only fails if a `Display` impl does
+                let _ = decode_into(read_buf, &mut s);
+                s
+            }
+        }
+    }
+}
+
 /// Number of bytes it takes to store the size of a type.
 pub const SIZE_LENGTH: usize = std::mem::size_of::<usize>();
 
+/// Written in place of a value whose encoded bytes are corrupt, when decoding
+/// into a writer
+pub const CORRUPT: &str = "<corrupt>";
+
+/// Writes [`CORRUPT`] into `out`, for [`Serialize::decode_into`] to return
+/// when `read_buf` is corrupt. Nothing is left to read, since where the next
+/// value would start is unknown.
+pub fn write_corrupt<'a>(out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+    out.write_str(CORRUPT)?;
+    Ok(&[])
+}
+
+/// Splits `read_buf` at `mid`, returning [`Error::DecodeCorrupt`] instead of
+/// panicking if it is shorter than `mid`
+pub(crate) fn split_checked(read_buf: &[u8], mid: usize) -> Result<(&[u8], &[u8]), Error> {
+    read_buf.split_at_checked(mid).ok_or(Error::DecodeCorrupt)
+}
+
 /// Contains the decode function required to decode `buffer` back into a `String`
 /// representation.
 #[derive(Clone)]
 pub struct Store<'buf> {
-    decode_fn: DecodeFn,
+    decoder: Decoder,
     buffer: &'buf [u8],
 }
 
 impl Store<'_> {
-    pub fn new(decode_fn: DecodeFn, buffer: &[u8]) -> Store {
-        Store { decode_fn, buffer }
+    pub fn new(decode_fn: DecodeFn, buffer: &[u8]) -> Store<'_> {
+        Store {
+            decoder: Decoder::Owned(decode_fn),
+            buffer,
+        }
+    }
+
+    /// Store decoded straight into the formatter when displayed, usually
+    /// given `Self::decode_into` in [`Serialize::encode`]
+    pub fn new_into(decode_into: DecodeIntoFn, buffer: &[u8]) -> Store<'_> {
+        Store {
+            decoder: Decoder::Into(decode_into),
+            buffer,
+        }
+    }
+
+    pub fn decoder(&self) -> Decoder {
+        self.decoder
     }
 
     pub fn as_string(&self) -> String {
-        let (s, _) = (self.decode_fn)(self.buffer);
-        s
+        self.decoder.decode(self.buffer)
+    }
+
+    /// Decodes `buffer` straight into `w`, e.g. the line being formatted,
+    /// rather than into a `String` as [`as_string`](Store::as_string) does
+    ///
+    /// ```
+    /// use quicklog::serialize::Serialize;
+    ///
+    /// let mut buf = [0; 16];
+    /// let (store, _) = 42_u64.encode(&mut buf);
+    ///
+    /// let mut line = String::from("qty=");
+    /// store.write_to(&mut line).unwrap();
+    /// assert_eq!(line, "qty=42");
+    /// ```
+    pub fn write_to<W: Write + ?Sized>(&self, mut w: &mut W) -> fmt::Result {
+        self.decoder.decode_into(self.buffer, &mut w).map(|_| ())
     }
 }
 
 impl Display for Store<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.as_string())
+        self.write_to(f)
     }
 }
 
 macro_rules! gen_serialize {
     ($primitive:ty) => {
+        gen_serialize!(
+            $primitive,
+            |out: &mut dyn Write, x: $primitive, _format: FloatFormat| write!(out, "{}", x)
+        );
+    };
+    // `$write` writes the decoded value into `out`, given the float format to
+    // apply
+    ($primitive:ty, $write:expr) => {
         impl Serialize for $primitive {
             fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
                 let size = self.buffer_size_required();
                 let (x, rest) = write_buf.split_at_mut(size);
                 x.copy_from_slice(&self.to_le_bytes());
 
-                (Store::new(Self::decode, x), rest)
+                (Store::new_into(Self::decode_into, x), rest)
             }
 
             fn decode(read_buf: &[u8]) -> (String, &[u8]) {
-                let (chunk, rest) = read_buf.split_at(std::mem::size_of::<$primitive>());
-                let x = <$primitive>::from_le_bytes(chunk.try_into().unwrap());
-
-                (format!("{}", x), rest)
+                Self::try_decode(read_buf).unwrap()
             }
 
             fn buffer_size_required(&self) -> usize {
                 std::mem::size_of::<$primitive>()
             }
+
+            fn decode_into<'a>(
+                read_buf: &'a [u8],
+                out: &mut dyn Write,
+            ) -> Result<&'a [u8], fmt::Error> {
+                let Ok((chunk, rest)) = split_checked(read_buf, std::mem::size_of::<$primitive>())
+                else {
+                    return write_corrupt(out);
+                };
+                let x = <$primitive>::from_le_bytes(chunk.try_into().unwrap());
+
+                $write(out, x, FloatFormat::global())?;
+                Ok(rest)
+            }
+
+            fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+                let (chunk, rest) = split_checked(read_buf, std::mem::size_of::<$primitive>())?;
+                let x = <$primitive>::from_le_bytes(chunk.try_into().unwrap());
+
+                let mut s = String::new();
+                let _ = $write(&mut s, x, FloatFormat::global());
+                Ok((s, rest))
+            }
+        }
+
+        impl DecodeNumeric for $primitive {
+            fn try_decode_f64(read_buf: &[u8]) -> Result<(f64, &[u8]), Error> {
+                let (chunk, rest) = split_checked(read_buf, std::mem::size_of::<$primitive>())?;
+                let x = <$primitive>::from_le_bytes(chunk.try_into().unwrap());
+
+                Ok((x as f64, rest))
+            }
+
+            fn decode_formatted(read_buf: &[u8], format: FloatFormat) -> (String, &[u8]) {
+                let Ok((chunk, rest)) = split_checked(read_buf, std::mem::size_of::<$primitive>())
+                else {
+                    return (CORRUPT.to_string(), &[]);
+                };
+                let x = <$primitive>::from_le_bytes(chunk.try_into().unwrap());
+
+                let mut s = String::new();
+                let _ = $write(&mut s, x, format);
+                (s, rest)
+            }
         }
     };
 }
 
+gen_serialize!(i8);
+gen_serialize!(i16);
 gen_serialize!(i32);
 gen_serialize!(i64);
+gen_serialize!(i128);
 gen_serialize!(isize);
-gen_serialize!(f32);
-gen_serialize!(f64);
+gen_serialize!(f32, |out: &mut dyn Write, x: f32, format: FloatFormat| {
+    format.write(out, x)
+});
+gen_serialize!(f64, |out: &mut dyn Write, x: f64, format: FloatFormat| {
+    format.write(out, x)
+});
+gen_serialize!(u8);
+gen_serialize!(u16);
 gen_serialize!(u32);
 gen_serialize!(u64);
 gen_serialize!(u128);
 gen_serialize!(usize);
 
+impl Serialize for bool {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        let (x, rest) = write_buf.split_at_mut(self.buffer_size_required());
+        x[0] = *self as u8;
+
+        (Store::new_into(Self::decode_into, x), rest)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        Self::try_decode(read_buf).unwrap()
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        1
+    }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        let Some((x, rest)) = read_buf.split_first() else {
+            return write_corrupt(out);
+        };
+
+        out.write_str(if *x != 0 { "true" } else { "false" })?;
+        Ok(rest)
+    }
+
+    fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+        let (x, rest) = read_buf.split_first().ok_or(Error::DecodeCorrupt)?;
+
+        Ok((format!("{}", *x != 0), rest))
+    }
+}
+
+impl Serialize for char {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        let (x, rest) = write_buf.split_at_mut(self.buffer_size_required());
+        x.copy_from_slice(&(*self as u32).to_le_bytes());
+
+        (Store::new_into(Self::decode_into, x), rest)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        Self::try_decode(read_buf).unwrap()
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        std::mem::size_of::<u32>()
+    }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        let Ok((chunk, rest)) = split_checked(read_buf, std::mem::size_of::<u32>()) else {
+            return write_corrupt(out);
+        };
+        let x = u32::from_le_bytes(chunk.try_into().unwrap());
+
+        out.write_char(char::from_u32(x).unwrap_or(char::REPLACEMENT_CHARACTER))?;
+        Ok(rest)
+    }
+
+    fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+        let (chunk, rest) = split_checked(read_buf, std::mem::size_of::<u32>())?;
+        let x = u32::from_le_bytes(chunk.try_into().unwrap());
+
+        Ok((
+            char::from_u32(x)
+                .unwrap_or(char::REPLACEMENT_CHARACTER)
+                .to_string(),
+            rest,
+        ))
+    }
+}
+
+/// Renders bytes which [`FixedSizeSerialize::try_from_le_bytes`] rejected, as
+/// `Unknown(<n>)` where `n` is the bytes read as a little-endian integer, e.g.
+/// `Unknown(7)` for an unknown enum discriminant. Longer values are rendered
+/// as hex digits.
+pub fn format_unknown(bytes: &[u8]) -> String {
+    if bytes.len() <= std::mem::size_of::<u128>() {
+        let mut value = [0; std::mem::size_of::<u128>()];
+        value[..bytes.len()].copy_from_slice(bytes);
+        format!("Unknown({})", u128::from_le_bytes(value))
+    } else {
+        let mut s = String::from("Unknown(0x");
+        for byte in bytes.iter().rev() {
+            let _ = write!(s, "{:02x}", byte);
+        }
+        s.push(')');
+        s
+    }
+}
+
 /// Macro to generate `FixedSizeSerialize` implementations for primitive types.
 ///
 /// This macro creates implementations that delegate to the primitive type's
@@ -265,7 +626,9 @@ macro_rules! impl_fixed_size_serialize_newtype {
 /// Macro to generate `FixedSizeSerialize` implementations for enums.
 ///
 /// This macro handles unit enums with explicit discriminant values,
-/// serializing them as single bytes.
+/// serializing them as single bytes. Unknown discriminants are rejected by
+/// `try_from_le_bytes`, and rendered as `Unknown(<n>)` by `SerializeSelective`
+/// structs, while `from_le_bytes` panics on them.
 ///
 /// # Example
 ///
@@ -298,13 +661,17 @@ macro_rules! impl_fixed_size_serialize_enum {
             }
 
             fn from_le_bytes(bytes: [u8; 1]) -> Self {
+                Self::try_from_le_bytes(bytes).unwrap_or_else(|| panic!(
+                    "Invalid {} discriminant: {}",
+                    stringify!($enum_type),
+                    bytes[0]
+                ))
+            }
+
+            fn try_from_le_bytes(bytes: [u8; 1]) -> Option<Self> {
                 match bytes[0] {
-                    $($value => Self::$variant,)+
-                    _ => panic!(
-                        "Invalid {} discriminant: {}",
-                        stringify!($enum_type),
-                        bytes[0]
-                    ),
+                    $($value => Some(Self::$variant),)+
+                    _ => None,
                 }
             }
         }
@@ -338,7 +705,8 @@ macro_rules! impl_fixed_size_serialize_enum {
 ///
 /// The macro takes the enum type as the first argument, followed by all
 /// its variant names. This is necessary to generate the string representation
-/// for the `decode` function.
+/// for the `decode` function. `#[derive(Serialize)]` supports enums without
+/// repeating the variants, including variants carrying data.
 #[macro_export]
 macro_rules! gen_serialize_enum {
     ($enum_type:ty, $($variant:ident),+) => {
@@ -349,25 +717,30 @@ macro_rules! gen_serialize_enum {
                 let (x, rest) = write_buf.split_at_mut(size);
                 x.copy_from_slice(&discriminant.to_le_bytes());
 
-                ($crate::serialize::Store::new(Self::decode, x), rest)
+                ($crate::serialize::Store::new_into(Self::decode_into, x), rest)
             }
 
             fn decode(read_buf: &[u8]) -> (String, &[u8]) {
-                let (chunk, rest) = read_buf.split_at(std::mem::size_of::<u8>());
-                let discriminant = u8::from_le_bytes(chunk.try_into().unwrap());
+                Self::try_decode(read_buf).unwrap()
+            }
 
-                let variant_name = match discriminant {
+            fn buffer_size_required(&self) -> usize {
+                std::mem::size_of::<u8>()
+            }
+
+            fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), $crate::Error> {
+                let (discriminant, rest) = read_buf
+                    .split_first()
+                    .ok_or($crate::Error::DecodeCorrupt)?;
+
+                let variant_name = match *discriminant {
                     $(
                         x if x == <$enum_type>::$variant as u8 => stringify!($variant),
                     )+
                     _ => "UnknownVariant",
                 };
 
-                (variant_name.to_string(), rest)
-            }
-
-            fn buffer_size_required(&self) -> usize {
-                std::mem::size_of::<u8>()
+                Ok((variant_name.to_string(), rest))
             }
         }
     };
@@ -382,21 +755,84 @@ impl Serialize for &str {
         len_chunk.copy_from_slice(&str_len.to_le_bytes());
         str_chunk.copy_from_slice(self.as_bytes());
 
-        (Store::new(Self::decode, chunk), rest)
+        (Store::new_into(Self::decode_into, chunk), rest)
     }
 
     fn decode(read_buf: &[u8]) -> (String, &[u8]) {
-        let (len_chunk, chunk) = read_buf.split_at(SIZE_LENGTH);
-        let str_len = usize::from_le_bytes(len_chunk.try_into().unwrap());
+        Self::try_decode(read_buf).unwrap()
+    }
 
-        let (str_chunk, rest) = chunk.split_at(str_len);
-        let s = from_utf8(str_chunk).unwrap();
+    fn buffer_size_required(&self) -> usize {
+        SIZE_LENGTH + self.len()
+    }
 
-        (s.to_string(), rest)
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        let Ok((s, rest)) = decode_str(read_buf) else {
+            return write_corrupt(out);
+        };
+        out.write_str(s)?;
+        Ok(rest)
+    }
+
+    fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+        let (s, rest) = decode_str(read_buf)?;
+        Ok((s.to_string(), rest))
+    }
+}
+
+/// Reads a string encoded by `&str`, borrowing it from `read_buf`
+fn decode_str(read_buf: &[u8]) -> Result<(&str, &[u8]), Error> {
+    let (len_chunk, chunk) = split_checked(read_buf, SIZE_LENGTH)?;
+    let str_len = usize::from_le_bytes(len_chunk.try_into().unwrap());
+
+    let (str_chunk, rest) = split_checked(chunk, str_len)?;
+    let s = from_utf8(str_chunk).map_err(|_| Error::DecodeCorrupt)?;
+    Ok((s, rest))
+}
+
+impl Serialize for String {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        self.as_str().encode(write_buf)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        <&str as Serialize>::decode(read_buf)
     }
 
     fn buffer_size_required(&self) -> usize {
-        SIZE_LENGTH + self.len()
+        self.as_str().buffer_size_required()
+    }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        <&str as Serialize>::decode_into(read_buf, out)
+    }
+
+    fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+        <&str as Serialize>::try_decode(read_buf)
+    }
+}
+
+/// Fixed-capacity strings are encoded the same way as `&str`, so only the
+/// used length is copied rather than the full capacity
+impl<const N: usize> Serialize for heapless::String<N> {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        self.as_str().encode(write_buf)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        <&str as Serialize>::decode(read_buf)
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        self.as_str().buffer_size_required()
+    }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        <&str as Serialize>::decode_into(read_buf, out)
+    }
+
+    fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+        <&str as Serialize>::try_decode(read_buf)
     }
 }
 
@@ -418,26 +854,27 @@ where
                 let (_inner_store, _) = value.encode(&mut chunk[1..]);
 
                 // Create new store that includes the marker
-                (Store::new(Self::decode, chunk), rest)
+                (Store::new_into(Self::decode_into, chunk), rest)
             }
             None => {
                 let (chunk, rest) = write_buf.split_at_mut(1);
                 chunk[0] = 0; // None marker
-                (Store::new(Self::decode, chunk), rest)
+                (Store::new_into(Self::decode_into, chunk), rest)
             }
         }
     }
 
     fn decode(read_buf: &[u8]) -> (String, &[u8]) {
-        let marker = read_buf[0];
-        if marker == 0 {
-            // None case
-            ("None".to_string(), &read_buf[1..])
-        } else {
-            // Some case - decode the inner value
-            let (inner_string, remaining) = T::decode(&read_buf[1..]);
-            (format!("Some({})", inner_string), remaining)
+        Self::try_decode(read_buf).unwrap()
+    }
+
+    fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+        let (marker, read_buf) = read_buf.split_first().ok_or(Error::DecodeCorrupt)?;
+        if *marker == 0 {
+            return Ok(("None".to_string(), read_buf));
         }
+        let (inner_string, remaining) = T::try_decode(read_buf)?;
+        Ok((format!("Some({})", inner_string), remaining))
     }
 
     fn buffer_size_required(&self) -> usize {
@@ -446,61 +883,194 @@ where
             None => 1,                                           // just the marker
         }
     }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        let Some((marker, read_buf)) = read_buf.split_first() else {
+            return write_corrupt(out);
+        };
+        if *marker == 0 {
+            out.write_str("None")?;
+            return Ok(read_buf);
+        }
+        out.write_str("Some(")?;
+        let remaining = T::decode_into(read_buf, out)?;
+        out.write_char(')')?;
+        Ok(remaining)
+    }
 }
 
-/// Blanket implementation of Serialize for Vec<T> where T implements Serialize
-impl<T> Serialize for Vec<T>
+/// Blanket implementation of Serialize for Result<T, E> where T and E implement Serialize
+impl<T, E> Serialize for Result<T, E>
 where
     T: Serialize,
+    E: Serialize,
 {
     fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
         let total_size = self.buffer_size_required();
         let (chunk, rest) = write_buf.split_at_mut(total_size);
 
-        // Write length as usize (8 bytes on 64-bit platforms)
-        let len_bytes = self.len().to_le_bytes();
-        chunk[0..SIZE_LENGTH].copy_from_slice(&len_bytes);
-
-        // Encode each element sequentially after the length
-        let mut offset = SIZE_LENGTH;
-        for item in self.iter() {
-            let (_, _remaining) = item.encode(&mut chunk[offset..]);
-            let item_size = item.buffer_size_required();
-            offset += item_size;
+        // Write Ok/Err marker, followed by the value
+        match self {
+            Ok(ref value) => {
+                chunk[0] = 1;
+                value.encode(&mut chunk[1..]);
+            }
+            Err(ref err) => {
+                chunk[0] = 0;
+                err.encode(&mut chunk[1..]);
+            }
         }
 
-        (Store::new(Self::decode, chunk), rest)
+        (Store::new_into(Self::decode_into, chunk), rest)
     }
 
     fn decode(read_buf: &[u8]) -> (String, &[u8]) {
-        // Read the length from the first SIZE_LENGTH bytes
-        let len_bytes: [u8; SIZE_LENGTH] = read_buf[0..SIZE_LENGTH].try_into().unwrap();
-        let len = usize::from_le_bytes(len_bytes);
-
-        let mut offset = SIZE_LENGTH;
-        let mut elements = Vec::with_capacity(len);
-
-        // Decode each element
-        for _ in 0..len {
-            let (elem_string, remaining) = T::decode(&read_buf[offset..]);
-            elements.push(elem_string);
-            // Calculate how many bytes were consumed
-            offset = read_buf.len() - remaining.len();
+        Self::try_decode(read_buf).unwrap()
+    }
+
+    fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+        let (marker, read_buf) = read_buf.split_first().ok_or(Error::DecodeCorrupt)?;
+        if *marker == 0 {
+            let (inner_string, remaining) = E::try_decode(read_buf)?;
+            Ok((format!("Err({})", inner_string), remaining))
+        } else {
+            let (inner_string, remaining) = T::try_decode(read_buf)?;
+            Ok((format!("Ok({})", inner_string), remaining))
+        }
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        1 + match self {
+            Ok(ref value) => value.buffer_size_required(),
+            Err(ref err) => err.buffer_size_required(),
         }
+    }
 
-        // Format as a comma-separated list in brackets
-        let formatted = if elements.is_empty() {
-            "[]".to_string()
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        let Some((marker, read_buf)) = read_buf.split_first() else {
+            return write_corrupt(out);
+        };
+        let remaining = if *marker == 0 {
+            out.write_str("Err(")?;
+            E::decode_into(read_buf, out)?
         } else {
-            format!("[{}]", elements.join(", "))
+            out.write_str("Ok(")?;
+            T::decode_into(read_buf, out)?
         };
+        out.write_char(')')?;
+        Ok(remaining)
+    }
+}
 
-        (formatted, &read_buf[offset..])
+/// Blanket implementation of Serialize for Vec<T> where T implements Serialize
+impl<T> Serialize for Vec<T>
+where
+    T: Serialize,
+{
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        encode_seq(self, write_buf)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        Self::try_decode(read_buf).unwrap()
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        seq_size_required(self)
+    }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        let Ok((len_chunk, mut remaining)) = split_checked(read_buf, SIZE_LENGTH) else {
+            return write_corrupt(out);
+        };
+        let len = usize::from_le_bytes(len_chunk.try_into().unwrap());
+
+        out.write_char('[')?;
+        for i in 0..len {
+            if i > 0 {
+                out.write_str(", ")?;
+            }
+            // stops at a corrupted length rather than writing every missing
+            // element, unless elements are encoded into zero bytes
+            if remaining.is_empty() && T::try_decode(remaining).is_err() {
+                out.write_str(CORRUPT)?;
+                break;
+            }
+            remaining = T::decode_into(remaining, out)?;
+        }
+        out.write_char(']')?;
+        Ok(remaining)
+    }
+
+    fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+        let (len_chunk, mut remaining) = split_checked(read_buf, SIZE_LENGTH)?;
+        let len = usize::from_le_bytes(len_chunk.try_into().unwrap());
+
+        let mut formatted = String::from("[");
+        for i in 0..len {
+            if i > 0 {
+                formatted.push_str(", ");
+            }
+            let (elem_string, rest) = T::try_decode(remaining)?;
+            formatted.push_str(&elem_string);
+            remaining = rest;
+        }
+        formatted.push(']');
+
+        Ok((formatted, remaining))
+    }
+}
+
+/// Encodes `items` in the format of [`Vec<T>`], which decodes them
+pub(crate) fn encode_seq<'buf, T: Serialize>(
+    items: &[T],
+    write_buf: &'buf mut [u8],
+) -> (Store<'buf>, &'buf mut [u8]) {
+    let total_size = seq_size_required(items);
+    let (chunk, rest) = write_buf.split_at_mut(total_size);
+
+    // Write length as usize (8 bytes on 64-bit platforms)
+    let len_bytes = items.len().to_le_bytes();
+    chunk[0..SIZE_LENGTH].copy_from_slice(&len_bytes);
+
+    // Encode each element sequentially after the length
+    let mut offset = SIZE_LENGTH;
+    for item in items {
+        let (_, _remaining) = item.encode(&mut chunk[offset..]);
+        let item_size = item.buffer_size_required();
+        offset += item_size;
+    }
+
+    (Store::new_into(Vec::<T>::decode_into, chunk), rest)
+}
+
+/// Size of `items` encoded in the format of [`Vec<T>`]
+pub(crate) fn seq_size_required<T: Serialize>(items: &[T]) -> usize {
+    // Size for length prefix + sum of all element sizes
+    SIZE_LENGTH + items.iter().map(|item| item.buffer_size_required()).sum::<usize>()
+}
+
+/// Fixed-capacity vectors are encoded the same way as `Vec<T>`, so only the
+/// used length is copied rather than the full capacity
+impl<T: Serialize, const N: usize> Serialize for heapless::Vec<T, N> {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        encode_seq(self, write_buf)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        Vec::<T>::decode(read_buf)
     }
 
     fn buffer_size_required(&self) -> usize {
-        // Size for length prefix + sum of all element sizes
-        SIZE_LENGTH + self.iter().map(|item| item.buffer_size_required()).sum::<usize>()
+        seq_size_required(self)
+    }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        Vec::<T>::decode_into(read_buf, out)
+    }
+
+    fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+        Vec::<T>::try_decode(read_buf)
     }
 }
 
@@ -521,6 +1091,14 @@ where
     fn buffer_size_required(&self) -> usize {
         (*self).buffer_size_required()
     }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        T::decode_into(read_buf, out)
+    }
+
+    fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+        T::try_decode(read_buf)
+    }
 }
 
 /// Blanket implementation of Serialize for &mut T where T implements Serialize
@@ -541,10 +1119,69 @@ where
     fn buffer_size_required(&self) -> usize {
         (**self).buffer_size_required()
     }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        T::decode_into(read_buf, out)
+    }
+
+    fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+        T::try_decode(read_buf)
+    }
+}
+
+/// Encodes `value` into `write_buf`, returning [`Error::BufferTooSmall`] instead
+/// of panicking when `write_buf` is too small to fit the encoded value.
+///
+/// ```
+/// use quicklog::{serialize::try_encode, Error};
+///
+/// let mut buf = [0; 4];
+/// assert!(try_encode(&1_u32, &mut buf).is_ok());
+/// assert!(matches!(
+///     try_encode(&1_u64, &mut buf),
+///     Err(Error::BufferTooSmall { required: 8, available: 4 })
+/// ));
+/// ```
+pub fn try_encode<'buf, T: Serialize + ?Sized>(
+    value: &T,
+    write_buf: &'buf mut [u8],
+) -> Result<(Store<'buf>, &'buf mut [u8]), Error> {
+    let required = value.buffer_size_required();
+    if required > write_buf.len() {
+        return Err(Error::BufferTooSmall {
+            required,
+            available: write_buf.len(),
+        });
+    }
+
+    Ok(value.encode(write_buf))
+}
+
+/// Behavior of the logging macros when a `^` argument cannot be encoded, e.g.
+/// because it requires more than [`MAX_SERIALIZE_BUFFER_CAPACITY`] bytes.
+///
+/// Records dropped this way are counted, see [`encode_error_count`].
+///
+/// ```
+/// # use quicklog::{init, serialize::EncodeErrorPolicy};
+/// // fail loudly in tests instead of silently losing records
+/// init!(encode_error_policy = EncodeErrorPolicy::Panic);
+/// ```
+///
+/// [`MAX_SERIALIZE_BUFFER_CAPACITY`]: crate::constants::MAX_SERIALIZE_BUFFER_CAPACITY
+/// [`encode_error_count`]: crate::encode_error_count
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EncodeErrorPolicy {
+    /// Drops the record, without evaluating the arguments after the one that
+    /// failed to encode
+    #[default]
+    Drop,
+    /// Panics with the [`Error`] returned while encoding
+    Panic,
 }
 
 /// Eager evaluation into a String for debug structs
-pub fn encode_debug<T: std::fmt::Debug>(val: T, write_buf: &mut [u8]) -> (Store, &mut [u8]) {
+pub fn encode_debug<T: std::fmt::Debug>(val: T, write_buf: &mut [u8]) -> (Store<'_>, &mut [u8]) {
     let val_string = format!("{:?}", val);
     let str_len = val_string.len();
 
@@ -553,7 +1190,7 @@ pub fn encode_debug<T: std::fmt::Debug>(val: T, write_buf: &mut [u8]) -> (Store,
     len_chunk.copy_from_slice(&str_len.to_le_bytes());
     str_chunk.copy_from_slice(val_string.as_bytes());
 
-    (Store::new(<&str as Serialize>::decode, chunk), rest)
+    (Store::new_into(<&str as Serialize>::decode_into, chunk), rest)
 }
 
 #[cfg(test)]
@@ -0,0 +1,166 @@
+use std::{
+    fmt::{self, Write},
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+};
+
+use super::{Serialize, Store};
+
+/// Tag preceding the address of an [`IpAddr`] or [`SocketAddr`]
+const TAG_V4: u8 = 4;
+const TAG_V6: u8 = 6;
+
+const PORT_SIZE: usize = std::mem::size_of::<u16>();
+
+/// Encodes the 4 octets of the address
+impl Serialize for Ipv4Addr {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        let (chunk, rest) = write_buf.split_at_mut(self.buffer_size_required());
+        chunk.copy_from_slice(&self.octets());
+
+        (Store::new_into(Self::decode_into, chunk), rest)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        let (ip, rest) = read_ipv4(read_buf);
+        (ip.to_string(), rest)
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        4
+    }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        let (ip, rest) = read_ipv4(read_buf);
+        write!(out, "{}", ip)?;
+        Ok(rest)
+    }
+}
+
+/// Encodes the 16 octets of the address
+impl Serialize for Ipv6Addr {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        let (chunk, rest) = write_buf.split_at_mut(self.buffer_size_required());
+        chunk.copy_from_slice(&self.octets());
+
+        (Store::new_into(Self::decode_into, chunk), rest)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        let (ip, rest) = read_ipv6(read_buf);
+        (ip.to_string(), rest)
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        16
+    }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        let (ip, rest) = read_ipv6(read_buf);
+        write!(out, "{}", ip)?;
+        Ok(rest)
+    }
+}
+
+/// Encodes a tag for the address family, followed by the octets of the address
+impl Serialize for IpAddr {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        let (chunk, rest) = write_buf.split_at_mut(self.buffer_size_required());
+        let (tag, addr) = chunk.split_at_mut(1);
+        match self {
+            IpAddr::V4(ip) => {
+                tag[0] = TAG_V4;
+                ip.encode(addr);
+            }
+            IpAddr::V6(ip) => {
+                tag[0] = TAG_V6;
+                ip.encode(addr);
+            }
+        }
+
+        (Store::new_into(Self::decode_into, chunk), rest)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        let (ip, rest) = read_ip(read_buf);
+        (ip.to_string(), rest)
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        1 + match self {
+            IpAddr::V4(ip) => ip.buffer_size_required(),
+            IpAddr::V6(ip) => ip.buffer_size_required(),
+        }
+    }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        let (ip, rest) = read_ip(read_buf);
+        write!(out, "{}", ip)?;
+        Ok(rest)
+    }
+}
+
+/// Encodes the address like [`IpAddr`], followed by the port. The flow info
+/// and scope id of IPv6 socket addresses are not logged.
+impl Serialize for SocketAddr {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        let (chunk, rest) = write_buf.split_at_mut(self.buffer_size_required());
+        let (ip, port) = chunk.split_at_mut(chunk.len() - PORT_SIZE);
+        self.ip().encode(ip);
+        port.copy_from_slice(&self.port().to_le_bytes());
+
+        (Store::new_into(Self::decode_into, chunk), rest)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        let (addr, rest) = read_socket_addr(read_buf);
+        (addr.to_string(), rest)
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        self.ip().buffer_size_required() + PORT_SIZE
+    }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        let (addr, rest) = read_socket_addr(read_buf);
+        write!(out, "{}", addr)?;
+        Ok(rest)
+    }
+}
+
+fn read_ipv4(read_buf: &[u8]) -> (Ipv4Addr, &[u8]) {
+    let (chunk, rest) = read_buf.split_at(4);
+    let octets: [u8; 4] = chunk.try_into().unwrap();
+    (Ipv4Addr::from(octets), rest)
+}
+
+fn read_ipv6(read_buf: &[u8]) -> (Ipv6Addr, &[u8]) {
+    let (chunk, rest) = read_buf.split_at(16);
+    let octets: [u8; 16] = chunk.try_into().unwrap();
+    (Ipv6Addr::from(octets), rest)
+}
+
+fn read_ip(read_buf: &[u8]) -> (IpAddr, &[u8]) {
+    let (tag, read_buf) = read_buf.split_at(1);
+    match tag[0] {
+        TAG_V4 => {
+            let (ip, rest) = read_ipv4(read_buf);
+            (ip.into(), rest)
+        }
+        _ => {
+            let (ip, rest) = read_ipv6(read_buf);
+            (ip.into(), rest)
+        }
+    }
+}
+
+fn read_socket_addr(read_buf: &[u8]) -> (SocketAddr, &[u8]) {
+    let (ip, read_buf) = read_ip(read_buf);
+    let (port, rest) = read_buf.split_at(PORT_SIZE);
+    let port = u16::from_le_bytes(port.try_into().unwrap());
+
+    let addr = match ip {
+        IpAddr::V4(ip) => SocketAddr::V4(SocketAddrV4::new(ip, port)),
+        IpAddr::V6(ip) => SocketAddr::V6(SocketAddrV6::new(ip, port, 0, 0)),
+    };
+    (addr, rest)
+}
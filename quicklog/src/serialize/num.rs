@@ -0,0 +1,119 @@
+//! Non-zero and atomic integers, encoded like the integer they hold
+
+use std::{
+    fmt::{self, Write},
+    num::{
+        NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+        NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+    },
+    sync::atomic::{
+        AtomicI16, AtomicI32, AtomicI64, AtomicI8, AtomicIsize, AtomicU16, AtomicU32, AtomicU64,
+        AtomicU8, AtomicUsize, Ordering,
+    },
+};
+
+use super::{FixedSizeSerialize, Serialize, Store};
+use crate::Error;
+
+/// Implements `Serialize` and `FixedSizeSerialize` for `$wrapper`, holding a
+/// `$primitive` of `$n` bytes, read with `$get` and written back with `$new`
+macro_rules! gen_serialize_wrapper {
+    ($wrapper:ty, $primitive:ty, $n:expr, $get:expr, $new:expr) => {
+        impl Serialize for $wrapper {
+            fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+                $get(self).encode(write_buf)
+            }
+
+            fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+                <$primitive>::decode(read_buf)
+            }
+
+            fn buffer_size_required(&self) -> usize {
+                std::mem::size_of::<$primitive>()
+            }
+
+            fn decode_into<'a>(
+                read_buf: &'a [u8],
+                out: &mut dyn Write,
+            ) -> Result<&'a [u8], fmt::Error> {
+                <$primitive>::decode_into(read_buf, out)
+            }
+
+            fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+                <$primitive>::try_decode(read_buf)
+            }
+        }
+
+        impl FixedSizeSerialize<$n> for $wrapper {
+            fn to_le_bytes(&self) -> [u8; $n] {
+                $get(self).to_le_bytes()
+            }
+
+            fn from_le_bytes(bytes: [u8; $n]) -> Self {
+                $new(<$primitive>::from_le_bytes(bytes))
+            }
+        }
+    };
+}
+
+/// Non-zero integers, whose `from_le_bytes` panics on zero
+macro_rules! gen_serialize_non_zero {
+    ($($wrapper:ident($primitive:ty, $n:expr)),* $(,)?) => {
+        $(
+            gen_serialize_wrapper!(
+                $wrapper,
+                $primitive,
+                $n,
+                |x: &$wrapper| x.get(),
+                |x: $primitive| $wrapper::new(x).expect(concat!(
+                    "Invalid ",
+                    stringify!($wrapper),
+                    ": 0"
+                ))
+            );
+        )*
+    };
+}
+
+/// Atomic integers, loaded with `Ordering::Relaxed` when logged
+macro_rules! gen_serialize_atomic {
+    ($($wrapper:ident($primitive:ty, $n:expr)),* $(,)?) => {
+        $(
+            gen_serialize_wrapper!(
+                $wrapper,
+                $primitive,
+                $n,
+                |x: &$wrapper| x.load(Ordering::Relaxed),
+                $wrapper::new
+            );
+        )*
+    };
+}
+
+gen_serialize_non_zero! {
+    NonZeroU8(u8, 1),
+    NonZeroI8(i8, 1),
+    NonZeroU16(u16, 2),
+    NonZeroI16(i16, 2),
+    NonZeroU32(u32, 4),
+    NonZeroI32(i32, 4),
+    NonZeroU64(u64, 8),
+    NonZeroI64(i64, 8),
+    NonZeroU128(u128, 16),
+    NonZeroI128(i128, 16),
+    NonZeroUsize(usize, 8), // Assuming 64-bit target
+    NonZeroIsize(isize, 8), // Assuming 64-bit target
+}
+
+gen_serialize_atomic! {
+    AtomicU8(u8, 1),
+    AtomicI8(i8, 1),
+    AtomicU16(u16, 2),
+    AtomicI16(i16, 2),
+    AtomicU32(u32, 4),
+    AtomicI32(i32, 4),
+    AtomicU64(u64, 8),
+    AtomicI64(i64, 8),
+    AtomicUsize(usize, 8), // Assuming 64-bit target
+    AtomicIsize(isize, 8), // Assuming 64-bit target
+}
@@ -0,0 +1,108 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Write},
+    rc::Rc,
+    sync::Arc,
+};
+
+use super::{Serialize, Store};
+use crate::Error;
+
+/// Generates a `Serialize` implementation for a smart pointer, delegating to
+/// the value it points to
+macro_rules! gen_serialize_smart_ptr {
+    ($ptr:ident) => {
+        impl<T> Serialize for $ptr<T>
+        where
+            T: Serialize + ?Sized,
+        {
+            fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+                (**self).encode(write_buf)
+            }
+
+            fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+                T::decode(read_buf)
+            }
+
+            fn buffer_size_required(&self) -> usize {
+                (**self).buffer_size_required()
+            }
+
+            fn decode_into<'a>(
+                read_buf: &'a [u8],
+                out: &mut dyn Write,
+            ) -> Result<&'a [u8], fmt::Error> {
+                T::decode_into(read_buf, out)
+            }
+
+            fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+                T::try_decode(read_buf)
+            }
+        }
+    };
+}
+
+gen_serialize_smart_ptr!(Box);
+gen_serialize_smart_ptr!(Arc);
+gen_serialize_smart_ptr!(Rc);
+
+impl<T> Serialize for Cow<'_, T>
+where
+    T: Serialize + ToOwned + ?Sized,
+{
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        self.as_ref().encode(write_buf)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        T::decode(read_buf)
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        self.as_ref().buffer_size_required()
+    }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        T::decode_into(read_buf, out)
+    }
+
+    fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+        T::try_decode(read_buf)
+    }
+}
+
+/// Generates a `Serialize` implementation for a pointer to `str`, encoded the
+/// same way as `&str`
+macro_rules! gen_serialize_str_ptr {
+    ($ptr:ty) => {
+        impl Serialize for $ptr {
+            fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+                (&**self).encode(write_buf)
+            }
+
+            fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+                <&str>::decode(read_buf)
+            }
+
+            fn buffer_size_required(&self) -> usize {
+                (&**self).buffer_size_required()
+            }
+
+            fn decode_into<'a>(
+                read_buf: &'a [u8],
+                out: &mut dyn Write,
+            ) -> Result<&'a [u8], fmt::Error> {
+                <&str>::decode_into(read_buf, out)
+            }
+
+            fn try_decode(read_buf: &[u8]) -> Result<(String, &[u8]), Error> {
+                <&str>::try_decode(read_buf)
+            }
+        }
+    };
+}
+
+gen_serialize_str_ptr!(Box<str>);
+gen_serialize_str_ptr!(Arc<str>);
+gen_serialize_str_ptr!(Rc<str>);
+gen_serialize_str_ptr!(Cow<'_, str>);
@@ -0,0 +1,69 @@
+use std::fmt::{self, Display, Write};
+
+use super::{Serialize, Store, SIZE_LENGTH};
+
+/// String which lives for the whole program, encoded as its pointer and
+/// length instead of a copy of its bytes.
+///
+/// String literals passed as `^` arguments are wrapped automatically, so this
+/// is only needed for `&'static str` values which are not literals, e.g.
+/// constants or names looked up from a static table.
+///
+/// ```
+/// # use quicklog::{info, serialize::StaticStr};
+/// const VENUE: &str = "XNAS";
+///
+/// // 16 bytes are queued on 64-bit targets, whatever the length of the string
+/// info!(venue = ^StaticStr(VENUE), "order sent");
+/// // literals are detected by the macros
+/// info!("order {}", ^"rejected");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct StaticStr(pub &'static str);
+
+impl Display for StaticStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl Serialize for StaticStr {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        let (chunk, rest) = write_buf.split_at_mut(self.buffer_size_required());
+        let (ptr_chunk, len_chunk) = chunk.split_at_mut(SIZE_LENGTH);
+
+        ptr_chunk.copy_from_slice(&(self.0.as_ptr() as usize).to_le_bytes());
+        len_chunk.copy_from_slice(&self.0.len().to_le_bytes());
+
+        (Store::new_into(Self::decode_into, chunk), rest)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        let (s, rest) = decode_static(read_buf);
+        (s.to_string(), rest)
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        2 * SIZE_LENGTH
+    }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        let (s, rest) = decode_static(read_buf);
+        out.write_str(s)?;
+        Ok(rest)
+    }
+}
+
+/// Reads back the string whose pointer and length were written by `encode`
+fn decode_static(read_buf: &[u8]) -> (&'static str, &[u8]) {
+    let (chunk, rest) = read_buf.split_at(2 * SIZE_LENGTH);
+    let (ptr_chunk, len_chunk) = chunk.split_at(SIZE_LENGTH);
+    let ptr = usize::from_le_bytes(ptr_chunk.try_into().unwrap()) as *const u8;
+    let len = usize::from_le_bytes(len_chunk.try_into().unwrap());
+
+    // SAFETY: written by `encode` from a `&'static str` within this
+    // process, which is still valid UTF-8 and alive
+    let s = unsafe { std::str::from_utf8_unchecked(std::slice::from_raw_parts(ptr, len)) };
+
+    (s, rest)
+}
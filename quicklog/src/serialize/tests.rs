@@ -1,5 +1,8 @@
 use crate::gen_serialize_enum;
-use crate::serialize::encode_debug;
+use crate::serialize::{
+    encode_debug, try_encode, AnySerialize, Bytes, ErasedSerialize, FieldMap, StaticStr,
+};
+use crate::Error;
 
 use super::Serialize;
 
@@ -24,6 +27,81 @@ fn serialize_primitives() {
     assert_primitive_encode_decode!(u32, 999);
     assert_primitive_encode_decode!(u64, 9999);
     assert_primitive_encode_decode!(usize, 99999);
+    assert_primitive_encode_decode!(u8, 255);
+    assert_primitive_encode_decode!(i8, -128);
+    assert_primitive_encode_decode!(u16, 65535);
+    assert_primitive_encode_decode!(i16, -1234);
+    assert_primitive_encode_decode!(i128, -123456789);
+    assert_primitive_encode_decode!(bool, true);
+    assert_primitive_encode_decode!(bool, false);
+    assert_primitive_encode_decode!(char, 'a');
+    assert_primitive_encode_decode!(char, '€');
+}
+
+#[test]
+fn serialize_time() {
+    use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+    let mut buf = [0; 128];
+    let latency = Duration::from_micros(1_234);
+    let (store, _) = latency.encode(&mut buf);
+    assert_eq!(format!("{}", store), "1.234ms");
+
+    let (store, _) = Duration::new(2, 500).encode(&mut buf);
+    assert_eq!(format!("{}", store), "2.0000005s");
+
+    let time = UNIX_EPOCH + Duration::new(1_698_048_000, 123_456_789);
+    let (store, _) = time.encode(&mut buf);
+    assert_eq!(format!("{}", store), "2023-10-23T08:00:00.123456789Z");
+
+    let (store, _) = (UNIX_EPOCH - Duration::from_secs(1)).encode(&mut buf);
+    assert_eq!(format!("{}", store), "1969-12-31T23:59:59Z");
+
+    // instants are logged as the corresponding wall-clock time
+    let before = SystemTime::now() - Duration::from_secs(1);
+    let (store, _) = Instant::now().encode(&mut buf);
+    let logged = chrono::DateTime::parse_from_rfc3339(&format!("{}", store)).unwrap();
+    let after = SystemTime::now() + Duration::from_secs(1);
+    assert!(chrono::DateTime::<chrono::Utc>::from(before) <= logged);
+    assert!(logged <= chrono::DateTime::<chrono::Utc>::from(after));
+}
+
+#[test]
+fn serialize_net() {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+    let mut buf = [0; 128];
+    let v4 = Ipv4Addr::new(10, 0, 0, 1);
+    let v6 = "2001:db8::1".parse::<Ipv6Addr>().unwrap();
+
+    let (store, _) = v4.encode(&mut buf);
+    assert_eq!(v4.buffer_size_required(), 4);
+    assert_eq!(format!("{}", store), "10.0.0.1");
+    let (store, _) = v6.encode(&mut buf);
+    assert_eq!(v6.buffer_size_required(), 16);
+    assert_eq!(format!("{}", store), "2001:db8::1");
+
+    for ip in [IpAddr::from(v4), IpAddr::from(v6)] {
+        let (store, _) = ip.encode(&mut buf);
+        assert_eq!(format!("{}", store), ip.to_string());
+    }
+    assert_eq!(IpAddr::from(v4).buffer_size_required(), 5);
+
+    let peers: [SocketAddr; 2] = [
+        "10.0.0.1:443".parse().unwrap(),
+        "[2001:db8::1]:9000".parse().unwrap(),
+    ];
+    for peer in peers {
+        let (store, _) = peer.encode(&mut buf);
+        assert_eq!(format!("{}", store), peer.to_string());
+    }
+    assert_eq!(peers[0].buffer_size_required(), 7);
+    assert_eq!(peers[1].buffer_size_required(), 19);
+
+    // multiple addresses decode back to back
+    let (a, chunk) = peers[0].encode(&mut buf);
+    let (b, _) = IpAddr::from(v6).encode(chunk);
+    assert_eq!(format!("{} {}", a, b), "10.0.0.1:443 2001:db8::1");
 }
 
 #[test]
@@ -52,6 +130,273 @@ fn serialize_str() {
     assert_eq!(s, format!("{}", store).as_str())
 }
 
+#[test]
+fn serialize_string() {
+    let mut buf = [0; 128];
+    let s = String::from("hello world");
+    let (store, rest) = s.encode(&mut buf);
+
+    assert_eq!(s, format!("{}", store));
+    assert_eq!(rest.len(), 128 - s.buffer_size_required());
+    assert_eq!(s.buffer_size_required(), s.as_str().buffer_size_required());
+}
+
+#[test]
+fn serialize_heapless_string() {
+    let mut buf = [0; 128];
+    let mut s: heapless::String<16> = heapless::String::new();
+    s.push_str("BTC-USD").unwrap();
+    let (store, _) = s.encode(&mut buf);
+
+    assert_eq!("BTC-USD", format!("{}", store));
+    // Only the used length is encoded, not the full capacity
+    assert_eq!(s.buffer_size_required(), super::SIZE_LENGTH + 7);
+}
+
+#[test]
+fn serialize_heapless_vec() {
+    use crate::serialize::DecodeTyped;
+
+    let mut buf = [0; 128];
+    let mut levels: heapless::Vec<u32, 16> = heapless::Vec::new();
+    levels.extend_from_slice(&[100, 200]).unwrap();
+    let (store, _) = levels.encode(&mut buf);
+
+    // Same format as `Vec<T>`, only the used length is encoded
+    assert_eq!(format!("{}", store), "[100, 200]");
+    assert_eq!(
+        levels.buffer_size_required(),
+        vec![100_u32, 200].buffer_size_required()
+    );
+    let (decoded, _) = heapless::Vec::<u32, 16>::decode_typed(&buf);
+    assert_eq!(decoded, [100, 200]);
+}
+
+#[cfg(feature = "smallvec")]
+#[test]
+fn serialize_smallvec() {
+    let mut buf = [0; 128];
+    let inline: smallvec::SmallVec<[u16; 4]> = smallvec::smallvec![1, 2];
+    let spilled: smallvec::SmallVec<[u16; 1]> = smallvec::smallvec![3, 4];
+    let (a, chunk) = inline.encode(&mut buf);
+    let (b, _) = spilled.encode(chunk);
+
+    assert_eq!(format!("{} {}", a, b), "[1, 2] [3, 4]");
+    assert_eq!(inline.buffer_size_required(), super::SIZE_LENGTH + 4);
+}
+
+#[cfg(feature = "arrayvec")]
+#[test]
+fn serialize_arrayvec() {
+    let mut buf = [0; 128];
+    let mut fills: arrayvec::ArrayVec<&str, 8> = arrayvec::ArrayVec::new();
+    fills.push("a");
+    fills.push("b");
+    let (store, _) = fills.encode(&mut buf);
+
+    assert_eq!(format!("{}", store), "[a, b]");
+    assert_eq!(
+        fills.buffer_size_required(),
+        vec!["a", "b"].buffer_size_required()
+    );
+}
+
+#[test]
+fn serialize_non_zero_and_atomic() {
+    use std::{
+        num::{NonZeroI32, NonZeroU64},
+        sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    };
+
+    use crate::serialize::FixedSizeSerialize;
+
+    let mut buf = [0; 64];
+    let order_id = NonZeroU64::new(42).unwrap();
+    let fills = AtomicU64::new(7);
+    fills.fetch_add(1, Ordering::Relaxed);
+    let (a, chunk) = order_id.encode(&mut buf);
+    let (b, chunk) = NonZeroI32::new(-3).unwrap().encode(chunk);
+    let (c, _) = fills.encode(chunk);
+
+    assert_eq!(format!("{} {} {}", a, b, c), "42 -3 8");
+    assert_eq!(order_id.buffer_size_required(), 8);
+    assert_eq!(AtomicUsize::new(0).buffer_size_required(), 8);
+
+    let bytes = FixedSizeSerialize::<8>::to_le_bytes(&fills);
+    assert_eq!(bytes, 8_u64.to_le_bytes());
+    let loaded = <AtomicU64 as FixedSizeSerialize<8>>::from_le_bytes(bytes);
+    assert_eq!(loaded.load(Ordering::Relaxed), 8);
+    assert_eq!(
+        <NonZeroU64 as FixedSizeSerialize<8>>::from_le_bytes(42_u64.to_le_bytes()),
+        order_id
+    );
+}
+
+#[test]
+fn serialize_static_str() {
+    let mut buf = [0; 128];
+    let s = StaticStr("a string which is longer than its pointer and length");
+    let (store, rest) = s.encode(&mut buf);
+
+    assert_eq!(s.0, format!("{}", store));
+    // Only the pointer and length are encoded, whatever the length
+    assert_eq!(s.buffer_size_required(), 2 * super::SIZE_LENGTH);
+    let remaining = rest.len();
+    assert_eq!(remaining, 128 - s.buffer_size_required());
+    assert_eq!(StaticStr::decode(&buf).1.len(), remaining);
+}
+
+#[test]
+fn try_encode_buffer_too_small() {
+    let mut buf = [0; 32];
+    let s = "hello world";
+
+    let (store, rest) = try_encode(&s, &mut buf).unwrap();
+    assert_eq!(s, store.as_string());
+    assert_eq!(rest.len(), 32 - s.buffer_size_required());
+
+    let s = "hello world, but a lot longer";
+    match try_encode(&s, &mut buf) {
+        Err(Error::BufferTooSmall {
+            required,
+            available,
+        }) => {
+            assert_eq!(required, s.buffer_size_required());
+            assert_eq!(available, 32);
+        }
+        _ => panic!("expected Error::BufferTooSmall"),
+    }
+    assert!(matches!(
+        s.try_encode(&mut buf),
+        Err(Error::BufferTooSmall { available: 32, .. })
+    ));
+    assert!(s.try_encode(&mut [0; 64]).is_ok());
+}
+
+#[test]
+fn try_decode_corrupt() {
+    use crate::serialize::{Store, CORRUPT, SIZE_LENGTH};
+
+    let mut buf = [0; 64];
+    let (store, _) = 42_u64.encode(&mut buf);
+    assert_eq!(store.to_string(), "42");
+    assert_eq!(u64::try_decode(&buf[..8]).unwrap().0, "42");
+    assert!(matches!(
+        u64::try_decode(&buf[..3]),
+        Err(Error::DecodeCorrupt)
+    ));
+    // truncated bytes are written as a placeholder when flushing
+    let truncated = Store::new_into(u64::decode_into, &buf[..3]);
+    assert_eq!(truncated.to_string(), CORRUPT);
+
+    // length prefix past the end of the buffer, or invalid UTF-8
+    let s = "hello";
+    s.encode(&mut buf);
+    assert_eq!(<&str>::try_decode(&buf).unwrap().0, "hello");
+    buf[..SIZE_LENGTH].copy_from_slice(&usize::MAX.to_le_bytes());
+    assert!(matches!(
+        <&str>::try_decode(&buf),
+        Err(Error::DecodeCorrupt)
+    ));
+    buf[..SIZE_LENGTH].copy_from_slice(&5_usize.to_le_bytes());
+    buf[SIZE_LENGTH] = 0xff;
+    assert!(matches!(
+        String::try_decode(&buf),
+        Err(Error::DecodeCorrupt)
+    ));
+
+    // the length of a sequence cannot make decoding allocate or loop for
+    // each missing element
+    let v = vec![1_u32, 2, 3];
+    let (store, _) = v.encode(&mut buf);
+    assert_eq!(store.to_string(), "[1, 2, 3]");
+    buf[..SIZE_LENGTH].copy_from_slice(&usize::MAX.to_le_bytes());
+    assert!(matches!(
+        Vec::<u32>::try_decode(&buf[..20]),
+        Err(Error::DecodeCorrupt)
+    ));
+    let corrupted = Store::new_into(Vec::<u32>::decode_into, &buf[..20]);
+    assert_eq!(corrupted.to_string(), "[1, 2, 3, <corrupt>]");
+
+    assert!(matches!(
+        Option::<u8>::try_decode(&[]),
+        Err(Error::DecodeCorrupt)
+    ));
+    assert!(matches!(
+        Option::<u8>::try_decode(&[1]),
+        Err(Error::DecodeCorrupt)
+    ));
+    assert_eq!(Option::<u8>::try_decode(&[0]).unwrap().0, "None");
+}
+
+#[test]
+fn try_decode_typed_corrupt() {
+    use crate::serialize::{DecodeNumeric, DecodeTyped, FloatFormat, CORRUPT, SIZE_LENGTH};
+
+    let mut buf = [0; 64];
+    42_u64.encode(&mut buf);
+    assert_eq!(u64::try_decode_typed(&buf[..8]).unwrap().0, 42);
+    assert!(matches!(
+        u64::try_decode_typed(&buf[..3]),
+        Err(Error::DecodeCorrupt)
+    ));
+    assert_eq!(u64::try_decode_f64(&buf[..8]).unwrap().0, 42.0);
+    assert!(matches!(
+        u64::try_decode_f64(&buf[..3]),
+        Err(Error::DecodeCorrupt)
+    ));
+    assert_eq!(
+        f64::decode_formatted(&buf[..3], FloatFormat::Shortest),
+        (CORRUPT.to_string(), &[][..])
+    );
+    assert_eq!(
+        u64::decode_formatted(&buf[..3], FloatFormat::Shortest),
+        (CORRUPT.to_string(), &[][..])
+    );
+
+    "hello".encode(&mut buf);
+    assert_eq!(<&str>::try_decode_typed(&buf).unwrap().0, "hello");
+    buf[..SIZE_LENGTH].copy_from_slice(&usize::MAX.to_le_bytes());
+    assert!(matches!(
+        String::try_decode_typed(&buf),
+        Err(Error::DecodeCorrupt)
+    ));
+
+    // a corrupted length neither allocates for it nor reads past the end
+    vec![1_u32, 2, 3].encode(&mut buf);
+    assert_eq!(Vec::<u32>::try_decode_typed(&buf).unwrap().0, [1, 2, 3]);
+    buf[..SIZE_LENGTH].copy_from_slice(&usize::MAX.to_le_bytes());
+    assert!(matches!(
+        Vec::<u32>::try_decode_typed(&buf[..20]),
+        Err(Error::DecodeCorrupt)
+    ));
+
+    assert!(matches!(
+        Option::<u8>::try_decode_typed(&[]),
+        Err(Error::DecodeCorrupt)
+    ));
+    assert!(matches!(
+        Result::<u8, bool>::try_decode_typed(&[0]),
+        Err(Error::DecodeCorrupt)
+    ));
+    assert_eq!(Option::<u8>::try_decode_typed(&[1, 5]).unwrap().0, Some(5));
+}
+
+#[test]
+fn try_get_chunk_too_large() {
+    use crate::{constants::MAX_SERIALIZE_BUFFER_CAPACITY, serialize::buffer::ByteBuffer};
+
+    let mut buffer = ByteBuffer::new();
+    assert_eq!(buffer.try_get_chunk_as_mut(16).unwrap().len(), 16);
+    assert!(matches!(
+        buffer.try_get_chunk_as_mut(MAX_SERIALIZE_BUFFER_CAPACITY + 1),
+        Err(Error::BufferTooSmall {
+            available: MAX_SERIALIZE_BUFFER_CAPACITY,
+            ..
+        })
+    ));
+}
+
 #[test]
 fn serialize_debug() {
     #[derive(Debug)]
@@ -176,6 +521,7 @@ fn serialize_enum_roundtrip() {
 }
 
 #[test]
+#[allow(non_local_definitions)]
 fn serialize_level_enum() {
     // Test with existing Level enum from crate::level
     use crate::level::Level;
@@ -211,6 +557,65 @@ fn serialize_level_enum() {
     assert_eq!(Level::Error as u8, 4);
 }
 
+#[test]
+fn serialize_result() {
+    let mut buf = [0; 128];
+
+    let ok: Result<u64, &str> = Ok(42);
+    assert_eq!(ok.buffer_size_required(), 9); // 1 marker + 8 bytes for u64
+    let (store, _) = ok.encode(&mut buf);
+    assert_eq!(store.as_string(), "Ok(42)");
+
+    let err: Result<u64, &str> = Err("order rejected");
+    let (store, _) = err.encode(&mut buf);
+    assert_eq!(store.as_string(), "Err(order rejected)");
+
+    // nested with other blanket impls, decoded back to back
+    let nested: Result<Option<i32>, String> = Ok(None);
+    let failed: Result<Vec<u8>, i16> = Err(-1);
+    let (a, chunk) = nested.encode(&mut buf);
+    let (b, _) = failed.encode(chunk);
+    assert_eq!(format!("{} {}", a, b), "Ok(None) Err(-1)");
+}
+
+#[test]
+fn serialize_smart_pointers() {
+    use std::{borrow::Cow, rc::Rc, sync::Arc};
+
+    let mut buf = [0; 128];
+
+    let boxed = Box::new(42_u64);
+    assert_eq!(boxed.buffer_size_required(), 8);
+    let (store, _) = boxed.encode(&mut buf);
+    assert_eq!(store.as_string(), "42");
+
+    let shared = Arc::new(vec![1_i32, 2, 3]);
+    let (store, _) = shared.encode(&mut buf);
+    assert_eq!(store.as_string(), "[1, 2, 3]");
+
+    let local = Rc::new(Some(String::from("BTCUSD")));
+    let (store, _) = local.encode(&mut buf);
+    assert_eq!(store.as_string(), "Some(BTCUSD)");
+
+    let borrowed: Cow<'_, u32> = Cow::Borrowed(&7);
+    let owned: Cow<'_, u32> = Cow::Owned(8);
+    let (a, chunk) = borrowed.encode(&mut buf);
+    let (b, _) = owned.encode(chunk);
+    assert_eq!(format!("{} {}", a, b), "7 8");
+
+    // pointers to str are encoded like &str
+    let symbol: Arc<str> = Arc::from("ETHUSD");
+    let venue: Cow<'_, str> = Cow::Borrowed("venue");
+    let name: Box<str> = Box::from("name");
+    let tag: Rc<str> = Rc::from("tag");
+    assert_eq!(symbol.buffer_size_required(), "ETHUSD".buffer_size_required());
+    let (a, chunk) = symbol.encode(&mut buf);
+    let (b, chunk) = venue.encode(chunk);
+    let (c, chunk) = name.encode(chunk);
+    let (d, _) = tag.encode(chunk);
+    assert_eq!(format!("{} {} {} {}", a, b, c, d), "ETHUSD venue name tag");
+}
+
 #[test]
 fn serialize_option_some() {
     let mut buf = [0; 128];
@@ -508,6 +913,7 @@ fn serialize_mutable_reference() {
     }
 
     let mut counter = 100u32;
+    #[allow(clippy::needless_borrows_for_generic_args)]
     let size = requires_serialize(&mut counter);
     assert_eq!(size, std::mem::size_of::<u32>());
 
@@ -516,3 +922,243 @@ fn serialize_mutable_reference() {
     let size_vec = requires_serialize(&mut vec_data);
     assert_eq!(size_vec, 8 + 3 * 4); // length + 3 i32s
 }
+
+#[test]
+fn serialize_erased() {
+    let values: Vec<Box<dyn AnySerialize>> = vec![
+        Box::new(-1_i32),
+        Box::new("hello"),
+        Box::new(vec![1_u64, 2, 3]),
+        Box::new(None::<u32>),
+    ];
+
+    let mut buf = [0u8; 128];
+    let mut rest = &mut buf[..];
+    let mut decoded = Vec::new();
+    for value in values.iter() {
+        let erased = ErasedSerialize::from_dyn(value.as_ref());
+        assert_eq!(erased.bytes().len(), value.any_buffer_size_required());
+        decoded.push(erased.to_string());
+
+        // encoding through the trait object decodes to the same output
+        let (store, remaining) = value.encode_any(rest);
+        assert_eq!(store.to_string(), erased.to_string());
+        rest = remaining;
+    }
+    assert_eq!(decoded, ["-1", "hello", "[1, 2, 3]", "None"]);
+
+    assert_eq!(ErasedSerialize::new(&42_u64).to_string(), "42");
+}
+
+#[test]
+fn serialize_field_map() {
+    let mut fields = FieldMap::with_capacity(4);
+    fields
+        .insert("strategy", &"momentum")
+        .insert("window", &20_u32)
+        .insert("levels", &vec![1.5_f64, 2.5])
+        .insert("window", &30_u32);
+    assert_eq!(fields.len(), 3);
+    assert_eq!(fields.get("window").unwrap().to_string(), "30");
+
+    let mut buf = [0u8; 256];
+    let (store, rest) = fields.encode(&mut buf);
+    assert_eq!(256 - rest.len(), fields.buffer_size_required());
+    assert_eq!(
+        format!("{}", store),
+        "{strategy=momentum window=30 levels=[1.5, 2.5]}"
+    );
+
+    // nested within other containers
+    let nested = vec![FieldMap::new().with("a", &1_i32), FieldMap::new()];
+    let (store, _) = nested.encode(&mut buf);
+    assert_eq!(format!("{}", store), "[{a=1}, {}]");
+}
+
+#[test]
+fn serialize_decode_into() {
+    fn assert_decode_into<T: Serialize>(value: T) {
+        let mut buf = [0u8; 128];
+        let (_, rest) = value.encode(&mut buf);
+        let len = 128 - rest.len();
+
+        let (decoded, _) = T::decode(&buf[..len]);
+        let mut line = String::new();
+        let remaining = T::decode_into(&buf[..len], &mut line).unwrap();
+        assert_eq!(line, decoded);
+        assert!(remaining.is_empty());
+    }
+
+    assert_decode_into(-7_i32);
+    assert_decode_into(1.5_f64);
+    assert_decode_into(true);
+    assert_decode_into('€');
+    assert_decode_into("hello");
+    assert_decode_into(String::from("world"));
+    assert_decode_into(StaticStr("static"));
+    assert_decode_into(Some(3_u8));
+    assert_decode_into(None::<u8>);
+    assert_decode_into(Ok::<u32, &str>(1));
+    assert_decode_into(Err::<u32, &str>("rejected"));
+    assert_decode_into(vec![Some(1_u16), None]);
+    assert_decode_into(Vec::<u64>::new());
+    assert_decode_into(Box::new(5_u64));
+    assert_decode_into(std::net::Ipv4Addr::new(10, 0, 0, 1));
+    assert_decode_into(std::net::IpAddr::from([0xfe80, 0, 0, 0, 0, 0, 0, 1]));
+    assert_decode_into(std::net::SocketAddr::from(([127, 0, 0, 1], 8080)));
+    assert_decode_into(std::time::Duration::from_micros(1_234));
+
+    // writes after what the buffer already holds, e.g. the start of a line
+    let mut buf = [0u8; 32];
+    let (store, _) = vec![1_u32, 2].encode(&mut buf);
+    let mut line = String::from("sizes=");
+    store.decoder().decode_into(&buf[..16], &mut line).unwrap();
+    assert_eq!(line, "sizes=[1, 2]");
+}
+
+#[test]
+fn serialize_bytes() {
+    let mut buf = [0u8; 64];
+    let decode = |buf: &mut [u8], bytes: Bytes| {
+        let (store, rest) = bytes.encode(buf);
+        assert_eq!(rest.len(), 64 - bytes.buffer_size_required());
+        store.to_string()
+    };
+
+    assert_eq!(decode(&mut buf, Bytes::hex(&[0x00, 0x0f, 0xff])), "000fff");
+    assert_eq!(decode(&mut buf, Bytes::hex(b"")), "");
+    for (bytes, expected) in [
+        (&b""[..], ""),
+        (b"f", "Zg=="),
+        (b"fo", "Zm8="),
+        (b"foo", "Zm9v"),
+        (b"foobar", "Zm9vYmFy"),
+    ] {
+        assert_eq!(decode(&mut buf, Bytes::base64(bytes)), expected);
+    }
+
+    // the bytes themselves are not copied
+    let large = [0u8; 1024];
+    let len_only = Bytes::len_only(&large);
+    assert_eq!(len_only.buffer_size_required(), 1 + super::SIZE_LENGTH);
+    assert_eq!(decode(&mut buf, len_only), "<1024 bytes>");
+
+    let slice: &[u8] = &[1, 2, 3];
+    let (store, _) = slice.encode(&mut buf);
+    assert_eq!(store.to_string(), "010203");
+}
+
+#[test]
+fn serialize_fixed_point() {
+    use crate::serialize::{FixedPoint, FixedSizeSerialize};
+
+    let mut buf = [0; 64];
+    let (a, chunk) = FixedPoint::<4>(1_012_500).encode(&mut buf);
+    let (b, chunk) = FixedPoint::<2>(-5).encode(chunk);
+    let (c, _) = FixedPoint::<0>(i64::MIN).encode(chunk);
+    assert_eq!(
+        format!("{} {} {}", a, b, c),
+        "101.2500 -0.05 -9223372036854775808"
+    );
+    assert_eq!(FixedPoint::<4>(1).buffer_size_required(), 8);
+    assert_eq!(FixedPoint::<18>(i64::MAX).to_string(), "9.223372036854775807");
+
+    let bytes = FixedPoint::<4>(-42).to_le_bytes();
+    assert_eq!(FixedPoint::<4>::from_le_bytes(bytes), FixedPoint(-42));
+}
+
+#[cfg(feature = "rust_decimal")]
+#[test]
+fn serialize_decimal() {
+    use crate::serialize::FixedSizeSerialize;
+    use rust_decimal::Decimal;
+
+    let mut buf = [0; 64];
+    let price = Decimal::new(10_125, 2);
+    let (a, chunk) = price.encode(&mut buf);
+    let (b, _) = Decimal::new(-1, 8).encode(chunk);
+    assert_eq!(price.buffer_size_required(), 16);
+    assert_eq!(format!("{} {}", a, b), "101.25 -0.00000001");
+
+    let bytes = FixedSizeSerialize::<16>::to_le_bytes(&price);
+    assert_eq!(<Decimal as FixedSizeSerialize<16>>::from_le_bytes(bytes), price);
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn serialize_chrono_datetime() {
+    use chrono::{DateTime, TimeZone, Utc};
+
+    let mut buf = [0; 32];
+    let fill = Utc.timestamp_opt(1_698_048_000, 123_456_789).unwrap();
+    let (store, _) = fill.encode(&mut buf);
+    assert_eq!(fill.buffer_size_required(), 8);
+    assert_eq!(format!("{}", store), "2023-10-23T08:00:00.123456789Z");
+
+    // saturates instead of wrapping around
+    let (store, _) = DateTime::<Utc>::MAX_UTC.encode(&mut buf);
+    assert_eq!(format!("{}", store), "2262-04-11T23:47:16.854775807Z");
+}
+
+#[cfg(feature = "time")]
+#[test]
+fn serialize_time_offset_datetime() {
+    use ::time::{OffsetDateTime, UtcOffset};
+
+    let mut buf = [0; 32];
+    let fill = OffsetDateTime::from_unix_timestamp_nanos(1_698_048_000_123_000_000).unwrap();
+    let (a, chunk) = fill.encode(&mut buf);
+    // rendered in UTC
+    let local = fill.to_offset(UtcOffset::from_hms(8, 0, 0).unwrap());
+    let (b, _) = local.encode(chunk);
+    assert_eq!(
+        format!("{} {}", a, b),
+        "2023-10-23T08:00:00.123Z 2023-10-23T08:00:00.123Z"
+    );
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn serialize_uuid() {
+    use crate::serialize::FixedSizeSerialize;
+
+    let order_id = uuid::Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+    let mut buf = [0; 64];
+    let (a, chunk) = order_id.encode(&mut buf);
+    let (b, _) = uuid::Uuid::nil().encode(chunk);
+    assert_eq!(order_id.buffer_size_required(), 16);
+    assert_eq!(
+        format!("{} {}", a, b),
+        "67e55044-10b1-426f-9247-bb680e5fe0c8 00000000-0000-0000-0000-000000000000"
+    );
+
+    let mut line = String::new();
+    a.write_to(&mut line).unwrap();
+    assert_eq!(line, order_id.to_string());
+
+    let bytes = FixedSizeSerialize::<16>::to_le_bytes(&order_id);
+    assert_eq!(
+        <uuid::Uuid as FixedSizeSerialize<16>>::from_le_bytes(bytes),
+        order_id
+    );
+}
+
+#[cfg(feature = "compression")]
+#[test]
+fn serialize_compressed() {
+    use crate::serialize::compress::encode_compressed;
+
+    let snapshot: Vec<u64> = (0..512).map(|level| level % 4).collect();
+    let mut buf = vec![0u8; 8192];
+    let mut chunk_size = 0;
+    let store = encode_compressed(&snapshot, |size| {
+        chunk_size = size;
+        &mut buf[..size]
+    });
+    assert!(chunk_size < snapshot.buffer_size_required() / 4);
+    assert_eq!(format!("{}", store), format!("{:?}", snapshot));
+
+    let mut buf = [0u8; 64];
+    let store = encode_compressed(&"tiny", |size| &mut buf[..size]);
+    assert_eq!(format!("{}", store), "tiny");
+}
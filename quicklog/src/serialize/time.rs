@@ -0,0 +1,158 @@
+use std::{
+    fmt::{self, Write},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use chrono::{SecondsFormat, TimeZone, Utc};
+use once_cell::sync::Lazy;
+
+use super::{Serialize, Store};
+
+/// `Instant` and `SystemTime` captured at the same point in time, so that
+/// instants can be logged as wall-clock time
+static ANCHOR: Lazy<(Instant, SystemTime)> = Lazy::new(|| (Instant::now(), SystemTime::now()));
+
+/// Nanoseconds since the unix epoch, saturating at the bounds of `i64`
+fn epoch_nanos(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(after) => i64::try_from(after.as_nanos()).unwrap_or(i64::MAX),
+        Err(before) => i64::try_from(before.duration().as_nanos())
+            .map(|nanos| -nanos)
+            .unwrap_or(i64::MIN),
+    }
+}
+
+/// Encodes as `secs` followed by `nanos`, decoded in its `Debug` representation,
+/// e.g. `1.234ms`
+impl Serialize for Duration {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        let (chunk, rest) = write_buf.split_at_mut(self.buffer_size_required());
+        let (secs, nanos) = chunk.split_at_mut(std::mem::size_of::<u64>());
+        secs.copy_from_slice(&self.as_secs().to_le_bytes());
+        nanos.copy_from_slice(&self.subsec_nanos().to_le_bytes());
+
+        (Store::new_into(Self::decode_into, chunk), rest)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        let (duration, rest) = read_duration(read_buf);
+        (format!("{:?}", duration), rest)
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        std::mem::size_of::<u64>() + std::mem::size_of::<u32>()
+    }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        let (duration, rest) = read_duration(read_buf);
+        write!(out, "{:?}", duration)?;
+        Ok(rest)
+    }
+}
+
+/// Encodes nanoseconds since the unix epoch, decoded like [`SystemTime`]
+fn encode_epoch_nanos(nanos: i64, write_buf: &mut [u8]) -> (Store<'_>, &mut [u8]) {
+    let (chunk, rest) = write_buf.split_at_mut(std::mem::size_of::<i64>());
+    chunk.copy_from_slice(&nanos.to_le_bytes());
+
+    (Store::new(SystemTime::decode, chunk), rest)
+}
+
+fn read_duration(read_buf: &[u8]) -> (Duration, &[u8]) {
+    let (secs, read_buf) = read_buf.split_at(std::mem::size_of::<u64>());
+    let (nanos, rest) = read_buf.split_at(std::mem::size_of::<u32>());
+    let duration = Duration::new(
+        u64::from_le_bytes(secs.try_into().unwrap()),
+        u32::from_le_bytes(nanos.try_into().unwrap()),
+    );
+    (duration, rest)
+}
+
+/// Encodes as nanoseconds since the unix epoch, decoded as RFC 3339, e.g.
+/// `2023-10-23T08:00:00.123456789Z`
+impl Serialize for SystemTime {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        encode_epoch_nanos(epoch_nanos(*self), write_buf)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        let (chunk, rest) = read_buf.split_at(std::mem::size_of::<i64>());
+        let nanos = i64::from_le_bytes(chunk.try_into().unwrap());
+
+        (
+            Utc.timestamp_nanos(nanos)
+                .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            rest,
+        )
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        std::mem::size_of::<i64>()
+    }
+}
+
+/// Encodes the wall-clock time corresponding to the instant, decoded like
+/// [`SystemTime`]
+impl Serialize for Instant {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        let (anchor_instant, anchor_time) = *ANCHOR;
+        let time = if *self >= anchor_instant {
+            anchor_time.checked_add(*self - anchor_instant)
+        } else {
+            anchor_time.checked_sub(anchor_instant - *self)
+        };
+        time.unwrap_or(anchor_time).encode(write_buf)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        SystemTime::decode(read_buf)
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        std::mem::size_of::<i64>()
+    }
+}
+
+/// Encodes as nanoseconds since the unix epoch, decoded like [`SystemTime`].
+/// Saturates outside of the years 1677 to 2262.
+#[cfg(feature = "chrono")]
+impl Serialize for chrono::DateTime<Utc> {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        let nanos = self
+            .timestamp_nanos_opt()
+            .unwrap_or(if self.timestamp() < 0 {
+                i64::MIN
+            } else {
+                i64::MAX
+            });
+        encode_epoch_nanos(nanos, write_buf)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        SystemTime::decode(read_buf)
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        std::mem::size_of::<i64>()
+    }
+}
+
+/// Encodes as nanoseconds since the unix epoch, decoded like [`SystemTime`],
+/// i.e. in UTC whatever the offset. Saturates outside of the years 1677 to
+/// 2262.
+#[cfg(feature = "time")]
+impl Serialize for ::time::OffsetDateTime {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        let nanos = self.unix_timestamp_nanos();
+        let nanos = i64::try_from(nanos).unwrap_or(if nanos < 0 { i64::MIN } else { i64::MAX });
+        encode_epoch_nanos(nanos, write_buf)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        SystemTime::decode(read_buf)
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        std::mem::size_of::<i64>()
+    }
+}
@@ -0,0 +1,223 @@
+//! Decoding of encoded arguments back into typed values, for tools which
+//! analyse binary logs rather than their display text.
+
+use super::{decode_str, split_checked, Serialize, SIZE_LENGTH};
+use crate::Error;
+
+/// Types which can be decoded back into a typed value, rather than only into
+/// display text through [`Serialize::decode`].
+///
+/// Implemented for primitives, strings, `Option`, `Result`, `Vec` and
+/// references, and generated for structs deriving `SerializeSelective` with
+/// `#[serialize(decode_typed)]`.
+///
+/// ```
+/// use quicklog::serialize::{DecodeTyped, Serialize};
+///
+/// let mut buf = [0; 32];
+/// let value = (Some(5_u64), "abc");
+/// let (_, rest) = value.0.encode(&mut buf);
+/// value.1.encode(rest);
+///
+/// let (a, rest) = <Option<u64> as DecodeTyped>::decode_typed(&buf);
+/// let (b, _) = <&str as DecodeTyped>::decode_typed(rest);
+/// assert_eq!((a, b.as_str()), (Some(5), "abc"));
+/// ```
+pub trait DecodeTyped: Serialize {
+    /// Value reconstructed from the encoded bytes, e.g. `String` for `&str`
+    type Decoded;
+
+    /// Decodes the value from a byte buffer.
+    ///
+    /// Returns the value as well as the remainder of `read_buf` that was not
+    /// read.
+    fn decode_typed(read_buf: &[u8]) -> (Self::Decoded, &[u8]);
+
+    /// Fallible version of `decode_typed`, returning [`Error::DecodeCorrupt`]
+    /// for bytes which were not encoded by `encode` instead of panicking.
+    /// Defaults to calling `decode_typed`.
+    ///
+    /// Built-in types and generated implementations check their input, so
+    /// that tools reading binary logs can skip a corrupted record.
+    fn try_decode_typed(read_buf: &[u8]) -> Result<(Self::Decoded, &[u8]), Error> {
+        Ok(Self::decode_typed(read_buf))
+    }
+}
+
+macro_rules! gen_decode_typed {
+    ($($primitive:ty),*) => {
+        $(
+            impl DecodeTyped for $primitive {
+                type Decoded = $primitive;
+
+                fn decode_typed(read_buf: &[u8]) -> (Self::Decoded, &[u8]) {
+                    Self::try_decode_typed(read_buf).unwrap()
+                }
+
+                fn try_decode_typed(read_buf: &[u8]) -> Result<(Self::Decoded, &[u8]), Error> {
+                    let (chunk, rest) = split_checked(read_buf, std::mem::size_of::<$primitive>())?;
+
+                    Ok((<$primitive>::from_le_bytes(chunk.try_into().unwrap()), rest))
+                }
+            }
+        )*
+    };
+}
+
+gen_decode_typed!(i8, i16, i32, i64, i128, isize, f32, f64, u8, u16, u32, u64, u128, usize);
+
+impl DecodeTyped for bool {
+    type Decoded = bool;
+
+    fn decode_typed(read_buf: &[u8]) -> (Self::Decoded, &[u8]) {
+        Self::try_decode_typed(read_buf).unwrap()
+    }
+
+    fn try_decode_typed(read_buf: &[u8]) -> Result<(Self::Decoded, &[u8]), Error> {
+        let (chunk, rest) = split_checked(read_buf, 1)?;
+
+        Ok((chunk[0] != 0, rest))
+    }
+}
+
+impl DecodeTyped for char {
+    type Decoded = char;
+
+    fn decode_typed(read_buf: &[u8]) -> (Self::Decoded, &[u8]) {
+        Self::try_decode_typed(read_buf).unwrap()
+    }
+
+    fn try_decode_typed(read_buf: &[u8]) -> Result<(Self::Decoded, &[u8]), Error> {
+        let (x, rest) = u32::try_decode_typed(read_buf)?;
+
+        Ok((
+            char::from_u32(x).unwrap_or(char::REPLACEMENT_CHARACTER),
+            rest,
+        ))
+    }
+}
+
+impl DecodeTyped for &str {
+    type Decoded = String;
+
+    fn decode_typed(read_buf: &[u8]) -> (Self::Decoded, &[u8]) {
+        Self::try_decode_typed(read_buf).unwrap()
+    }
+
+    fn try_decode_typed(read_buf: &[u8]) -> Result<(Self::Decoded, &[u8]), Error> {
+        let (s, rest) = decode_str(read_buf)?;
+        Ok((s.to_string(), rest))
+    }
+}
+
+impl DecodeTyped for String {
+    type Decoded = String;
+
+    fn decode_typed(read_buf: &[u8]) -> (Self::Decoded, &[u8]) {
+        <&str as DecodeTyped>::decode_typed(read_buf)
+    }
+
+    fn try_decode_typed(read_buf: &[u8]) -> Result<(Self::Decoded, &[u8]), Error> {
+        <&str as DecodeTyped>::try_decode_typed(read_buf)
+    }
+}
+
+impl<const N: usize> DecodeTyped for heapless::String<N> {
+    type Decoded = String;
+
+    fn decode_typed(read_buf: &[u8]) -> (Self::Decoded, &[u8]) {
+        <&str as DecodeTyped>::decode_typed(read_buf)
+    }
+
+    fn try_decode_typed(read_buf: &[u8]) -> Result<(Self::Decoded, &[u8]), Error> {
+        <&str as DecodeTyped>::try_decode_typed(read_buf)
+    }
+}
+
+impl<T: DecodeTyped> DecodeTyped for Option<T> {
+    type Decoded = Option<T::Decoded>;
+
+    fn decode_typed(read_buf: &[u8]) -> (Self::Decoded, &[u8]) {
+        Self::try_decode_typed(read_buf).unwrap()
+    }
+
+    fn try_decode_typed(read_buf: &[u8]) -> Result<(Self::Decoded, &[u8]), Error> {
+        match read_buf.split_first() {
+            Some((0, rest)) => Ok((None, rest)),
+            Some((_, rest)) => {
+                let (value, rest) = T::try_decode_typed(rest)?;
+                Ok((Some(value), rest))
+            }
+            None => Err(Error::DecodeCorrupt),
+        }
+    }
+}
+
+impl<T: DecodeTyped, E: DecodeTyped> DecodeTyped for Result<T, E> {
+    type Decoded = Result<T::Decoded, E::Decoded>;
+
+    fn decode_typed(read_buf: &[u8]) -> (Self::Decoded, &[u8]) {
+        Self::try_decode_typed(read_buf).unwrap()
+    }
+
+    fn try_decode_typed(read_buf: &[u8]) -> Result<(Self::Decoded, &[u8]), Error> {
+        match read_buf.split_first() {
+            Some((0, rest)) => {
+                let (err, rest) = E::try_decode_typed(rest)?;
+                Ok((Err(err), rest))
+            }
+            Some((_, rest)) => {
+                let (value, rest) = T::try_decode_typed(rest)?;
+                Ok((Ok(value), rest))
+            }
+            None => Err(Error::DecodeCorrupt),
+        }
+    }
+}
+
+impl<T: DecodeTyped> DecodeTyped for Vec<T> {
+    type Decoded = Vec<T::Decoded>;
+
+    fn decode_typed(read_buf: &[u8]) -> (Self::Decoded, &[u8]) {
+        Self::try_decode_typed(read_buf).unwrap()
+    }
+
+    fn try_decode_typed(read_buf: &[u8]) -> Result<(Self::Decoded, &[u8]), Error> {
+        let (len_chunk, mut rest) = split_checked(read_buf, SIZE_LENGTH)?;
+        let len = usize::from_le_bytes(len_chunk.try_into().unwrap());
+
+        // a corrupted length must not reserve more than the buffer can hold
+        let mut elements = Vec::with_capacity(len.min(rest.len()));
+        for _ in 0..len {
+            let (element, remaining) = T::try_decode_typed(rest)?;
+            elements.push(element);
+            rest = remaining;
+        }
+
+        Ok((elements, rest))
+    }
+}
+
+impl<T: DecodeTyped, const N: usize> DecodeTyped for heapless::Vec<T, N> {
+    type Decoded = Vec<T::Decoded>;
+
+    fn decode_typed(read_buf: &[u8]) -> (Self::Decoded, &[u8]) {
+        Vec::<T>::decode_typed(read_buf)
+    }
+
+    fn try_decode_typed(read_buf: &[u8]) -> Result<(Self::Decoded, &[u8]), Error> {
+        Vec::<T>::try_decode_typed(read_buf)
+    }
+}
+
+impl<T: DecodeTyped + ?Sized> DecodeTyped for &T {
+    type Decoded = T::Decoded;
+
+    fn decode_typed(read_buf: &[u8]) -> (Self::Decoded, &[u8]) {
+        T::decode_typed(read_buf)
+    }
+
+    fn try_decode_typed(read_buf: &[u8]) -> Result<(Self::Decoded, &[u8]), Error> {
+        T::try_decode_typed(read_buf)
+    }
+}
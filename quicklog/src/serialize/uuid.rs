@@ -0,0 +1,48 @@
+use std::fmt::{self, Write};
+
+use ::uuid::Uuid;
+
+use super::{FixedSizeSerialize, Serialize, Store};
+
+const UUID_SIZE: usize = 16;
+
+/// Encodes the 16 bytes of the UUID, decoded to its canonical hyphenated form,
+/// e.g. `67e55044-10b1-426f-9247-bb680e5fe0c8`
+impl Serialize for Uuid {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        let (chunk, rest) = write_buf.split_at_mut(UUID_SIZE);
+        chunk.copy_from_slice(self.as_bytes());
+
+        (Store::new_into(Self::decode_into, chunk), rest)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        let (uuid, rest) = read_uuid(read_buf);
+        (uuid.hyphenated().to_string(), rest)
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        UUID_SIZE
+    }
+
+    fn decode_into<'a>(read_buf: &'a [u8], out: &mut dyn Write) -> Result<&'a [u8], fmt::Error> {
+        let (uuid, rest) = read_uuid(read_buf);
+        write!(out, "{}", uuid.hyphenated())?;
+        Ok(rest)
+    }
+}
+
+impl FixedSizeSerialize<UUID_SIZE> for Uuid {
+    fn to_le_bytes(&self) -> [u8; UUID_SIZE] {
+        self.as_u128().to_le_bytes()
+    }
+
+    fn from_le_bytes(bytes: [u8; UUID_SIZE]) -> Self {
+        Uuid::from_u128(u128::from_le_bytes(bytes))
+    }
+}
+
+fn read_uuid(read_buf: &[u8]) -> (Uuid, &[u8]) {
+    let (bytes, rest) = read_buf.split_at(UUID_SIZE);
+    (Uuid::from_bytes(bytes.try_into().unwrap()), rest)
+}
@@ -0,0 +1,320 @@
+//! Spans, which prepend their name and fields to every record logged while
+//! they are entered, e.g. to give records the context of the request or order
+//! they belong to.
+//!
+//! ```
+//! # use quicklog::{enter, info, init, span};
+//! init!();
+//! # let (order_id, qty) = (42_u64, 10_u32);
+//! let _order = enter!("order", id = order_id, venue = "XNAS");
+//! let fill = span!("fill", qty = qty);
+//! let _fill = enter!(fill);
+//! info!("filled");
+//! // [...]order{id=42 venue=XNAS}:fill{qty=10}: filled
+//! ```
+//!
+//! Entered spans are kept on a stack per thread, and are exited when the
+//! guard returned by [`Span::enter`] is dropped. Field values implement
+//! [`Serialize`]: they are encoded once when the span is created, and only
+//! decoded when the records logged within it are flushed.
+//!
+//! Spans do not need the `trace` feature. With it, entering a span also
+//! enters a fastrace local span of the same name, so that the records logged
+//! within it show up as events of that span in traces.
+
+use std::{
+    cell::RefCell,
+    fmt::{self, Display, Formatter},
+    marker::PhantomData,
+    sync::Arc,
+};
+
+use crate::{
+    logfmt,
+    serialize::{FieldMap, Serialize},
+};
+
+thread_local! {
+    /// Innermost span entered on this thread, linked to the enclosing ones
+    static CURRENT: RefCell<Option<Arc<Frame>>> = const { RefCell::new(None) };
+}
+
+/// Name and fields prepended to the records logged while the span is
+/// entered, created with [`span!`](crate::span!)
+#[derive(Clone)]
+pub struct Span {
+    name: &'static str,
+    fields: FieldMap,
+}
+
+impl Span {
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            fields: FieldMap::new(),
+        }
+    }
+
+    /// Adds the field `key`, encoding its value right away
+    pub fn with<T: Serialize>(mut self, key: &'static str, value: &T) -> Self {
+        self.fields.insert(key, value);
+        self
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Fields of the span, in the order they were added
+    pub fn fields(&self) -> &FieldMap {
+        &self.fields
+    }
+
+    /// Enters the span on this thread until the returned guard is dropped
+    pub fn enter(self) -> SpanGuard {
+        #[cfg(feature = "trace")]
+        let local_span = fastrace::local::LocalSpan::enter_with_local_parent(self.name);
+        let previous = CURRENT.with(|current| {
+            let mut current = current.borrow_mut();
+            let parent = current.take();
+            *current = Some(Arc::new(Frame {
+                span: self,
+                parent: parent.clone(),
+                #[cfg(feature = "trace")]
+                fastrace_span_id: fastrace::prelude::SpanContext::current_local_parent()
+                    .map(|context| context.span_id.0),
+            }));
+            parent
+        });
+        SpanGuard {
+            previous,
+            #[cfg(feature = "trace")]
+            _local_span: local_span,
+            _not_send: PhantomData,
+        }
+    }
+}
+
+/// Spans entered on this thread, from the outermost to the innermost
+pub fn current() -> Vec<Span> {
+    let mut spans = Vec::new();
+    let mut frame = CURRENT.with(|current| current.borrow().clone());
+    while let Some(inner) = frame {
+        spans.push(inner.span.clone());
+        frame = inner.parent.clone();
+    }
+    spans.reverse();
+    spans
+}
+
+/// Entered span, along with the spans it was entered within
+pub(crate) struct Frame {
+    span: Span,
+    parent: Option<Arc<Frame>>,
+    /// Id of the fastrace local span entered along with the span, if there
+    /// was a parent to enter it within
+    #[cfg(feature = "trace")]
+    fastrace_span_id: Option<u64>,
+}
+
+impl Frame {
+    /// Writes `outer{key=value}:inner{key=value}`, from the outermost span
+    fn write_spans(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(parent) = &self.parent {
+            parent.write_spans(f)?;
+            f.write_str(":")?;
+        }
+        f.write_str(self.span.name)?;
+        if !self.span.fields.is_empty() {
+            f.write_str("{")?;
+            for (i, (key, value)) in self.span.fields.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(" ")?;
+                }
+                write!(f, "{}={}", key, value)?;
+            }
+            f.write_str("}")?;
+        }
+        Ok(())
+    }
+
+    /// Writes the names of the spans as `outer:inner`
+    fn write_names(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(parent) = &self.parent {
+            parent.write_names(f)?;
+            f.write_str(":")?;
+        }
+        f.write_str(self.span.name)
+    }
+
+    /// Writes the fields of the spans as logfmt pairs, from the outermost span
+    fn write_fields(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some(parent) = &self.parent {
+            parent.write_fields(f)?;
+        }
+        for (key, value) in self.span.fields.iter() {
+            logfmt::__write_field(f, key, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Exits the span when dropped, returned by [`Span::enter`]
+#[must_use = "the span is exited as soon as the guard is dropped"]
+pub struct SpanGuard {
+    previous: Option<Arc<Frame>>,
+    /// Fastrace span entered along with the span, exited after it
+    #[cfg(feature = "trace")]
+    _local_span: fastrace::local::LocalSpan,
+    /// Spans are entered per thread, so the guard cannot be sent to another
+    /// one
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for SpanGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+/// Log line of a record logged within spans, preceded by the spans
+pub(crate) struct SpanLine {
+    pub(crate) log_line: Box<dyn Display>,
+    pub(crate) spans: Arc<Frame>,
+}
+
+impl Display for SpanLine {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str(" span=")?;
+            self.spans.write_names(f)?;
+            self.spans.write_fields(f)?;
+        } else {
+            self.spans.write_spans(f)?;
+            f.write_str(": ")?;
+        }
+        // forwards the formatter as-is, keeping the alternate flag for logfmt
+        self.log_line.fmt(f)
+    }
+}
+
+/// Spans to prepend to a record logged now, if any
+#[inline]
+pub(crate) fn current_if_entered() -> Option<Arc<Frame>> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+/// Name of the innermost span entered on this thread, if it is the fastrace
+/// span `span_id` rather than one entered within it through fastrace directly
+#[cfg(feature = "trace")]
+pub(crate) fn name_of_fastrace_span(span_id: u64) -> Option<&'static str> {
+    CURRENT.with(|current| {
+        current
+            .borrow()
+            .as_ref()
+            .filter(|frame| frame.fastrace_span_id == Some(span_id))
+            .map(|frame| frame.span.name)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(log_line: &'static str) -> SpanLine {
+        SpanLine {
+            log_line: Box::new(log_line),
+            spans: current_if_entered().unwrap(),
+        }
+    }
+
+    #[test]
+    fn nested_spans() {
+        let outer = Span::new("order")
+            .with("id", &42_u64)
+            .with("venue", &"XNAS")
+            .enter();
+        {
+            let _inner = Span::new("fill").with("qty", &10_u32).enter();
+            let names = current().iter().map(Span::name).collect::<Vec<_>>();
+            assert_eq!(names, ["order", "fill"]);
+            assert_eq!(
+                line("filled").to_string(),
+                "order{id=42 venue=XNAS}:fill{qty=10}: filled"
+            );
+            assert_eq!(
+                format!("{:#}", line(" msg=filled")),
+                " span=order:fill id=42 venue=XNAS qty=10 msg=filled"
+            );
+        }
+        assert_eq!(line("sent").to_string(), "order{id=42 venue=XNAS}: sent");
+        drop(outer);
+        assert!(current_if_entered().is_none());
+    }
+
+    #[test]
+    fn spans_without_fields() {
+        let _outer = Span::new("session").enter();
+        let _inner = Span::new("heartbeat").enter();
+        assert_eq!(line("sent").to_string(), "session:heartbeat: sent");
+    }
+
+    #[cfg(feature = "trace")]
+    #[test]
+    fn records_are_events_of_the_fastrace_span() {
+        use chrono::Utc;
+        use fastrace::{collector::TestReporter, prelude::SpanContext};
+
+        use crate::{
+            callsite::{Metadata, __Callsite},
+            level::Level,
+            LogRecord, PatternFormatter, QuickLogFormatter,
+        };
+
+        static CALLSITE: __Callsite =
+            __Callsite::new(Metadata::new(Level::Info, "app", "src/main.rs", 1));
+
+        let (reporter, records) = TestReporter::new();
+        fastrace::set_reporter(reporter, fastrace::collector::Config::default());
+        {
+            let root = fastrace::Span::root("root", SpanContext::random());
+            let _root = root.set_local_parent();
+            let _fill = Span::new("fill").enter();
+            let (trace_id, span_id, span_name) = crate::__trace_event("filled {}", Level::Info);
+            let context = SpanContext::current_local_parent().unwrap();
+            assert_eq!(trace_id, Some(context.trace_id.0));
+            assert_eq!(span_id, Some(context.span_id.0));
+            assert_eq!(span_name, Some("fill"));
+
+            // and printed along with the ids
+            let record = LogRecord {
+                callsite: CALLSITE.id(),
+                log_line: Box::new("filled 7"),
+                tag: None,
+                sequence: 0,
+                metric: None,
+                trace_id,
+                span_id,
+                span_name,
+            };
+            let line = QuickLogFormatter::new().custom_format(Utc::now(), record);
+            assert!(line.starts_with(&format!(
+                "[trace_id={:032x} span_id={:016x} span_name=fill] ",
+                context.trace_id.0, context.span_id.0
+            )));
+
+            // fastrace spans entered directly have no name to give
+            let _local = fastrace::local::LocalSpan::enter_with_local_parent("local");
+            let (_, _, span_name) = crate::__trace_event("sent", Level::Info);
+            assert_eq!(span_name, None);
+        }
+        fastrace::flush();
+
+        let records = records.lock();
+        let fill = records.iter().find(|span| span.name == "fill").unwrap();
+        assert_eq!(fill.events.len(), 1);
+        assert_eq!(fill.events[0].name, "filled {}");
+        assert_eq!(fill.events[0].properties, [("level".into(), "INFO".into())]);
+    }
+}
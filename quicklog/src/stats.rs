@@ -0,0 +1,480 @@
+//! Live statistics about the health of the logger, enabled with the `stats`
+//! feature.
+//!
+//! Counters are updated while logging and flushing, and can be read from any
+//! thread through [`snapshot`]. [`serve`] starts a helper thread which serves
+//! the latest [`StatsSnapshot`] over HTTP, so that dashboards such as Grafana
+//! can scrape logging health directly:
+//!
+//! * `/metrics` serves the stats in the Prometheus text exposition format, with
+//!   metric names starting with a configurable prefix, see [`serve_with_prefix`]
+//! * every other path serves the stats as JSON
+//!
+//! ```no_run
+//! quicklog::init!();
+//! // serves stats on http://127.0.0.1:9100/, and metrics such as
+//! // `quicklog_records_logged_total` on http://127.0.0.1:9100/metrics
+//! let _handle = quicklog::stats::serve("127.0.0.1:9100")?;
+//! # Ok::<(), std::io::Error>(())
+//! ```
+
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, ToSocketAddrs},
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    thread::JoinHandle,
+    time::{Duration, Instant},
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{
+    level::{self, Level, LevelFilter},
+    tag::{self, Tag},
+};
+
+/// Number of levels which have their own counter
+const LEVELS: usize = 5;
+/// Flush latencies are bucketed by powers of two nanoseconds
+const LATENCY_BUCKETS: usize = 64;
+
+/// Counters shared between the logger and readers of the stats
+pub(crate) struct Stats {
+    start: Lazy<Instant>,
+    logged: [AtomicU64; LEVELS],
+    dropped: AtomicU64,
+    pre_init_dropped: AtomicU64,
+    flushed: AtomicU64,
+    flush_errors: AtomicU64,
+    bytes_flushed: AtomicU64,
+    queue_len: AtomicUsize,
+    queue_high_water: AtomicUsize,
+    capacity: AtomicUsize,
+    flush_latency: [AtomicU64; LATENCY_BUCKETS],
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const ZERO: AtomicU64 = AtomicU64::new(0);
+
+pub(crate) static STATS: Stats = Stats {
+    start: Lazy::new(Instant::now),
+    logged: [ZERO; LEVELS],
+    dropped: ZERO,
+    pre_init_dropped: ZERO,
+    flushed: ZERO,
+    flush_errors: ZERO,
+    bytes_flushed: ZERO,
+    queue_len: AtomicUsize::new(0),
+    queue_high_water: AtomicUsize::new(0),
+    capacity: AtomicUsize::new(0),
+    flush_latency: [ZERO; LATENCY_BUCKETS],
+};
+
+impl Stats {
+    pub(crate) fn record_init(&self, capacity: usize) {
+        Lazy::force(&self.start);
+        self.capacity.store(capacity, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_logged(&self, level: Level, queue_len: usize) {
+        self.logged[level as usize].fetch_add(1, Ordering::Relaxed);
+        self.queue_len.store(queue_len, Ordering::Relaxed);
+        self.queue_high_water
+            .fetch_max(queue_len, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_dropped(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_pre_init_dropped(&self) {
+        self.pre_init_dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn record_flushed(
+        &self,
+        latency: Duration,
+        bytes: usize,
+        ok: bool,
+        queue_len: usize,
+    ) {
+        if ok {
+            self.flushed.fetch_add(1, Ordering::Relaxed);
+            self.bytes_flushed
+                .fetch_add(bytes as u64, Ordering::Relaxed);
+        } else {
+            self.flush_errors.fetch_add(1, Ordering::Relaxed);
+        }
+        let nanos = latency.as_nanos().min(u64::MAX as u128) as u64;
+        let bucket = (u64::BITS - nanos.leading_zeros()) as usize;
+        self.flush_latency[bucket.min(LATENCY_BUCKETS - 1)].fetch_add(1, Ordering::Relaxed);
+        self.queue_len.store(queue_len, Ordering::Relaxed);
+    }
+}
+
+/// Point in time copy of the logger statistics
+#[derive(Clone, Debug)]
+pub struct StatsSnapshot {
+    /// Time since the logger was initialized
+    pub uptime: Duration,
+    /// Number of records logged, indexed by [`Level`]
+    pub logged: [u64; LEVELS],
+    /// Number of records dropped because the queue was full
+    pub dropped: u64,
+    /// Number of records dropped because they were logged before the logger
+    /// was initialized
+    pub pre_init_dropped: u64,
+    /// Number of records flushed successfully
+    pub flushed: u64,
+    /// Number of records which the flusher failed to flush
+    pub flush_errors: u64,
+    /// Number of bytes of formatted log lines flushed successfully
+    pub bytes_flushed: u64,
+    /// Number of records waiting in the queue to be flushed
+    pub queue_len: usize,
+    /// Highest number of records that were waiting in the queue at once
+    pub queue_high_water: usize,
+    /// Number of records the queue holds
+    pub capacity: usize,
+    /// Global level filter, see [`set_max_level`](crate::level::set_max_level)
+    pub max_level: LevelFilter,
+    /// Tags with a level filter, such as muted tags with [`LevelFilter::Off`],
+    /// see [`Tag::set_filter`]
+    pub tag_filters: Vec<(Tag, LevelFilter)>,
+    flush_latency: [u64; LATENCY_BUCKETS],
+}
+
+/// Takes a snapshot of the logger statistics, can be called from any thread
+pub fn snapshot() -> StatsSnapshot {
+    let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+    StatsSnapshot {
+        uptime: STATS.start.elapsed(),
+        logged: std::array::from_fn(|i| load(&STATS.logged[i])),
+        dropped: load(&STATS.dropped),
+        pre_init_dropped: load(&STATS.pre_init_dropped),
+        flushed: load(&STATS.flushed),
+        flush_errors: load(&STATS.flush_errors),
+        bytes_flushed: load(&STATS.bytes_flushed),
+        queue_len: STATS.queue_len.load(Ordering::Relaxed),
+        queue_high_water: STATS.queue_high_water.load(Ordering::Relaxed),
+        capacity: STATS.capacity.load(Ordering::Relaxed),
+        max_level: level::max_level(),
+        tag_filters: tag::filtered(),
+        flush_latency: std::array::from_fn(|i| load(&STATS.flush_latency[i])),
+    }
+}
+
+impl StatsSnapshot {
+    /// Number of records logged at `level`
+    pub fn logged(&self, level: Level) -> u64 {
+        self.logged[level as usize]
+    }
+
+    /// Upper bound of the flush latency at percentile `p`, between 0.0 and
+    /// 100.0. Latencies are bucketed by powers of two, so this is accurate to
+    /// within a factor of two.
+    pub fn flush_latency_percentile(&self, p: f64) -> Duration {
+        let total: u64 = self.flush_latency.iter().sum();
+        let target = ((p.clamp(0.0, 100.0) / 100.0) * total as f64).ceil() as u64;
+        let mut seen = 0;
+        for (bucket, count) in self.flush_latency.iter().enumerate() {
+            seen += count;
+            if seen >= target.max(1) {
+                // bucket `b` holds latencies below 2^b nanoseconds
+                let upper = if bucket == 0 { 0 } else { 1 << bucket };
+                return Duration::from_nanos(upper);
+            }
+        }
+        Duration::ZERO
+    }
+
+    /// Records per second logged at each level since `previous`, or since the
+    /// logger was initialized
+    pub fn logged_per_sec(&self, previous: Option<&StatsSnapshot>) -> [f64; LEVELS] {
+        let (logged, uptime) =
+            previous.map_or(([0; LEVELS], Duration::ZERO), |p| (p.logged, p.uptime));
+        let secs = self.uptime.saturating_sub(uptime).as_secs_f64();
+        let mut rates = [0.0; LEVELS];
+        if secs > 0.0 {
+            for (rate, (now, before)) in rates.iter_mut().zip(self.logged.iter().zip(logged)) {
+                *rate = now.saturating_sub(before) as f64 / secs;
+            }
+        }
+        rates
+    }
+
+    /// Serializes the snapshot into JSON, with rates computed since `previous`
+    pub fn to_json(&self, previous: Option<&StatsSnapshot>) -> String {
+        const NAMES: [&str; LEVELS] = ["trace", "debug", "info", "warn", "error"];
+        let by_level = |values: &dyn Fn(usize) -> String| {
+            NAMES
+                .iter()
+                .enumerate()
+                .map(|(i, name)| format!("\"{}\":{}", name, values(i)))
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let rates = self.logged_per_sec(previous);
+        let micros = |p| self.flush_latency_percentile(p).as_nanos() as f64 / 1000.0;
+
+        format!(
+            concat!(
+                "{{\"uptime_secs\":{:.3},\"logged\":{{{}}},\"logged_per_sec\":{{{}}},",
+                "\"dropped\":{},\"pre_init_dropped\":{},\"flushed\":{},\"flush_errors\":{},\"bytes_flushed\":{},",
+                "\"queue\":{{\"len\":{},\"high_water\":{},\"capacity\":{}}},",
+                "\"max_level\":\"{}\",\"tag_filters\":{{{}}},",
+                "\"flush_latency_us\":{{\"p50\":{},\"p90\":{},\"p99\":{},\"p999\":{}}}}}"
+            ),
+            self.uptime.as_secs_f64(),
+            by_level(&|i| self.logged[i].to_string()),
+            by_level(&|i| format!("{:.3}", rates[i])),
+            self.dropped,
+            self.pre_init_dropped,
+            self.flushed,
+            self.flush_errors,
+            self.bytes_flushed,
+            self.queue_len,
+            self.queue_high_water,
+            self.capacity,
+            self.max_level,
+            self.tag_filters
+                .iter()
+                .map(|(tag, filter)| format!("\"{}\":\"{}\"", tag, filter))
+                .collect::<Vec<_>>()
+                .join(","),
+            micros(50.0),
+            micros(90.0),
+            micros(99.0),
+            micros(99.9),
+        )
+    }
+
+    /// Serializes the snapshot into the Prometheus text exposition format, with
+    /// every metric name starting with `prefix`
+    pub fn to_prometheus(&self, prefix: &str) -> String {
+        const NAMES: [&str; LEVELS] = ["trace", "debug", "info", "warn", "error"];
+        let mut out = String::new();
+        let header = |out: &mut String, name: &str, kind: &str, help: &str| {
+            out.push_str(&format!("# HELP {}_{} {}\n", prefix, name, help));
+            out.push_str(&format!("# TYPE {}_{} {}\n", prefix, name, kind));
+        };
+
+        header(
+            &mut out,
+            "records_logged_total",
+            "counter",
+            "Number of records logged",
+        );
+        for (level, count) in NAMES.iter().zip(self.logged) {
+            out.push_str(&format!(
+                "{}_records_logged_total{{level=\"{}\"}} {}\n",
+                prefix, level, count
+            ));
+        }
+
+        let single_values = [
+            (
+                "records_dropped_total",
+                "counter",
+                "Number of records dropped because the queue was full",
+                self.dropped,
+            ),
+            (
+                "records_dropped_pre_init_total",
+                "counter",
+                "Number of records dropped because they were logged before initialization",
+                self.pre_init_dropped,
+            ),
+            (
+                "records_flushed_total",
+                "counter",
+                "Number of records flushed successfully",
+                self.flushed,
+            ),
+            (
+                "flush_errors_total",
+                "counter",
+                "Number of records which the flusher failed to flush",
+                self.flush_errors,
+            ),
+            (
+                "bytes_flushed_total",
+                "counter",
+                "Number of bytes of formatted log lines flushed successfully",
+                self.bytes_flushed,
+            ),
+            (
+                "queue_length",
+                "gauge",
+                "Number of records waiting in the queue to be flushed",
+                self.queue_len as u64,
+            ),
+            (
+                "queue_high_water",
+                "gauge",
+                "Highest number of records that were waiting in the queue at once",
+                self.queue_high_water as u64,
+            ),
+            (
+                "queue_capacity",
+                "gauge",
+                "Number of records the queue holds",
+                self.capacity as u64,
+            ),
+        ];
+        for (name, kind, help, value) in single_values {
+            header(&mut out, name, kind, help);
+            out.push_str(&format!("{}_{} {}\n", prefix, name, value));
+        }
+
+        header(
+            &mut out,
+            "level_filter",
+            "gauge",
+            "Level filter applied globally (empty tag) and to each filtered tag",
+        );
+        let filters = std::iter::once(("", self.max_level)).chain(
+            self.tag_filters
+                .iter()
+                .map(|(tag, filter)| (tag.name(), *filter)),
+        );
+        for (tag, filter) in filters {
+            out.push_str(&format!(
+                "{}_level_filter{{tag=\"{}\",filter=\"{}\"}} 1\n",
+                prefix, tag, filter
+            ));
+        }
+
+        header(
+            &mut out,
+            "flush_latency_seconds",
+            "summary",
+            "Upper bound of the latency of flushing a single record",
+        );
+        for (quantile, p) in [
+            ("0.5", 50.0),
+            ("0.9", 90.0),
+            ("0.99", 99.0),
+            ("0.999", 99.9),
+        ] {
+            out.push_str(&format!(
+                "{}_flush_latency_seconds{{quantile=\"{}\"}} {}\n",
+                prefix,
+                quantile,
+                self.flush_latency_percentile(p).as_secs_f64()
+            ));
+        }
+
+        out
+    }
+}
+
+/// Starts a helper thread serving [`snapshot`] over HTTP on `addr`, with
+/// Prometheus metric names starting with `quicklog`. See the
+/// [module documentation](self) for the paths served.
+pub fn serve<A: ToSocketAddrs>(addr: A) -> io::Result<JoinHandle<()>> {
+    serve_with_prefix(addr, "quicklog")
+}
+
+/// Starts a helper thread serving [`snapshot`] over HTTP on `addr`, with
+/// Prometheus metric names starting with `prefix`.
+pub fn serve_with_prefix<A: ToSocketAddrs>(addr: A, prefix: &str) -> io::Result<JoinHandle<()>> {
+    let listener = TcpListener::bind(addr)?;
+    let prefix = prefix.to_string();
+    std::thread::Builder::new()
+        .name("quicklog-stats".to_string())
+        .spawn(move || {
+            let mut previous = None;
+            for stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(&stream);
+                let mut request_line = String::new();
+                if reader.read_line(&mut request_line).is_err() {
+                    continue;
+                }
+                // read until the end of the request headers
+                let mut line = String::new();
+                while reader
+                    .read_line(&mut line)
+                    .is_ok_and(|n| n > 0 && line != "\r\n")
+                {
+                    line.clear();
+                }
+
+                let current = snapshot();
+                let (content_type, body) = match request_line.split(' ').nth(1) {
+                    Some("/metrics") => ("text/plain; version=0.0.4", current.to_prometheus(&prefix)),
+                    _ => {
+                        let body = current.to_json(previous.as_ref());
+                        previous = Some(current);
+                        ("application/json", body)
+                    }
+                };
+                let _ = write!(
+                    &stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    content_type,
+                    body.len(),
+                    body
+                );
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flush_latency_percentiles() {
+        let mut snapshot = snapshot();
+        snapshot.flush_latency = [0; LATENCY_BUCKETS];
+        // 90 flushes of about 1us, 10 flushes of about 1ms
+        snapshot.flush_latency[10] = 90;
+        snapshot.flush_latency[20] = 10;
+
+        assert_eq!(
+            snapshot.flush_latency_percentile(50.0),
+            Duration::from_nanos(1 << 10)
+        );
+        assert_eq!(
+            snapshot.flush_latency_percentile(90.0),
+            Duration::from_nanos(1 << 10)
+        );
+        assert_eq!(
+            snapshot.flush_latency_percentile(99.0),
+            Duration::from_nanos(1 << 20)
+        );
+    }
+
+    #[test]
+    fn prometheus_format() {
+        let mut snapshot = snapshot();
+        snapshot.logged = [0, 0, 3, 1, 0];
+        snapshot.queue_high_water = 7;
+        let metrics = snapshot.to_prometheus("app_log");
+
+        assert!(metrics.contains("# TYPE app_log_records_logged_total counter\n"));
+        assert!(metrics.contains("app_log_records_logged_total{level=\"info\"} 3\n"));
+        assert!(metrics.contains("app_log_records_logged_total{level=\"warn\"} 1\n"));
+        assert!(metrics.contains("app_log_queue_high_water 7\n"));
+        assert!(metrics.contains("app_log_flush_latency_seconds{quantile=\"0.99\"} "));
+    }
+
+    #[test]
+    fn level_filters() {
+        let mut snapshot = snapshot();
+        snapshot.max_level = LevelFilter::Info;
+        snapshot.tag_filters = vec![(Tag::new("stats-market-data"), LevelFilter::Off)];
+
+        let metrics = snapshot.to_prometheus("app_log");
+        assert!(metrics.contains("app_log_level_filter{tag=\"\",filter=\"INFO\"} 1\n"));
+        assert!(
+            metrics.contains("app_log_level_filter{tag=\"stats-market-data\",filter=\"OFF\"} 1\n")
+        );
+
+        let json = snapshot.to_json(None);
+        assert!(
+            json.contains("\"max_level\":\"INFO\",\"tag_filters\":{\"stats-market-data\":\"OFF\"}")
+        );
+    }
+}
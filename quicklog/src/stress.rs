@@ -0,0 +1,228 @@
+//! Soak-test harness which hammers the logging queue with configurable producer
+//! patterns, and accounts for every record that was logged, dropped or flushed.
+//!
+//! Enabled with the `stress` feature. This can be used to validate how the
+//! logger behaves on overflow, as well as to validate your own [`Flush`]
+//! implementation under load, since records are flushed through whichever
+//! flusher is currently attached to the logger.
+//!
+//! ```
+//! use quicklog::stress::{run, ProducerPattern, StressConfig};
+//! use quicklog::with_flush;
+//! use quicklog_flush::noop_flusher::NoopFlusher;
+//!
+//! quicklog::init!();
+//! with_flush!(NoopFlusher);
+//!
+//! let report = run(&StressConfig {
+//!     pattern: ProducerPattern::Interleaved {
+//!         records: 10_000,
+//!         logs_per_flush: 2,
+//!     },
+//!     payload_len: 64,
+//! });
+//!
+//! assert!(report.is_consistent());
+//! println!("{}", report);
+//! ```
+//!
+//! [`Flush`]: quicklog_flush::Flush
+
+use std::fmt::Display;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::{
+    callsite::{Metadata, __Callsite},
+    level::Level,
+    logger,
+    queue::QueueFullPolicy,
+    Error, Log, LogRecord,
+};
+
+/// Describes how records are produced relative to how they are flushed.
+///
+/// Since the logger is single-threaded, producing and flushing are interleaved
+/// on the calling thread.
+#[derive(Debug, Clone, Copy)]
+pub enum ProducerPattern {
+    /// Logs all `records` back to back, and only flushes afterwards
+    Burst {
+        /// Number of records to log
+        records: usize,
+    },
+    /// Logs `logs_per_flush` records for every record flushed, so that the
+    /// producer steadily outpaces the consumer
+    Interleaved {
+        /// Number of records to log
+        records: usize,
+        /// Number of records logged before a single record is flushed
+        logs_per_flush: usize,
+    },
+    /// Logs `burst_size` records in each of `bursts`, flushing up to
+    /// `flushes_between` records between each burst
+    Bursts {
+        /// Number of bursts
+        bursts: usize,
+        /// Number of records logged in a single burst
+        burst_size: usize,
+        /// Number of records flushed between bursts
+        flushes_between: usize,
+    },
+}
+
+/// Configuration for a single stress run.
+#[derive(Debug, Clone)]
+pub struct StressConfig {
+    /// Producer pattern to log records with
+    pub pattern: ProducerPattern,
+    /// Length of the string payload attached to every record
+    pub payload_len: usize,
+}
+
+/// Accounting of a single stress run.
+#[derive(Debug, Clone, Default)]
+pub struct StressReport {
+    /// Number of records the producer attempted to log
+    pub attempted: usize,
+    /// Number of records successfully enqueued
+    pub enqueued: usize,
+    /// Number of records dropped because the queue was full, either on
+    /// enqueue or by being overwritten, depending on the [`QueueFullPolicy`]
+    pub dropped: usize,
+    /// Number of records flushed successfully
+    pub flushed: usize,
+    /// Number of records which the flusher failed to flush
+    pub flush_errors: usize,
+    /// Total time taken, including the final drain of the queue
+    pub elapsed: Duration,
+}
+
+impl StressReport {
+    /// Checks that every record attempted is accounted for, i.e. it was either
+    /// dropped because the queue was full, or flushed, and that every record
+    /// which failed to be enqueued was counted as dropped.
+    pub fn is_consistent(&self) -> bool {
+        self.attempted == self.flushed + self.flush_errors + self.dropped
+            && self
+                .attempted
+                .checked_sub(self.enqueued)
+                .is_some_and(|refused| refused <= self.dropped)
+    }
+}
+
+impl Display for StressReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "attempted={} enqueued={} dropped={} flushed={} flush_errors={} elapsed={:?}",
+            self.attempted,
+            self.enqueued,
+            self.dropped,
+            self.flushed,
+            self.flush_errors,
+            self.elapsed
+        )
+    }
+}
+
+/// Runs a single stress run against the global logger, initializing it if
+/// needed, and returns the accounting for the run.
+///
+/// Any records already in the queue are flushed before the run starts, and
+/// are not included in the returned [`StressReport`].
+pub fn run(config: &StressConfig) -> StressReport {
+    let logger = logger();
+    logger.init();
+    while !matches!(logger.flush_one(), Err(Error::Empty)) {}
+
+    let payload: Arc<str> = "x".repeat(config.payload_len).into();
+    let mut report = StressReport::default();
+    let start = Instant::now();
+
+    match config.pattern {
+        ProducerPattern::Burst { records } => {
+            log_n(&mut report, &payload, records);
+        }
+        ProducerPattern::Interleaved {
+            records,
+            logs_per_flush,
+        } => {
+            let logs_per_flush = logs_per_flush.max(1);
+            while report.attempted < records {
+                let n = logs_per_flush.min(records - report.attempted);
+                log_n(&mut report, &payload, n);
+                flush_n(&mut report, 1);
+            }
+        }
+        ProducerPattern::Bursts {
+            bursts,
+            burst_size,
+            flushes_between,
+        } => {
+            for _ in 0..bursts {
+                log_n(&mut report, &payload, burst_size);
+                flush_n(&mut report, flushes_between);
+            }
+        }
+    }
+
+    flush_n(&mut report, usize::MAX);
+    report.elapsed = start.elapsed();
+
+    report
+}
+
+/// Logs `n` records, recording whether each was enqueued and how many records
+/// were dropped in the process
+fn log_n(report: &mut StressReport, payload: &Arc<str>, n: usize) {
+    let logger = logger();
+    for _ in 0..n {
+        let seq = report.attempted;
+        let payload = Arc::clone(payload);
+        static CALLSITE: __Callsite =
+            __Callsite::new(Metadata::new(Level::Info, module_path!(), file!(), line!()));
+        let record = LogRecord {
+            callsite: CALLSITE.id(),
+            log_line: Box::new(lazy_format::make_lazy_format!(|f| write!(
+                f,
+                "stress seq={} payload={}",
+                seq, payload
+            ))),
+            tag: None,
+            sequence: 0,
+            metric: None,
+            #[cfg(feature = "trace")]
+            trace_id: None,
+            #[cfg(feature = "trace")]
+            span_id: None,
+            #[cfg(feature = "trace")]
+            span_name: None,
+        };
+
+        report.attempted += 1;
+        if logger.queue_full_policy() == QueueFullPolicy::Block && logger.is_queue_full() {
+            // flush on behalf of the producer, which would otherwise block
+            // forever, as nothing flushes on another thread
+            flush_n(report, 1);
+        }
+
+        let dropped = logger.dropped_count();
+        if logger.log(record).is_ok() {
+            report.enqueued += 1;
+        }
+        report.dropped += logger.dropped_count() - dropped;
+    }
+}
+
+/// Flushes up to `n` records, stopping early if the queue is empty
+fn flush_n(report: &mut StressReport, n: usize) {
+    let logger = logger();
+    for _ in 0..n {
+        match logger.flush_one() {
+            Ok(()) => report.flushed += 1,
+            Err(Error::Empty) => break,
+            Err(_) => report.flush_errors += 1,
+        }
+    }
+}
@@ -0,0 +1,225 @@
+//! Defines [`Tag`], a small interned id attached to records with the `tag:`
+//! option of the logging macros.
+//!
+//! Tags are independent of the level and module path of a record, and can be
+//! used to route records to a different flusher when flushing, see
+//! [`with_tag_flush!`], or muted at runtime with [`Tag::set_filter`], e.g. to
+//! silence a noisy market data feed without touching the other records.
+//!
+//! ```
+//! # use quicklog::{flush, info, init, with_tag_flush};
+//! # use quicklog_flush::{file_flusher::FileFlusher, stdout_flusher::StdoutFlusher};
+//! init!();
+//! with_tag_flush!("audit", FileFlusher::new("logs/audit.log"));
+//! with_tag_flush!("research", StdoutFlusher);
+//!
+//! // flushed into logs/audit.log
+//! info!(tag: audit, "order accepted");
+//! // flushed into the default flusher
+//! info!("book updated");
+//! # flush!();
+//! ```
+//!
+//! ```
+//! # use quicklog::{info, init, level::LevelFilter, tag::Tag};
+//! # init!();
+//! # let px = 100.5;
+//! Tag::new("market-data").set_filter(LevelFilter::Off);
+//! // skipped without evaluating its arguments
+//! info!(tag: "market-data", "tick {}", px);
+//! Tag::new("market-data").set_filter(LevelFilter::Trace);
+//! ```
+//!
+//! [`with_tag_flush!`]: crate::with_tag_flush
+
+use std::{
+    fmt::{self, Display, Formatter},
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        OnceLock, PoisonError, RwLock,
+    },
+};
+
+use crate::level::{Level, LevelFilter};
+
+/// Name and level filter of a tag
+struct TagEntry {
+    name: &'static str,
+    /// Leaked so that call sites can check it without locking [`TAGS`]
+    filter: &'static AtomicU8,
+}
+
+/// All tags, indexed by their id
+static TAGS: RwLock<Vec<TagEntry>> = RwLock::new(Vec::new());
+
+/// Interned tag name, cheap to copy and compare
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Tag(u16);
+
+impl Tag {
+    /// Returns the tag named `name`, interning it if it was not used yet
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than `u16::MAX + 1` distinct tags are created.
+    pub fn new(name: &'static str) -> Self {
+        if let Some(tag) = Self::find(name) {
+            return tag;
+        }
+
+        let mut tags = TAGS.write().unwrap_or_else(PoisonError::into_inner);
+        // may have been interned while waiting for the lock
+        if let Some(id) = tags.iter().position(|tag| tag.name == name) {
+            return Self(id as u16);
+        }
+        let id = u16::try_from(tags.len()).expect("too many distinct tags");
+        tags.push(TagEntry {
+            name,
+            filter: Box::leak(Box::new(AtomicU8::new(LevelFilter::Trace as u8))),
+        });
+        Self(id)
+    }
+
+    /// Returns the tag named `name` if it was interned already
+    pub fn find(name: &str) -> Option<Self> {
+        let tags = TAGS.read().unwrap_or_else(PoisonError::into_inner);
+        tags.iter()
+            .position(|tag| tag.name == name)
+            .map(|id| Self(id as u16))
+    }
+
+    /// Interned id of the tag
+    pub fn id(self) -> u16 {
+        self.0
+    }
+
+    /// Name the tag was created with
+    pub fn name(self) -> &'static str {
+        self.entry(|entry| entry.name)
+    }
+
+    /// Sets the minimum level of records logged with this tag, on top of the
+    /// global [`max_level`]. [`LevelFilter::Off`] mutes the tag entirely.
+    ///
+    /// [`max_level`]: crate::level::max_level
+    pub fn set_filter(self, filter: LevelFilter) {
+        self.filter_cell().store(filter as u8, Ordering::Relaxed);
+    }
+
+    /// Minimum level of records logged with this tag, [`LevelFilter::Trace`]
+    /// unless changed with [`Tag::set_filter`]
+    pub fn filter(self) -> LevelFilter {
+        LevelFilter::from_u8(self.filter_cell().load(Ordering::Relaxed))
+    }
+
+    fn filter_cell(self) -> &'static AtomicU8 {
+        self.entry(|entry| entry.filter)
+    }
+
+    fn entry<T>(self, f: impl FnOnce(&TagEntry) -> T) -> T {
+        f(&TAGS.read().unwrap_or_else(PoisonError::into_inner)[usize::from(self.0)])
+    }
+}
+
+/// Tags with a filter other than [`LevelFilter::Trace`]
+#[cfg(any(test, feature = "stats"))]
+pub(crate) fn filtered() -> Vec<(Tag, LevelFilter)> {
+    let tags = TAGS.read().unwrap_or_else(PoisonError::into_inner);
+    tags.iter()
+        .enumerate()
+        .map(|(id, tag)| {
+            let filter = LevelFilter::from_u8(tag.filter.load(Ordering::Relaxed));
+            (Tag(id as u16), filter)
+        })
+        .filter(|(_, filter)| *filter != LevelFilter::Trace)
+        .collect()
+}
+
+impl From<&'static str> for Tag {
+    fn from(name: &'static str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl Display for Tag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// **Internal API**
+///
+/// Tag of a logging macro call site, interned the first time it is logged
+#[doc(hidden)]
+pub struct __CallsiteTag {
+    name: &'static str,
+    interned: OnceLock<(Tag, &'static AtomicU8)>,
+}
+
+impl __CallsiteTag {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            interned: OnceLock::new(),
+        }
+    }
+
+    fn interned(&self) -> &(Tag, &'static AtomicU8) {
+        self.interned.get_or_init(|| {
+            let tag = Tag::new(self.name);
+            (tag, tag.filter_cell())
+        })
+    }
+
+    pub fn get(&self) -> Tag {
+        self.interned().0
+    }
+
+    /// Whether the filter of the tag enables `level`
+    pub fn is_enabled(&self, level: Level) -> bool {
+        level as u8 >= self.interned().1.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Tag, __CallsiteTag};
+    use crate::level::{Level, LevelFilter};
+
+    #[test]
+    fn interning() {
+        let audit = Tag::new("test-audit");
+        let research = Tag::new("test-research");
+        assert_ne!(audit, research);
+        assert_eq!(Tag::new("test-audit"), audit);
+        assert_eq!(Tag::from("test-research"), research);
+        assert_eq!(Tag::find("test-audit"), Some(audit));
+        assert_eq!(Tag::find("test-missing"), None);
+        assert_eq!(audit.name(), "test-audit");
+        assert_eq!(research.to_string(), "test-research");
+
+        let callsite = __CallsiteTag::new("test-audit");
+        assert_eq!(callsite.get(), audit);
+        assert_eq!(callsite.get(), audit);
+    }
+
+    #[test]
+    fn filters() {
+        let feed = Tag::new("test-feed");
+        let callsite = __CallsiteTag::new("test-feed");
+        assert_eq!(feed.filter(), LevelFilter::Trace);
+        assert!(callsite.is_enabled(Level::Debug));
+
+        feed.set_filter(LevelFilter::Warn);
+        assert!(!callsite.is_enabled(Level::Info));
+        assert!(callsite.is_enabled(Level::Warn));
+
+        feed.set_filter(LevelFilter::Off);
+        assert!(!callsite.is_enabled(Level::Error));
+        assert!(super::filtered().contains(&(feed, LevelFilter::Off)));
+
+        feed.set_filter(LevelFilter::Trace);
+        assert!(callsite.is_enabled(Level::Trace));
+        assert!(!super::filtered().iter().any(|(tag, _)| *tag == feed));
+    }
+}
@@ -0,0 +1,168 @@
+//! Helpers for testing what an application logs, without writing a flusher
+//! and formatter for every test.
+//!
+//! [`capture`] returns a [`CaptureGuard`] which records everything flushed
+//! while it is alive, and restores the previous flusher and formatter when
+//! dropped. [`assert_logged_eq!`] and [`assert_logged_contains!`] flush the
+//! queue and check the captured messages.
+//!
+//! ```
+//! use quicklog::{assert_logged_contains, assert_logged_eq, info, test_utils::capture, warn};
+//!
+//! let capture = capture();
+//! info!(symbol = "BTC-USD", "order {} filled", 1);
+//! warn!("book crossed");
+//!
+//! assert_logged_eq!(capture, ["order 1 filled symbol=BTC-USD", "book crossed"]);
+//! // messages are cleared by `assert_logged_eq!`
+//! info!("reconnected after {}ms", 250);
+//! assert_logged_contains!(capture, "reconnected");
+//! ```
+//!
+//! The logger is global, so tests capturing logs should not run concurrently
+//! on the same thread pool, e.g. run them with `--test-threads=1` or keep them
+//! in a single test.
+//!
+//! [`assert_logged_eq!`]: crate::assert_logged_eq
+//! [`assert_logged_contains!`]: crate::assert_logged_contains
+
+use std::{cell::RefCell, rc::Rc};
+
+use chrono::{DateTime, Utc};
+use quicklog_flush::{noop_flusher::NoopFlusher, Flush};
+
+use crate::{level::Level, logger, tag::Tag, LogRecord, PatternFormatter};
+
+/// Record flushed while a [`CaptureGuard`] was alive
+#[derive(Clone, Debug, PartialEq)]
+pub struct CapturedRecord {
+    pub level: Level,
+    pub module_path: &'static str,
+    pub tag: Option<Tag>,
+    /// Message followed by the fields, as written by the default formatter
+    pub message: String,
+}
+
+type Captured = Rc<RefCell<Vec<CapturedRecord>>>;
+
+/// Keeps the records it formats instead of producing a log line
+struct CaptureFormatter(Captured);
+
+impl PatternFormatter for CaptureFormatter {
+    fn custom_format(&mut self, _time: DateTime<Utc>, log_record: LogRecord) -> String {
+        self.0.borrow_mut().push(CapturedRecord {
+            level: log_record.level(),
+            module_path: log_record.module_path(),
+            tag: log_record.tag,
+            message: log_record.log_line.to_string(),
+        });
+        String::new()
+    }
+}
+
+/// Captures the records flushed by the logger until dropped, see [`capture`]
+#[must_use = "records are only captured while the guard is alive"]
+pub struct CaptureGuard {
+    captured: Captured,
+    previous: Option<(Box<dyn Flush>, Box<dyn PatternFormatter>)>,
+}
+
+/// Initializes the logger if needed, and captures every record flushed until
+/// the returned guard is dropped
+pub fn capture() -> CaptureGuard {
+    let logger = logger();
+    logger.init();
+
+    let captured = Captured::default();
+    let flusher = logger.replace_flush(Box::new(NoopFlusher::new()));
+    let formatter = logger.replace_formatter(Box::new(CaptureFormatter(captured.clone())));
+    CaptureGuard {
+        captured,
+        previous: Some((flusher, formatter)),
+    }
+}
+
+impl CaptureGuard {
+    /// Flushes the queue, returning every record captured since the guard
+    /// was created or last cleared
+    pub fn records(&self) -> Vec<CapturedRecord> {
+        logger().flush_all();
+        self.captured.borrow().clone()
+    }
+
+    /// Flushes the queue, returning the messages of every record captured
+    /// since the guard was created or last cleared
+    pub fn messages(&self) -> Vec<String> {
+        logger().flush_all();
+        let captured = self.captured.borrow();
+        captured.iter().map(|record| record.message.clone()).collect()
+    }
+
+    /// Forgets the records captured so far
+    pub fn clear(&self) {
+        self.captured.borrow_mut().clear();
+    }
+}
+
+impl Drop for CaptureGuard {
+    fn drop(&mut self) {
+        let logger = logger();
+        // records logged while capturing are not written to the restored flusher
+        logger.flush_all();
+        if let Some((flusher, formatter)) = self.previous.take() {
+            logger.replace_flush(flusher);
+            logger.replace_formatter(formatter);
+        }
+    }
+}
+
+/// Asserts that the messages captured by a [`CaptureGuard`] are equal to the
+/// given ones, in order, after flushing the queue. The captured messages are
+/// cleared afterwards.
+///
+/// ```
+/// # use quicklog::{assert_logged_eq, info, test_utils::capture};
+/// let capture = capture();
+/// info!(px = 101.5, "filled");
+/// assert_logged_eq!(capture, ["filled px=101.5"]);
+/// assert_logged_eq!(capture, []);
+/// ```
+///
+/// [`CaptureGuard`]: crate::test_utils::CaptureGuard
+#[macro_export]
+macro_rules! assert_logged_eq {
+    ($capture:expr, [$($expected:expr),* $(,)?]) => {{
+        let expected: &[&str] = &[$($expected),*];
+        let capture = &$capture;
+        assert_eq!(capture.messages(), expected, "logged messages differ");
+        capture.clear();
+    }};
+}
+
+/// Asserts that a message captured by a [`CaptureGuard`] contains the given
+/// text, after flushing the queue. The captured messages are cleared
+/// afterwards.
+///
+/// ```
+/// # use quicklog::{assert_logged_contains, info, test_utils::capture};
+/// let capture = capture();
+/// info!("reconnected after {}ms", 250);
+/// assert_logged_contains!(capture, "after 250ms");
+/// ```
+///
+/// [`CaptureGuard`]: crate::test_utils::CaptureGuard
+#[macro_export]
+macro_rules! assert_logged_contains {
+    ($capture:expr, $text:expr) => {{
+        let text: &str = $text;
+        let capture = &$capture;
+        let messages = capture.messages();
+        assert!(
+            messages.iter().any(|message| message.contains(text)),
+            "no logged message contains {:?}, logged: {:?}",
+            text,
+            messages
+        );
+        capture.clear();
+    }};
+}
@@ -0,0 +1,304 @@
+//! Defines [`TimestampFormat`], which controls how timestamps of log records
+//! are represented when flushed.
+//!
+//! ```
+//! # use quicklog::{init, timestamp::TimestampFormat};
+//! // timestamps are flushed as nanoseconds since the unix epoch
+//! init!(timestamp_format = TimestampFormat::EpochNanos);
+//! ```
+//!
+//! Formatters write timestamps through a [`CachedTimestamp`], which only
+//! renders the part up to the seconds once per second.
+
+use std::fmt::{Display, Write};
+
+use chrono::{DateTime, NaiveDateTime, SecondsFormat, TimeZone, Timelike, Utc};
+
+/// Representation of the timestamp of a log record.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// `Debug` representation of the timestamp, e.g. `2023-10-23T08:00:00.123456789Z`
+    #[default]
+    Default,
+    /// RFC 3339 with nanosecond precision, e.g. `2023-10-23T08:00:00.123456789Z`
+    Rfc3339,
+    /// Nanoseconds since the unix epoch, e.g. `1698048000123456789`
+    EpochNanos,
+    /// Microseconds since the unix epoch, e.g. `1698048000123456`
+    EpochMicros,
+    /// Custom `strftime`-like pattern, see [`chrono::format::strftime`] for
+    /// the supported specifiers
+    Custom(String),
+}
+
+impl TimestampFormat {
+    /// Returns a [`Display`] implementation that formats `time` in this format
+    pub fn display(&self, time: DateTime<Utc>) -> FormattedTimestamp<'_> {
+        FormattedTimestamp { format: self, time }
+    }
+
+    /// Parses a timestamp previously formatted in this format. Custom patterns
+    /// without a timezone are assumed to be in UTC.
+    pub fn parse(&self, timestamp: &str) -> Option<DateTime<Utc>> {
+        match self {
+            TimestampFormat::Default | TimestampFormat::Rfc3339 => {
+                DateTime::parse_from_rfc3339(timestamp)
+                    .ok()
+                    .map(|time| time.with_timezone(&Utc))
+            }
+            TimestampFormat::EpochNanos => {
+                let nanos = timestamp.parse::<i64>().ok()?;
+                Some(Utc.timestamp_nanos(nanos))
+            }
+            TimestampFormat::EpochMicros => {
+                let micros = timestamp.parse::<i64>().ok()?;
+                Utc.timestamp_opt(
+                    micros.div_euclid(1_000_000),
+                    micros.rem_euclid(1_000_000) as u32 * 1_000,
+                )
+                .single()
+            }
+            TimestampFormat::Custom(pattern) => DateTime::parse_from_str(timestamp, pattern)
+                .map(|time| time.with_timezone(&Utc))
+                .or_else(|_| {
+                    NaiveDateTime::parse_from_str(timestamp, pattern).map(|time| time.and_utc())
+                })
+                .ok(),
+        }
+    }
+}
+
+/// Timestamp paired with the [`TimestampFormat`] it should be displayed in,
+/// returned by [`TimestampFormat::display`].
+pub struct FormattedTimestamp<'a> {
+    format: &'a TimestampFormat,
+    time: DateTime<Utc>,
+}
+
+impl Display for FormattedTimestamp<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let time = self.time;
+        match self.format {
+            TimestampFormat::Default => write!(f, "{:?}", time),
+            TimestampFormat::Rfc3339 => {
+                write!(f, "{}", time.to_rfc3339_opts(SecondsFormat::Nanos, true))
+            }
+            TimestampFormat::EpochNanos => write!(
+                f,
+                "{}",
+                time.timestamp() as i128 * 1_000_000_000 + time.timestamp_subsec_nanos() as i128
+            ),
+            TimestampFormat::EpochMicros => write!(
+                f,
+                "{}",
+                time.timestamp() as i128 * 1_000_000 + time.timestamp_subsec_micros() as i128
+            ),
+            TimestampFormat::Custom(pattern) => write!(f, "{}", time.format(pattern)),
+        }
+    }
+}
+
+/// Writes timestamps in a [`TimestampFormat`], caching the formatted part up
+/// to the seconds so that only the sub-second digits are rendered for
+/// timestamps within the same second as the previous one.
+///
+/// Custom patterns are not cached, since the sub-second digits may be
+/// anywhere in them.
+///
+/// ```
+/// # use chrono::{TimeZone, Utc};
+/// # use quicklog::timestamp::{CachedTimestamp, TimestampFormat};
+/// let mut timestamp = CachedTimestamp::new(TimestampFormat::Rfc3339);
+/// let mut line = String::new();
+/// timestamp.write(&mut line, Utc.timestamp_opt(1_698_048_000, 123_456_789).unwrap());
+/// assert_eq!(line, "2023-10-23T08:00:00.123456789Z");
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CachedTimestamp {
+    format: TimestampFormat,
+    /// Second since the unix epoch which `prefix` was rendered for
+    second: Option<i64>,
+    prefix: String,
+}
+
+impl CachedTimestamp {
+    pub fn new(format: TimestampFormat) -> Self {
+        Self {
+            format,
+            second: None,
+            prefix: String::new(),
+        }
+    }
+
+    /// Format timestamps are written in
+    pub fn format(&self) -> &TimestampFormat {
+        &self.format
+    }
+
+    /// Changes the format timestamps are written in, clearing the cache
+    pub fn set_format(&mut self, format: TimestampFormat) {
+        self.format = format;
+        self.second = None;
+    }
+
+    /// Appends `time` to `out`, formatted like [`TimestampFormat::display`]
+    pub fn write(&mut self, out: &mut String, time: DateTime<Utc>) {
+        let second = time.timestamp();
+        let nanos = time.nanosecond();
+        // Leap seconds, and epoch timestamps which are not made of the
+        // seconds followed by the padded sub-second digits
+        let cacheable = match self.format {
+            TimestampFormat::Default | TimestampFormat::Rfc3339 => nanos < 1_000_000_000,
+            TimestampFormat::EpochNanos | TimestampFormat::EpochMicros => {
+                nanos < 1_000_000_000 && second > 0
+            }
+            TimestampFormat::Custom(_) => false,
+        };
+        if !cacheable {
+            let _ = write!(out, "{}", self.format.display(time));
+            return;
+        }
+
+        if self.second != Some(second) {
+            self.render_prefix(time);
+            self.second = Some(second);
+        }
+        out.push_str(&self.prefix);
+        match self.format {
+            // `Debug` leaves out zeroes, like `NaiveTime`
+            TimestampFormat::Default => {
+                if nanos.is_multiple_of(1_000_000) && nanos != 0 {
+                    out.push('.');
+                    push_digits(out, nanos / 1_000_000, 3);
+                } else if nanos.is_multiple_of(1_000) && nanos != 0 {
+                    out.push('.');
+                    push_digits(out, nanos / 1_000, 6);
+                } else if nanos != 0 {
+                    out.push('.');
+                    push_digits(out, nanos, 9);
+                }
+                out.push('Z');
+            }
+            TimestampFormat::Rfc3339 => {
+                out.push('.');
+                push_digits(out, nanos, 9);
+                out.push('Z');
+            }
+            TimestampFormat::EpochNanos => push_digits(out, nanos, 9),
+            TimestampFormat::EpochMicros => push_digits(out, nanos / 1_000, 6),
+            TimestampFormat::Custom(_) => unreachable!(),
+        }
+    }
+
+    /// Renders the part of `time` up to the seconds
+    fn render_prefix(&mut self, time: DateTime<Utc>) {
+        self.prefix.clear();
+        let seconds = time.with_nanosecond(0).unwrap_or(time);
+        match self.format {
+            TimestampFormat::Default | TimestampFormat::Rfc3339 => {
+                let _ = write!(
+                    self.prefix,
+                    "{}",
+                    seconds.to_rfc3339_opts(SecondsFormat::Secs, true)
+                );
+                self.prefix.pop();
+            }
+            TimestampFormat::EpochNanos | TimestampFormat::EpochMicros => {
+                let _ = write!(self.prefix, "{}", time.timestamp());
+            }
+            TimestampFormat::Custom(_) => {}
+        }
+    }
+}
+
+/// Appends the `width` last decimal digits of `value`, padded with zeroes
+fn push_digits(out: &mut String, mut value: u32, width: usize) {
+    let mut digits = [b'0'; 9];
+    for digit in digits[..width].iter_mut().rev() {
+        *digit = b'0' + (value % 10) as u8;
+        value /= 10;
+    }
+    // Only ASCII digits were written
+    out.push_str(std::str::from_utf8(&digits[..width]).unwrap_or_default());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn time() -> DateTime<Utc> {
+        Utc.timestamp_opt(1_698_048_000, 123_456_789).unwrap()
+    }
+
+    #[test]
+    fn parse_formatted_timestamps() {
+        let formats = [
+            TimestampFormat::Default,
+            TimestampFormat::Rfc3339,
+            TimestampFormat::EpochNanos,
+            TimestampFormat::Custom("%Y-%m-%d %H:%M:%S%.9f".to_string()),
+        ];
+
+        for format in formats {
+            let formatted = format.display(time()).to_string();
+            assert_eq!(format.parse(&formatted), Some(time()));
+        }
+
+        let micros = TimestampFormat::EpochMicros;
+        let formatted = micros.display(time()).to_string();
+        assert_eq!(
+            micros.parse(&formatted).unwrap().timestamp_subsec_nanos(),
+            123_456_000
+        );
+    }
+
+    #[test]
+    fn format_timestamps() {
+        let cases = [
+            (TimestampFormat::Default, "2023-10-23T08:00:00.123456789Z"),
+            (TimestampFormat::Rfc3339, "2023-10-23T08:00:00.123456789Z"),
+            (TimestampFormat::EpochNanos, "1698048000123456789"),
+            (TimestampFormat::EpochMicros, "1698048000123456"),
+            (
+                TimestampFormat::Custom("%Y%m%d %H:%M:%S%.3f".to_string()),
+                "20231023 08:00:00.123",
+            ),
+        ];
+
+        for (format, expected) in cases {
+            assert_eq!(format.display(time()).to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn cached_timestamps_match_display() {
+        let formats = [
+            TimestampFormat::Default,
+            TimestampFormat::Rfc3339,
+            TimestampFormat::EpochNanos,
+            TimestampFormat::EpochMicros,
+            TimestampFormat::Custom("%H:%M:%S%.6f".to_string()),
+        ];
+        let times = [
+            (1_698_048_000, 123_456_789),
+            (1_698_048_000, 123_456_000),
+            (1_698_048_000, 123_000_000),
+            (1_698_048_000, 0),
+            (1_698_048_000, 5),
+            (1_698_048_001, 999_999_999),
+            (1_698_047_999, 1_500_000_000),
+            (0, 42),
+            (-1, 42),
+        ];
+
+        for format in formats {
+            let mut cached = CachedTimestamp::new(format.clone());
+            for (secs, nanos) in times {
+                let time = Utc.timestamp_opt(secs, nanos).unwrap();
+                let mut line = String::new();
+                cached.write(&mut line, time);
+                assert_eq!(line, format.display(time).to_string());
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! Bridge from the [`tracing`] ecosystem into quicklog, enabled with the
+//! `tracing-bridge` feature.
+//!
+//! [`QuicklogLayer`] implements [`Layer`], so that events emitted by libraries
+//! using `tracing` are funneled into quicklog's queue and formatted by its
+//! [`PatternFormatter`]. The fields of these events are formatted eagerly, but
+//! flushing is still deferred until [`flush!`] is called.
+//!
+//! As with quicklog's own macros, events should be emitted on the thread which
+//! owns the logger.
+//!
+//! ```
+//! use tracing_subscriber::prelude::*;
+//!
+//! quicklog::init!();
+//! tracing_subscriber::registry()
+//!     .with(quicklog::tracing_bridge::QuicklogLayer)
+//!     .init();
+//!
+//! // e.g. emitted from within a dependency
+//! tracing::info!(venue = "exchange", "connected");
+//! quicklog::flush!();
+//! ```
+//!
+//! [`tracing`]: https://docs.rs/tracing
+//! [`Layer`]: tracing_subscriber::Layer
+//! [`PatternFormatter`]: crate::PatternFormatter
+//! [`flush!`]: crate::flush
+
+use std::fmt::{Debug, Write};
+
+use tracing_core::{
+    field::{Field, Visit},
+    Event, Subscriber,
+};
+use tracing_subscriber::{layer::Context, Layer};
+
+use crate::{callsite, level::Level, logfmt::FormattedLine, logger, Log, LogRecord};
+
+/// [`Layer`] which pushes `tracing` events onto quicklog's queue.
+///
+/// The message of an event is followed by its other fields, formatted as
+/// `name=value`, similar to quicklog's own structured fields.
+pub struct QuicklogLayer;
+
+fn to_level(level: &tracing_core::Level) -> Level {
+    match *level {
+        tracing_core::Level::TRACE => Level::Trace,
+        tracing_core::Level::DEBUG => Level::Debug,
+        tracing_core::Level::INFO => Level::Info,
+        tracing_core::Level::WARN => Level::Warn,
+        _ => Level::Error,
+    }
+}
+
+/// Collects the message of an event, followed by its other fields
+struct EventVisitor(FormattedLine);
+
+impl Visit for EventVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.0.message.push_str(value);
+        } else {
+            self.0.fields.push((field.name(), value.to_string()));
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.0.message, "{:?}", value);
+        } else {
+            self.0.fields.push((field.name(), format!("{:?}", value)));
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for QuicklogLayer {
+    fn enabled(&self, metadata: &tracing_core::Metadata<'_>, _ctx: Context<'_, S>) -> bool {
+        crate::is_level_enabled!(to_level(metadata.level()))
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let mut visitor = EventVisitor(FormattedLine::new(String::new()));
+        event.record(&mut visitor);
+
+        let log_record = LogRecord {
+            callsite: callsite::intern(
+                to_level(metadata.level()),
+                metadata.module_path().unwrap_or_default(),
+                metadata.file().unwrap_or_default(),
+                metadata.line().unwrap_or_default(),
+            ),
+            log_line: Box::new(visitor.0),
+            tag: None,
+            sequence: 0,
+            metric: None,
+            #[cfg(feature = "trace")]
+            trace_id: None,
+            #[cfg(feature = "trace")]
+            span_id: None,
+            #[cfg(feature = "trace")]
+            span_name: None,
+        };
+        logger().log(log_record).unwrap_or(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use quicklog_flush::Flush;
+    use tracing_subscriber::prelude::*;
+
+    use super::QuicklogLayer;
+    use crate::{flush_all, with_flush};
+
+    struct VecFlusher(Rc<RefCell<Vec<String>>>);
+
+    impl Flush for VecFlusher {
+        fn flush_one(&mut self, display: String) {
+            self.0.borrow_mut().push(display)
+        }
+    }
+
+    #[test]
+    fn events_are_queued() {
+        let lines = Rc::new(RefCell::new(Vec::new()));
+        crate::init!();
+        with_flush!(VecFlusher(lines.clone()));
+
+        let subscriber = tracing_subscriber::registry().with(QuicklogLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!("hello {}", "world");
+            tracing::warn!(venue = "exchange", retries = 3, "disconnected");
+        });
+        assert!(lines.borrow().is_empty());
+
+        flush_all!();
+        let lines = lines.borrow();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("]hello world\n"));
+        assert!(lines[1].ends_with("]disconnected venue=exchange retries=3\n"));
+    }
+}
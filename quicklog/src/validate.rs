@@ -0,0 +1,238 @@
+//! Startup validation of decode functions, to catch decoders which panic or
+//! read a different number of bytes than were encoded before a record using
+//! them is flushed in production.
+//!
+//! A sample value is registered for every type logged with the `^` prefix,
+//! and [`validate_decoders`] encodes each of them into a scratch buffer and
+//! decodes it back, exactly like the logger does with the records of those
+//! call sites. Run it at startup behind a debug flag, or from a test.
+//!
+//! ```
+//! # use quicklog::validate::{register_decode_sample, validate_decoders};
+//! #[derive(quicklog::Serialize)]
+//! struct Fill {
+//!     price: u64,
+//!     size: u32,
+//! }
+//!
+//! register_decode_sample(Fill { price: 100, size: 2 });
+//! register_decode_sample("BTCUSD");
+//!
+//! assert_eq!(validate_decoders().unwrap(), 2);
+//! ```
+
+use std::{
+    any::{type_name, Any},
+    fmt::Display,
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{PoisonError, RwLock},
+};
+
+use crate::serialize::Serialize;
+
+/// Bytes written after the encoded value, so that decoders reading past the
+/// end of a value are caught instead of stopping at the end of the buffer
+const TRAILING_BYTES: usize = 64;
+/// Value of the trailing bytes
+const TRAILING_BYTE: u8 = 0xA5;
+
+type DecodeCheck = Box<dyn Fn() -> Result<(), DecodeCheckError> + Send + Sync>;
+
+static DECODE_CHECKS: RwLock<Vec<DecodeCheck>> = RwLock::new(Vec::new());
+
+/// Step of encoding and decoding a sample which failed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeStage {
+    /// `Serialize::encode`, when logging
+    Encode,
+    /// `Serialize::decode`, when flushing
+    Decode,
+}
+
+impl Display for DecodeStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Encode => write!(f, "encode"),
+            Self::Decode => write!(f, "decode"),
+        }
+    }
+}
+
+/// Reason a sample failed validation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DecodeCheckError {
+    /// Encoding or decoding the sample panicked
+    Panicked {
+        /// Name of the type of the sample
+        type_name: &'static str,
+        stage: DecodeStage,
+        /// Message of the panic, if it was a string
+        message: Option<String>,
+    },
+    /// `encode` wrote a different number of bytes than
+    /// `buffer_size_required` returned
+    EncodedSize {
+        type_name: &'static str,
+        /// Bytes returned by `buffer_size_required`
+        required: usize,
+        /// Bytes written by `encode`
+        encoded: usize,
+    },
+    /// `decode` read a different number of bytes than were encoded, which
+    /// would misalign the following arguments of the record
+    Misaligned {
+        type_name: &'static str,
+        /// Bytes written by `encode`
+        encoded: usize,
+        /// Bytes read by `decode`
+        decoded: usize,
+    },
+}
+
+impl DecodeCheckError {
+    /// Name of the type of the sample which failed
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::Panicked { type_name, .. }
+            | Self::EncodedSize { type_name, .. }
+            | Self::Misaligned { type_name, .. } => type_name,
+        }
+    }
+}
+
+impl Display for DecodeCheckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Panicked {
+                type_name,
+                stage,
+                message,
+            } => {
+                write!(f, "{} of `{}` panicked", stage, type_name)?;
+                match message {
+                    Some(message) => write!(f, ": {}", message),
+                    None => Ok(()),
+                }
+            }
+            Self::EncodedSize {
+                type_name,
+                required,
+                encoded,
+            } => write!(
+                f,
+                "`{}` requires {} bytes but encoded {}",
+                type_name, required, encoded
+            ),
+            Self::Misaligned {
+                type_name,
+                encoded,
+                decoded,
+            } => write!(
+                f,
+                "`{}` encoded {} bytes but decoded {}",
+                type_name, encoded, decoded
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DecodeCheckError {}
+
+/// Registers `sample` to be checked by [`validate_decoders`]. Register one
+/// sample for every type logged with the `^` prefix, preferably one
+/// exercising variable-sized fields such as strings and vectors.
+pub fn register_decode_sample<T>(sample: T)
+where
+    T: Serialize + Send + Sync + 'static,
+{
+    let mut checks = DECODE_CHECKS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner);
+    checks.push(Box::new(move || check_decode(&sample)));
+}
+
+/// Removes all registered samples
+pub fn clear_decode_samples() {
+    DECODE_CHECKS
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clear();
+}
+
+/// Checks every registered sample with [`check_decode`], returning how many
+/// were checked, or the errors of all samples which failed.
+///
+/// Panics are caught, but still reported by the panic hook, i.e. printed to
+/// stderr by default.
+pub fn validate_decoders() -> Result<usize, Vec<DecodeCheckError>> {
+    let checks = DECODE_CHECKS.read().unwrap_or_else(PoisonError::into_inner);
+    let errors: Vec<_> = checks.iter().filter_map(|check| check().err()).collect();
+    if errors.is_empty() {
+        Ok(checks.len())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Encodes `sample` and decodes it back, checking that neither panics, and
+/// that `buffer_size_required`, `encode` and `decode` agree on the number of
+/// bytes of the value.
+pub fn check_decode<T: Serialize>(sample: &T) -> Result<(), DecodeCheckError> {
+    let type_name = type_name::<T>();
+    let panicked = |stage, payload: Box<dyn Any + Send>| DecodeCheckError::Panicked {
+        type_name,
+        stage,
+        message: panic_message(payload),
+    };
+
+    let required = sample.buffer_size_required();
+    let mut buf = vec![TRAILING_BYTE; required + TRAILING_BYTES];
+    let total = buf.len();
+    // decoded through the store when flushing, and through `decode` when
+    // nested in another value
+    let (remaining, store_decoded) = catch_unwind(AssertUnwindSafe(|| {
+        let (store, rest) = sample.encode(&mut buf);
+        let remaining = rest.len();
+        (
+            remaining,
+            catch_unwind(AssertUnwindSafe(|| store.as_string())),
+        )
+    }))
+    .map_err(|payload| panicked(DecodeStage::Encode, payload))?;
+
+    let encoded = total - remaining;
+    if encoded != required {
+        return Err(DecodeCheckError::EncodedSize {
+            type_name,
+            required,
+            encoded,
+        });
+    }
+
+    store_decoded.map_err(|payload| panicked(DecodeStage::Decode, payload))?;
+    let remaining = catch_unwind(|| T::decode(&buf).1.len())
+        .map_err(|payload| panicked(DecodeStage::Decode, payload))?;
+
+    let decoded = total - remaining;
+    if decoded != encoded {
+        return Err(DecodeCheckError::Misaligned {
+            type_name,
+            encoded,
+            decoded,
+        });
+    }
+
+    Ok(())
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> Option<String> {
+    payload
+        .downcast::<String>()
+        .map(|message| *message)
+        .or_else(|payload| {
+            payload
+                .downcast::<&str>()
+                .map(|message| message.to_string())
+        })
+        .ok()
+}
@@ -0,0 +1,250 @@
+//! Versioned binary layout of record headers, the single source of truth for
+//! tools reading binary logs outside of Rust.
+//!
+//! Every record written in binary form starts with a [`RecordHeader`],
+//! followed by `payload_len` bytes of payload. All integers are little-endian.
+//!
+//! Version 1, [`RecordHeader::SIZE`] = 24 bytes:
+//!
+//! | Offset | Size | Field          | Description                                          |
+//! |--------|------|----------------|------------------------------------------------------|
+//! | 0      | 2    | magic          | `b"QL"`, see [`MAGIC`]                               |
+//! | 2      | 1    | version        | Layout version, see [`WIRE_VERSION`]                 |
+//! | 3      | 1    | level          | `0` TRACE, `1` DEBUG, `2` INFO, `3` WARN, `4` ERROR  |
+//! | 4      | 4    | payload_len    | `u32`, bytes of payload following the header         |
+//! | 8      | 8    | sequence       | `u64`, see [`LogRecord::sequence`]                   |
+//! | 16     | 8    | timestamp      | `u64`, nanoseconds since the unix epoch              |
+//!
+//! New versions only append fields, so readers can decode the fields they
+//! know of any version up to their own, and must skip `payload_len` bytes
+//! past the header size of the version they read. The layout of a released
+//! version never changes, which is checked by the compatibility tests in
+//! `tests/wire.rs`.
+//!
+//! ```
+//! # use quicklog::{level::Level, wire::RecordHeader};
+//! let header = RecordHeader::new(Level::Warn, 1_698_048_000_000_000_000, 42, 11);
+//! let bytes = header.to_bytes();
+//!
+//! let decoded = RecordHeader::from_bytes(&bytes).unwrap();
+//! assert_eq!(decoded.level(), Level::Warn);
+//! assert_eq!(decoded.sequence(), 42);
+//! ```
+//!
+//! [`WireWriter`] writes every record in this form, with the message of the
+//! record as its UTF-8 payload.
+//!
+//! [`LogRecord::sequence`]: crate::LogRecord::sequence
+
+use std::{
+    fmt::{self, Display, Write as _},
+    io::{self, Write},
+};
+
+use chrono::{DateTime, Utc};
+
+use crate::{level::Level, LogRecord, RecordSink};
+
+/// Bytes every record header starts with
+pub const MAGIC: [u8; 2] = *b"QL";
+/// Version of the layout written by this version of quicklog
+pub const WIRE_VERSION: u8 = 1;
+
+/// Header of a record written in binary form, see the [module docs](self)
+/// for its layout
+#[non_exhaustive]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RecordHeader {
+    version: u8,
+    level: Level,
+    payload_len: u32,
+    sequence: u64,
+    timestamp_nanos: u64,
+}
+
+/// Error returned when bytes cannot be decoded as a [`RecordHeader`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WireError {
+    /// Fewer bytes than the header of the version being read
+    TooShort { required: usize, available: usize },
+    /// The bytes do not start with [`MAGIC`]
+    BadMagic([u8; 2]),
+    /// Written by a newer version of quicklog
+    UnsupportedVersion(u8),
+    /// Level byte outside of `0..=4`
+    InvalidLevel(u8),
+}
+
+impl Display for WireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort {
+                required,
+                available,
+            } => write!(
+                f,
+                "record header requires {} bytes but only {} are available",
+                required, available
+            ),
+            Self::BadMagic(magic) => write!(f, "bad record header magic {:02x?}", magic),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported record header version {}, at most {} is supported",
+                version, WIRE_VERSION
+            ),
+            Self::InvalidLevel(level) => write!(f, "invalid level {} in record header", level),
+        }
+    }
+}
+
+impl std::error::Error for WireError {}
+
+impl RecordHeader {
+    /// Size in bytes of the header of the current [`WIRE_VERSION`]
+    pub const SIZE: usize = 24;
+
+    /// Header of the current [`WIRE_VERSION`] for a record followed by
+    /// `payload_len` bytes
+    pub fn new(level: Level, timestamp_nanos: u64, sequence: u64, payload_len: u32) -> Self {
+        Self {
+            version: WIRE_VERSION,
+            level,
+            payload_len,
+            sequence,
+            timestamp_nanos,
+        }
+    }
+
+    /// Layout version the header was written with
+    pub fn version(&self) -> u8 {
+        self.version
+    }
+
+    /// Level of the record
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// Bytes of payload following the header
+    pub fn payload_len(&self) -> u32 {
+        self.payload_len
+    }
+
+    /// Sequence number of the record, see [`LogRecord::sequence`]
+    ///
+    /// [`LogRecord::sequence`]: crate::LogRecord::sequence
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Time the record was logged, in nanoseconds since the unix epoch
+    pub fn timestamp_nanos(&self) -> u64 {
+        self.timestamp_nanos
+    }
+
+    /// Size in bytes of the header of `version`
+    pub fn size_of_version(version: u8) -> Option<usize> {
+        match version {
+            1 => Some(Self::SIZE),
+            _ => None,
+        }
+    }
+
+    /// Encodes the header in the layout of its version
+    pub fn to_bytes(&self) -> [u8; Self::SIZE] {
+        let mut bytes = [0; Self::SIZE];
+        bytes[0..2].copy_from_slice(&MAGIC);
+        bytes[2] = self.version;
+        bytes[3] = self.level as u8;
+        bytes[4..8].copy_from_slice(&self.payload_len.to_le_bytes());
+        bytes[8..16].copy_from_slice(&self.sequence.to_le_bytes());
+        bytes[16..24].copy_from_slice(&self.timestamp_nanos.to_le_bytes());
+        bytes
+    }
+
+    /// Decodes a header from the start of `bytes`, which may be followed by
+    /// the payload
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, WireError> {
+        let too_short = |required| WireError::TooShort {
+            required,
+            available: bytes.len(),
+        };
+
+        let prefix = bytes.get(..4).ok_or_else(|| too_short(4))?;
+        let magic = [prefix[0], prefix[1]];
+        if magic != MAGIC {
+            return Err(WireError::BadMagic(magic));
+        }
+        let version = prefix[2];
+        let size = Self::size_of_version(version).ok_or(WireError::UnsupportedVersion(version))?;
+        let bytes = bytes.get(..size).ok_or_else(|| too_short(size))?;
+        let level = match prefix[3] {
+            0 => Level::Trace,
+            1 => Level::Debug,
+            2 => Level::Info,
+            3 => Level::Warn,
+            4 => Level::Error,
+            level => return Err(WireError::InvalidLevel(level)),
+        };
+        let u32_at =
+            |offset: usize| u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap());
+        let u64_at =
+            |offset: usize| u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap());
+
+        Ok(Self {
+            version,
+            level,
+            payload_len: u32_at(4),
+            sequence: u64_at(8),
+            timestamp_nanos: u64_at(16),
+        })
+    }
+}
+
+/// [`RecordSink`] writing every record as a [`RecordHeader`] followed by its
+/// message, e.g. into a file with [`with_record_sink!`]. Wrap the writer in a
+/// [`BufWriter`](std::io::BufWriter) to avoid a write per record.
+///
+/// [`with_record_sink!`]: crate::with_record_sink!
+pub struct WireWriter<W: Write> {
+    writer: W,
+    payload: String,
+}
+
+impl<W: Write> WireWriter<W> {
+    /// Writes records into `writer`
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            payload: String::new(),
+        }
+    }
+
+    /// Returns the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write> RecordSink for WireWriter<W> {
+    fn write_record(&mut self, time: DateTime<Utc>, log_record: &LogRecord) -> io::Result<usize> {
+        self.payload.clear();
+        let _ = write!(self.payload, "{}", log_record.log_line);
+        let payload_len = u32::try_from(self.payload.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "payload too large"))?;
+        let timestamp_nanos = time.timestamp_nanos_opt().unwrap_or(0) as u64;
+        let header = RecordHeader::new(
+            log_record.level(),
+            timestamp_nanos,
+            log_record.sequence,
+            payload_len,
+        );
+        self.writer.write_all(&header.to_bytes())?;
+        self.writer.write_all(self.payload.as_bytes())?;
+        Ok(RecordHeader::SIZE + self.payload.len())
+    }
+
+    fn flush_buffered(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
@@ -0,0 +1,88 @@
+use std::{cell::RefCell, io, rc::Rc};
+
+use quicklog::{
+    audit,
+    constants::MAX_SERIALIZE_BUFFER_CAPACITY,
+    flush_all, flush_audit, info, init,
+    level::{set_max_level, LevelFilter},
+    with_audit_flush, with_flush, FlushGuard,
+};
+use quicklog_flush::Flush;
+
+/// Fails to flush while `failing` is set
+#[derive(Clone, Default)]
+struct FlakyFlusher {
+    lines: Rc<RefCell<Vec<String>>>,
+    failing: Rc<RefCell<bool>>,
+}
+
+impl FlakyFlusher {
+    fn take(&self) -> Vec<String> {
+        self.lines
+            .borrow_mut()
+            .drain(..)
+            .map(|line| line[line.find(']').unwrap() + 1..].trim_end().to_string())
+            .collect()
+    }
+}
+
+impl Flush for FlakyFlusher {
+    fn flush_one(&mut self, display: String) {
+        self.lines.borrow_mut().push(display);
+    }
+
+    fn try_flush_one(&mut self, display: String) -> io::Result<()> {
+        if *self.failing.borrow() {
+            return Err(io::ErrorKind::BrokenPipe.into());
+        }
+        self.flush_one(display);
+        Ok(())
+    }
+}
+
+fn main() {
+    let global = FlakyFlusher::default();
+    init!();
+    with_flush!(global.clone());
+    let audit = FlakyFlusher::default();
+    with_audit_flush!(audit.clone());
+
+    // audit records are kept apart, and never filtered by level
+    set_max_level(LevelFilter::Off);
+    let (order_id, qty) = (7, 100);
+    info!("filtered out");
+    audit!(order_id, qty, "order filled");
+    assert_eq!(flush_all!(), 0);
+    assert_eq!(flush_audit!(), 1);
+    assert_eq!(audit.take(), ["order filled order_id=7 qty=100"]);
+    assert!(global.take().is_empty());
+
+    // `^` arguments are encoded into the buffer of the audit channel, so
+    // wrapping around the global buffer leaves them intact
+    set_max_level(LevelFilter::Trace);
+    let symbol = "AAPL";
+    audit!(^symbol, "order filled");
+    let payload = "x".repeat(1000);
+    for _ in 0..MAX_SERIALIZE_BUFFER_CAPACITY / 500 {
+        info!(^payload, "bulk");
+    }
+    assert_eq!(flush_audit!(), 1);
+    assert_eq!(audit.take(), ["order filled symbol=AAPL"]);
+    flush_all!();
+    global.take();
+
+    // lines which fail to flush are written out first once the sink is back
+    *audit.failing.borrow_mut() = true;
+    audit!("trade {}", 1);
+    audit!("trade {}", 2);
+    assert_eq!(flush_audit!(), 0);
+    assert!(audit.take().is_empty());
+    *audit.failing.borrow_mut() = false;
+    audit!("trade {}", 3);
+    // and by the guard when exiting
+    drop(FlushGuard::new());
+    assert_eq!(
+        audit.take(),
+        ["trade 1", "trade 2", "trade 3"]
+    );
+}
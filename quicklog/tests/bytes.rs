@@ -0,0 +1,37 @@
+use quicklog::{
+    info,
+    serialize::{set_bytes_format, BytesFormat},
+};
+
+mod common;
+
+fn main() {
+    setup!();
+
+    let payload = vec![0xde_u8, 0xad, 0xbe, 0xef];
+    assert_message_equal!(info!("sent {}", hex(^payload)), "sent deadbeef");
+    assert_message_equal!(
+        info!(payload = base64(^payload), "sent"),
+        "sent payload=3q2+7w=="
+    );
+    assert_message_equal!(info!(byte_len(^payload), "sent"), "sent payload=<4 bytes>");
+    // any `AsRef<[u8]>`, including references and arrays
+    let header = [1_u8, 2];
+    assert_message_equal!(
+        info!(body = hex(^&payload[1..3]), hex(^header), "frame"),
+        "frame body=adbe header=0102"
+    );
+
+    // byte slices follow the global format
+    let slice: &[u8] = &payload;
+    assert_message_equal!(info!("{}", ^slice), "deadbeef");
+    set_bytes_format(BytesFormat::Base64);
+    assert_message_equal!(info!("{}", ^slice), "3q2+7w==");
+    set_bytes_format(BytesFormat::Hex);
+
+    // a function named `hex` is still called as usual
+    fn hex(x: u8) -> String {
+        format!("{:x}", x)
+    }
+    assert_message_equal!(info!("{}", hex(255)), "ff");
+}
@@ -0,0 +1,83 @@
+use chrono::{DateTime, Utc};
+use quicklog::{
+    callsite::{CallsiteId, Metadata},
+    flush_all, info, init,
+    level::Level,
+    warn, with_filter, with_flush, with_formatter, LogRecord, PatternFormatter,
+};
+
+use common::VecFlusher;
+
+mod common;
+
+static mut VEC: Vec<String> = Vec::new();
+
+/// Formats records with the location of their call site
+struct LocationFormatter;
+
+impl PatternFormatter for LocationFormatter {
+    fn custom_format(&mut self, _time: DateTime<Utc>, log_record: LogRecord) -> String {
+        format!(
+            "{} {} {} {:p} {} {}\n",
+            log_record.level(),
+            log_record.module_path(),
+            log_record.metadata(),
+            log_record.metadata(),
+            log_record.callsite.as_u32(),
+            log_record.log_line
+        )
+    }
+}
+
+fn flushed() -> Vec<String> {
+    unsafe { (*std::ptr::addr_of_mut!(VEC)).drain(..).collect() }
+}
+
+fn log(i: usize) {
+    info!("call {}", i);
+}
+
+fn main() {
+    init!();
+    with_flush!(unsafe { VecFlusher::new(&mut *std::ptr::addr_of_mut!(VEC)) });
+    with_formatter!(LocationFormatter);
+
+    let line = line!() + 1;
+    warn!("here");
+    log(0);
+    log(1);
+    assert_eq!(flush_all!(), 3);
+
+    let lines = flushed();
+    let location = format!("{}:{}", file!(), line);
+    let expected = format!("WARN {} {} ", module_path!(), location);
+    assert!(lines[0].starts_with(&expected), "{}", lines[0]);
+    assert!(lines[0].ends_with("here\n"));
+
+    // records of the same call site share its static metadata
+    let address = |line: &str| line.split(' ').nth(3).unwrap().to_string();
+    assert_eq!(address(&lines[1]), address(&lines[2]));
+    assert_ne!(address(&lines[0]), address(&lines[1]));
+
+    // and are registered under the same id, which resolves to that metadata
+    let id = |line: &str| line.split(' ').nth(4).unwrap().parse::<u32>().unwrap();
+    assert_eq!(id(&lines[1]), id(&lines[2]));
+    assert_ne!(id(&lines[0]), id(&lines[1]));
+    let metadata = Metadata::new(Level::Info, "gateway", "", 0);
+    let static_metadata: &'static Metadata = Box::leak(Box::new(metadata));
+    let callsite = CallsiteId::of(static_metadata);
+    assert_eq!(CallsiteId::of(static_metadata), callsite);
+    assert!(std::ptr::eq(callsite.metadata(), static_metadata));
+
+    // filtered out records are neither queued nor dropped
+    with_filter!(|metadata: &Metadata| metadata.level() >= Level::Warn);
+    log(2);
+    warn!("kept");
+    assert_eq!(flush_all!(), 1);
+    assert!(flushed()[0].ends_with("kept\n"));
+    assert_eq!(quicklog::dropped_count(), 0);
+
+    quicklog::logger().clear_filter();
+    log(3);
+    assert_eq!(flush_all!(), 1);
+}
@@ -0,0 +1,53 @@
+use quicklog::{
+    callsite::Metadata, callsite_stats, dropped_count, flush_all, info, level::Level, warn,
+    with_filter,
+};
+
+mod common;
+
+fn heartbeat() {
+    info!("heartbeat");
+}
+
+fn order(id: u64) {
+    warn!(id = ^id, "rejected");
+}
+
+fn main() {
+    // counted as dropped, since there is no queue yet
+    heartbeat();
+    setup!();
+
+    for _ in 0..4 {
+        heartbeat();
+    }
+    order(1);
+    with_filter!(|metadata: &Metadata| metadata.level() >= Level::Warn);
+    heartbeat();
+    order(2);
+    let ignored = line!() + 1;
+    info!("ignored");
+    assert_eq!(flush_all!(), 6);
+
+    // busiest call sites first, and filtered records are not counted
+    let stats = callsite_stats();
+    assert_eq!(stats.len(), 3);
+    let (busiest, rejected) = (&stats[0], &stats[1]);
+    assert_eq!(busiest.metadata().level(), Level::Info);
+    assert_eq!((busiest.hits, busiest.dropped), (5, 1));
+    assert_eq!(rejected.metadata().level(), Level::Warn);
+    assert_eq!((rejected.hits, rejected.dropped), (2, 0));
+    assert!(rejected.last_hit >= busiest.last_hit);
+    assert_eq!(stats[2].metadata().line(), ignored);
+    assert_eq!((stats[2].hits, stats[2].last_hit), (0, None));
+
+    // records dropped because the queue is full are counted per call site
+    quicklog::logger().clear_filter();
+    while dropped_count() == 0 {
+        heartbeat();
+    }
+    let busiest = callsite_stats()[0];
+    assert_eq!(busiest.callsite, stats[0].callsite);
+    assert_eq!(busiest.dropped, 2);
+    flush_all!();
+}
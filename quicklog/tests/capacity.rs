@@ -0,0 +1,22 @@
+use quicklog::{dropped_count, flush_all, info, init, logger};
+
+mod common;
+
+fn main() {
+    init!(capacity = 4);
+    setup!();
+    assert_eq!(logger().capacity(), 4);
+
+    for i in 0..5 {
+        info!("{}", i);
+    }
+    assert_eq!(dropped_count(), 1);
+
+    flush_all!();
+    let lines = unsafe { common::from_log_lines(&VEC, common::message_from_log_line) };
+    assert_eq!(lines, vec!["0", "1", "2", "3"]);
+
+    // capacity can no longer change once initialized
+    init!(capacity = 8);
+    assert_eq!(logger().capacity(), 4);
+}
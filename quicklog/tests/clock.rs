@@ -0,0 +1,31 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use quicklog::{info, with_clock};
+use quicklog_clock::Clock;
+
+mod common;
+
+static SIMULATED_TIME: AtomicU64 = AtomicU64::new(0);
+
+struct SimulatedClock;
+
+impl Clock for SimulatedClock {
+    fn get_time(&self) -> u64 {
+        SIMULATED_TIME.load(Ordering::Relaxed)
+    }
+}
+
+fn timestamp_from_log_line(log_line: &str) -> String {
+    log_line[..log_line.find(']').unwrap() + 1].to_string()
+}
+
+fn main() {
+    setup!();
+    with_clock!(SimulatedClock);
+
+    SIMULATED_TIME.store(1_698_048_000_000_000_000, Ordering::Relaxed);
+    helper_assert!(@ info!("first"), "[2023-10-23T08:00:00Z]".to_string(), timestamp_from_log_line);
+
+    SIMULATED_TIME.store(1_698_048_000_000_001_500, Ordering::Relaxed);
+    helper_assert!(@ info!("second"), "[2023-10-23T08:00:00.000001500Z]".to_string(), timestamp_from_log_line);
+}
@@ -36,7 +36,9 @@ impl PatternFormatter for TestFormatter {
     fn custom_format(&mut self, time: DateTime<Utc>, log_record: LogRecord) -> String {
         format!(
             "[{:?}][{}]\t{}\n",
-            time, log_record.level, log_record.log_line
+            time,
+            log_record.level(),
+            log_record.log_line
         )
     }
 }
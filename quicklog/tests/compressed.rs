@@ -0,0 +1,36 @@
+use quicklog::info;
+
+use common::SerializeStruct;
+
+mod common;
+
+fn main() {
+    setup!();
+
+    // large, repetitive arguments such as book snapshots compress well
+    let snapshot: Vec<u64> = (0..256).map(|level| level % 8).collect();
+    let expected = format!("{:?}", snapshot);
+    assert_message_equal!(
+        info!("snapshot: {}", compressed(^snapshot)),
+        format!("snapshot: {}", expected)
+    );
+    assert_message_equal!(
+        info!(compressed(^snapshot), "book"),
+        format!("book snapshot={}", expected)
+    );
+    assert_message_equal!(
+        info!(book = compressed(^snapshot), depth = snapshot.len(), "book"),
+        format!("book book={} depth=256", expected)
+    );
+
+    let s = SerializeStruct {
+        symbol: String::from("Hello"),
+    };
+    assert_message_equal!(info!(compressed(^s), ^s), "s=Hello s=Hello");
+
+    // a function named `compressed` is still called as usual
+    fn compressed(x: u32) -> u32 {
+        x * 2
+    }
+    assert_message_equal!(info!("{}", compressed(21)), "42");
+}
@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+
+use quicklog::{
+    hooks::{clear_decode_hooks, lookup, register_decode_hook},
+    info,
+};
+
+use common::SerializeStruct;
+
+mod common;
+
+fn main() {
+    setup!();
+
+    let symbol_id = 1_u32;
+    let s = SerializeStruct {
+        symbol: String::from("Hello"),
+    };
+
+    // values are kept as is without hooks
+    assert_message_equal!(
+        info!(symbol = symbol_id, "order filled"),
+        "order filled symbol=1"
+    );
+
+    register_decode_hook("symbol", lookup(HashMap::from([(1_u32, "BTCUSD")])));
+    register_decode_hook("order.side", |side| match side {
+        "0" => Some("buy".to_string()),
+        _ => None,
+    });
+    register_decode_hook("s", |value| Some(value.to_uppercase()));
+
+    assert_message_equal!(
+        info!(symbol = symbol_id, "order filled"),
+        "order filled symbol=BTCUSD"
+    );
+    assert_message_equal!(
+        info!(symbol = symbol_id, order.side = 0, qty = 5, "order {}:", 42),
+        "order 42: symbol=BTCUSD order.side=buy qty=5"
+    );
+    assert_message_equal!(info!(^s), "s=HELLO");
+    // values without a mapping are kept
+    assert_message_equal!(info!(symbol = 2, order.side = 1), "symbol=2 order.side=1");
+    // only named fields are enriched
+    assert_message_equal!(info!("symbol {}", symbol_id), "symbol 1");
+
+    clear_decode_hooks();
+    assert_message_equal!(info!(symbol = symbol_id), "symbol=1");
+}
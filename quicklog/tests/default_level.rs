@@ -0,0 +1,63 @@
+use quicklog::info;
+
+mod common;
+
+#[quicklog::default_level(Warn)]
+mod quiet {
+    use quicklog::{debug, error, info, trace, warn};
+
+    pub(crate) fn log_all(value: &str) {
+        trace!("trace {}", value);
+        debug!(?value, "debug");
+        info!("info {}", value);
+        quicklog::info!("qualified info {}", value);
+        warn!("warn {}", value);
+        error!("error {}", value);
+    }
+}
+
+#[quicklog::default_level(Level::Info)]
+fn info_and_above() {
+    quicklog::debug!("debug");
+    quicklog::info!("info");
+}
+
+#[quicklog::default_level(Error)]
+mod other_macros {
+    // not imported from quicklog, so left alone
+    macro_rules! info {
+        ($message:expr) => {
+            $message.to_string()
+        };
+    }
+
+    pub(crate) fn local_info() -> String {
+        info!("kept")
+    }
+}
+
+use quicklog as reexported;
+
+#[quicklog::default_level(Warn, crate = reexported)]
+fn through_reexport() {
+    reexported::info!("info");
+    reexported::warn!("warn");
+}
+
+fn main() {
+    setup!();
+
+    // Only levels at or above `Warn` are emitted from `quiet`
+    assert_message_with_level_equal!(quiet::log_all("a"), "[WARN]\twarn a".to_string());
+    assert_message_with_level_equal!(quicklog::flush!(), "[ERROR]\terror a".to_string());
+
+    assert_message_with_level_equal!(info_and_above(), "[INFO]\tinfo".to_string());
+
+    // Macros which are not quicklog's keep their meaning
+    assert_eq!(other_macros::local_info(), "kept");
+
+    assert_message_with_level_equal!(through_reexport(), "[WARN]\twarn".to_string());
+
+    // Outside of the annotated scopes, levels are unaffected
+    assert_message_with_level_equal!(info!("hello"), "[INFO]\thello".to_string());
+}
@@ -12,4 +12,20 @@ fn derive() {
     t.pass("tests/derive/derive_08_nested_generics.rs");
     t.pass("tests/derive/derive_09_backward_compat.rs");
     t.pass("tests/derive/derive_10_unused_generics.rs");
+    t.pass("tests/derive/derive_11_selective_nested.rs");
+    t.pass("tests/derive/derive_12_selective_strings.rs");
+    t.pass("tests/derive/derive_13_named.rs");
+    t.pass("tests/derive/derive_14_selective_nested_options.rs");
+    t.pass("tests/derive/derive_15_enum.rs");
+    t.pass("tests/derive/derive_16_skip.rs");
+    t.pass("tests/derive/derive_17_selective_max_size.rs");
+    t.pass("tests/derive/derive_18_decode_format.rs");
+    t.pass("tests/derive/derive_19_selective_decode_typed.rs");
+    t.pass("tests/derive/derive_20_float_format.rs");
+    t.pass("tests/derive/derive_21_decode_into.rs");
+    t.pass("tests/derive/derive_22_selective_with.rs");
+    t.pass("tests/derive/derive_23_selective_fmt.rs");
+    t.pass("tests/derive/derive_24_selective_unknown_enum.rs");
+    t.pass("tests/derive/derive_25_try_decode.rs");
+    t.pass("tests/derive/derive_26_selective_decode_format.rs");
 }
@@ -12,5 +12,5 @@ fn main() {
     let mut buf = [0; 128];
 
     let (store, _) = s.encode(&mut buf);
-    assert_eq!(format!("{}", s.size), format!("{}", store))
+    assert_eq!(format!("size={}", s.size), format!("{}", store))
 }
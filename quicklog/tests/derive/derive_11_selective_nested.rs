@@ -0,0 +1,83 @@
+// Testing SerializeSelective with nested structs marked `#[serialize(nested)]`
+use quicklog::serialize::Serialize as _;
+use quicklog::{Serialize, SerializeSelective};
+
+#[derive(SerializeSelective)]
+struct Price {
+    #[serialize]
+    pub px: f64,
+    #[serialize]
+    pub qty: Option<u64>,
+
+    // Not serialized
+    pub venue: String,
+}
+
+#[derive(Serialize)]
+struct Fees {
+    maker: f64,
+    taker: f64,
+}
+
+#[derive(SerializeSelective)]
+struct Order {
+    #[serialize]
+    pub id: u64,
+    #[serialize(nested)]
+    pub price: Price,
+    #[serialize(nested)]
+    pub fees: Fees,
+    #[serialize]
+    pub size: u32,
+
+    // Not serialized
+    pub status: String,
+}
+
+fn main() {
+    let order = Order {
+        id: 42,
+        price: Price {
+            px: 100.5,
+            qty: Some(3),
+            venue: "XNAS".to_string(),
+        },
+        fees: Fees {
+            maker: 0.25,
+            taker: 0.5,
+        },
+        size: 7,
+        status: "Active".to_string(),
+    };
+
+    let mut buf = [0; 256];
+    let (store, rest) = order.encode(&mut buf);
+    let output = format!("{}", store);
+
+    assert_eq!(
+        output,
+        "id=42 price={px=100.5 qty=3} fees={maker=0.25 taker=0.5} size=7"
+    );
+    assert!(!output.contains("XNAS"));
+    assert!(!output.contains("Active"));
+
+    // 8 (id) + (8 + 1 + 8) (price) + (8 + 8) (fees) + 4 (size)
+    assert_eq!(order.buffer_size_required(), 45);
+    assert_eq!(rest.len(), 256 - 45);
+
+    // Nested `None` still decodes with the correct offsets for later fields
+    let order = Order {
+        price: Price {
+            px: 1.0,
+            qty: None,
+            venue: String::new(),
+        },
+        ..order
+    };
+    let mut buf = [0; 256];
+    let (store, _) = order.encode(&mut buf);
+    assert_eq!(
+        format!("{}", store),
+        "id=42 price={px=1 qty=None} fees={maker=0.25 taker=0.5} size=7"
+    );
+}
@@ -0,0 +1,53 @@
+// Testing SerializeSelective with string-like fields
+use quicklog::serialize::Serialize as _;
+use quicklog::SerializeSelective;
+
+#[derive(SerializeSelective)]
+struct Quote<'a> {
+    #[serialize]
+    pub symbol: &'static str,
+    #[serialize]
+    pub venue: &'a str,
+    #[serialize]
+    pub account: String,
+    #[serialize]
+    pub book: heapless::String<8>,
+    #[serialize]
+    pub bid: f64,
+    #[serialize]
+    pub tag: Option<&'static str>,
+    #[serialize]
+    pub note: Option<String>,
+
+    // Not serialized
+    pub raw: String,
+}
+
+fn main() {
+    let venue = String::from("XNAS");
+    let mut book = heapless::String::new();
+    book.push_str("L2").unwrap();
+
+    let quote = Quote {
+        symbol: "AAPL",
+        venue: venue.as_str(),
+        account: "acc-1".to_string(),
+        book,
+        bid: 189.5,
+        tag: Some("mm"),
+        note: None,
+        raw: "ignored".to_string(),
+    };
+
+    let mut buf = [0; 256];
+    let (store, _) = quote.encode(&mut buf);
+
+    assert_eq!(
+        format!("{}", store),
+        "symbol=AAPL venue=XNAS account=acc-1 book=L2 bid=189.5 tag=mm note=None"
+    );
+
+    // Strings are length-prefixed, 8 bytes each on 64-bit targets
+    let size = (8 + 4) + (8 + 4) + (8 + 5) + (8 + 2) + 8 + (1 + 8 + 2) + 1;
+    assert_eq!(quote.buffer_size_required(), size);
+}
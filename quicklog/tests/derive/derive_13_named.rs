@@ -0,0 +1,77 @@
+// Testing `#[serialize(transparent)]`, `#[serialize(named)]` and named fields.
+use quicklog::serialize::Serialize as _;
+use quicklog::Serialize;
+
+#[derive(Serialize)]
+struct Timestamp(u64);
+
+#[derive(Serialize)]
+#[serialize(transparent)]
+struct Price(u64);
+
+#[derive(Serialize)]
+#[serialize(named)]
+struct Quantity(u64);
+
+#[derive(Serialize)]
+#[serialize(named)]
+struct Level(u64, Quantity);
+
+#[derive(Serialize)]
+struct Fill {
+    id: u32,
+    r#type: u8,
+}
+
+#[derive(Serialize)]
+#[serialize(transparent)]
+struct Quote {
+    bid: u32,
+    ask: u32,
+}
+
+#[derive(Serialize)]
+#[serialize(named)]
+struct Order<'a> {
+    id: u32,
+    symbol: &'a str,
+    qty: Quantity,
+}
+
+fn main() {
+    let mut buf = [0; 128];
+
+    let (store, _) = Timestamp(100).encode(&mut buf);
+    assert_eq!(format!("{}", store), "100");
+
+    let (store, _) = Price(100).encode(&mut buf);
+    assert_eq!(format!("{}", store), "100");
+
+    let (store, _) = Quantity(100).encode(&mut buf);
+    assert_eq!(format!("{}", store), "Quantity(100)");
+
+    let (store, _) = Level(101, Quantity(5)).encode(&mut buf);
+    assert_eq!(format!("{}", store), "Level(101, Quantity(5))");
+
+    // fields are named by default, unless opted out of
+    let (store, _) = Fill { id: 3, r#type: 1 }.encode(&mut buf);
+    assert_eq!(format!("{}", store), "id=3 type=1");
+    let (store, _) = Quote { bid: 99, ask: 101 }.encode(&mut buf);
+    assert_eq!(format!("{}", store), "99 101");
+
+    let order = Order {
+        id: 1,
+        symbol: "BTCUSD",
+        qty: Quantity(5),
+    };
+    let (store, rest) = order.encode(&mut buf);
+    assert_eq!(
+        format!("{}", store),
+        "Order { id: 1, symbol: BTCUSD, qty: Quantity(5) }"
+    );
+
+    // named newtypes still decode back to back with other values
+    let (a, rest) = Quantity(7).encode(rest);
+    let (b, _) = Timestamp(8).encode(rest);
+    assert_eq!(format!("{} {}", a, b), "Quantity(7) 8");
+}
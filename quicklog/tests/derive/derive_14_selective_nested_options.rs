@@ -0,0 +1,64 @@
+// Testing SerializeSelective with nested Option fields
+use quicklog::serialize::Serialize as _;
+use quicklog::SerializeSelective;
+
+#[derive(SerializeSelective)]
+struct Update {
+    #[serialize]
+    pub id: u64,
+    #[serialize]
+    pub price: Option<Option<f64>>,
+    #[serialize]
+    pub size: Option<Option<Option<u32>>>,
+    #[serialize]
+    pub venue: Option<Option<&'static str>>,
+    #[serialize]
+    pub note: Option<Option<Option<String>>>,
+}
+
+fn main() {
+    let mut buf = [0; 256];
+
+    let update = Update {
+        id: 1,
+        price: Some(Some(100.5)),
+        size: Some(Some(Some(7))),
+        venue: Some(Some("XNAS")),
+        note: Some(Some(Some("first".to_string()))),
+    };
+    let (store, _) = update.encode(&mut buf);
+    assert_eq!(
+        format!("{}", store),
+        "id=1 price=Some(Some(100.5)) size=Some(Some(Some(7))) venue=Some(Some(XNAS)) note=Some(Some(Some(first)))"
+    );
+    let size = 8 + (2 + 8) + (3 + 4) + (2 + 8 + 4) + (3 + 8 + 5);
+    assert_eq!(update.buffer_size_required(), size);
+
+    let update = Update {
+        id: 2,
+        price: Some(None),
+        size: Some(Some(None)),
+        venue: Some(None),
+        note: Some(None),
+    };
+    let (store, _) = update.encode(&mut buf);
+    assert_eq!(
+        format!("{}", store),
+        "id=2 price=Some(None) size=Some(Some(None)) venue=Some(None) note=Some(None)"
+    );
+    assert_eq!(update.buffer_size_required(), 8 + 2 + 3 + 2 + 2);
+
+    let update = Update {
+        id: 3,
+        price: None,
+        size: None,
+        venue: None,
+        note: None,
+    };
+    let (store, _) = update.encode(&mut buf);
+    assert_eq!(
+        format!("{}", store),
+        "id=3 price=None size=None venue=None note=None"
+    );
+    assert_eq!(update.buffer_size_required(), 8 + 1 + 1 + 1 + 1);
+}
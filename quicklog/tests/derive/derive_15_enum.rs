@@ -0,0 +1,100 @@
+// Testing enums with unit and data-carrying variants
+use quicklog::serialize::Serialize as _;
+use quicklog::Serialize;
+
+#[derive(Serialize)]
+struct Quantity(u64);
+
+#[derive(Serialize)]
+enum Side {
+    Buy,
+    Sell,
+}
+
+// Discriminants do not need to be contiguous
+#[derive(Serialize)]
+#[repr(u8)]
+enum Status {
+    New = 10,
+    Filled = 20,
+}
+
+#[derive(Serialize)]
+enum Event<'a> {
+    Heartbeat,
+    Fill(u64, f64),
+    Cancel { id: u64, reason: &'a str },
+    Amend { side: Side, qty: Quantity },
+}
+
+#[derive(Serialize)]
+#[serialize(named)]
+enum Venue {
+    Binance,
+    Other(u16),
+}
+
+#[derive(Serialize)]
+enum Wrapper<T: quicklog::serialize::Serialize> {
+    Value(T),
+    Empty,
+}
+
+#[derive(Serialize)]
+enum Never {}
+
+fn main() {
+    let mut buf = [0; 256];
+
+    let (store, rest) = Side::Sell.encode(&mut buf);
+    assert_eq!(format!("{}", store), "Sell");
+    assert_eq!(Side::Buy.buffer_size_required(), 1);
+
+    // encoded by their index rather than their discriminant
+    let (store, _) = Status::Filled.encode(rest);
+    assert_eq!(format!("{}", store), "Filled");
+    let (store, _) = Status::New.encode(&mut buf);
+    assert_eq!(format!("{}", store), "New");
+
+    let (store, _) = Event::Heartbeat.encode(&mut buf);
+    assert_eq!(format!("{}", store), "Heartbeat");
+
+    let fill = Event::Fill(1, 100.5);
+    assert_eq!(fill.buffer_size_required(), 1 + 8 + 8);
+    let (store, _) = fill.encode(&mut buf);
+    assert_eq!(format!("{}", store), "Fill(1, 100.5)");
+
+    let reason = String::from("user");
+    let cancel = Event::Cancel {
+        id: 2,
+        reason: &reason,
+    };
+    let (store, _) = cancel.encode(&mut buf);
+    assert_eq!(format!("{}", store), "Cancel { id: 2, reason: user }");
+
+    let amend = Event::Amend {
+        side: Side::Buy,
+        qty: Quantity(5),
+    };
+    assert_eq!(amend.buffer_size_required(), 1 + 1 + 8);
+    let (store, _) = amend.encode(&mut buf);
+    assert_eq!(format!("{}", store), "Amend { side: Buy, qty: 5 }");
+
+    let (store, _) = Venue::Binance.encode(&mut buf);
+    assert_eq!(format!("{}", store), "Venue::Binance");
+    let (store, _) = Venue::Other(7).encode(&mut buf);
+    assert_eq!(format!("{}", store), "Venue::Other(7)");
+
+    let (store, rest) = Wrapper::Value(3_u32).encode(&mut buf);
+    assert_eq!(format!("{}", store), "Value(3)");
+    let (store, _) = Wrapper::<u32>::Empty.encode(rest);
+    assert_eq!(format!("{}", store), "Empty");
+
+    // enums decode back to back with other values
+    let (a, rest) = Side::Buy.encode(&mut buf);
+    let (b, rest) = Event::Fill(3, 1.5).encode(rest);
+    let (c, _) = 9_u32.encode(rest);
+    assert_eq!(format!("{} {} {}", a, b, c), "Buy Fill(3, 1.5) 9");
+
+    let _ = |never: Never| never.buffer_size_required();
+}
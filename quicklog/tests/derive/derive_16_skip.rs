@@ -0,0 +1,89 @@
+// Testing `#[serialize(skip)]` on struct and enum variant fields
+use quicklog::serialize::Serialize as _;
+use quicklog::Serialize;
+
+#[derive(Serialize)]
+struct Message {
+    id: u64,
+    #[serialize(skip)]
+    payload: Vec<u8>,
+    len: usize,
+}
+
+#[derive(Serialize)]
+#[serialize(named)]
+struct Login<'a> {
+    user: &'a str,
+    #[serialize(skip)]
+    password: &'a str,
+}
+
+#[derive(Serialize)]
+struct Frame(u32, #[serialize(skip)] Vec<u8>, u8);
+
+// Only one field left, decoded by the field itself
+#[derive(Serialize)]
+struct Wrapper(#[serialize(skip)] Vec<u8>, u16);
+
+#[derive(Serialize)]
+#[serialize(named)]
+struct Secret {
+    #[serialize(skip)]
+    key: [u8; 32],
+}
+
+#[derive(Serialize)]
+enum Event {
+    Data(u64, #[serialize(skip)] Vec<u8>),
+    Auth {
+        user: &'static str,
+        #[serialize(skip)]
+        token: String,
+    },
+    Opaque(#[serialize(skip)] Vec<u8>),
+}
+
+fn main() {
+    let mut buf = [0; 128];
+
+    let message = Message {
+        id: 1,
+        payload: vec![0; 1024],
+        len: 1024,
+    };
+    assert_eq!(message.buffer_size_required(), 16);
+    let (store, _) = message.encode(&mut buf);
+    assert_eq!(format!("{}", store), "id=1 len=1024");
+
+    let login = Login {
+        user: "alice",
+        password: "hunter2",
+    };
+    let (store, _) = login.encode(&mut buf);
+    assert_eq!(format!("{}", store), "Login { user: alice }");
+
+    let (store, _) = Frame(7, vec![1, 2, 3], 9).encode(&mut buf);
+    assert_eq!(format!("{}", store), "7 9");
+
+    let (store, _) = Wrapper(vec![1], 5).encode(&mut buf);
+    assert_eq!(format!("{}", store), "5");
+
+    let secret = Secret { key: [1; 32] };
+    assert_eq!(secret.buffer_size_required(), 0);
+    let (store, rest) = secret.encode(&mut buf);
+    assert_eq!(format!("{}", store), "Secret");
+    assert_eq!(rest.len(), 128);
+
+    let (store, _) = Event::Data(3, vec![0; 64]).encode(&mut buf);
+    assert_eq!(format!("{}", store), "Data(3)");
+    let auth = Event::Auth {
+        user: "bob",
+        token: "t0k3n".to_string(),
+    };
+    let (store, _) = auth.encode(&mut buf);
+    assert_eq!(format!("{}", store), "Auth { user: bob }");
+    let opaque = Event::Opaque(vec![0; 64]);
+    assert_eq!(opaque.buffer_size_required(), 1);
+    let (store, _) = opaque.encode(&mut buf);
+    assert_eq!(format!("{}", store), "Opaque");
+}
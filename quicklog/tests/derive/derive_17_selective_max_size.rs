@@ -0,0 +1,59 @@
+// Testing the MAX_ENCODED_SIZE constant generated by SerializeSelective
+use quicklog::serialize::Serialize as _;
+use quicklog::SerializeSelective;
+
+#[derive(SerializeSelective)]
+struct Order {
+    #[serialize]
+    pub oid: u64,
+    #[serialize]
+    pub price: Option<f64>,
+    #[serialize]
+    pub size: Option<Option<u32>>,
+    pub status: &'static str,
+}
+
+#[derive(SerializeSelective)]
+struct Fill<T>
+where
+    T: quicklog::serialize::FixedSizeSerialize<2> + std::fmt::Display,
+{
+    #[serialize]
+    pub venue: T,
+    #[serialize]
+    pub qty: i32,
+}
+
+fn main() {
+    assert_eq!(Order::MAX_ENCODED_SIZE, 8 + (1 + 8) + (2 + 4));
+
+    // the constant can size buffers on the stack
+    let mut buf = [0; Order::MAX_ENCODED_SIZE];
+    let order = Order {
+        oid: 1,
+        price: Some(10.5),
+        size: Some(Some(3)),
+        status: "open",
+    };
+    assert_eq!(order.buffer_size_required(), Order::MAX_ENCODED_SIZE);
+    let (store, rest) = order.encode(&mut buf);
+    assert!(rest.is_empty());
+    assert_eq!(format!("{}", store), "oid=1 price=10.5 size=Some(Some(3))");
+
+    let order = Order {
+        oid: 2,
+        price: None,
+        size: Some(None),
+        status: "cancelled",
+    };
+    assert!(order.buffer_size_required() < Order::MAX_ENCODED_SIZE);
+    let (store, _) = order.encode(&mut buf);
+    assert_eq!(format!("{}", store), "oid=2 price=None size=Some(None)");
+
+    assert_eq!(Fill::<u16>::MAX_ENCODED_SIZE, 2 + 4);
+    let fill = Fill { venue: 7_u16, qty: -5 };
+    assert_eq!(fill.buffer_size_required(), Fill::<u16>::MAX_ENCODED_SIZE);
+    let mut buf = [0; Fill::<u16>::MAX_ENCODED_SIZE];
+    let (store, _) = fill.encode(&mut buf);
+    assert_eq!(format!("{}", store), "venue=7 qty=-5");
+}
@@ -0,0 +1,60 @@
+// Testing decode-time `unit`, `scale` and `precision` field attributes
+use quicklog::serialize::Serialize as _;
+use quicklog::Serialize;
+
+#[derive(Serialize)]
+struct Fill {
+    id: u64,
+    #[serialize(scale = 0.01, precision = 2, unit = " USD")]
+    pnl_cents: i64,
+    #[serialize(unit = "bps")]
+    spread: u32,
+    #[serialize(scale = 8, unit = "ns")]
+    latency_ticks: u64,
+}
+
+// Only one field, which must still be decoded with its unit
+#[derive(Serialize)]
+struct Latency(#[serialize(unit = "us")] u64);
+
+#[derive(Serialize)]
+#[serialize(named)]
+struct Price(#[serialize(precision = 3)] f64);
+
+#[derive(Serialize)]
+enum Event {
+    Fill {
+        #[serialize(scale = 0.5)]
+        qty: u32,
+    },
+    Timeout(#[serialize(unit = "ms")] u16),
+}
+
+fn main() {
+    let mut buf = [0; 128];
+
+    let fill = Fill {
+        id: 1,
+        pnl_cents: -12345,
+        spread: 3,
+        latency_ticks: 125,
+    };
+    // Raw values are still encoded as-is
+    assert_eq!(fill.buffer_size_required(), 28);
+    let (store, _) = fill.encode(&mut buf);
+    assert_eq!(
+        format!("{}", store),
+        "id=1 pnl_cents=-123.45 USD spread=3bps latency_ticks=1000ns"
+    );
+
+    let (store, _) = Latency(250).encode(&mut buf);
+    assert_eq!(format!("{}", store), "250us");
+
+    let (store, _) = Price(100.5).encode(&mut buf);
+    assert_eq!(format!("{}", store), "Price(100.500)");
+
+    let (store, _) = Event::Fill { qty: 3 }.encode(&mut buf);
+    assert_eq!(format!("{}", store), "Fill { qty: 1.5 }");
+    let (store, _) = Event::Timeout(30).encode(&mut buf);
+    assert_eq!(format!("{}", store), "Timeout(30ms)");
+}
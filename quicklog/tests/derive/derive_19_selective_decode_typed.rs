@@ -0,0 +1,100 @@
+// Testing typed decoding of SerializeSelective structs with `decode_typed`
+use quicklog::serialize::{DecodeTyped, FixedSizeSerialize, Serialize as _};
+use quicklog::SerializeSelective;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OrderId(u64);
+
+impl FixedSizeSerialize<8> for OrderId {
+    fn to_le_bytes(&self) -> [u8; 8] {
+        self.0.to_le_bytes()
+    }
+
+    fn from_le_bytes(bytes: [u8; 8]) -> Self {
+        OrderId(u64::from_le_bytes(bytes))
+    }
+}
+
+impl std::fmt::Display for OrderId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(SerializeSelective)]
+#[serialize(decode_typed(derive(Debug, Clone, PartialEq)))]
+pub struct Price {
+    #[serialize]
+    pub px: f64,
+    #[serialize]
+    pub qty: u64,
+}
+
+#[derive(SerializeSelective)]
+#[serialize(decode_typed(derive(Debug, Clone, PartialEq)))]
+pub struct Order<'a> {
+    #[serialize]
+    pub oid: OrderId,
+    #[serialize]
+    pub cloid: Option<Option<u64>>,
+    #[serialize]
+    pub symbol: &'a str,
+    #[serialize]
+    pub account: Option<String>,
+    #[serialize(nested)]
+    pub price: Price,
+    #[allow(dead_code)]
+    pub notes: Vec<String>,
+}
+
+// Without derives on the decoded struct
+#[derive(SerializeSelective)]
+#[serialize(decode_typed)]
+struct Heartbeat {
+    #[serialize]
+    seq: u32,
+}
+
+fn main() {
+    let order = Order {
+        oid: OrderId(7),
+        cloid: Some(None),
+        symbol: "BTC-USD",
+        account: Some("main".to_string()),
+        price: Price {
+            px: 100.5,
+            qty: 3,
+        },
+        notes: vec!["ignored".to_string()],
+    };
+
+    let mut buf = [0; 256];
+    let (store, rest) = order.encode(&mut buf);
+    let display = format!("{}", store);
+    let used = 256 - rest.len();
+
+    let (decoded, remaining) = Order::decode_typed(&buf[..used]);
+    assert!(remaining.is_empty());
+    assert_eq!(
+        decoded,
+        OrderDecoded {
+            oid: OrderId(7),
+            cloid: Some(None),
+            symbol: "BTC-USD".to_string(),
+            account: Some("main".to_string()),
+            price: PriceDecoded {
+                px: 100.5,
+                qty: 3,
+            },
+        }
+    );
+    // The display text is unchanged
+    assert_eq!(
+        display,
+        "oid=7 cloid=Some(None) symbol=BTC-USD account=main price={px=100.5 qty=3}"
+    );
+
+    let (_, _) = Heartbeat { seq: 9 }.encode(&mut buf);
+    let (heartbeat, _) = Heartbeat::decode_typed(&buf);
+    assert_eq!(heartbeat.seq, 9);
+}
@@ -0,0 +1,80 @@
+// Testing the global `FloatFormat` and its per-field overrides
+use quicklog::serialize::{set_float_format, FloatFormat, Serialize as _};
+use quicklog::{Serialize, SerializeSelective};
+
+#[derive(Serialize)]
+struct Quote {
+    bid: f64,
+    #[serialize(shortest)]
+    ratio: f32,
+    #[serialize(precision = 4)]
+    ask: f64,
+    #[serialize(scale = 0.01)]
+    pnl_cents: i64,
+    size: u64,
+}
+
+#[derive(SerializeSelective)]
+struct Order {
+    #[serialize]
+    px: f64,
+    #[serialize]
+    stop_px: Option<f64>,
+    #[serialize(shortest)]
+    fee: f32,
+    #[serialize(precision = 1)]
+    qty: f64,
+    #[serialize]
+    id: u64,
+}
+
+fn main() {
+    let mut buf = [0; 128];
+    let quote = Quote {
+        bid: 101.5,
+        ratio: 0.1,
+        ask: 101.75,
+        pnl_cents: -12345,
+        size: 3,
+    };
+    let order = Order {
+        px: 0.1,
+        stop_px: Some(99.0),
+        fee: 0.25,
+        qty: 2.0,
+        id: 7,
+    };
+
+    // Shortest by default
+    let (store, _) = quote.encode(&mut buf);
+    assert_eq!(
+        format!("{}", store),
+        "bid=101.5 ratio=0.1 ask=101.7500 pnl_cents=-123.45 size=3"
+    );
+    let (store, _) = order.encode(&mut buf);
+    assert_eq!(
+        format!("{}", store),
+        "px=0.1 stop_px=99 fee=0.25 qty=2.0 id=7"
+    );
+    let (store, _) = 0.1_f32.encode(&mut buf);
+    assert_eq!(format!("{}", store), "0.1");
+
+    set_float_format(FloatFormat::Fixed(2));
+    assert_eq!(FloatFormat::global(), FloatFormat::Fixed(2));
+
+    let (store, _) = quote.encode(&mut buf);
+    assert_eq!(
+        format!("{}", store),
+        "bid=101.50 ratio=0.1 ask=101.7500 pnl_cents=-123.45 size=3"
+    );
+    let (store, _) = order.encode(&mut buf);
+    assert_eq!(
+        format!("{}", store),
+        "px=0.10 stop_px=99.00 fee=0.25 qty=2.0 id=7"
+    );
+    let (store, _) = 0.125_f64.encode(&mut buf);
+    assert_eq!(format!("{}", store), "0.12");
+    // Integers are unaffected
+    let (store, _) = 5_u32.encode(&mut buf);
+    assert_eq!(format!("{}", store), "5");
+}
@@ -0,0 +1,77 @@
+// Testing that `decode_into` writes the same text as `decode`
+use quicklog::serialize::Serialize as _;
+use quicklog::Serialize;
+
+#[derive(Serialize)]
+struct Order<'a> {
+    id: u64,
+    symbol: &'a str,
+    // cannot shadow the writer of `decode_into`
+    #[serialize(unit = "bps")]
+    out: u32,
+}
+
+#[derive(Serialize)]
+#[serialize(named)]
+struct Quote {
+    bid: Option<f64>,
+    sizes: Vec<u32>,
+}
+
+#[derive(Serialize)]
+#[serialize(named)]
+struct Empty {
+    #[serialize(skip)]
+    _reserved: u8,
+}
+
+#[derive(Serialize)]
+enum Event {
+    Heartbeat,
+    Ack(u64),
+    Reject { id: u64, reason: String },
+}
+
+fn assert_decode_into<T: quicklog::serialize::Serialize>(value: T) {
+    let mut buf = [0; 256];
+    let (_, rest) = value.encode(&mut buf);
+    let len = 256 - rest.len();
+
+    let (decoded, decode_rest) = T::decode(&buf[..len]);
+    let mut line = String::new();
+    let decode_into_rest = T::decode_into(&buf[..len], &mut line).unwrap();
+    assert_eq!(line, decoded);
+    assert!(decode_rest.is_empty());
+    assert!(decode_into_rest.is_empty());
+}
+
+fn main() {
+    assert_decode_into(Order {
+        id: 1,
+        symbol: "BTC-USD",
+        out: 3,
+    });
+    assert_decode_into(Quote {
+        bid: Some(100.5),
+        sizes: vec![1, 2],
+    });
+    assert_decode_into(Empty { _reserved: 0 });
+    assert_decode_into(Event::Heartbeat);
+    assert_decode_into(Event::Ack(7));
+    assert_decode_into(Event::Reject {
+        id: 7,
+        reason: "too late".to_string(),
+    });
+
+    // decoded straight into the formatter when displayed
+    let mut buf = [0; 64];
+    let (store, _) = Event::Ack(7).encode(&mut buf);
+    assert_eq!(format!("{}", store), "Ack(7)");
+    let (store, _) = Empty { _reserved: 0 }.encode(&mut buf);
+    assert_eq!(format!("{}", store), "Empty");
+
+    // unknown variants are decoded as such
+    let mut line = String::new();
+    Event::decode_into(&[9], &mut line).unwrap();
+    assert_eq!(line, "UnknownVariant");
+}
@@ -0,0 +1,87 @@
+// Testing SerializeSelective with fields encoded by `#[serialize(with = "path")]`
+use quicklog::serialize::{DecodeTyped, Serialize as _};
+use quicklog::SerializeSelective;
+
+/// Keeps at most the first 16 bytes of a string
+mod truncated {
+    const MAX_LEN: usize = 16;
+
+    pub fn buffer_size_required(value: &String) -> usize {
+        1 + value.len().min(MAX_LEN)
+    }
+
+    pub fn encode(value: &String, write_buf: &mut [u8]) {
+        let (len, bytes) = write_buf.split_first_mut().unwrap();
+        *len = bytes.len() as u8;
+        bytes.copy_from_slice(&value.as_bytes()[..bytes.len()]);
+    }
+
+    pub fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        let (len, rest) = read_buf.split_first().unwrap();
+        let (bytes, rest) = rest.split_at(*len as usize);
+        (String::from_utf8_lossy(bytes).into_owned(), rest)
+    }
+}
+
+/// Venue code packed into a fixed array, without `FixedSizeSerialize`
+struct Venue([u8; 4]);
+
+mod venue {
+    pub fn buffer_size_required(_: &super::Venue) -> usize {
+        4
+    }
+
+    pub fn encode(value: &super::Venue, write_buf: &mut [u8]) {
+        write_buf.copy_from_slice(&value.0);
+    }
+
+    pub fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        let (code, rest) = read_buf.split_at(4);
+        (String::from_utf8_lossy(code).into_owned(), rest)
+    }
+}
+
+#[derive(SerializeSelective)]
+#[serialize(decode_typed(derive(Debug, PartialEq)))]
+struct Order {
+    #[serialize]
+    pub id: u64,
+    #[serialize(with = "truncated")]
+    pub note: String,
+    #[serialize(with = "venue")]
+    pub venue: Venue,
+    #[serialize]
+    pub size: u32,
+}
+
+fn main() {
+    let order = Order {
+        id: 42,
+        note: "client requested a partial cancel".to_string(),
+        venue: Venue(*b"XNAS"),
+        size: 7,
+    };
+
+    let mut buf = [0; 128];
+    let (store, rest) = order.encode(&mut buf);
+    assert_eq!(
+        format!("{}", store),
+        "id=42 note=client requested venue=XNAS size=7"
+    );
+
+    // 8 (id) + (1 + 16) (note) + 4 (venue) + 4 (size)
+    assert_eq!(order.buffer_size_required(), 33);
+    assert_eq!(rest.len(), 128 - 33);
+
+    let (decoded, rest) = Order::decode_typed(&buf);
+    assert_eq!(
+        decoded,
+        OrderDecoded {
+            id: 42,
+            note: "client requested".to_string(),
+            venue: "XNAS".to_string(),
+            size: 7,
+        }
+    );
+    assert_eq!(rest.len(), 128 - 33);
+}
@@ -0,0 +1,45 @@
+// Testing SerializeSelective fields rendered with `#[serialize(fmt = "...")]`
+use quicklog::serialize::Serialize as _;
+use quicklog::SerializeSelective;
+
+#[derive(SerializeSelective)]
+struct Order {
+    #[serialize(fmt = "{:.4}")]
+    px: f64,
+    #[serialize(fmt = "{:.2}")]
+    stop_px: Option<f64>,
+    #[serialize(fmt = "{:#x}")]
+    flags: u16,
+    #[serialize(fmt = "{:06}")]
+    account: u32,
+    #[serialize]
+    id: u64,
+}
+
+fn main() {
+    let mut buf = [0; 128];
+    let order = Order {
+        px: 101.5,
+        stop_px: Some(99.0),
+        flags: 0x0104,
+        account: 42,
+        id: 7,
+    };
+
+    let (store, _) = order.encode(&mut buf);
+    assert_eq!(
+        format!("{}", store),
+        "px=101.5000 stop_px=99.00 flags=0x104 account=000042 id=7"
+    );
+
+    // None is still rendered as is
+    let order = Order {
+        stop_px: None,
+        ..order
+    };
+    let (store, _) = order.encode(&mut buf);
+    assert_eq!(
+        format!("{}", store),
+        "px=101.5000 stop_px=None flags=0x104 account=000042 id=7"
+    );
+}
@@ -0,0 +1,52 @@
+// Testing that SerializeSelective structs render invalid enum bytes instead of
+// panicking, e.g. when reading data written by a newer version
+use quicklog::serialize::{format_unknown, FixedSizeSerialize, Serialize as _};
+use quicklog::{impl_fixed_size_serialize_enum, SerializeSelective};
+
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Side {
+    Buy = 0,
+    Sell = 1,
+}
+impl_fixed_size_serialize_enum!(Side, Buy = 0, Sell = 1);
+
+impl std::fmt::Display for Side {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(self, f)
+    }
+}
+
+#[derive(SerializeSelective)]
+struct Order {
+    #[serialize]
+    side: Side,
+    #[serialize]
+    prev_side: Option<Side>,
+    #[serialize]
+    id: u64,
+}
+
+fn main() {
+    assert_eq!(Side::try_from_le_bytes([1]), Some(Side::Sell));
+    assert_eq!(Side::try_from_le_bytes([7]), None);
+    assert_eq!(format_unknown(&[7]), "Unknown(7)");
+    assert_eq!(format_unknown(&[0xff; 20]), format!("Unknown(0x{})", "ff".repeat(20)));
+
+    let order = Order {
+        side: Side::Buy,
+        prev_side: Some(Side::Sell),
+        id: 42,
+    };
+    let mut buf = [0; 64];
+    let (store, _) = order.encode(&mut buf);
+    assert_eq!(format!("{}", store), "side=Buy prev_side=Sell id=42");
+
+    // Corrupt both discriminants, later fields still decode at the right offset
+    let (side, rest) = buf.split_at_mut(1);
+    side[0] = 7;
+    rest[1] = 9;
+    let (output, rest) = Order::decode(&buf);
+    assert_eq!(output, "side=Unknown(7) prev_side=Unknown(9) id=42");
+    assert_eq!(rest.len(), 64 - order.buffer_size_required());
+}
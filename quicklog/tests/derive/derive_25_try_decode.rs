@@ -0,0 +1,113 @@
+// Testing that derived implementations reject corrupt bytes with `try_decode`,
+// and write them as `<corrupt>` when decoded into a writer
+use quicklog::serialize::{DecodeTyped, Serialize, Store, CORRUPT};
+use quicklog::{Error, Serialize, SerializeSelective};
+
+#[derive(Serialize)]
+struct Fill {
+    id: u64,
+    venue: String,
+    #[serialize(unit = "bps")]
+    fee: u32,
+}
+
+#[derive(Serialize)]
+enum Event {
+    Heartbeat,
+    Fill(u64, f64),
+}
+
+#[derive(SerializeSelective)]
+#[serialize(decode_typed(derive(Debug, PartialEq)))]
+struct Order {
+    #[serialize]
+    id: u64,
+    #[serialize]
+    stop_px: Option<f64>,
+    #[serialize]
+    symbol: String,
+}
+
+fn main() {
+    let mut buf = [0; 128];
+
+    let fill = Fill {
+        id: 7,
+        venue: "XNAS".to_string(),
+        fee: 3,
+    };
+    let (store, _) = fill.encode(&mut buf);
+    assert_eq!(store.to_string(), "id=7 venue=XNAS fee=3bps");
+    let size = fill.buffer_size_required();
+    assert_eq!(
+        Fill::try_decode(&buf[..size]).unwrap().0,
+        "id=7 venue=XNAS fee=3bps"
+    );
+    // the unit field is cut short, then the string as well
+    assert!(matches!(
+        Fill::try_decode(&buf[..size - 1]),
+        Err(Error::DecodeCorrupt)
+    ));
+    let truncated = Store::new_into(Fill::decode_into, &buf[..size - 1]);
+    assert_eq!(
+        truncated.to_string(),
+        format!("id=7 venue=XNAS fee={}", CORRUPT)
+    );
+    let truncated = Store::new_into(Fill::decode_into, &buf[..10]);
+    assert_eq!(
+        truncated.to_string(),
+        format!("id=7 venue={} fee={}", CORRUPT, CORRUPT)
+    );
+
+    let (store, _) = Event::Fill(1, 2.5).encode(&mut buf);
+    assert_eq!(store.to_string(), "Fill(1, 2.5)");
+    assert!(matches!(Event::try_decode(&[]), Err(Error::DecodeCorrupt)));
+    assert!(matches!(
+        Event::try_decode(&buf[..5]),
+        Err(Error::DecodeCorrupt)
+    ));
+    assert_eq!(Event::try_decode(&[0]).unwrap().0, "Heartbeat");
+    assert_eq!(
+        Store::new_into(Event::decode_into, &[]).to_string(),
+        CORRUPT
+    );
+
+    let order = Order {
+        id: 42,
+        stop_px: Some(99.5),
+        symbol: "AAPL".to_string(),
+    };
+    let (store, _) = order.encode(&mut buf);
+    assert_eq!(store.to_string(), "id=42 stop_px=99.5 symbol=AAPL");
+    let size = order.buffer_size_required();
+    assert!(Order::try_decode(&buf[..size]).is_ok());
+    assert!(matches!(
+        Order::try_decode(&buf[..12]),
+        Err(Error::DecodeCorrupt)
+    ));
+    assert!(matches!(
+        Order::try_decode(&buf[..size - 2]),
+        Err(Error::DecodeCorrupt)
+    ));
+    // the whole record is written as corrupt, as its fields are decoded together
+    let truncated = Store::new_into(Order::decode_into, &buf[..size - 2]);
+    assert_eq!(truncated.to_string(), CORRUPT);
+
+    // typed decoding checks its input the same way
+    let (decoded, rest) = Order::try_decode_typed(&buf[..size]).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(
+        decoded,
+        OrderDecoded {
+            id: 42,
+            stop_px: Some(99.5),
+            symbol: "AAPL".to_string(),
+        }
+    );
+    for len in [0, 4, 8, 12, size - 2] {
+        assert!(matches!(
+            Order::try_decode_typed(&buf[..len]),
+            Err(Error::DecodeCorrupt)
+        ));
+    }
+}
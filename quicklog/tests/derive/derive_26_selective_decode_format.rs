@@ -0,0 +1,50 @@
+// Testing SerializeSelective fields rendered with `unit` and `scale`
+use quicklog::serialize::Serialize as _;
+use quicklog::SerializeSelective;
+
+#[derive(SerializeSelective)]
+struct Fill {
+    #[serialize]
+    id: u64,
+    #[serialize(scale = 0.01, precision = 2, unit = " USD")]
+    pnl_cents: i64,
+    #[serialize(unit = "bps")]
+    spread: u32,
+    #[serialize(scale = 8, unit = "ns")]
+    latency_ticks: Option<u64>,
+    #[serialize(scale = 0.5)]
+    qty: u32,
+    // Not serialized
+    venue: String,
+}
+
+fn main() {
+    let mut buf = [0; 128];
+
+    let fill = Fill {
+        id: 1,
+        pnl_cents: -12345,
+        spread: 3,
+        latency_ticks: Some(125),
+        qty: 3,
+        venue: "XNAS".to_string(),
+    };
+    // Raw values are still encoded as-is
+    assert_eq!(fill.buffer_size_required(), 33);
+    let (store, _) = fill.encode(&mut buf);
+    assert_eq!(
+        format!("{}", store),
+        "id=1 pnl_cents=-123.45 USD spread=3bps latency_ticks=1000ns qty=1.5"
+    );
+
+    // None is still rendered as is, without its unit
+    let fill = Fill {
+        latency_ticks: None,
+        ..fill
+    };
+    let (store, _) = fill.encode(&mut buf);
+    assert_eq!(
+        format!("{}", store),
+        "id=1 pnl_cents=-123.45 USD spread=3bps latency_ticks=None qty=1.5"
+    );
+}
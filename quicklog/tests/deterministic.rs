@@ -0,0 +1,76 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use quicklog::{
+    flush_all, info, init, logfmt::LogfmtFormatter, queue::QueueFullPolicy, serialize::FieldMap,
+    timestamp::TimestampFormat, warn, with_flush, with_output,
+};
+use quicklog_clock::Clock;
+
+use common::{SerializeStruct, VecFlusher};
+
+mod common;
+
+static mut TEXT: Vec<String> = Vec::new();
+static mut LOGFMT: Vec<String> = Vec::new();
+
+/// Replays timestamps recorded in market data
+static REPLAYED_TIME: AtomicU64 = AtomicU64::new(0);
+
+struct ReplayClock;
+
+impl Clock for ReplayClock {
+    fn get_time(&self) -> u64 {
+        REPLAYED_TIME.load(Ordering::Relaxed)
+    }
+}
+
+const GOLDEN_TEXT: [&str; 4] = [
+    "[1698048000000000000][0]order 1 accepted serialize=BTC-USD\n",
+    "[1698048000000000500][1]order 1 filled px=101.5 qty=2\n",
+    "[1698048000000001000][2]params: params={strategy=momentum window=20}\n",
+    // sequence 3 was dropped as the queue was full
+    "[1698048000000002000][4]queue drained\n",
+];
+
+const GOLDEN_LOGFMT: [&str; 4] = [
+    "ts=1698048000000000000 level=INFO seq=0 msg=\"order 1 accepted\" serialize=BTC-USD\n",
+    "ts=1698048000000000500 level=WARN seq=1 msg=\"order 1 filled\" px=101.5 qty=2\n",
+    "ts=1698048000000001000 level=INFO seq=2 msg=params: params=\"{strategy=momentum window=20}\"\n",
+    "ts=1698048000000002000 level=INFO seq=4 msg=\"queue drained\"\n",
+];
+
+fn main() {
+    init!(
+        capacity = 3,
+        queue_full_policy = QueueFullPolicy::DropNewest,
+        timestamp_format = TimestampFormat::EpochNanos,
+        deterministic = ReplayClock,
+    );
+    assert!(quicklog::logger().is_deterministic());
+    with_flush!(unsafe { VecFlusher::new(&mut *std::ptr::addr_of_mut!(TEXT)) });
+    with_output!(LogfmtFormatter::new(), unsafe {
+        VecFlusher::new(&mut *std::ptr::addr_of_mut!(LOGFMT))
+    });
+
+    let serialize = SerializeStruct {
+        symbol: String::from("BTC-USD"),
+    };
+    REPLAYED_TIME.store(1_698_048_000_000_000_000, Ordering::Relaxed);
+    info!(^serialize, "order {} accepted", 1);
+    REPLAYED_TIME.store(1_698_048_000_000_000_500, Ordering::Relaxed);
+    warn!(px = 101.5, qty = 2, "order {} filled", 1);
+    REPLAYED_TIME.store(1_698_048_000_000_001_000, Ordering::Relaxed);
+    let params = FieldMap::new()
+        .with("strategy", &"momentum")
+        .with("window", &20_u32);
+    info!(^params, "params:");
+    info!("dropped");
+    assert_eq!(flush_all!(), 3);
+
+    REPLAYED_TIME.store(1_698_048_000_000_002_000, Ordering::Relaxed);
+    info!("queue drained");
+    assert_eq!(flush_all!(), 1);
+
+    assert_eq!(unsafe { &*std::ptr::addr_of!(TEXT) }, &GOLDEN_TEXT);
+    assert_eq!(unsafe { &*std::ptr::addr_of!(LOGFMT) }, &GOLDEN_LOGFMT);
+}
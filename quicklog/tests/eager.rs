@@ -30,4 +30,8 @@ fn main() {
         info!(a = %s2, "single eager display with prefix and name:"),
         format!("single eager display with prefix and name: a={}", s2)
     );
+    assert_message_equal!(
+        info!(a = ?s1, "prefixed arg after fmt str: {b}", b = ?s1),
+        format!("prefixed arg after fmt str: {:?} a={:?}", s1, s1)
+    );
 }
@@ -0,0 +1,67 @@
+use std::cell::Cell;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use quicklog::{
+    constants::MAX_SERIALIZE_BUFFER_CAPACITY,
+    encode_error_count, info, logger,
+    serialize::{EncodeErrorPolicy, Serialize, Store},
+};
+
+mod common;
+
+/// Requires more bytes than the byte buffer can ever hold
+struct Oversized;
+
+impl Serialize for Oversized {
+    fn encode<'buf>(&self, _: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        panic!("encode is never called when the chunk cannot be obtained");
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        panic!("a record of {} bytes was flushed", read_buf.len());
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        MAX_SERIALIZE_BUFFER_CAPACITY + 1
+    }
+}
+
+fn main() {
+    setup!();
+    let flushed = || unsafe {
+        let lines = common::from_log_lines(&VEC, common::message_from_log_line);
+        VEC.clear();
+        lines
+    };
+
+    // the record is dropped and counted by default, without evaluating the
+    // arguments after the one that failed
+    assert_eq!(logger().encode_error_policy(), EncodeErrorPolicy::Drop);
+    let evaluated = Cell::new(false);
+    info!(before = ^1_u64, "queued");
+    info!(
+        big = ^Oversized,
+        after = {
+            evaluated.set(true);
+            2
+        },
+        "dropped"
+    );
+    info!("{} {}", ^Oversized, ^3_u64);
+    info!(after = ^4_u64, "queued");
+    assert!(!evaluated.get());
+    assert_eq!(encode_error_count(), 2);
+
+    assert_eq!(quicklog::flush_all!(), 2);
+    assert_eq!(flushed(), vec!["queued before=1", "queued after=4"]);
+    assert_eq!(quicklog::dropped_count(), 0);
+
+    // or fails loudly
+    logger().use_encode_error_policy(EncodeErrorPolicy::Panic);
+    let panicked = catch_unwind(AssertUnwindSafe(|| {
+        info!(big = ^Oversized, "panics");
+    }));
+    assert!(panicked.is_err());
+    assert_eq!(encode_error_count(), 2);
+    assert_eq!(quicklog::flush_all!(), 0);
+}
@@ -0,0 +1,5 @@
+use quicklog::audit;
+
+fn main() {
+    audit!(sample: 10, "order filled");
+}
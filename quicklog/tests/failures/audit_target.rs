@@ -0,0 +1,6 @@
+use quicklog::{audit, config::Config, Quicklog};
+
+fn main() {
+    let mut logger = Quicklog::new(Config::new());
+    audit!(target: logger, "order filled");
+}
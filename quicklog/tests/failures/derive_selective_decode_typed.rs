@@ -0,0 +1,10 @@
+use quicklog::SerializeSelective;
+
+#[derive(SerializeSelective)]
+#[serialize(decode_typed)]
+struct Wrapper<T> {
+    #[serialize]
+    value: T,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use quicklog::Serialize;
+
+#[derive(Serialize)]
+#[serialize(transparent, named)]
+struct Conflicting(u64);
+
+#[derive(Serialize)]
+#[serialize(unknown)]
+struct Unknown(u64);
+
+fn main() {}
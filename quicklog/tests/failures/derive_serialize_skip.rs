@@ -0,0 +1,10 @@
+use quicklog::Serialize;
+
+#[derive(Serialize)]
+struct Unknown {
+    id: u64,
+    #[serialize(omit)]
+    payload: Vec<u8>,
+}
+
+fn main() {}
@@ -0,0 +1,5 @@
+use quicklog::info;
+
+fn main() {
+    info!("order id" = 1, "bad field name");
+}
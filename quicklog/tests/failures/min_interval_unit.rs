@@ -0,0 +1,6 @@
+use quicklog::info;
+
+fn main() {
+    info!(min_interval: 100, "no unit");
+    info!(min_interval: 5min, "unknown unit");
+}
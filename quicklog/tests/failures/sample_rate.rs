@@ -0,0 +1,5 @@
+use quicklog::info;
+
+fn main() {
+    info!(sample: 0, "never");
+}
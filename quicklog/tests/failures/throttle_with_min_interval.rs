@@ -0,0 +1,5 @@
+use quicklog::info;
+
+fn main() {
+    info!(min_interval: 100ms, throttle: 1s, "both");
+}
@@ -58,4 +58,16 @@ fn main() {
         ),
         format!("reuse debug, nested field, able to reuse after pass by ref: reuse.debug={:?} some_inner_field.some.field.included=hello world able.to.reuse.s2.borrow={}", s1, &s2)
     );
+    assert_message_equal!(
+        info!(order.type = "limit", order.r#ref = 7, "keywords:"),
+        "keywords: order.type=limit order.ref=7"
+    );
+    assert_message_equal!(
+        info!("http.status-code" = 200, "span{id}" = 3, "string names:"),
+        "string names: http.status-code=200 span{id}=3"
+    );
+    assert_message_equal!(
+        info!("http.status-code" = 200, order.id = ^5_u64),
+        "http.status-code=200 order.id=5"
+    );
 }
@@ -0,0 +1,16 @@
+use quicklog::{info, serialize::FloatFormat, with_float_format};
+
+mod common;
+
+fn main() {
+    setup!();
+    with_float_format!(FloatFormat::Fixed(3));
+
+    let px = 101.5_f64;
+    assert_message_equal!(info!(^px, "filled"), "filled px=101.500");
+    // Only `^` arguments are decoded, others are formatted when logging
+    assert_message_equal!(info!("filled at {}", px), "filled at 101.5");
+
+    with_float_format!(FloatFormat::Shortest);
+    assert_message_equal!(info!(^px, "filled"), "filled px=101.5");
+}
@@ -0,0 +1,76 @@
+use std::{sync::Mutex, time::Duration};
+
+use quicklog::{
+    flush_all, flush_with_deadline, flush_with_result, info, init, with_flush, FlushResult,
+};
+use quicklog_flush::Flush;
+
+static FLUSHED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Flusher which logs a new record for every record it flushes, like a
+/// producer which keeps logging while the queue is being drained
+struct ProducingFlusher;
+
+impl Flush for ProducingFlusher {
+    fn flush_one(&mut self, display: String) {
+        FLUSHED.lock().unwrap().push(display);
+        info!("logged during flush");
+    }
+}
+
+fn flushed() -> Vec<String> {
+    FLUSHED
+        .lock()
+        .unwrap()
+        .drain(..)
+        .map(|line| line[line.find(']').unwrap() + 1..].trim_end().to_string())
+        .collect()
+}
+
+fn main() {
+    init!();
+    with_flush!(ProducingFlusher);
+
+    assert_eq!(flush_all!(), 0);
+
+    for i in 0..10 {
+        info!("{}", i);
+    }
+    // only the records queued before flushing are written
+    assert_eq!(flush_all!(), 10);
+    assert_eq!(
+        flushed(),
+        (0..10).map(|i| i.to_string()).collect::<Vec<_>>()
+    );
+
+    // records logged during the previous flush are written by the next one
+    info!("after");
+    assert_eq!(flush_all!(), 11);
+    let lines = flushed();
+    assert_eq!(lines.len(), 11);
+    assert!(lines[..10].iter().all(|line| line == "logged during flush"));
+    assert_eq!(lines[10], "after");
+
+    // records logged during the flush are reported as remaining
+    let result = flush_with_result!();
+    let bytes = FLUSHED.lock().unwrap().iter().map(String::len).sum();
+    assert_eq!(
+        result,
+        FlushResult {
+            events_flushed: 11,
+            bytes_written: bytes,
+            events_remaining: 11,
+            dropped_since_last: 0,
+        }
+    );
+
+    // an exhausted budget still flushes a single record
+    flushed();
+    let result = flush_with_deadline!(Duration::ZERO);
+    assert_eq!((result.events_flushed, result.events_remaining), (1, 11));
+    assert_eq!(flushed(), ["logged during flush"]);
+
+    // a generous budget flushes every record queued at the time of calling
+    let result = flush_with_deadline!(Duration::from_secs(60));
+    assert_eq!((result.events_flushed, result.events_remaining), (11, 11));
+}
@@ -0,0 +1,51 @@
+use std::sync::{Arc, Mutex};
+
+use quicklog::{flush_async, info, init, warn, with_async_flush, with_tag_flush};
+use quicklog_flush::{AsyncFlush, FlushFuture, Level};
+
+mod common;
+
+/// Collects lines after yielding to the runtime, like a flusher waiting on
+/// async I/O
+struct YieldingFlusher(Arc<Mutex<Vec<(Level, String)>>>);
+
+impl AsyncFlush for YieldingFlusher {
+    fn flush_record(&mut self, level: Level, display: String) -> FlushFuture<'_> {
+        Box::pin(async move {
+            tokio::task::yield_now().await;
+            let message = display[display.find(']').unwrap() + 1..].trim_end();
+            self.0.lock().unwrap().push((level, message.to_string()));
+            Ok(())
+        })
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    init!();
+    let lines = Arc::new(Mutex::new(Vec::new()));
+    with_async_flush!(YieldingFlusher(lines.clone()));
+
+    assert_eq!(flush_async!().await, 0);
+
+    static mut AUDIT: Vec<String> = Vec::new();
+    with_tag_flush!("audit", unsafe { common::VecFlusher::new(&mut AUDIT) });
+
+    info!("hello {}", "world");
+    info!(tag: audit, "order {} accepted", 7);
+    warn!("careful");
+    // nothing else flushes meanwhile, so the flush can run as a task
+    let flushed = tokio::spawn(flush_async!()).await.unwrap();
+    assert_eq!(flushed, 3);
+    // tagged records still go to the flusher of their tag
+    let audit = unsafe { &*std::ptr::addr_of!(AUDIT) };
+    assert_eq!(audit.len(), 1);
+    assert!(audit[0].trim_end().ends_with("order 7 accepted"));
+    assert_eq!(
+        *lines.lock().unwrap(),
+        [
+            (Level::Info, "hello world".to_string()),
+            (Level::Warn, "careful".to_string())
+        ]
+    );
+}
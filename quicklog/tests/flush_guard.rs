@@ -0,0 +1,45 @@
+use std::sync::Mutex;
+
+use quicklog::{info, init_with_guard, with_flush};
+use quicklog_flush::Flush;
+
+static WRITTEN: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Flusher which only writes its lines once asked to write out its buffer
+#[derive(Default)]
+struct BufferingFlusher(Vec<String>);
+
+impl Flush for BufferingFlusher {
+    fn flush_one(&mut self, display: String) {
+        self.0.push(display);
+    }
+
+    fn flush_buffered(&mut self) -> std::io::Result<()> {
+        WRITTEN.lock().unwrap().append(&mut self.0);
+        Ok(())
+    }
+}
+
+fn written() -> Vec<String> {
+    WRITTEN
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|line| line[line.find(']').unwrap() + 1..].trim_end().to_string())
+        .collect()
+}
+
+fn main() {
+    {
+        let _guard = init_with_guard!(capacity = 16);
+        with_flush!(BufferingFlusher::default());
+
+        info!("first");
+        info!("second");
+        assert!(written().is_empty());
+    }
+
+    // dropping the guard drained the queue and wrote out the buffer
+    assert_eq!(written(), ["first", "second"]);
+    assert_eq!(quicklog::logger().capacity(), 16);
+}
@@ -0,0 +1,38 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+};
+
+use quicklog::{dropped_count, flush_all, info, init};
+
+mod common;
+
+const RECORDS: usize = 100_000;
+
+fn main() {
+    init!(capacity = 64);
+    setup!();
+
+    // records are flushed on another thread while this one keeps logging
+    static DONE: AtomicBool = AtomicBool::new(false);
+    let flushing = thread::spawn(|| {
+        while !DONE.load(Ordering::Acquire) {
+            flush_all!();
+            thread::yield_now();
+        }
+        flush_all!();
+    });
+    for i in 0..RECORDS {
+        info!("{}", i);
+    }
+    DONE.store(true, Ordering::Release);
+    flushing.join().unwrap();
+
+    let lines = unsafe { common::from_log_lines(&VEC, common::message_from_log_line) };
+    assert_eq!(lines.len(), RECORDS - dropped_count());
+    let numbers = lines
+        .iter()
+        .map(|line| line.parse::<usize>().unwrap())
+        .collect::<Vec<_>>();
+    assert!(numbers.windows(2).all(|pair| pair[0] < pair[1]));
+}
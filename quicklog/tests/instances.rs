@@ -0,0 +1,114 @@
+use std::{cell::RefCell, rc::Rc, thread};
+
+use quicklog::{
+    config::Config, constants::MAX_SERIALIZE_BUFFER_CAPACITY, error, flush_all, info, init,
+    queue::QueueFullPolicy, warn, with_flush, Quicklog,
+};
+use quicklog_flush::Flush;
+
+#[derive(Clone, Default)]
+struct SharedFlusher(Rc<RefCell<Vec<String>>>);
+
+impl SharedFlusher {
+    fn take(&self) -> Vec<String> {
+        self.0
+            .borrow_mut()
+            .drain(..)
+            .map(|line| line[line.find(']').unwrap() + 1..].trim_end().to_string())
+            .collect()
+    }
+}
+
+impl Flush for SharedFlusher {
+    fn flush_one(&mut self, display: String) {
+        self.0.borrow_mut().push(display);
+    }
+}
+
+thread_local! {
+    static MARKET_DATA_LOGGER: RefCell<Option<Quicklog>> = const { RefCell::new(None) };
+}
+
+fn main() {
+    let global = SharedFlusher::default();
+    init!();
+    with_flush!(global.clone());
+
+    let market_data = SharedFlusher::default();
+    let mut market_data_logger =
+        Quicklog::new(Config::new().with_capacity(2).with_flush(market_data.clone()));
+    let orders = SharedFlusher::default();
+    let mut orders_logger = Quicklog::new(Config::new().with_flush(orders.clone()));
+    let orders_ref = &mut orders_logger;
+
+    info!(target: market_data_logger, "bid {}", 100);
+    info!(target: market_data_logger, depth = 5, "ask {}", 101);
+    // the queue of the instance is full, unlike the global one
+    warn!(target: market_data_logger, "dropped");
+    error!(target: orders_ref, tag: audit, "order {} rejected", 7);
+    info!("global");
+
+    assert_eq!(market_data_logger.dropped_count(), 1);
+    assert_eq!(market_data_logger.flush_all(), 2);
+    assert_eq!(market_data.take(), ["bid 100", "ask 101 depth=5"]);
+    assert_eq!(orders_logger.flush_all(), 1);
+    assert_eq!(orders.take(), ["order 7 rejected"]);
+    assert_eq!(flush_all!(), 1);
+    assert_eq!(global.take(), ["global"]);
+
+    // spilled records keep the values of their encoded arguments
+    let bursty = SharedFlusher::default();
+    let mut bursty_logger = Quicklog::new(
+        Config::new()
+            .with_capacity(1)
+            .with_queue_full_policy(QueueFullPolicy::Spill { max_records: 2 })
+            .with_flush(bursty.clone()),
+    );
+    for price in [100_u64, 101, 102, 103] {
+        info!(target: bursty_logger, ^price, "quote");
+    }
+    assert_eq!(
+        (bursty_logger.spilled_count(), bursty_logger.dropped_count()),
+        (2, 1)
+    );
+    assert_eq!(bursty_logger.flush_all(), 3);
+    assert_eq!(
+        bursty.take(),
+        ["quote price=100", "quote price=101", "quote price=102"]
+    );
+
+    // `^` arguments are encoded into the byte buffer of their own logger, so
+    // wrapping around the global buffer leaves queued instance records intact
+    let symbol = "AAPL";
+    info!(target: market_data_logger, ^symbol, "book updated");
+    let payload = "x".repeat(1000);
+    for i in 0..MAX_SERIALIZE_BUFFER_CAPACITY / 500 {
+        info!(^payload, "bulk");
+        if i == 1 {
+            info!(target: market_data_logger, ^symbol, bid = ^100_u64, "quote");
+        }
+    }
+    assert_eq!(market_data_logger.flush_all(), 2);
+    assert_eq!(
+        market_data.take(),
+        ["book updated symbol=AAPL", "quote symbol=AAPL bid=100"]
+    );
+    flush_all!();
+    assert!(global
+        .take()
+        .iter()
+        .all(|line| *line == format!("bulk payload={}", payload)));
+
+    // an instance per thread
+    thread::spawn(|| {
+        let lines = SharedFlusher::default();
+        MARKET_DATA_LOGGER.with_borrow_mut(|logger| {
+            let logger = logger.insert(Quicklog::new(Config::new().with_flush(lines.clone())));
+            info!(target: logger, "from thread");
+            logger.flush_all();
+        });
+        assert_eq!(lines.take(), ["from thread"]);
+    })
+    .join()
+    .unwrap();
+}
@@ -0,0 +1,50 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use quicklog::{
+    flush_all, info, layout::LayoutFormatter, timestamp::TimestampFormat, warn, with_clock,
+    with_formatter, with_timestamp_format,
+};
+use quicklog_clock::Clock;
+
+mod common;
+
+struct SimulatedClock;
+
+static SIMULATED_TIME: AtomicU64 = AtomicU64::new(1_698_048_000_000_000_000);
+
+impl Clock for SimulatedClock {
+    fn get_time(&self) -> u64 {
+        SIMULATED_TIME.load(Ordering::Relaxed)
+    }
+}
+
+fn main() {
+    setup!();
+    let flushed = || unsafe { (*std::ptr::addr_of_mut!(VEC)).drain(..).collect::<Vec<_>>() };
+    with_clock!(SimulatedClock);
+    with_timestamp_format!(TimestampFormat::EpochNanos);
+    with_formatter!(LayoutFormatter::new("{ts} [{level}] {module} - {msg}").unwrap());
+
+    info!(symbol = "BTC-USD", "order {} filled", 1);
+    assert_eq!(flush_all!(), 1);
+    assert_eq!(
+        flushed(),
+        [format!(
+            "1698048000000000000 [INFO] {} - order 1 filled symbol=BTC-USD\n",
+            module_path!()
+        )]
+    );
+
+    // the timestamp format of the logger still applies
+    with_formatter!(LayoutFormatter::new("{{{level}}} {file}:{line} {tag}|{seq}| {msg}").unwrap());
+    with_timestamp_format!(TimestampFormat::Rfc3339);
+    let line = line!() + 1;
+    warn!(tag: audit, "checked");
+    assert_eq!(flush_all!(), 1);
+    assert_eq!(
+        flushed(),
+        [format!("{{WARN}} {}:{} audit|1| checked\n", file!(), line)]
+    );
+
+    assert!(LayoutFormatter::new("{ts} {thread}").is_err());
+}
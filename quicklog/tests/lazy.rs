@@ -0,0 +1,68 @@
+use std::cell::Cell;
+
+use quicklog::{debug, flush, info, level::LevelFilter, with_filter};
+
+mod common;
+
+#[derive(Clone, Debug)]
+struct Book {
+    levels: Vec<f64>,
+}
+
+thread_local! {
+    static CALLS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn summary() -> String {
+    CALLS.with(|calls| calls.set(calls.get() + 1));
+    "summary".to_string()
+}
+
+fn calls() -> usize {
+    CALLS.with(Cell::get)
+}
+
+fn main() {
+    setup!();
+    let flushed = || unsafe {
+        let lines = common::from_log_lines(&VEC, common::message_from_log_line);
+        VEC.clear();
+        lines
+    };
+
+    // not called when the level is disabled
+    quicklog::level::set_max_level(LevelFilter::Info);
+    debug!("state: {}", lazy || summary());
+    assert_eq!(calls(), 0);
+
+    // only called when flushing
+    info!("state: {}", lazy || summary());
+    assert_eq!(calls(), 0);
+    flush!();
+    assert_eq!(calls(), 1);
+    assert_eq!(flushed(), ["state: summary"]);
+
+    // not called for records dropped by the runtime filter
+    with_filter!(|metadata: &quicklog::callsite::Metadata| metadata.line() == 0);
+    info!("state: {}", lazy || summary());
+    flush!();
+    assert_eq!(calls(), 1);
+    quicklog::logger().clear_filter();
+
+    // prefixes, fields and captured values
+    let book = Book {
+        levels: vec![101.5, 101.75],
+    };
+    let depth = book.levels.len();
+    info!(
+        depth = lazy move || depth * 2,
+        ?lazy move || book.clone(),
+        "book {}",
+        ^lazy || 5_u64
+    );
+    flush!();
+    assert_eq!(
+        flushed(),
+        ["book 5 depth=4 book.clone()=Book { levels: [101.5, 101.75] }"]
+    );
+}
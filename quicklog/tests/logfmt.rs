@@ -0,0 +1,61 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use quicklog::{info, logfmt::LogfmtFormatter, warn, with_clock, with_formatter};
+use quicklog_clock::Clock;
+
+use common::{SerializeStruct, Something};
+
+mod common;
+
+static SIMULATED_TIME: AtomicU64 = AtomicU64::new(1_698_048_000_000_000_000);
+
+struct SimulatedClock;
+
+impl Clock for SimulatedClock {
+    fn get_time(&self) -> u64 {
+        SIMULATED_TIME.load(Ordering::Relaxed)
+    }
+}
+
+fn trim_newline(log_line: &str) -> String {
+    log_line.trim_end().to_string()
+}
+
+fn main() {
+    setup!();
+    with_clock!(SimulatedClock);
+    with_formatter!(LogfmtFormatter::new());
+
+    let s = Something {
+        some_str: "Hello world",
+    };
+    let serialize = SerializeStruct {
+        symbol: String::from("BTC-USD"),
+    };
+    let price = 101.5;
+
+    helper_assert!(
+        @ info!("hello world"),
+        "ts=2023-10-23T08:00:00Z level=INFO msg=\"hello world\"".to_string(),
+        trim_newline
+    );
+    helper_assert!(
+        @ warn!(symbol = "BTC-USD", px = price, "order {} filled", 42),
+        "ts=2023-10-23T08:00:00Z level=WARN msg=\"order 42 filled\" symbol=BTC-USD px=101.5".to_string(),
+        trim_newline
+    );
+    helper_assert!(
+        @ info!(?s, %s, ^serialize, empty = ""),
+        format!(
+            "ts=2023-10-23T08:00:00Z level=INFO s={:?} s=\"{}\" serialize=BTC-USD empty=\"\"",
+            format!("{:?}", s),
+            s
+        ),
+        trim_newline
+    );
+    helper_assert!(
+        @ info!(order.id = ^42_u64, order.px = price, "http.status-code" = 200, "filled"),
+        "ts=2023-10-23T08:00:00Z level=INFO msg=filled order.id=42 order.px=101.5 http.status-code=200".to_string(),
+        trim_newline
+    );
+}
@@ -0,0 +1,50 @@
+use quicklog::info;
+
+mod common;
+
+static mut VEC: Vec<String> = Vec::new();
+
+fn flushed() -> Vec<String> {
+    unsafe {
+        let lines = common::from_log_lines(&*std::ptr::addr_of!(VEC), common::message_from_log_line);
+        (*std::ptr::addr_of_mut!(VEC)).clear();
+        lines
+    }
+}
+
+#[quicklog::main(
+    capacity = 1024,
+    formatter = common::TestFormatter::new(),
+    flush = unsafe { common::VecFlusher::new(&mut *std::ptr::addr_of_mut!(VEC)) }
+)]
+fn run(fail: bool) -> Result<u32, String> {
+    info!("started");
+    if fail {
+        return Err("failed".to_string());
+    }
+    info!("done");
+    Ok(42)
+}
+
+#[quicklog::main]
+fn run_panicking() {
+    info!("about to panic");
+    panic!("boom");
+}
+
+fn main() {
+    // records are flushed when the function returns, early or not
+    assert_eq!(run(false), Ok(42));
+    assert_eq!(flushed(), ["started", "done"]);
+    assert_eq!(run(true), Err("failed".to_string()));
+    assert_eq!(flushed(), ["started"]);
+    assert_eq!(quicklog::logger().capacity(), 1024);
+
+    // and when it panics, along with the panic
+    assert!(std::panic::catch_unwind(run_panicking).is_err());
+    let lines = flushed();
+    assert_eq!(lines[0], "about to panic");
+    assert!(lines[1].starts_with("panicked at "), "{}", lines[1]);
+    assert!(lines[1].ends_with(": boom"), "{}", lines[1]);
+    assert_eq!(lines.len(), 2);
+}
@@ -0,0 +1,89 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use quicklog::metric::{MetricValue, Metrics};
+use quicklog::{
+    flush_all, info, level::LevelFilter, metric, with_clock, with_metrics_report,
+    with_metrics_sink,
+};
+use quicklog_clock::Clock;
+
+mod common;
+
+static SIMULATED_TIME: AtomicU64 = AtomicU64::new(0);
+
+struct SimulatedClock;
+
+impl Clock for SimulatedClock {
+    fn get_time(&self) -> u64 {
+        SIMULATED_TIME.load(Ordering::Relaxed)
+    }
+}
+
+fn advance(duration: Duration) {
+    SIMULATED_TIME.fetch_add(duration.as_nanos() as u64, Ordering::Relaxed);
+}
+
+fn main() {
+    setup!();
+    with_clock!(SimulatedClock);
+    let flushed = || unsafe {
+        let lines = common::from_log_lines(&VEC, common::message_from_log_line);
+        VEC.clear();
+        lines
+    };
+
+    // samples are aggregated rather than flushed, and are not filtered by level
+    quicklog::level::set_max_level(LevelFilter::Error);
+    metric!(counter: "orders", 1);
+    metric!(counter: "orders", 2_u64);
+    metric!(gauge: "position", -3);
+    metric!(histogram: "latency_us", 10);
+    metric!(histogram: "latency_us", 30.0);
+    quicklog::level::set_max_level(LevelFilter::Trace);
+    info!("order filled");
+    assert_eq!(flush_all!(), 1);
+    assert_eq!(flushed(), ["order filled"]);
+
+    let metrics = quicklog::logger().metrics();
+    assert_eq!(metrics.get("orders"), Some(MetricValue::Counter(3.0)));
+    assert_eq!(metrics.get("position"), Some(MetricValue::Gauge(-3.0)));
+    match metrics.get("latency_us") {
+        Some(MetricValue::Histogram(summary)) => {
+            assert_eq!((summary.count, summary.min, summary.max), (2, 10.0, 30.0));
+            assert_eq!(summary.mean(), 20.0);
+        }
+        other => panic!("unexpected histogram {:?}", other),
+    }
+
+    // reported as a log line once the interval elapsed
+    with_metrics_report!(Duration::from_secs(10));
+    metric!(counter: "orders", 1);
+    flush_all!();
+    assert!(flushed().is_empty());
+    advance(Duration::from_secs(10));
+    metric!(histogram: "latency_us", 5);
+    flush_all!();
+    assert_eq!(
+        flushed(),
+        ["metrics latency_us.count=3 latency_us.min=5 latency_us.max=30 latency_us.mean=15 orders=4 position=-3"]
+    );
+    // histograms start over after a report
+    assert_eq!(quicklog::logger().metrics().get("latency_us"), None);
+
+    // or into a sink
+    let reports: Rc<RefCell<Vec<Metrics>>> = Rc::default();
+    let sink_reports = reports.clone();
+    with_metrics_sink!(Duration::from_secs(1), move |_time, metrics: &Metrics| {
+        sink_reports.borrow_mut().push(metrics.clone())
+    });
+    metric!(gauge: "position", 2);
+    flush_all!();
+    advance(Duration::from_secs(1));
+    quicklog::flush!();
+    assert!(flushed().is_empty());
+    assert_eq!(reports.borrow().len(), 1);
+    assert_eq!(reports.borrow()[0].to_string(), "orders=4 position=2");
+}
@@ -0,0 +1,67 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use quicklog::{info, warn, with_clock};
+use quicklog_clock::Clock;
+
+mod common;
+
+static SIMULATED_TIME: AtomicU64 = AtomicU64::new(0);
+
+struct SimulatedClock;
+
+impl Clock for SimulatedClock {
+    fn get_time(&self) -> u64 {
+        SIMULATED_TIME.load(Ordering::Relaxed)
+    }
+}
+
+fn advance(nanos: u64) {
+    SIMULATED_TIME.fetch_add(nanos, Ordering::Relaxed);
+}
+
+fn log_tick(i: usize) {
+    info!(min_interval: 100ms, "tick {}", i);
+}
+
+fn main() {
+    setup!();
+    with_clock!(SimulatedClock);
+    let flushed = || unsafe {
+        let lines = common::from_log_lines(&VEC, common::message_from_log_line);
+        VEC.clear();
+        lines
+    };
+
+    // only the first call within the interval is logged
+    for i in 0..5 {
+        log_tick(i);
+        advance(30_000_000);
+    }
+    quicklog::flush_all!();
+    assert_eq!(flushed(), vec!["tick 0", "tick 4"]);
+
+    // arguments of skipped calls are not evaluated
+    let mut evaluated = 0;
+    let mut evaluate = || {
+        evaluated += 1;
+        evaluated
+    };
+    for _ in 0..3 {
+        warn!(min_interval: 1s, "evaluated {}", evaluate());
+    }
+    assert_eq!(evaluated, 1);
+    quicklog::flush_all!();
+    assert_eq!(flushed(), vec!["evaluated 1"]);
+
+    // every call site keeps its own interval
+    advance(100_000_000);
+    log_tick(5);
+    info!(min_interval: 500us, symbol = "BTCUSD", "other site");
+    log_tick(6);
+    info!(min_interval: 500us, symbol = "BTCUSD", "other site");
+    quicklog::flush_all!();
+    assert_eq!(
+        flushed(),
+        vec!["tick 5", "other site symbol=BTCUSD", "other site symbol=BTCUSD"]
+    );
+}
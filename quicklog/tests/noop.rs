@@ -0,0 +1,52 @@
+use std::cell::Cell;
+
+use quicklog::{
+    audit, flush, flush_all, flush_audit, info, init, try_flush, try_init, warn, with_audit_flush,
+    with_flush, with_flush_into_file, Error, Serialize,
+};
+use quicklog_flush::{noop_flusher::NoopFlusher, Flush};
+
+#[derive(Serialize)]
+struct Order {
+    id: u64,
+}
+
+struct PanickingFlusher;
+
+impl Flush for PanickingFlusher {
+    fn flush_one(&mut self, _display: String) {
+        panic!("nothing should be flushed");
+    }
+}
+
+#[test]
+fn noop() {
+    init!(capacity = 8);
+    assert!(try_init!().is_ok());
+    with_flush!(PanickingFlusher);
+    with_flush_into_file!("logs/noop.log");
+
+    // arguments are type-checked, but never evaluated
+    let evaluated = Cell::new(0);
+    let next = || {
+        evaluated.set(evaluated.get() + 1);
+        evaluated.get()
+    };
+    let order = Order { id: 1 };
+    for _ in 0..16 {
+        info!(id = next(), ^order, "order {}", next());
+        warn!(min_interval: 1ms, "{}", next());
+    }
+    assert_eq!(evaluated.get(), 0);
+
+    assert!(matches!(try_flush!(), Err(Error::Empty)));
+    flush!();
+    assert_eq!(flush_all!(), 0);
+    assert!(!quicklog::is_level_enabled!(quicklog::level::Level::Error));
+
+    // except for the audit channel
+    with_audit_flush!(NoopFlusher);
+    audit!(^order, "order {}", next());
+    assert_eq!(evaluated.get(), 1);
+    assert_eq!(flush_audit!(), 1);
+}
@@ -0,0 +1,39 @@
+use quicklog::{info, level::LevelFilter, warn_once};
+
+mod common;
+
+fn deprecated(i: usize) {
+    warn_once!(option = "max_depth", "deprecated option, call {}", i);
+}
+
+fn main() {
+    setup!();
+    let flushed = || unsafe {
+        let lines = common::from_log_lines(&VEC, common::message_from_log_line);
+        VEC.clear();
+        lines
+    };
+
+    // calls while the level is disabled do not count
+    quicklog::level::set_max_level(LevelFilter::Error);
+    deprecated(0);
+    quicklog::level::set_max_level(LevelFilter::Trace);
+
+    for i in 1..100 {
+        deprecated(i);
+    }
+    quicklog::flush_all!();
+    assert_eq!(flushed(), vec!["deprecated option, call 1 option=max_depth"]);
+
+    // every call site logs once
+    for _ in 0..3 {
+        info!(once: true, "first site");
+        info!(once: true, "second site");
+        info!(once: false, "every time");
+    }
+    quicklog::flush_all!();
+    assert_eq!(
+        flushed(),
+        vec!["first site", "second site", "every time", "every time", "every time"]
+    );
+}
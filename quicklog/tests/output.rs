@@ -0,0 +1,65 @@
+use quicklog::{
+    flush_all, info, level::Level, logfmt::LogfmtFormatter, warn, wire::RecordHeader,
+    wire::WireWriter, with_output, with_record_sink,
+};
+
+use common::{SerializeStruct, VecFlusher};
+
+mod common;
+
+static mut OUTPUT: Vec<String> = Vec::new();
+static mut BINARY: Vec<u8> = Vec::new();
+
+fn main() {
+    setup!();
+    with_output!(LogfmtFormatter::new(), unsafe {
+        VecFlusher::new(&mut *std::ptr::addr_of_mut!(OUTPUT))
+    });
+    with_record_sink!(WireWriter::new(unsafe {
+        &mut *std::ptr::addr_of_mut!(BINARY)
+    }));
+
+    let serialize = SerializeStruct {
+        symbol: String::from("BTC-USD"),
+    };
+    info!(^serialize, "order {} accepted", 1);
+    warn!(px = 101.5, "order {} filled", 1);
+    assert_eq!(flush_all!(), 2);
+
+    // the same records are written in both formats
+    let text = unsafe { common::from_log_lines(&VEC, common::message_from_log_line) };
+    assert_eq!(
+        text,
+        [
+            "order 1 accepted serialize=BTC-USD",
+            "order 1 filled px=101.5"
+        ]
+    );
+
+    let output = unsafe { &*std::ptr::addr_of!(OUTPUT) };
+    assert_eq!(output.len(), 2);
+    assert!(output[0].contains("level=INFO msg=\"order 1 accepted\" serialize=BTC-USD"));
+    assert!(output[1].contains("level=WARN msg=\"order 1 filled\" px=101.5"));
+
+    // and in binary form, as a header followed by the message
+    let binary = unsafe { &*std::ptr::addr_of!(BINARY) };
+    let mut binary = binary.as_slice();
+    let mut records = Vec::new();
+    while !binary.is_empty() {
+        let header = RecordHeader::from_bytes(binary).unwrap();
+        let (record, rest) = binary[RecordHeader::SIZE..].split_at(header.payload_len() as usize);
+        records.push((
+            header.level(),
+            header.sequence(),
+            std::str::from_utf8(record).unwrap(),
+        ));
+        binary = rest;
+    }
+    assert_eq!(
+        records,
+        [
+            (Level::Info, 0, "order 1 accepted serialize=BTC-USD"),
+            (Level::Warn, 1, "order 1 filled px=101.5")
+        ]
+    );
+}
@@ -0,0 +1,52 @@
+use quicklog::{flush_all, info, pre_init_dropped_count, try_flush, warn, Error, Serialize};
+
+mod common;
+
+#[derive(Serialize)]
+struct Config {
+    venue: &'static str,
+}
+
+/// Stands in for a library which logs on its own, independently of when the
+/// binary initializes the logger
+mod library {
+    use super::Config;
+    use quicklog::{info, warn};
+
+    pub fn connect() {
+        let config = Config { venue: "XNAS" };
+        info!(^config, "connecting");
+        warn!("retrying in {}ms", 10);
+    }
+}
+
+fn main() {
+    // library called before the binary initialized the logger
+    library::connect();
+    info!("binary starting");
+    assert_eq!(pre_init_dropped_count(), 3);
+    assert!(matches!(try_flush!(), Err(Error::NotInitialized)));
+    assert_eq!(flush_all!(), 0);
+
+    setup!();
+    // records dropped before initialization are never flushed
+    assert!(matches!(try_flush!(), Err(Error::Empty)));
+
+    // library called after the binary initialized the logger
+    library::connect();
+    let config = Config { venue: "XLON" };
+    warn!(^config, "binary started");
+    assert_eq!(flush_all!(), 3);
+    assert_eq!(pre_init_dropped_count(), 3);
+
+    let lines =
+        unsafe { common::from_log_lines(&*std::ptr::addr_of!(VEC), common::message_from_log_line) };
+    assert_eq!(
+        lines,
+        [
+            "connecting config=venue=XNAS",
+            "retrying in 10ms",
+            "binary started config=venue=XLON"
+        ]
+    );
+}
@@ -0,0 +1,48 @@
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use quicklog::{
+    info,
+    serialize::{Serialize, Store},
+};
+
+mod common;
+
+/// Writes part of its bytes, then panics like a buggy `encode` would
+struct PanicsMidEncode;
+
+impl Serialize for PanicsMidEncode {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        write_buf[..4].copy_from_slice(&[0xFF; 4]);
+        panic!("encode failed halfway");
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        panic!("a record of {} bytes was flushed", read_buf.len());
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        16
+    }
+}
+
+fn main() {
+    setup!();
+    let flushed = || unsafe {
+        let lines = common::from_log_lines(&VEC, common::message_from_log_line);
+        VEC.clear();
+        lines
+    };
+
+    info!(before = ^1_u64, "queued");
+    // the panic is not caught by the logger, but the record it was encoding
+    // is never queued, so the flusher cannot read its partial bytes
+    let panicked = catch_unwind(AssertUnwindSafe(|| {
+        info!(after = ^2_u64, torn = ^PanicsMidEncode, "torn");
+    }));
+    assert!(panicked.is_err());
+    info!(after = ^3_u64, "queued");
+
+    assert_eq!(quicklog::flush_all!(), 2);
+    assert_eq!(flushed(), vec!["queued before=1", "queued after=3"]);
+    assert_eq!(quicklog::dropped_count(), 0);
+}
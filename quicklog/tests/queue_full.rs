@@ -0,0 +1,122 @@
+use std::{
+    sync::atomic::{AtomicBool, Ordering},
+    thread,
+};
+
+use quicklog::{
+    dropped_count, flush_all, flush_with_result, info, logger, queue::QueueFullPolicy,
+    spilled_count,
+};
+
+mod common;
+
+/// Logs increasing numbers until a record is dropped, returning how many
+/// records were logged
+fn log_until_dropped() -> usize {
+    let dropped = dropped_count();
+    let mut logged = 0;
+    while dropped_count() == dropped {
+        info!("{}", logged);
+        logged += 1;
+    }
+    logged
+}
+
+fn main() {
+    setup!();
+    let flushed = || unsafe { common::from_log_lines(&VEC, common::message_from_log_line) };
+
+    // the new record is dropped by default
+    assert_eq!(logger().queue_full_policy(), QueueFullPolicy::DropNewest);
+    let logged = log_until_dropped();
+    assert_eq!(dropped_count(), 1);
+    flush_all!();
+    let lines = flushed();
+    assert_eq!(lines.len(), logged - 1);
+    assert_eq!(lines.first().unwrap(), "0");
+    assert_eq!(lines.last().unwrap(), &(logged - 2).to_string());
+    unsafe { VEC.clear() };
+
+    // the oldest record is overwritten
+    logger().use_queue_full_policy(QueueFullPolicy::DropOldest);
+    let logged = log_until_dropped();
+    info!("{}", logged);
+    assert_eq!(dropped_count(), 3);
+    flush_all!();
+    let lines = flushed();
+    assert_eq!(lines.len(), logged - 1);
+    assert_eq!(lines.first().unwrap(), "2");
+    assert_eq!(lines.last().unwrap(), &logged.to_string());
+    unsafe { VEC.clear() };
+
+    // the producer waits for records flushed on another thread to make room,
+    // so nothing is dropped
+    logger().use_queue_full_policy(QueueFullPolicy::Block);
+    static DONE: AtomicBool = AtomicBool::new(false);
+    let flushing = thread::spawn(|| {
+        while !DONE.load(Ordering::Acquire) {
+            flush_all!();
+            thread::yield_now();
+        }
+        flush_all!();
+    });
+    for i in 0..2 * logged {
+        info!("{}", i);
+    }
+    DONE.store(true, Ordering::Release);
+    flushing.join().unwrap();
+    assert_eq!(dropped_count(), 3);
+    let lines = flushed();
+    assert_eq!(lines.len(), 2 * logged);
+    assert_eq!(lines.last().unwrap(), &(2 * logged - 1).to_string());
+    unsafe { VEC.clear() };
+
+    // drops are reported by the next flush with a result, since the previous one
+    logger().use_queue_full_policy(QueueFullPolicy::DropNewest);
+    let logged = log_until_dropped();
+    info!("dropped too");
+    let result = flush_with_result!();
+    assert_eq!(result.events_flushed, logged - 1);
+    assert_eq!(result.events_remaining, 0);
+    assert_eq!(result.dropped_since_last, 5);
+    assert_eq!(flush_with_result!().dropped_since_last, 0);
+    unsafe { VEC.clear() };
+
+    // records are spilled past the queue, and flushed after it in order
+    let capacity = logged - 1;
+    logger().use_queue_full_policy(QueueFullPolicy::Spill { max_records: 3 });
+    for i in 0..capacity + 4 {
+        info!(next = i + 1, "{}", i);
+    }
+    assert_eq!(spilled_count(), 3);
+    assert_eq!(dropped_count(), 6);
+    assert_eq!(flush_all!(), capacity + 3);
+    let lines = flushed();
+    assert_eq!(lines.len(), capacity + 3);
+    assert_eq!(lines.first().unwrap(), "0 next=1");
+    assert_eq!(
+        lines.last().unwrap(),
+        &format!("{} next={}", capacity + 2, capacity + 3)
+    );
+    unsafe { VEC.clear() };
+
+    // flushing one record at a time while the oldest are dropped past the
+    // position the flusher last read
+    logger().use_queue_full_policy(QueueFullPolicy::DropOldest);
+    info!("a");
+    info!("b");
+    quicklog::flush!();
+    for i in 0..capacity + 2 {
+        info!("{}", i);
+    }
+    for _ in 0..capacity + 4 {
+        quicklog::flush!();
+    }
+    let lines = flushed();
+    assert_eq!(lines.len(), capacity + 1);
+    assert_eq!(lines.first().unwrap(), "a");
+    assert_eq!(lines[1], "2");
+    assert_eq!(lines.last().unwrap(), &(capacity + 1).to_string());
+    info!("after");
+    assert_eq!(flush_all!(), 1);
+}
@@ -0,0 +1,66 @@
+use quicklog::{flush_all, info, route_target, with_tag_flush};
+
+use common::VecFlusher;
+
+mod common;
+
+static mut FIX: Vec<String> = Vec::new();
+static mut SESSION: Vec<String> = Vec::new();
+static mut AUDIT: Vec<String> = Vec::new();
+
+mod gateway {
+    pub mod fix {
+        pub fn log(msg: &'static str) {
+            quicklog::info!("{}", msg);
+        }
+
+        pub mod session {
+            pub fn log(msg: &'static str) {
+                quicklog::info!("{}", msg);
+            }
+
+            pub fn log_tagged(msg: &'static str) {
+                quicklog::info!(tag: audit, "{}", msg);
+            }
+        }
+    }
+
+    pub mod fixture {
+        pub fn log(msg: &'static str) {
+            quicklog::info!("{}", msg);
+        }
+    }
+}
+
+fn messages(vec: *const Vec<String>) -> Vec<String> {
+    common::from_log_lines(unsafe { &*vec }, common::message_from_log_line)
+}
+
+fn main() {
+    setup!();
+    let root = module_path!();
+    route_target(&format!("{}::gateway::fix", root), unsafe {
+        VecFlusher::new(&mut *std::ptr::addr_of_mut!(FIX))
+    });
+    route_target(&format!("{}::gateway::fix::session", root), unsafe {
+        VecFlusher::new(&mut *std::ptr::addr_of_mut!(SESSION))
+    });
+    with_tag_flush!("audit", unsafe {
+        VecFlusher::new(&mut *std::ptr::addr_of_mut!(AUDIT))
+    });
+
+    info!("main");
+    gateway::fix::log("fix");
+    gateway::fix::session::log("session");
+    gateway::fix::session::log_tagged("tagged");
+    // only whole module names match
+    gateway::fixture::log("fixture");
+    assert_eq!(flush_all!(), 5);
+
+    assert_eq!(messages(std::ptr::addr_of!(VEC)), ["main", "fixture"]);
+    assert_eq!(messages(std::ptr::addr_of!(FIX)), ["fix"]);
+    // the most specific target wins
+    assert_eq!(messages(std::ptr::addr_of!(SESSION)), ["session"]);
+    // tags take precedence over targets
+    assert_eq!(messages(std::ptr::addr_of!(AUDIT)), ["tagged"]);
+}
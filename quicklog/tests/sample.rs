@@ -0,0 +1,42 @@
+use quicklog::{debug, info};
+
+mod common;
+
+fn log_tick(i: usize) {
+    debug!(sample: 3, "tick {}", ^i);
+}
+
+fn main() {
+    setup!();
+    let flushed = || unsafe {
+        let lines = common::from_log_lines(&VEC, common::message_from_log_line);
+        VEC.clear();
+        lines
+    };
+
+    // one call out of every 3 is logged, starting with the first
+    for i in 0..7 {
+        log_tick(i);
+    }
+    quicklog::flush_all!();
+    assert_eq!(flushed(), vec!["tick 0", "tick 3", "tick 6"]);
+
+    // arguments of skipped calls are not evaluated
+    let evaluated = std::cell::Cell::new(0);
+    let evaluate = || {
+        evaluated.set(evaluated.get() + 1);
+        evaluated.get()
+    };
+    for _ in 0..4 {
+        info!(sample: 2, "evaluated {}", evaluate());
+    }
+    assert_eq!(evaluated.get(), 2);
+    quicklog::flush_all!();
+    assert_eq!(flushed(), vec!["evaluated 1", "evaluated 2"]);
+
+    // every call site keeps its own counter
+    log_tick(7);
+    info!(sample: 1, "always");
+    quicklog::flush_all!();
+    assert_eq!(flushed(), vec!["always"]);
+}
@@ -0,0 +1,71 @@
+use quicklog::{
+    debug, info,
+    level::{set_max_level, Level, LevelFilter},
+    schema::{self, ArgKind, ArgSchema},
+    warn,
+};
+
+mod common;
+
+fn log(id: u64) {
+    info!(id = ^id, "called");
+}
+
+fn main() {
+    setup!();
+    let payload = [0xde_u8, 0xad];
+    let name = "ETH-USD";
+
+    let line = line!() + 1;
+    warn!(order.side = ?'B', %name, hex(^payload), "sent {} \"{}\"", ^1.5_f64, 7_u32);
+    info!("{}", lazy move || payload.len());
+    log(1);
+    log(2);
+    set_max_level(LevelFilter::Info);
+    debug!("disabled");
+    quicklog::flush!();
+
+    // registered once per call site, when first reached with its level enabled
+    let schemas = schema::schemas();
+    assert_eq!(schemas.len(), 3);
+
+    let sent = &schemas[0];
+    assert_eq!(sent.metadata().level(), Level::Warn);
+    assert_eq!(sent.metadata().file(), file!());
+    assert_eq!(sent.metadata().line(), line);
+    assert_eq!(sent.format(), "sent {} \"{}\"");
+    assert_eq!(
+        sent.args(),
+        [
+            ArgSchema::new(None, ArgKind::Serialize, "f64"),
+            ArgSchema::new(None, ArgKind::Normal, "u32"),
+            ArgSchema::new(Some("order.side"), ArgKind::Debug, "char"),
+            ArgSchema::new(Some("name"), ArgKind::Display, "&str"),
+            ArgSchema::new(Some("payload"), ArgKind::Hex, "[u8]"),
+        ]
+    );
+    assert_eq!(
+        schemas[1].args(),
+        [ArgSchema::new(None, ArgKind::Lazy, "usize")]
+    );
+    assert_eq!(schemas[2].format(), "called");
+
+    let json = schema::to_json();
+    let expected = format!(
+        concat!(
+            "{{\"version\":1,\"callsites\":[{{\"id\":{},\"level\":\"WARN\",\"module_path\":\"{}\",",
+            "\"file\":\"{}\",\"line\":{},\"format\":\"sent {{}} \\\"{{}}\\\"\",\"args\":[",
+            "{{\"name\":null,\"kind\":\"serialize\",\"type\":\"f64\"}},"
+        ),
+        sent.callsite().as_u32(),
+        module_path!(),
+        file!(),
+        line
+    );
+    assert!(json.starts_with(&expected), "{}", json);
+    assert!(json.ends_with(concat!(
+        "\"format\":\"called\",\"args\":[",
+        "{\"name\":\"id\",\"kind\":\"serialize\",\"type\":\"u64\"}]}]}"
+    )));
+    assert_eq!(json.matches("\"level\"").count(), 3);
+}
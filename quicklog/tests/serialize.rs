@@ -1,4 +1,7 @@
-use quicklog::info;
+use quicklog::{
+    info,
+    serialize::{FieldMap, StaticStr},
+};
 
 use common::{BigStruct, SerializeStruct};
 
@@ -26,6 +29,20 @@ fn main() {
         )
     );
 
+    // Test small primitives
+    let enabled = true;
+    let side = 'B';
+    let venue_id = 7_u8;
+    let offset = -300_i16;
+    assert_message_equal!(
+        info!("flag: {} side: {} venue: {} offset: {}", ^enabled, ^side, ^venue_id, ^offset),
+        "flag: true side: B venue: 7 offset: -300"
+    );
+
+    // Test durations
+    let latency = std::time::Duration::from_micros(1_234);
+    assert_message_equal!(info!(^latency, "tick-to-trade"), "tick-to-trade latency=1.234ms");
+
     // Test Vec serialization
     let vec_i32: Vec<i32> = vec![1, 2, 3, 4, 5];
     assert_message_equal!(info!("numbers: {}", ^vec_i32), "numbers: [1, 2, 3, 4, 5]");
@@ -42,4 +59,20 @@ fn main() {
         info!("options: {}", ^vec_opt),
         "options: [Some(10), None, Some(20)]"
     );
+
+    // Test dynamic fields
+    let fields = FieldMap::new()
+        .with("strategy", &"momentum")
+        .with("window", &20_u32);
+    assert_message_equal!(
+        info!(^fields, "config:"),
+        "config: fields={strategy=momentum window=20}"
+    );
+
+    // Test static strings, encoded as their pointer and length
+    const VENUE: &str = "XNAS";
+    assert_message_equal!(
+        info!(venue = ^StaticStr(VENUE), "order {}", ^"rejected"),
+        "order rejected venue=XNAS"
+    );
 }
@@ -0,0 +1,36 @@
+use quicklog::{enter, info, span, warn};
+
+mod common;
+
+fn fill(qty: u32) {
+    let _fill = enter!("fill", qty = qty);
+    info!(price = ^101.5_f64, "filled");
+}
+
+fn main() {
+    setup!();
+
+    assert_message_equal!(info!("no span"), "no span");
+
+    let order = span!("order", id = 42_u64, venue = "XNAS");
+    {
+        let _order = enter!(order.clone());
+        assert_message_equal!(warn!("sent {}", ^1_u8), "order{id=42 venue=XNAS}: sent 1");
+        assert_message_equal!(
+            fill(10),
+            "order{id=42 venue=XNAS}:fill{qty=10}: filled price=101.5"
+        );
+    }
+    assert_message_equal!(info!("exited"), "exited");
+
+    // spans are entered per thread
+    let _order = enter!(order);
+    std::thread::spawn(|| {
+        assert!(quicklog::span::current().is_empty());
+        let _session = enter!("session");
+        assert_eq!(quicklog::span::current().len(), 1);
+    })
+    .join()
+    .unwrap();
+    assert_eq!(quicklog::span::current()[0].name(), "order");
+}
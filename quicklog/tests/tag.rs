@@ -0,0 +1,75 @@
+use quicklog::{
+    flush_all, info, level::LevelFilter, logfmt::LogfmtFormatter, tag::Tag, warn, with_formatter,
+    with_tag_flush,
+};
+
+mod common;
+
+fn main() {
+    setup!();
+    static mut AUDIT: Vec<String> = Vec::new();
+    static mut RESEARCH: Vec<String> = Vec::new();
+    with_tag_flush!("audit", unsafe { common::VecFlusher::new(&mut AUDIT) });
+    with_tag_flush!(Tag::new("research"), unsafe {
+        common::VecFlusher::new(&mut RESEARCH)
+    });
+    let take = |lines: &mut Vec<String>| {
+        let messages = common::from_log_lines(lines, common::message_from_log_line);
+        lines.clear();
+        messages
+    };
+
+    let id = 7;
+    info!(tag: audit, "order {} accepted", id);
+    warn!(tag: "research", signal = 0.5, "signal");
+    info!(tag: audit, min_interval: 1s, "order {} filled", id);
+    // tags without a flusher are flushed into the default flusher
+    info!(tag: other, "untagged route");
+    info!("book updated");
+    assert_eq!(flush_all!(), 5);
+
+    unsafe {
+        assert_eq!(
+            take(&mut *std::ptr::addr_of_mut!(AUDIT)),
+            vec!["order 7 accepted", "order 7 filled"]
+        );
+        assert_eq!(
+            take(&mut *std::ptr::addr_of_mut!(RESEARCH)),
+            vec!["signal signal=0.5"]
+        );
+        assert_eq!(
+            take(&mut *std::ptr::addr_of_mut!(VEC)),
+            vec!["untagged route", "book updated"]
+        );
+    }
+    assert_eq!(Tag::find("other").map(Tag::name), Some("other"));
+
+    // logfmt output includes the tag
+    with_formatter!(LogfmtFormatter::new());
+    info!(tag: audit, "order {} cancelled", id);
+    flush_all!();
+    let line = unsafe { take(&mut *std::ptr::addr_of_mut!(AUDIT)) };
+    assert!(line[0].contains(" level=INFO tag=audit msg=\"order 7 cancelled\""));
+
+    // muted tags skip their records without evaluating the arguments
+    let feed = Tag::new("market-data");
+    feed.set_filter(LevelFilter::Off);
+    let evaluated = std::cell::Cell::new(false);
+    let px = || {
+        evaluated.set(true);
+        100.5
+    };
+    info!(tag: "market-data", "tick {}", px());
+    warn!(tag: "market-data", "gap");
+    assert_eq!(flush_all!(), 0);
+    assert!(!evaluated.get());
+
+    // filters only apply to their own tag
+    feed.set_filter(LevelFilter::Warn);
+    info!(tag: "market-data", "tick {}", px());
+    warn!(tag: "market-data", "gap");
+    info!(tag: audit, "order {} rejected", id);
+    assert_eq!(flush_all!(), 2);
+    assert!(!evaluated.get());
+    assert_eq!(feed.filter(), LevelFilter::Warn);
+}
@@ -0,0 +1,44 @@
+use quicklog::{
+    assert_logged_contains, assert_logged_eq, flush_all, info, level::Level, tag::Tag,
+    test_utils::capture, warn,
+};
+
+mod common;
+
+fn main() {
+    setup!();
+    let flushed = || unsafe { (*std::ptr::addr_of_mut!(VEC)).drain(..).collect::<Vec<_>>() };
+
+    {
+        let capture = capture();
+        info!("order {} accepted", 1);
+        warn!(tag: audit, px = 101.5, "order {} filled", 1);
+
+        let records = capture.records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].level, Level::Warn);
+        assert_eq!(records[1].module_path, module_path!());
+        assert_eq!(records[1].tag, Some(Tag::new("audit")));
+        assert_logged_eq!(capture, ["order 1 accepted", "order 1 filled px=101.5"]);
+        assert_logged_eq!(capture, []);
+
+        // guards nest, restoring the outer capture when dropped
+        {
+            let inner = quicklog::test_utils::capture();
+            info!("inner");
+            assert_logged_contains!(inner, "inner");
+        }
+        info!("outer");
+        assert_logged_contains!(capture, "out");
+
+        // records still queued when the guard is dropped are captured
+        info!("unflushed");
+    }
+
+    // the previous flusher and formatter are restored
+    assert!(flushed().is_empty());
+    info!("after capture");
+    assert_eq!(flush_all!(), 1);
+    let lines = flushed();
+    assert_eq!(common::message_from_log_line(&lines[0]), "after capture");
+}
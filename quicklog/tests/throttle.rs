@@ -0,0 +1,84 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use quicklog::{info, logfmt::LogfmtFormatter, warn_throttled, with_clock, with_formatter};
+use quicklog_clock::Clock;
+
+mod common;
+
+static SIMULATED_TIME: AtomicU64 = AtomicU64::new(0);
+
+struct SimulatedClock;
+
+impl Clock for SimulatedClock {
+    fn get_time(&self) -> u64 {
+        SIMULATED_TIME.load(Ordering::Relaxed)
+    }
+}
+
+fn advance(nanos: u64) {
+    SIMULATED_TIME.fetch_add(nanos, Ordering::Relaxed);
+}
+
+fn log_gap(seq: usize) {
+    warn_throttled!(100ms, venue = "XNAS", "gap at {}", seq);
+}
+
+fn main() {
+    setup!();
+    with_clock!(SimulatedClock);
+    let flushed = || unsafe {
+        let lines = common::from_log_lines(&VEC, common::message_from_log_line);
+        VEC.clear();
+        lines
+    };
+
+    // skipped calls are summarized on the next logged line
+    for seq in 0..5 {
+        log_gap(seq);
+        advance(30_000_000);
+    }
+    quicklog::flush_all!();
+    assert_eq!(
+        flushed(),
+        vec![
+            "gap at 0 venue=XNAS",
+            "gap at 4 venue=XNAS (suppressed 3 similar messages)"
+        ]
+    );
+
+    // arguments of skipped calls are not evaluated
+    let evaluated = std::cell::Cell::new(0);
+    let evaluate = || {
+        evaluated.set(evaluated.get() + 1);
+        evaluated.get()
+    };
+    for i in 0..3 {
+        if i == 2 {
+            advance(1_000_000_000);
+        }
+        info!(throttle: 1s, "evaluated {}", evaluate());
+    }
+    assert_eq!(evaluated.get(), 2);
+    quicklog::flush_all!();
+    assert_eq!(
+        flushed(),
+        vec![
+            "evaluated 1",
+            "evaluated 2 (suppressed 1 similar message)"
+        ]
+    );
+
+    // written as a field with logfmt
+    with_formatter!(LogfmtFormatter::new());
+    log_gap(5);
+    log_gap(6);
+    advance(100_000_000);
+    log_gap(7);
+    quicklog::flush_all!();
+    let lines = unsafe { (*std::ptr::addr_of_mut!(VEC)).drain(..).collect::<Vec<_>>() };
+    assert!(
+        lines[1].contains(r#"msg="gap at 7" venue=XNAS suppressed=1"#),
+        "{}",
+        lines[1]
+    );
+}
@@ -11,4 +11,41 @@ fn ui() {
     t.pass("tests/eager.rs");
     t.pass("tests/fields.rs");
     t.pass("tests/serialize.rs");
+    t.pass("tests/default_level.rs");
+    t.pass("tests/clock.rs");
+    t.pass("tests/queue_full.rs");
+    t.pass("tests/capacity.rs");
+    t.pass("tests/flush_thread.rs");
+    t.pass("tests/logfmt.rs");
+    t.pass("tests/decode_hooks.rs");
+    t.pass("tests/flush_all.rs");
+    t.pass("tests/min_interval.rs");
+    t.pass("tests/tag.rs");
+    t.pass("tests/compressed.rs");
+    t.pass("tests/bytes.rs");
+    t.pass("tests/pre_init.rs");
+    t.pass("tests/output.rs");
+    t.pass("tests/route_target.rs");
+    t.pass("tests/flush_guard.rs");
+    t.pass("tests/validate.rs");
+    t.pass("tests/deterministic.rs");
+    t.pass("tests/callsite.rs");
+    t.pass("tests/callsite_stats.rs");
+    t.pass("tests/layout.rs");
+    t.pass("tests/test_utils.rs");
+    t.pass("tests/float_format.rs");
+    t.pass("tests/throttle.rs");
+    t.pass("tests/sample.rs");
+    t.pass("tests/producer_panic.rs");
+    t.pass("tests/wire.rs");
+    t.pass("tests/schema.rs");
+    t.pass("tests/once.rs");
+    t.pass("tests/lazy.rs");
+    t.pass("tests/metric.rs");
+    t.pass("tests/main_attr.rs");
+    t.pass("tests/flush_async.rs");
+    t.pass("tests/instances.rs");
+    t.pass("tests/audit.rs");
+    t.pass("tests/encode_error.rs");
+    t.pass("tests/span.rs");
 }
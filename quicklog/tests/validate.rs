@@ -0,0 +1,131 @@
+use quicklog::{
+    serialize::{Serialize, Store},
+    validate::{
+        check_decode, clear_decode_samples, register_decode_sample, validate_decoders,
+        DecodeCheckError, DecodeStage,
+    },
+    Serialize,
+};
+
+#[derive(Serialize)]
+struct Fill {
+    symbol: String,
+    price: u64,
+    venues: Vec<u32>,
+}
+
+/// Encodes 8 bytes, but only decodes 4
+struct Misaligned(u64);
+
+impl Serialize for Misaligned {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        let (chunk, rest) = write_buf.split_at_mut(8);
+        chunk.copy_from_slice(&self.0.to_le_bytes());
+        (Store::new(Self::decode, chunk), rest)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        let (chunk, rest) = read_buf.split_at(4);
+        (
+            u32::from_le_bytes(chunk.try_into().unwrap()).to_string(),
+            rest,
+        )
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        8
+    }
+}
+
+/// Claims to need fewer bytes than it encodes
+struct Undersized(u64);
+
+impl Serialize for Undersized {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        self.0.encode(write_buf)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        u64::decode(read_buf)
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        4
+    }
+}
+
+/// Panics when decoding
+struct Corrupt;
+
+impl Serialize for Corrupt {
+    fn encode<'buf>(&self, write_buf: &'buf mut [u8]) -> (Store<'buf>, &'buf mut [u8]) {
+        let (chunk, rest) = write_buf.split_at_mut(1);
+        chunk[0] = 7;
+        (Store::new(Self::decode, chunk), rest)
+    }
+
+    fn decode(read_buf: &[u8]) -> (String, &[u8]) {
+        panic!("unknown variant {}", read_buf[0])
+    }
+
+    fn buffer_size_required(&self) -> usize {
+        1
+    }
+}
+
+fn main() {
+    // keep the caught panics out of the output
+    std::panic::set_hook(Box::new(|_| {}));
+
+    assert_eq!(check_decode(&5_u64), Ok(()));
+    assert_eq!(check_decode(&"abc"), Ok(()));
+    assert_eq!(
+        check_decode(&Misaligned(1)),
+        Err(DecodeCheckError::Misaligned {
+            type_name: std::any::type_name::<Misaligned>(),
+            encoded: 8,
+            decoded: 4,
+        })
+    );
+    assert_eq!(
+        check_decode(&Undersized(1)),
+        Err(DecodeCheckError::EncodedSize {
+            type_name: std::any::type_name::<Undersized>(),
+            required: 4,
+            encoded: 8,
+        })
+    );
+    let err = check_decode(&Corrupt).unwrap_err();
+    assert_eq!(
+        err,
+        DecodeCheckError::Panicked {
+            type_name: std::any::type_name::<Corrupt>(),
+            stage: DecodeStage::Decode,
+            message: Some("unknown variant 7".to_string()),
+        }
+    );
+    assert!(err.to_string().starts_with("decode of `"));
+
+    register_decode_sample(Fill {
+        symbol: "BTCUSD".to_string(),
+        price: 100,
+        venues: vec![1, 2],
+    });
+    register_decode_sample(5_u64);
+    assert_eq!(validate_decoders(), Ok(2));
+
+    register_decode_sample(Misaligned(1));
+    register_decode_sample(Corrupt);
+    let errors = validate_decoders().unwrap_err();
+    let failed: Vec<_> = errors.iter().map(|err| err.type_name()).collect();
+    assert_eq!(
+        failed,
+        [
+            std::any::type_name::<Misaligned>(),
+            std::any::type_name::<Corrupt>()
+        ]
+    );
+
+    clear_decode_samples();
+    assert_eq!(validate_decoders(), Ok(0));
+}
@@ -0,0 +1,80 @@
+// Compatibility tests of the binary record header layout. The golden bytes of
+// a released version must never change, add new versions instead.
+use quicklog::{
+    level::Level,
+    wire::{RecordHeader, WireError, MAGIC, WIRE_VERSION},
+};
+
+/// `RecordHeader::new(Level::Warn, 1_698_048_000_000_000_123, 42, 11)` in
+/// version 1
+const GOLDEN_V1: [u8; 24] = [
+    b'Q', b'L', // magic
+    1,    // version
+    3,    // level: WARN
+    11, 0, 0, 0, // payload_len
+    42, 0, 0, 0, 0, 0, 0, 0, // sequence
+    0x7b, 0x00, 0x90, 0xcb, 0xa8, 0xad, 0x90, 0x17, // timestamp
+];
+
+fn main() {
+    assert_eq!(MAGIC, *b"QL");
+    assert_eq!(WIRE_VERSION, 1);
+    assert_eq!(RecordHeader::SIZE, 24);
+    assert_eq!(RecordHeader::size_of_version(1), Some(24));
+
+    // encoding is stable
+    let header = RecordHeader::new(Level::Warn, 1_698_048_000_000_000_123, 42, 11);
+    assert_eq!(header.to_bytes(), GOLDEN_V1);
+
+    // decoding is stable, and ignores the payload following the header
+    let mut record = GOLDEN_V1.to_vec();
+    record.extend_from_slice(b"hello world");
+    let decoded = RecordHeader::from_bytes(&record).unwrap();
+    assert_eq!(decoded, header);
+    assert_eq!(decoded.version(), 1);
+    assert_eq!(decoded.level(), Level::Warn);
+    assert_eq!(decoded.payload_len(), 11);
+    assert_eq!(decoded.sequence(), 42);
+    assert_eq!(decoded.timestamp_nanos(), 1_698_048_000_000_000_123);
+    assert_eq!(
+        &record[RecordHeader::SIZE..][..decoded.payload_len() as usize],
+        b"hello world"
+    );
+
+    // every level round-trips through its byte
+    for (byte, level) in [Level::Trace, Level::Debug, Level::Info, Level::Warn, Level::Error]
+        .into_iter()
+        .enumerate()
+    {
+        let bytes = RecordHeader::new(level, 0, 0, 0).to_bytes();
+        assert_eq!(bytes[3], byte as u8);
+        assert_eq!(RecordHeader::from_bytes(&bytes).unwrap().level(), level);
+    }
+
+    // malformed headers are rejected
+    assert_eq!(
+        RecordHeader::from_bytes(&GOLDEN_V1[..10]),
+        Err(WireError::TooShort {
+            required: 24,
+            available: 10
+        })
+    );
+    let mut bad = GOLDEN_V1;
+    bad[0] = b'X';
+    assert_eq!(
+        RecordHeader::from_bytes(&bad),
+        Err(WireError::BadMagic(*b"XL"))
+    );
+    let mut bad = GOLDEN_V1;
+    bad[2] = 2;
+    assert_eq!(
+        RecordHeader::from_bytes(&bad),
+        Err(WireError::UnsupportedVersion(2))
+    );
+    let mut bad = GOLDEN_V1;
+    bad[3] = 5;
+    assert_eq!(
+        RecordHeader::from_bytes(&bad),
+        Err(WireError::InvalidLevel(5))
+    );
+}